@@ -0,0 +1,90 @@
+use eframe::egui::{self, accesskit, Response, WidgetInfo, WidgetType};
+
+use crate::notifications::Notification;
+use crate::poller::{display_name, ContainerSnapshot};
+
+// What screen readers announce for a button whose text alone is ambiguous, like the Stop
+// below the container list or a "▾" menu
+pub fn name_button(response: Response, name: &str) -> Response {
+    response.widget_info(|| WidgetInfo::labeled(WidgetType::Button, name));
+    response
+}
+
+// `name_button` for selectable labels, which are announced as toggles
+pub fn name_selectable(response: Response, selected: bool, name: &str) -> Response {
+    response.widget_info(|| WidgetInfo::selected(WidgetType::SelectableLabel, selected, name));
+    response
+}
+
+// Has screen readers read the widget out whenever it appears or changes, failures before
+// whatever is being read at the time
+pub fn announce(ctx: &egui::Context, response: &Response, urgent: bool) {
+    let live = if urgent {
+        accesskit::Live::Assertive
+    } else {
+        accesskit::Live::Polite
+    };
+    ctx.accesskit_node_builder(response.id, |builder| builder.set_live(live));
+}
+
+// A container row as read out: its name, state and status, e.g. "web, running, Up 2 hours
+// (healthy)". The list shows the state as a color only.
+pub fn container_name(name: &str, state: Option<&str>, status: Option<&str>) -> String {
+    [Some(name), state, status]
+        .into_iter()
+        .flatten()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// A notification as read out, its color says whether it worked
+pub fn notification_name(notification: &Notification) -> String {
+    let outcome = if notification.success {
+        "Done"
+    } else {
+        "Failed"
+    };
+    format!("{}: {}", outcome, notification.label())
+}
+
+// Containers that started, stopped, appeared or went away between two snapshots, with the
+// message to announce for each. Nothing is announced for the first snapshot.
+pub fn state_changes(
+    previous: &ContainerSnapshot,
+    current: &ContainerSnapshot,
+) -> Vec<(String, String)> {
+    if previous.is_empty() {
+        return Vec::new();
+    }
+    let state = |summary: &bollard::secret::ContainerSummary| {
+        summary
+            .state
+            .clone()
+            .unwrap_or_else(|| "unknown".to_string())
+    };
+    let mut changes: Vec<(String, String)> = current
+        .iter()
+        .filter_map(|(id, (summary, _))| {
+            let name = display_name(summary);
+            let message = match previous.get(id) {
+                None => format!("{} was created, {}", name, state(summary)),
+                Some((before, _)) if before.state != summary.state => {
+                    format!("{} is now {}, was {}", name, state(summary), state(before))
+                }
+                Some(_) => return None,
+            };
+            Some((id.clone(), message))
+        })
+        .chain(
+            previous
+                .iter()
+                .filter(|(id, _)| !current.contains_key(*id))
+                .map(|(id, (summary, _))| {
+                    (id.clone(), format!("{} was removed", display_name(summary)))
+                }),
+        )
+        .collect();
+    changes.sort();
+    changes
+}
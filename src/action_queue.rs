@@ -0,0 +1,180 @@
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use bollard::secret::ContainerSummary;
+use tokio::sync::mpsc;
+
+use crate::docker_api::{ContainerAction, RemoveOptions};
+use crate::error::{report, DockerrsError, ErrorSender};
+use crate::networks::remove_network;
+use crate::poller::display_name;
+use crate::read_only;
+use crate::unused_images::remove_unused_tags;
+use crate::utils::{remove_container, remove_containers, remove_image, remove_images};
+
+pub const DEFAULT_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+// Destructive actions that can be held back for a grace period before they are dispatched
+#[derive(Clone, Debug)]
+pub enum QueuedAction {
+    RemoveContainer(Box<ContainerSummary>, RemoveOptions),
+    RemoveContainers(Vec<ContainerSummary>, RemoveOptions),
+    RemoveImage(String),
+    // Dangling images, by ID
+    RemoveImages(Vec<String>),
+    RemoveUnusedTags(Vec<String>),
+    RemoveNetwork { id: String, name: String },
+}
+
+impl QueuedAction {
+    // For the action history, e.g. "Remove 2 containers: /web, /db"
+    pub fn describe(&self) -> String {
+        match self {
+            QueuedAction::RemoveContainer(summary, options) => {
+                ContainerAction::Remove(*options).describe(&display_name(summary))
+            }
+            QueuedAction::RemoveContainers(summaries, options) => {
+                let names: Vec<String> = summaries.iter().map(display_name).collect();
+                let target = format!("{} containers: {}", summaries.len(), names.join(", "));
+                ContainerAction::Remove(*options).describe(&target)
+            }
+            QueuedAction::RemoveImage(image) => format!("Remove image {}", image),
+            QueuedAction::RemoveImages(images) => format!("Remove {} images", images.len()),
+            QueuedAction::RemoveUnusedTags(tags) => {
+                format!("Remove {} unused image tags", tags.len())
+            }
+            QueuedAction::RemoveNetwork { name, .. } => format!("Remove network {}", name),
+        }
+    }
+
+    // Container removals only report failures, image and network removals report their outcome
+    // to `results` like the rest of the status messages
+    pub fn dispatch(
+        self,
+        errors: &ErrorSender,
+        results: &mpsc::UnboundedSender<Result<String, String>>,
+    ) {
+        if let Err(e) = read_only::check() {
+            return report(
+                errors,
+                DockerrsError::docker("Not dispatching a queued action", e),
+            );
+        }
+        let errors = errors.clone();
+        match self {
+            QueuedAction::RemoveContainer(summary, options) => {
+                tokio::spawn(async move {
+                    if let Err(e) = remove_container(&summary, options).await {
+                        report(&errors, e);
+                    }
+                });
+            }
+            QueuedAction::RemoveContainers(summaries, options) => {
+                tokio::spawn(async move { remove_containers(summaries, options, &errors).await });
+            }
+            QueuedAction::RemoveImage(image) => send_result(results, remove_image(image)),
+            QueuedAction::RemoveImages(images) => send_result(results, remove_images(images)),
+            QueuedAction::RemoveUnusedTags(tags) => send_result(results, remove_unused_tags(tags)),
+            QueuedAction::RemoveNetwork { id, name } => {
+                send_result(results, remove_network(id, name))
+            }
+        }
+    }
+}
+
+fn send_result(
+    results: &mpsc::UnboundedSender<Result<String, String>>,
+    task: impl Future<Output = Result<String, String>> + Send + 'static,
+) {
+    let results = results.clone();
+    tokio::spawn(async move {
+        let _ = results.send(task.await);
+    });
+}
+
+pub struct PendingAction {
+    pub id: u64,
+    pub label: String,
+    pub action: QueuedAction,
+    pub due: Instant,
+}
+
+impl PendingAction {
+    pub fn remaining(&self, now: Instant) -> Duration {
+        self.due.saturating_duration_since(now)
+    }
+}
+
+// The clock is always passed in by the caller so the queue never reads the time itself
+pub struct ActionQueue {
+    pub grace_period: Duration,
+    next_id: u64,
+    pending: Vec<PendingAction>,
+}
+
+impl Default for ActionQueue {
+    fn default() -> Self {
+        Self::new(DEFAULT_GRACE_PERIOD)
+    }
+}
+
+impl ActionQueue {
+    pub fn new(grace_period: Duration) -> Self {
+        Self {
+            grace_period,
+            next_id: 0,
+            pending: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, label: String, action: QueuedAction, now: Instant) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.pending.push(PendingAction {
+            id,
+            label,
+            action,
+            due: now + self.grace_period,
+        });
+        id
+    }
+
+    // Returns false if the action was already dispatched or cancelled
+    pub fn cancel(&mut self, id: u64) -> bool {
+        let before = self.pending.len();
+        self.pending.retain(|pending| pending.id != id);
+        self.pending.len() != before
+    }
+
+    // Cancels the most recently queued action
+    pub fn undo_last(&mut self) -> Option<PendingAction> {
+        self.pending.pop()
+    }
+
+    // Removes and returns every action whose countdown has elapsed, oldest first
+    pub fn take_due(&mut self, now: Instant) -> Vec<QueuedAction> {
+        let (due, waiting): (Vec<_>, Vec<_>) = self
+            .pending
+            .drain(..)
+            .partition(|pending| pending.due <= now);
+        self.pending = waiting;
+        due.into_iter().map(|pending| pending.action).collect()
+    }
+
+    pub fn pending(&self) -> &[PendingAction] {
+        &self.pending
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    // Queued actions are never flushed on shutdown, they are dropped and their labels returned
+    // so the binary can warn about them once the window is gone
+    pub fn drop_pending(&mut self) -> Vec<String> {
+        self.pending
+            .drain(..)
+            .map(|pending| pending.label)
+            .collect()
+    }
+}
@@ -0,0 +1,179 @@
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use bollard::container::InspectContainerOptions;
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+
+use crate::connection;
+use crate::error::DockerrsError;
+use crate::health::Health;
+use crate::poller::{display_name, ContainerSnapshot};
+
+// A flapping container notifies at most once per interval, every transition is still listed
+pub const NOTIFY_INTERVAL: Duration = Duration::from_secs(60);
+
+// Alerts kept in the list, the oldest are dropped
+pub const MAX_ALERTS: usize = 200;
+
+// Frames the tab bar stays inverted after a crash
+pub const FLASH_FRAMES: u32 = 30;
+
+// How alerts reach you, from the `alerts` section of the config file
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AlertSettings {
+    // Names of the containers watched from the start, `a` toggles more while running
+    pub watch: Vec<String>,
+    // A desktop notification through notify-send
+    pub desktop: bool,
+    // A bell on the terminal dockerrs was started from, for crashes while the window is in
+    // the background
+    pub bell: bool,
+    // Inverts the tab bar for a moment, for the same crashes
+    pub flash: bool,
+}
+
+impl Default for AlertSettings {
+    fn default() -> Self {
+        Self {
+            watch: Vec::new(),
+            desktop: true,
+            bell: false,
+            flash: false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Transition {
+    Exited(Option<i64>),
+    OomKilled,
+    Unhealthy,
+}
+
+impl Transition {
+    // A non-zero exit or an OOM kill, not a clean stop
+    pub fn is_crash(self) -> bool {
+        match self {
+            Transition::Exited(Some(code)) => code != 0,
+            Transition::Exited(None) | Transition::Unhealthy => false,
+            Transition::OomKilled => true,
+        }
+    }
+
+    pub fn describe(self) -> String {
+        match self {
+            Transition::Exited(Some(code)) => format!("exited with code {}", code),
+            Transition::Exited(None) => "exited".to_string(),
+            Transition::OomKilled => "was killed for running out of memory".to_string(),
+            Transition::Unhealthy => "became unhealthy".to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Alert {
+    // Unix seconds
+    pub at: i64,
+    pub container: String,
+    pub transition: Transition,
+}
+
+// Name a container is watched by, it survives the container being recreated
+pub fn watch_name(snapshot: &ContainerSnapshot, id: &str) -> Option<String> {
+    let (summary, _) = snapshot.get(id)?;
+    Some(display_name(summary).trim_start_matches('/').to_string())
+}
+
+// The exit code in a status like "Exited (137) 2 seconds ago"
+pub fn exit_code(status: &str) -> Option<i64> {
+    let rest = status.strip_prefix("Exited (")?;
+    rest[..rest.find(')')?].parse().ok()
+}
+
+// Watched containers that stopped running or turned unhealthy since the previous snapshot,
+// as ID, name and transition. Containers that are new or gone are not transitions.
+pub fn detect_transitions(
+    previous: &ContainerSnapshot,
+    current: &ContainerSnapshot,
+    watched: &HashSet<String>,
+) -> Vec<(String, String, Transition)> {
+    let mut transitions = Vec::new();
+    if watched.is_empty() {
+        return transitions;
+    }
+    for (id, (summary, _)) in current {
+        let Some((before, _)) = previous.get(id) else {
+            continue;
+        };
+        // Names are only looked up for the few containers whose status moved
+        if before.state == summary.state && before.status == summary.status {
+            continue;
+        }
+        let Some(name) = watch_name(current, id).filter(|name| watched.contains(name)) else {
+            continue;
+        };
+        let status = summary.status.as_deref().unwrap_or_default();
+        let health = |status: Option<&str>| status.and_then(Health::from_status);
+        let was_running = before.state.as_deref() == Some("running");
+        if was_running && summary.state.as_deref() == Some("exited") {
+            transitions.push((id.clone(), name, Transition::Exited(exit_code(status))));
+        } else if health(Some(status)) == Some(Health::Unhealthy)
+            && health(before.status.as_deref()) != Some(Health::Unhealthy)
+        {
+            transitions.push((id.clone(), name, Transition::Unhealthy));
+        }
+    }
+    transitions.sort_by(|a, b| a.1.cmp(&b.1));
+    transitions
+}
+
+// Remembers when each container last notified
+#[derive(Default)]
+pub struct AlertLimiter {
+    last: HashMap<String, Instant>,
+}
+
+impl AlertLimiter {
+    pub fn allow(&mut self, container: &str, now: Instant) -> bool {
+        match self.last.get(container) {
+            Some(last) if now.saturating_duration_since(*last) < NOTIFY_INTERVAL => false,
+            _ => {
+                self.last.insert(container.to_string(), now);
+                true
+            }
+        }
+    }
+}
+
+// The list endpoint cannot tell an OOM kill from other exits, the inspect can
+pub async fn exit_transition(container_id: String, code: Option<i64>) -> Transition {
+    let oom_killed = match connection::connect() {
+        Ok(docker) => docker
+            .inspect_container(&container_id, None::<InspectContainerOptions>)
+            .await
+            .ok()
+            .and_then(|inspect| inspect.state)
+            .and_then(|state| state.oom_killed)
+            .unwrap_or_default(),
+        Err(_) => false,
+    };
+    if oom_killed {
+        Transition::OomKilled
+    } else {
+        Transition::Exited(code)
+    }
+}
+
+pub async fn notify_desktop(summary: String, body: String) -> Result<(), DockerrsError> {
+    Command::new("notify-send")
+        .args(["--app-name=dockerrs", &summary, &body])
+        .status()
+        .await
+        .map(|_| ())
+        .map_err(|source| DockerrsError::Spawn {
+            program: "notify-send".to_string(),
+            source,
+        })
+}
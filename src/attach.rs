@@ -0,0 +1,182 @@
+use bollard::container::{AttachContainerOptions, InspectContainerOptions};
+use futures_util::stream::StreamExt;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::mpsc;
+
+use crate::connection;
+use crate::read_only;
+
+// Detaches from the container like `docker attach`, set with --detach-keys
+pub const DEFAULT_DETACH_KEYS: &str = "ctrl-p,ctrl-q";
+
+// Output kept in the attach window, older output is dropped
+pub const MAX_ATTACH_OUTPUT: usize = 256 * 1024;
+
+// Bytes of a detach key sequence in Docker's format, e.g. "ctrl-p,ctrl-q" or "ctrl-a,x"
+pub fn detach_key_bytes(keys: &str) -> Result<Vec<u8>, String> {
+    keys.split(',')
+        .map(|key| {
+            let invalid = || format!("Invalid detach key {:?}", key);
+            match key.strip_prefix("ctrl-") {
+                Some(value) => match value.as_bytes() {
+                    [letter @ b'a'..=b'z'] => Ok(letter - b'a' + 1),
+                    [b'@'] => Ok(0),
+                    [b'['] => Ok(27),
+                    [b'\\'] => Ok(28),
+                    [b']'] => Ok(29),
+                    [b'^'] => Ok(30),
+                    [b'_'] => Ok(31),
+                    _ => Err(invalid()),
+                },
+                None => match key.as_bytes() {
+                    [letter] if letter.is_ascii_alphabetic() => Ok(*letter),
+                    _ => Err(invalid()),
+                },
+            }
+        })
+        .collect()
+}
+
+// Drops the escape sequences a TTY program colors and moves the cursor with, the window
+// shows plain text
+pub fn strip_ansi(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\u{1b}' => {
+                if chars.next_if_eq(&'[').is_some() {
+                    // Parameters up to the final byte of a CSI sequence
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                } else {
+                    chars.next();
+                }
+            }
+            '\r' => {}
+            c => plain.push(c),
+        }
+    }
+    plain
+}
+
+// An attached stdin, stdout and stderr of a container's main process. Dropping it
+// detaches, the process keeps running.
+pub struct AttachSession {
+    pub name: String,
+    // Without a TTY the process does not echo what is typed
+    pub tty: bool,
+    pub open_stdin: bool,
+    pub output: String,
+    pub input: String,
+    pub ended: bool,
+    output_receiver: mpsc::UnboundedReceiver<String>,
+    input_sender: mpsc::UnboundedSender<Vec<u8>>,
+    detach_keys: Vec<u8>,
+}
+
+impl AttachSession {
+    pub fn send(&self, bytes: Vec<u8>) {
+        let _ = self.input_sender.send(bytes);
+    }
+
+    // Moves output that arrived since the last frame into `output`
+    pub fn receive(&mut self) {
+        loop {
+            match self.output_receiver.try_recv() {
+                Ok(text) => self.output.push_str(&text),
+                Err(mpsc::error::TryRecvError::Empty) => break,
+                Err(mpsc::error::TryRecvError::Disconnected) => {
+                    self.ended = true;
+                    break;
+                }
+            }
+        }
+        if self.output.len() > MAX_ATTACH_OUTPUT {
+            let mut start = self.output.len() - MAX_ATTACH_OUTPUT;
+            while !self.output.is_char_boundary(start) {
+                start += 1;
+            }
+            self.output.drain(..start);
+        }
+    }
+}
+
+impl Drop for AttachSession {
+    fn drop(&mut self) {
+        // The daemon ends the stream on the detach keys, the writer stops once they are out
+        let _ = self
+            .input_sender
+            .send(std::mem::take(&mut self.detach_keys));
+    }
+}
+
+pub async fn attach(
+    container_id: String,
+    name: String,
+    detach_keys: String,
+) -> Result<AttachSession, String> {
+    read_only::check()?;
+    let detach_bytes = detach_key_bytes(&detach_keys)?;
+    let docker = connection::connect()?;
+    let config = docker
+        .inspect_container(&container_id, None::<InspectContainerOptions>)
+        .await
+        .map_err(|e| format!("Failed to inspect container {}: {}", name, e))?
+        .config
+        .unwrap_or_default();
+    let options = AttachContainerOptions::<String> {
+        stdin: Some(true),
+        stdout: Some(true),
+        stderr: Some(true),
+        stream: Some(true),
+        logs: Some(false),
+        detach_keys: Some(detach_keys),
+    };
+    let attached = docker
+        .attach_container(&container_id, Some(options))
+        .await
+        .map_err(|e| format!("Failed to attach to {}: {}", name, e))?;
+
+    let (output_sender, output_receiver) = mpsc::unbounded_channel();
+    let (input_sender, mut input_receiver) = mpsc::unbounded_channel::<Vec<u8>>();
+    let mut output = attached.output;
+    let write_errors = output_sender.clone();
+    tokio::spawn(async move {
+        while let Some(chunk) = output.next().await {
+            let text = match chunk {
+                Ok(chunk) => strip_ansi(&String::from_utf8_lossy(&chunk.into_bytes())),
+                Err(e) => format!("\nattach ended: {}\n", e),
+            };
+            if output_sender.send(text).is_err() {
+                return;
+            }
+        }
+    });
+    let mut input = attached.input;
+    tokio::spawn(async move {
+        while let Some(bytes) = input_receiver.recv().await {
+            // Shown in the attach window with the output, where the typing happened
+            if let Err(e) = input.write_all(&bytes).await {
+                let _ = write_errors.send(format!("\nFailed to write to the container: {}\n", e));
+                return;
+            }
+            let _ = input.flush().await;
+        }
+    });
+
+    Ok(AttachSession {
+        name,
+        tty: config.tty.unwrap_or_default(),
+        open_stdin: config.open_stdin.unwrap_or_default(),
+        output: String::new(),
+        input: String::new(),
+        ended: false,
+        output_receiver,
+        input_sender,
+        detach_keys: detach_bytes,
+    })
+}
@@ -0,0 +1,119 @@
+use std::path::PathBuf;
+
+use tokio::io::AsyncWriteExt;
+use tokio::sync::mpsc;
+
+use crate::error::{report, DockerrsError, ErrorSender};
+use crate::time_format::format_timestamp;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuditKind {
+    // An action was sent to the daemon
+    Sent,
+    Succeeded,
+    Failed,
+}
+
+impl AuditKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            AuditKind::Sent => "sent",
+            AuditKind::Succeeded => "ok",
+            AuditKind::Failed => "failed",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AuditEntry {
+    // Unix seconds
+    pub at: i64,
+    pub kind: AuditKind,
+    pub message: String,
+}
+
+impl AuditEntry {
+    // One line of the audit log, e.g. "2024-05-01 12:00:00 sent    Stop web"
+    pub fn line(&self) -> String {
+        format!(
+            "{} {:<6} {}",
+            format_timestamp(self.at),
+            self.kind.label(),
+            self.message.replace('\n', " ")
+        )
+    }
+}
+
+// Everything dockerrs did this session: the actions it sent and the results that came back,
+// oldest first. With --audit-log every entry is appended to a file as well, by a task of its
+// own, so a slow or failing disk never holds up the window.
+#[derive(Default)]
+pub struct ActionHistory {
+    pub entries: Vec<AuditEntry>,
+    log: Option<mpsc::UnboundedSender<String>>,
+}
+
+impl ActionHistory {
+    // Appends to `path`, creating it if need be. Failing to write is reported once to `errors`.
+    pub fn with_log(path: PathBuf, errors: ErrorSender) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        tokio::spawn(write_log(path, receiver, errors));
+        Self {
+            entries: Vec::new(),
+            log: Some(sender),
+        }
+    }
+
+    pub fn sent(&mut self, message: String, at: i64) {
+        self.push(AuditKind::Sent, message, at);
+    }
+
+    pub fn result(&mut self, result: &Result<String, String>, at: i64) {
+        match result {
+            Ok(message) => self.push(AuditKind::Succeeded, message.clone(), at),
+            Err(message) => self.push(AuditKind::Failed, message.clone(), at),
+        }
+    }
+
+    fn push(&mut self, kind: AuditKind, message: String, at: i64) {
+        let entry = AuditEntry { at, kind, message };
+        if let Some(log) = &self.log {
+            let _ = log.send(entry.line());
+        }
+        self.entries.push(entry);
+    }
+}
+
+async fn write_log(path: PathBuf, mut lines: mpsc::UnboundedReceiver<String>, errors: ErrorSender) {
+    let context = format!("Failed to write the audit log {:?}", path);
+    let opened = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .await;
+    let mut file = match opened {
+        Ok(file) => file,
+        Err(source) => return report(&errors, DockerrsError::Io { context, source }),
+    };
+    let mut failed = false;
+    while let Some(line) = lines.recv().await {
+        let written = match file.write_all(format!("{}\n", line).as_bytes()).await {
+            Ok(()) => file.flush().await,
+            Err(e) => Err(e),
+        };
+        match written {
+            Ok(()) => failed = false,
+            Err(source) if !failed => {
+                failed = true;
+                report(
+                    &errors,
+                    DockerrsError::Io {
+                        context: context.clone(),
+                        source,
+                    },
+                );
+            }
+            Err(_) => {}
+        }
+    }
+}
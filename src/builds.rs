@@ -0,0 +1,152 @@
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use tokio::sync::{mpsc, oneshot};
+
+use crate::time_format::format_duration;
+
+// Finished and running builds kept per Dockerfile, older ones are forgotten
+pub const MAX_BUILDS_PER_FILE: usize = 5;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BuildStatus {
+    Running,
+    Succeeded,
+    Failed(String),
+}
+
+// One build of a Dockerfile with everything it printed
+pub struct BuildJob {
+    pub dockerfile: PathBuf,
+    pub tag: String,
+    pub started: Instant,
+    // Set once the build is done
+    pub duration: Option<Duration>,
+    pub status: BuildStatus,
+    pub output: Vec<String>,
+    output_receiver: mpsc::UnboundedReceiver<String>,
+    result_receiver: Option<oneshot::Receiver<Result<String, String>>>,
+}
+
+impl BuildJob {
+    // Runs `build` with a sender for its output lines
+    pub fn start<F, B>(dockerfile: PathBuf, tag: String, now: Instant, build: B) -> Self
+    where
+        B: FnOnce(mpsc::UnboundedSender<String>) -> F,
+        F: Future<Output = Result<String, String>> + Send + 'static,
+    {
+        let (output_sender, output_receiver) = mpsc::unbounded_channel();
+        let (result_sender, result_receiver) = oneshot::channel();
+        let task = build(output_sender);
+        tokio::spawn(async move {
+            let _ = result_sender.send(task.await);
+        });
+        Self {
+            dockerfile,
+            tag,
+            started: now,
+            duration: None,
+            status: BuildStatus::Running,
+            output: Vec::new(),
+            output_receiver,
+            result_receiver: Some(result_receiver),
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.status == BuildStatus::Running
+    }
+
+    // Time the build took, or has been running for
+    pub fn elapsed(&self, now: Instant) -> Duration {
+        self.duration
+            .unwrap_or_else(|| now.saturating_duration_since(self.started))
+    }
+
+    // E.g. "✓ api:latest 12s", running builds have no mark, a spinner goes with them
+    pub fn summary(&self, now: Instant) -> String {
+        let elapsed = format_duration(self.elapsed(now).as_secs() as i64);
+        match self.status {
+            BuildStatus::Running => format!("{} {}", self.tag, elapsed),
+            BuildStatus::Succeeded => format!("✓ {} {}", self.tag, elapsed),
+            BuildStatus::Failed(_) => format!("✗ {} {}", self.tag, elapsed),
+        }
+    }
+
+    // Collects output that arrived since the last call and returns it, along with the result
+    // once the build is done
+    pub fn receive(&mut self, now: Instant) -> (Vec<String>, Option<Result<String, String>>) {
+        let mut lines = Vec::new();
+        while let Ok(line) = self.output_receiver.try_recv() {
+            lines.push(line);
+        }
+        self.output.extend(lines.iter().cloned());
+        let Some(receiver) = &mut self.result_receiver else {
+            return (lines, None);
+        };
+        let result = match receiver.try_recv() {
+            Ok(result) => result,
+            Err(oneshot::error::TryRecvError::Empty) => return (lines, None),
+            Err(oneshot::error::TryRecvError::Closed) => {
+                Err(format!("Build of {} was cancelled", self.tag))
+            }
+        };
+        // Output sent right before the result is not lost
+        while let Ok(line) = self.output_receiver.try_recv() {
+            lines.push(line.clone());
+            self.output.push(line);
+        }
+        self.result_receiver = None;
+        self.duration = Some(now.saturating_duration_since(self.started));
+        self.status = match &result {
+            Ok(_) => BuildStatus::Succeeded,
+            Err(e) => BuildStatus::Failed(e.clone()),
+        };
+        (lines, Some(result))
+    }
+}
+
+// Recent builds per Dockerfile, newest first, only kept for the session
+#[derive(Default)]
+pub struct BuildHistory {
+    builds: HashMap<PathBuf, VecDeque<BuildJob>>,
+}
+
+impl BuildHistory {
+    pub fn push(&mut self, job: BuildJob) {
+        let jobs = self.builds.entry(job.dockerfile.clone()).or_default();
+        jobs.push_front(job);
+        jobs.truncate(MAX_BUILDS_PER_FILE);
+    }
+
+    pub fn jobs(&self, dockerfile: &Path) -> impl Iterator<Item = &BuildJob> {
+        self.builds.get(dockerfile).into_iter().flatten()
+    }
+
+    pub fn latest(&self, dockerfile: &Path) -> Option<&BuildJob> {
+        self.jobs(dockerfile).next()
+    }
+
+    // Builds are told apart by when they started
+    pub fn find(&self, dockerfile: &Path, started: Instant) -> Option<&BuildJob> {
+        self.jobs(dockerfile).find(|job| job.started == started)
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.builds.values().flatten().any(BuildJob::is_running)
+    }
+
+    // Output and results of every build since the last call
+    pub fn receive(&mut self, now: Instant) -> (Vec<String>, Vec<Result<String, String>>) {
+        let mut lines = Vec::new();
+        let mut results = Vec::new();
+        for job in self.builds.values_mut().flatten() {
+            let (output, result) = job.receive(now);
+            lines.extend(output);
+            results.extend(result);
+        }
+        (lines, results)
+    }
+}
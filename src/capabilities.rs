@@ -0,0 +1,102 @@
+use bollard::errors::Error;
+use bollard::system::Version;
+use eframe::egui;
+
+// Features that need a newer daemon API than the oldest engines we still see in the wild
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Feature {
+    ContainerStatsOneShot,
+    BuildCachePrune,
+    DiskUsageByType,
+    VolumePruneAll,
+}
+
+impl Feature {
+    pub fn min_api_version(self) -> (u32, u32) {
+        match self {
+            Feature::BuildCachePrune => (1, 39),
+            Feature::ContainerStatsOneShot => (1, 41),
+            Feature::DiskUsageByType => (1, 42),
+            Feature::VolumePruneAll => (1, 42),
+        }
+    }
+
+    pub fn requirement(self) -> String {
+        let (major, minor) = self.min_api_version();
+        format!("requires API ≥ {}.{}", major, minor)
+    }
+}
+
+// Parses API versions like "1.41", ignoring anything after the minor number
+pub fn parse_api_version(version: &str) -> Option<(u32, u32)> {
+    let mut parts = version.trim().trim_start_matches('v').split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts
+        .next()
+        .map(|minor| {
+            minor
+                .chars()
+                .take_while(char::is_ascii_digit)
+                .collect::<String>()
+        })
+        .unwrap_or_default()
+        .parse()
+        .ok()?;
+    Some((major, minor))
+}
+
+// The daemon version is negotiated once at connect time and cached here
+#[derive(Clone, Debug, Default)]
+pub struct Capabilities {
+    pub engine_version: Option<String>,
+    pub api_version: Option<(u32, u32)>,
+}
+
+impl Capabilities {
+    pub fn from_version(version: &Version) -> Self {
+        Self {
+            engine_version: version.version.clone(),
+            api_version: version.api_version.as_deref().and_then(parse_api_version),
+        }
+    }
+
+    // Until the version is known everything is assumed supported, the daemon has the last word
+    pub fn supports(&self, feature: Feature) -> bool {
+        self.api_version
+            .is_none_or(|version| version >= feature.min_api_version())
+    }
+
+    pub fn describe(&self) -> String {
+        match (&self.engine_version, self.api_version) {
+            (Some(engine), Some((major, minor))) => {
+                format!("Docker {} (API {}.{})", engine, major, minor)
+            }
+            (None, Some((major, minor))) => format!("Docker API {}.{}", major, minor),
+            _ => "Docker API unknown".to_string(),
+        }
+    }
+}
+
+// Gated endpoints answer 404 on older daemons, which bollard reports as a confusing error
+pub fn describe_error(feature: Feature, error: &Error) -> String {
+    match error {
+        Error::DockerResponseServerError {
+            status_code: 404, ..
+        } => format!("Not supported by this daemon ({})", feature.requirement()),
+        _ => error.to_string(),
+    }
+}
+
+// Buttons for gated features are rendered disabled with the requirement as hover text
+pub fn gated_button(
+    ui: &mut egui::Ui,
+    capabilities: &Capabilities,
+    feature: Feature,
+    text: &str,
+) -> egui::Response {
+    ui.add_enabled(capabilities.supports(feature), egui::Button::new(text))
+        .on_disabled_hover_text(format!(
+            "Not supported by this daemon, {}",
+            feature.requirement()
+        ))
+}
@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use clap::{Parser, Subcommand, ValueEnum};
+
+use crate::attach::DEFAULT_DETACH_KEYS;
+use crate::diagnostics::{self, CheckStatus};
+use crate::docker_api::{BollardDocker, DockerApi};
+use crate::graph;
+use crate::image_build::{BuildSettings, DEFAULT_MAX_CONTEXT_FILE_MB};
+use crate::label_filter::LabelFilter;
+use crate::log_stream::DEFAULT_MAX_LOG_LINES;
+use crate::ps;
+use crate::scan::{ScanSettings, DEFAULT_EXCLUDES};
+use crate::stats::DEFAULT_STATS_SAMPLES;
+use crate::theme::ThemeName;
+
+#[derive(Parser)]
+#[command(name = "dockerrs", about = "Show us your dockers!")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// Directory names to skip when scanning for compose files and Dockerfiles
+    #[arg(long, global = true, default_values = DEFAULT_EXCLUDES)]
+    pub exclude: Vec<String>,
+    /// Directory to scan for compose files and Dockerfiles, can be repeated
+    #[arg(long = "scan-dir", global = true)]
+    pub scan_dirs: Vec<PathBuf>,
+    /// How many directory levels below each scan root to descend into
+    #[arg(long, global = true)]
+    pub max_depth: Option<usize>,
+    /// Build images with the docker CLI instead of the daemon API
+    #[arg(long, global = true)]
+    pub cli_build: bool,
+    /// Files larger than this many MiB are left out of the build context
+    #[arg(long, global = true, default_value_t = DEFAULT_MAX_CONTEXT_FILE_MB)]
+    pub max_context_file_mb: u64,
+    /// Log lines kept in memory per log window before the oldest are dropped
+    #[arg(long, global = true, default_value_t = DEFAULT_MAX_LOG_LINES)]
+    pub max_log_lines: usize,
+    /// Keys that detach from an attached container, like `docker attach --detach-keys`
+    #[arg(long, global = true, default_value = DEFAULT_DETACH_KEYS)]
+    pub detach_keys: String,
+    /// CPU and memory samples kept per container for the details sparklines
+    #[arg(long, global = true, default_value_t = DEFAULT_STATS_SAMPLES)]
+    pub stats_samples: usize,
+    /// Show everything but refuse every change to containers, images, networks and volumes
+    #[arg(long, global = true)]
+    pub read_only: bool,
+    /// Config file with saved filters, defaults to ~/.config/dockerrs/config.yaml
+    #[arg(long, global = true)]
+    pub config: Option<PathBuf>,
+    /// Colors to draw with, monochrome by default when NO_COLOR is set
+    #[arg(long, global = true, value_enum)]
+    pub theme: Option<ThemeName>,
+    /// Open on the logs of this container, by name or ID prefix, following new output
+    #[arg(long, value_name = "CONTAINER")]
+    pub follow: Option<String>,
+    /// Check once the window opens whether the images of running containers have newer versions
+    /// in their registry
+    #[arg(long)]
+    pub check_updates: bool,
+    /// Append every action sent to the daemon and its result to this file
+    #[arg(long, value_name = "PATH")]
+    pub audit_log: Option<PathBuf>,
+    /// Announce container state changes as notifications, which screen readers read out
+    #[arg(long)]
+    pub screen_reader: bool,
+}
+
+impl Cli {
+    // Falls back to the current working directory when no roots were given
+    pub fn scan_settings(&self) -> ScanSettings {
+        let roots = if self.scan_dirs.is_empty() {
+            vec![std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."))]
+        } else {
+            self.scan_dirs.clone()
+        };
+        ScanSettings {
+            roots,
+            excludes: self.exclude.clone(),
+            max_depth: self.max_depth,
+        }
+    }
+
+    pub fn build_settings(&self) -> BuildSettings {
+        BuildSettings {
+            use_cli: self.cli_build,
+            max_context_file_size: self.max_context_file_mb * 1024 * 1024,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Export which containers sit on which networks as a Graphviz DOT file
+    ExportGraph {
+        #[arg(short, long, default_value = graph::DEFAULT_OUTPUT)]
+        output: PathBuf,
+        /// Also render an SVG next to the DOT file, with graphviz's `dot` binary when installed
+        #[arg(long)]
+        svg: bool,
+    },
+    /// Check the Docker setup and print what to attach to a bug report
+    Doctor {
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+    /// List all containers with their state, health, compose project and ports
+    Ps {
+        /// Print JSON instead of a table
+        #[arg(long)]
+        json: bool,
+        /// Only containers matching a label expression, e.g. env=staging,tier!=db
+        #[arg(long)]
+        filter: Option<String>,
+    },
+    /// Print the logs of a container by name or ID prefix
+    Logs {
+        container: String,
+        #[arg(long, default_value_t = 100)]
+        tail: usize,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+// `config` is the config file the window would use, for the doctor's checks
+pub async fn run(command: Command, config: Option<PathBuf>) -> Result<(), String> {
+    match command {
+        Command::ExportGraph { output, svg } => {
+            println!("{}", export_graph(&output, svg).await?);
+            Ok(())
+        }
+        Command::Doctor { format } => {
+            let results = diagnostics::run_checks(config).await;
+            match format {
+                OutputFormat::Text => println!("{}", diagnostics::format_text(&results)),
+                OutputFormat::Json => println!(
+                    "{}",
+                    serde_json::to_string_pretty(&results).map_err(|e| e.to_string())?
+                ),
+            }
+            if results
+                .iter()
+                .any(|result| result.status == CheckStatus::Fail)
+            {
+                return Err("Some checks failed".to_string());
+            }
+            Ok(())
+        }
+        Command::Ps { json, filter } => {
+            let filter = LabelFilter::parse(filter.as_deref().unwrap_or_default())?;
+            let rows = ps::list_containers(&BollardDocker::connect()?, &filter).await?;
+            if json {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&rows).map_err(|e| e.to_string())?
+                );
+            } else {
+                println!("{}", ps::format_table(&rows));
+            }
+            Ok(())
+        }
+        Command::Logs { container, tail } => {
+            let logs = ps::container_logs(&BollardDocker::connect()?, &container, tail).await?;
+            print!("{}", logs);
+            Ok(())
+        }
+    }
+}
+
+pub async fn export_graph(output: &Path, svg: bool) -> Result<String, String> {
+    let docker = BollardDocker::connect()?;
+    let networks = docker
+        .list_networks()
+        .await
+        .map_err(|e| format!("Failed to list networks: {}", e))?;
+    let containers = docker
+        .list_containers(true, HashMap::new())
+        .await
+        .map_err(|e| format!("Failed to list containers: {}", e))?;
+    graph::export(output, &networks, &containers, svg).await
+}
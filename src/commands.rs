@@ -0,0 +1,414 @@
+use eframe::egui::{Key, Modifiers};
+
+use crate::docker_api::{ContainerAction, RemoveOptions};
+use crate::docker_viewer_app::AppView;
+use crate::signals::SIGKILL;
+
+// Everything the keys and the command palette can do. Both go through
+// `DockerViewerApp::run_command`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Command {
+    Palette,
+    ShowView(AppView),
+    DiskUsage,
+    Alerts,
+    SystemInfo,
+    ActionHistory,
+    NotificationHistory,
+    Diagnostics,
+    RemoveDanglingImages,
+    CleanUpUnusedImages,
+    PruneImages,
+    // Takes a container name or ID prefix
+    FollowLogs,
+    // Takes a label expression
+    FilterByLabels,
+    // On every container in the bulk scope, after a confirmation
+    Bulk(ContainerAction),
+    KeepAlive,
+    SwitchLabelFilter,
+    ChooseColumns,
+    CycleSort,
+    SortByUsage,
+    TopConsumers,
+    CheckImageUpdates,
+    StartOrStop,
+    Remove,
+    Recreate,
+    CycleLogLevel,
+    ChangedFiles,
+    Watch,
+    LogTimeRange,
+    OpenNetwork,
+    OpenImage,
+    WrapLogs,
+    SplitView,
+    SelectLogLines,
+    CopyLogLines,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Binding {
+    Key(Modifiers, Key),
+    // Characters that need Shift on some layouts and not on others, matched as typed text
+    Text(&'static str),
+}
+
+impl Binding {
+    pub fn label(self) -> String {
+        match self {
+            Binding::Key(modifiers, key) => {
+                let mut label = String::new();
+                if modifiers.command {
+                    label.push_str("Ctrl+");
+                }
+                if modifiers.shift {
+                    label.push_str("Shift+");
+                }
+                label.push_str(key.symbol_or_name());
+                label
+            }
+            Binding::Text(text) => text.to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Scope {
+    Everywhere,
+    // The keys only work in the containers view, the palette runs it from anywhere
+    Containers,
+}
+
+pub struct CommandInfo {
+    pub command: Command,
+    pub name: &'static str,
+    pub bindings: &'static [Binding],
+    pub scope: Scope,
+    // What to ask for before running, for commands that take an argument
+    pub prompt: Option<&'static str>,
+}
+
+const fn command(
+    command: Command,
+    name: &'static str,
+    bindings: &'static [Binding],
+    scope: Scope,
+) -> CommandInfo {
+    CommandInfo {
+        command,
+        name,
+        bindings,
+        scope,
+        prompt: None,
+    }
+}
+
+const fn with_argument(command: Command, name: &'static str, prompt: &'static str) -> CommandInfo {
+    CommandInfo {
+        command,
+        name,
+        bindings: &[],
+        scope: Scope::Everywhere,
+        prompt: Some(prompt),
+    }
+}
+
+const CTRL: Modifiers = Modifiers::COMMAND;
+const SHIFT: Modifiers = Modifiers::SHIFT;
+const NONE: Modifiers = Modifiers::NONE;
+
+// Keys are matched in this order and extra Shift is ignored, so a Shift binding has to come
+// before the same key without it
+pub const COMMANDS: &[CommandInfo] = &[
+    command(
+        Command::Palette,
+        "Command palette",
+        &[Binding::Key(CTRL, Key::P), Binding::Text(":")],
+        Scope::Everywhere,
+    ),
+    command(
+        Command::ShowView(AppView::Containers),
+        "Switch to containers",
+        &[Binding::Key(CTRL, Key::Num1)],
+        Scope::Everywhere,
+    ),
+    command(
+        Command::ShowView(AppView::Composes),
+        "Switch to composes",
+        &[Binding::Key(CTRL, Key::Num2)],
+        Scope::Everywhere,
+    ),
+    command(
+        Command::ShowView(AppView::Dockerfiles),
+        "Switch to dockerfiles",
+        &[Binding::Key(CTRL, Key::Num3)],
+        Scope::Everywhere,
+    ),
+    command(
+        Command::ShowView(AppView::Images),
+        "Switch to images",
+        &[Binding::Key(CTRL, Key::Num4)],
+        Scope::Everywhere,
+    ),
+    command(
+        Command::ShowView(AppView::Networks),
+        "Switch to networks",
+        &[Binding::Key(CTRL, Key::Num5)],
+        Scope::Everywhere,
+    ),
+    command(
+        Command::ShowView(AppView::Volumes),
+        "Switch to volumes",
+        &[Binding::Key(CTRL, Key::Num6)],
+        Scope::Everywhere,
+    ),
+    command(
+        Command::DiskUsage,
+        "Disk usage",
+        &[Binding::Key(CTRL, Key::D)],
+        Scope::Everywhere,
+    ),
+    command(
+        Command::Alerts,
+        "Show alerts",
+        &[Binding::Key(SHIFT, Key::A)],
+        Scope::Everywhere,
+    ),
+    command(
+        Command::SystemInfo,
+        "System info",
+        &[Binding::Text("!")],
+        Scope::Everywhere,
+    ),
+    command(
+        Command::ActionHistory,
+        "Action history",
+        &[Binding::Key(NONE, Key::H)],
+        Scope::Everywhere,
+    ),
+    command(
+        Command::NotificationHistory,
+        "Notification history",
+        &[],
+        Scope::Everywhere,
+    ),
+    command(Command::Diagnostics, "Diagnostics", &[], Scope::Everywhere),
+    command(
+        Command::RemoveDanglingImages,
+        "Remove dangling images",
+        &[],
+        Scope::Everywhere,
+    ),
+    command(
+        Command::CleanUpUnusedImages,
+        "Clean up unused images",
+        &[],
+        Scope::Everywhere,
+    ),
+    command(Command::PruneImages, "Prune images", &[], Scope::Everywhere),
+    with_argument(
+        Command::FollowLogs,
+        "Open logs for…",
+        "Container name or ID prefix",
+    ),
+    with_argument(
+        Command::FilterByLabels,
+        "Filter containers by labels…",
+        "Label expression, like env=prod,!test",
+    ),
+    command(
+        Command::Bulk(ContainerAction::Stop),
+        "Stop all containers in scope",
+        &[Binding::Key(SHIFT, Key::S)],
+        Scope::Containers,
+    ),
+    command(
+        Command::Bulk(ContainerAction::Kill(SIGKILL)),
+        "Kill all containers in scope",
+        &[Binding::Key(SHIFT, Key::X)],
+        Scope::Containers,
+    ),
+    command(
+        Command::Bulk(ContainerAction::Remove(RemoveOptions::FORCE)),
+        "Remove all containers in scope",
+        &[Binding::Key(CTRL, Key::R)],
+        Scope::Containers,
+    ),
+    command(
+        Command::KeepAlive,
+        "Keep selected container alive",
+        &[Binding::Key(SHIFT, Key::K)],
+        Scope::Containers,
+    ),
+    command(
+        Command::SwitchLabelFilter,
+        "Switch saved label filter",
+        &[Binding::Key(NONE, Key::F)],
+        Scope::Containers,
+    ),
+    command(
+        Command::SelectLogLines,
+        "Select log lines",
+        &[Binding::Key(SHIFT, Key::V)],
+        Scope::Containers,
+    ),
+    command(
+        Command::CopyLogLines,
+        "Copy selected log lines",
+        &[Binding::Key(NONE, Key::Y)],
+        Scope::Containers,
+    ),
+    command(
+        Command::ChooseColumns,
+        "Choose columns",
+        &[Binding::Key(NONE, Key::V)],
+        Scope::Containers,
+    ),
+    command(
+        Command::CycleSort,
+        "Cycle sort order",
+        &[Binding::Key(NONE, Key::O)],
+        Scope::Containers,
+    ),
+    command(
+        Command::SortByUsage,
+        "Sort by CPU, again for memory",
+        &[Binding::Text("%")],
+        Scope::Containers,
+    ),
+    command(
+        Command::CheckImageUpdates,
+        "Check images for updates",
+        &[Binding::Key(NONE, Key::U)],
+        Scope::Containers,
+    ),
+    command(
+        Command::StartOrStop,
+        "Start or stop selected container",
+        &[Binding::Key(NONE, Key::Enter)],
+        Scope::Containers,
+    ),
+    command(
+        Command::Remove,
+        "Remove selected container",
+        &[Binding::Key(NONE, Key::Delete)],
+        Scope::Containers,
+    ),
+    command(
+        Command::Recreate,
+        "Recreate selected container",
+        &[],
+        Scope::Containers,
+    ),
+    command(
+        Command::CycleLogLevel,
+        "Cycle log level filter",
+        &[Binding::Key(NONE, Key::L)],
+        Scope::Containers,
+    ),
+    command(
+        Command::ChangedFiles,
+        "Show changed files of selected container",
+        &[Binding::Key(NONE, Key::D)],
+        Scope::Containers,
+    ),
+    command(
+        Command::Watch,
+        "Watch selected container for crashes",
+        &[Binding::Key(NONE, Key::A)],
+        Scope::Containers,
+    ),
+    command(
+        Command::TopConsumers,
+        "Top CPU and memory consumers",
+        &[Binding::Key(SHIFT, Key::T)],
+        Scope::Everywhere,
+    ),
+    command(
+        Command::LogTimeRange,
+        "Logs for a time range",
+        &[Binding::Key(NONE, Key::T)],
+        Scope::Containers,
+    ),
+    command(
+        Command::OpenNetwork,
+        "Open network of selected container",
+        &[Binding::Key(NONE, Key::N)],
+        Scope::Containers,
+    ),
+    command(
+        Command::OpenImage,
+        "Open image of selected container",
+        &[Binding::Key(NONE, Key::I)],
+        Scope::Containers,
+    ),
+    command(
+        Command::WrapLogs,
+        "Wrap log lines",
+        &[Binding::Key(NONE, Key::W)],
+        Scope::Containers,
+    ),
+    command(
+        Command::SplitView,
+        "Split list and logs",
+        &[Binding::Text("|")],
+        Scope::Containers,
+    ),
+];
+
+pub fn info(command: Command) -> Option<&'static CommandInfo> {
+    COMMANDS.iter().find(|info| info.command == command)
+}
+
+// How well `query` matches `candidate`, None when its characters do not all appear in order.
+// Case is ignored. Runs of consecutive characters and matches at the start of a word score
+// higher, gaps cost a little, so "sw net" finds "Switch to networks" before "Show alerts".
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i32> {
+    let candidate: Vec<char> = candidate.chars().flat_map(char::to_lowercase).collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous: Option<usize> = None;
+    for wanted in query
+        .chars()
+        .flat_map(char::to_lowercase)
+        .filter(|c| !c.is_whitespace())
+    {
+        let found = position + candidate[position..].iter().position(|c| *c == wanted)?;
+        score += 1;
+        let word_start = found == 0 || !candidate[found - 1].is_alphanumeric();
+        if word_start {
+            score += 8;
+        }
+        match previous {
+            Some(previous) if found == previous + 1 => score += 5,
+            Some(previous) => score -= (found - previous - 1).min(5) as i32,
+            None => score -= found.min(5) as i32,
+        }
+        previous = Some(found);
+        position = found + 1;
+    }
+    Some(score)
+}
+
+// Commands the palette offers for `query`, best match first and in registry order when
+// matched equally well
+pub fn matching_commands(query: &str) -> Vec<&'static CommandInfo> {
+    let mut matches: Vec<(i32, usize, &CommandInfo)> = COMMANDS
+        .iter()
+        .enumerate()
+        .filter(|(_, info)| info.command != Command::Palette)
+        .filter_map(|(index, info)| Some((fuzzy_score(query, info.name)?, index, info)))
+        .collect();
+    matches.sort_by_key(|(score, index, _)| (-score, *index));
+    matches.into_iter().map(|(_, _, info)| info).collect()
+}
+
+// What the palette window is showing: the list of commands, or the prompt of the one picked
+#[derive(Default)]
+pub struct CommandPalette {
+    pub query: String,
+    pub selected: usize,
+    // The command whose argument is being typed into `query`
+    pub prompting: Option<Command>,
+}
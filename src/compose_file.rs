@@ -0,0 +1,300 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use serde_yaml::Value;
+use tokio::process::Command;
+
+// Lightweight model of the parts of a compose file dockerrs cares about
+#[derive(Debug, Default, Deserialize)]
+pub struct ComposeFile {
+    #[serde(default)]
+    pub services: BTreeMap<String, Service>,
+    #[serde(default)]
+    pub networks: BTreeMap<String, Option<NetworkDefinition>>,
+    #[serde(default)]
+    pub volumes: BTreeMap<String, Option<Value>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Service {
+    pub image: Option<String>,
+    // Either a list of names or a map of per-network settings, only presence matters for now
+    pub networks: Option<Value>,
+    // A context path or a map with `context` and `dockerfile`
+    pub build: Option<Value>,
+    // Short "8080:80" strings, bare container ports or long-syntax maps
+    #[serde(default)]
+    pub ports: Vec<Value>,
+    // A list of service names or a map of them to conditions
+    pub depends_on: Option<Value>,
+}
+
+impl Service {
+    // The build context, e.g. "./api"
+    pub fn build_context(&self) -> Option<String> {
+        match self.build.as_ref()? {
+            Value::String(context) => Some(context.clone()),
+            Value::Mapping(build) => Some(
+                build
+                    .get("context")
+                    .and_then(Value::as_str)
+                    .unwrap_or(".")
+                    .to_string(),
+            ),
+            _ => None,
+        }
+    }
+
+    // Ports published on the host as "host:container", container-only ports are left out
+    pub fn published_ports(&self) -> Vec<String> {
+        self.ports
+            .iter()
+            .filter_map(|port| match port {
+                Value::String(port) if port.contains(':') => Some(port.clone()),
+                Value::Mapping(port) => {
+                    let text = |key: &str| match port.get(key)? {
+                        Value::String(value) => Some(value.clone()),
+                        Value::Number(value) => Some(value.to_string()),
+                        _ => None,
+                    };
+                    let published = text("published")?;
+                    let target = text("target")?;
+                    Some(match text("protocol") {
+                        Some(protocol) => format!("{}:{}/{}", published, target, protocol),
+                        None => format!("{}:{}", published, target),
+                    })
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    // Every host port the service binds, with port ranges expanded. Ports left for the daemon
+    // to pick, like "9000" or "127.0.0.1::80", are left out.
+    pub fn host_ports(&self) -> Vec<HostPort> {
+        self.ports
+            .iter()
+            .flat_map(|port| match port {
+                Value::String(port) => short_host_ports(port),
+                Value::Mapping(port) => {
+                    let text = |key: &str| match port.get(key)? {
+                        Value::String(value) => Some(value.clone()),
+                        Value::Number(value) => Some(value.to_string()),
+                        _ => None,
+                    };
+                    let protocol = text("protocol").unwrap_or_else(|| "tcp".to_string());
+                    host_port_range(text("host_ip"), text("published").as_deref(), &protocol)
+                }
+                _ => Vec::new(),
+            })
+            .collect()
+    }
+
+    pub fn depends_on(&self) -> Vec<String> {
+        match &self.depends_on {
+            Some(Value::Sequence(services)) => services
+                .iter()
+                .filter_map(|service| service.as_str().map(String::from))
+                .collect(),
+            Some(Value::Mapping(services)) => services
+                .keys()
+                .filter_map(|service| service.as_str().map(String::from))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+// A port a service publishes on the host
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HostPort {
+    // None when bound on every address
+    pub host_ip: Option<String>,
+    pub port: u16,
+    pub protocol: String,
+}
+
+impl fmt::Display for HostPort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.host_ip {
+            Some(ip) if ip.contains(':') => write!(f, "[{}]:{}/{}", ip, self.port, self.protocol),
+            Some(ip) => write!(f, "{}:{}/{}", ip, self.port, self.protocol),
+            None => write!(f, "{}/{}", self.port, self.protocol),
+        }
+    }
+}
+
+// "[HOST_IP:]HOST_PORT:CONTAINER_PORT[/PROTOCOL]", where the ports may be ranges like 8000-8005
+fn short_host_ports(port: &str) -> Vec<HostPort> {
+    let (mapping, protocol) = port.rsplit_once('/').unwrap_or((port, "tcp"));
+    let Some((host, _container)) = mapping.rsplit_once(':') else {
+        return Vec::new();
+    };
+    let (host_ip, published) = match host.rsplit_once(':') {
+        Some((ip, published)) => (Some(ip.trim_matches(['[', ']']).to_string()), published),
+        None => (None, host),
+    };
+    host_port_range(host_ip, Some(published), protocol)
+}
+
+fn host_port_range(
+    host_ip: Option<String>,
+    published: Option<&str>,
+    protocol: &str,
+) -> Vec<HostPort> {
+    let Some(published) = published.filter(|published| !published.is_empty()) else {
+        return Vec::new();
+    };
+    let (first, last) = published.split_once('-').unwrap_or((published, published));
+    let (Ok(first), Ok(last)) = (first.trim().parse::<u16>(), last.trim().parse::<u16>()) else {
+        return Vec::new();
+    };
+    (first..=last)
+        .map(|port| HostPort {
+            host_ip: host_ip.clone().filter(|ip| !ip.is_empty()),
+            port,
+            protocol: protocol.to_string(),
+        })
+        .collect()
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct NetworkDefinition {
+    pub name: Option<String>,
+    // `true` or the legacy `{ name: ... }` form
+    pub external: Option<Value>,
+}
+
+impl NetworkDefinition {
+    pub fn is_external(&self) -> bool {
+        match &self.external {
+            Some(Value::Bool(external)) => *external,
+            Some(Value::Null) | None => false,
+            Some(_) => true,
+        }
+    }
+}
+
+impl ComposeFile {
+    pub fn parse(content: &str) -> Result<Self, String> {
+        serde_yaml::from_str(content).map_err(|e| e.to_string())
+    }
+
+    // Later files override earlier ones, like `docker compose -f a.yaml -f b.yaml`
+    pub fn load(paths: &[PathBuf], working_dir: &Path) -> Result<Self, String> {
+        let mut merged = ComposeFile::default();
+        for path in paths {
+            let path = working_dir.join(path);
+            let content = std::fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+            let file = Self::parse(&content).map_err(|e| format!("{:?}: {}", path, e))?;
+            merged.services.extend(file.services);
+            merged.networks.extend(file.networks);
+            merged.volumes.extend(file.volumes);
+        }
+        Ok(merged)
+    }
+
+    // Services in the order compose starts them, dependencies first and ties by name.
+    // Fails on a dependency cycle or a dependency on a service that is not defined.
+    pub fn start_order(&self) -> Result<Vec<String>, String> {
+        for (name, service) in &self.services {
+            if let Some(missing) = service
+                .depends_on()
+                .into_iter()
+                .find(|dependency| !self.services.contains_key(dependency))
+            {
+                return Err(format!("{} depends on undefined service {}", name, missing));
+            }
+        }
+        let mut order = Vec::new();
+        let mut started = BTreeSet::new();
+        while order.len() < self.services.len() {
+            let next = self.services.iter().find(|(name, service)| {
+                !started.contains(*name)
+                    && service
+                        .depends_on()
+                        .iter()
+                        .all(|dependency| started.contains(dependency))
+            });
+            let Some((name, _)) = next else {
+                let waiting: Vec<&str> = self
+                    .services
+                    .keys()
+                    .filter(|name| !started.contains(*name))
+                    .map(String::as_str)
+                    .collect();
+                return Err(format!("Dependency cycle between {}", waiting.join(", ")));
+            };
+            started.insert(name.clone());
+            order.push(name.clone());
+        }
+        Ok(order)
+    }
+
+    // Networks compose creates for the project, as (key in the file, name on the daemon)
+    pub fn project_networks(&self, project: &str) -> Vec<(String, String)> {
+        let mut networks: Vec<(String, String)> = self
+            .networks
+            .iter()
+            .filter(|(_, definition)| !definition.as_ref().is_some_and(|d| d.is_external()))
+            .map(|(key, definition)| {
+                let name = definition
+                    .as_ref()
+                    .and_then(|definition| definition.name.clone())
+                    .unwrap_or_else(|| format!("{}_{}", project, key));
+                (key.clone(), name)
+            })
+            .collect();
+
+        // Services without explicit networks are attached to the implicit default network
+        if !self.networks.contains_key("default")
+            && self
+                .services
+                .values()
+                .any(|service| service.networks.is_none())
+        {
+            networks.push(("default".to_string(), format!("{}_default", project)));
+        }
+        networks
+    }
+}
+
+// Line numbers mentioned in a YAML or compose error, like "at line 12 column 3"
+pub fn error_lines(message: &str) -> Vec<usize> {
+    let mut lines: Vec<usize> = message
+        .match_indices("line ")
+        .filter_map(|(index, prefix)| {
+            let rest = &message[index + prefix.len()..];
+            let digits = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            rest[..digits].parse().ok()
+        })
+        .collect();
+    lines.sort_unstable();
+    lines.dedup();
+    lines
+}
+
+// Runs `docker compose config --quiet`, which also checks what serde_yaml does not, such as
+// variable interpolation and the schema
+pub async fn validate(path: &Path) -> Result<(), String> {
+    let directory = path.parent().unwrap_or(Path::new("."));
+    let output = Command::new("docker")
+        .arg("compose")
+        .arg("-f")
+        .arg(path)
+        .args(["config", "--quiet"])
+        .current_dir(directory)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run docker compose config: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
@@ -0,0 +1,161 @@
+use std::process::Stdio;
+
+use tokio::process::Child;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::attach::strip_ansi;
+use crate::utils::{compose_command, forward_lines, ComposeProject};
+
+// Lines of each service compose starts with, like `docker compose logs --tail 200`
+pub const COMPOSE_LOGS_TAIL: usize = 200;
+
+// Name shown for what compose says itself, such as its errors
+pub const COMPOSE_NAME: &str = "compose";
+
+// What `docker` prints when the Compose plugin is not installed
+const MISSING_PLUGIN: &str = "'compose' is not a docker command";
+
+// A line of `docker compose logs` split into the service prefix and the message
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ComposeLine {
+    // The service and replica, e.g. `web-1`, None for lines of compose itself
+    pub service: Option<String>,
+    // The ANSI color compose gave the prefix, 0 to 7 for black to white
+    pub color: Option<usize>,
+    pub message: String,
+}
+
+// Parses `web-1  | message`, where `--ansi always` wraps the prefix in a color escape.
+// Escapes in the message are dropped, the logs window shows plain text.
+pub fn parse_line(line: &str) -> ComposeLine {
+    let plain = strip_ansi(line);
+    let prefix = plain
+        .split_once('|')
+        .map(|(prefix, message)| (prefix.trim(), message))
+        .filter(|(prefix, _)| !prefix.is_empty() && !prefix.contains(char::is_whitespace));
+    match prefix {
+        Some((service, message)) => ComposeLine {
+            service: Some(service.to_string()),
+            color: prefix_color(line),
+            message: message.strip_prefix(' ').unwrap_or(message).to_string(),
+        },
+        None => ComposeLine {
+            service: None,
+            color: None,
+            message: explain(&plain),
+        },
+    }
+}
+
+// The foreground color of an escape at the start of the line, normal or bright
+fn prefix_color(line: &str) -> Option<usize> {
+    let parameters = line.strip_prefix("\u{1b}[")?;
+    let (parameters, _) = parameters.split_once('m')?;
+    parameters
+        .rsplit(';')
+        .filter_map(|parameter| parameter.parse::<usize>().ok())
+        .filter_map(|code| match code {
+            30..=37 => Some(code - 30),
+            90..=97 => Some(code - 90),
+            _ => None,
+        })
+        .next()
+}
+
+// Compose's own messages, with what to do about a missing Compose plugin
+pub fn explain(message: &str) -> String {
+    if message.contains(MISSING_PLUGIN) {
+        "docker compose is not available, install the Docker Compose plugin \
+         (https://docs.docker.com/compose/install/)"
+            .to_string()
+    } else {
+        message.to_string()
+    }
+}
+
+// A running `docker compose logs -f` of a project. Dropping it kills the process.
+pub struct ComposeLogs {
+    child: Child,
+    pub output: mpsc::UnboundedReceiver<String>,
+    readers: Vec<JoinHandle<()>>,
+    // Whether the exit of the process was reported
+    exited: bool,
+}
+
+impl ComposeLogs {
+    // Runs `docker compose logs -f` from the project's directory, so it finds the project
+    // even when no container carries the labels of every service
+    pub fn spawn(project: &ComposeProject) -> Result<Self, String> {
+        if !project.working_dir.is_dir() {
+            return Err(format!(
+                "Working directory {:?} of project {} no longer exists",
+                project.working_dir, project.name
+            ));
+        }
+        let mut command = compose_command(
+            &project.working_dir,
+            Some(&project.name),
+            &project.config_files,
+        );
+        command
+            .args(["--ansi", "always", "logs", "-f", "--tail"])
+            .arg(COMPOSE_LOGS_TAIL.to_string())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        let mut child = command.spawn().map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => {
+                "docker was not found on PATH, docker compose logs needs the Docker CLI with \
+                 the Compose plugin"
+                    .to_string()
+            }
+            _ => format!("Failed to execute docker compose logs: {}", e),
+        })?;
+        let (sender, output) = mpsc::unbounded_channel();
+        let readers = [
+            child
+                .stdout
+                .take()
+                .map(|stdout| forward_lines(stdout, sender.clone())),
+            child
+                .stderr
+                .take()
+                .map(|stderr| forward_lines(stderr, sender)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        Ok(Self {
+            child,
+            output,
+            readers,
+            exited: false,
+        })
+    }
+
+    // How the process ended, once, when it failed. Waits for the rest of its output, which
+    // says why.
+    pub fn failure(&mut self) -> Option<String> {
+        if self.exited || self.is_streaming() {
+            return None;
+        }
+        let status = self.child.try_wait().ok().flatten()?;
+        self.exited = true;
+        (!status.success()).then(|| format!("docker compose logs exited with {}", status))
+    }
+
+    pub fn is_streaming(&self) -> bool {
+        self.readers.iter().any(|reader| !reader.is_finished())
+    }
+}
+
+impl Drop for ComposeLogs {
+    fn drop(&mut self) {
+        // kill_on_drop does the same, this does not rely on it
+        let _ = self.child.start_kill();
+        for reader in &self.readers {
+            reader.abort();
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::alerts::AlertSettings;
+use crate::layout::{RowColumn, ALL_COLUMNS, DEFAULT_COLUMN_PRIORITY};
+use crate::theme::ThemeName;
+use crate::unused_images::DEFAULT_KEEP_DAYS;
+
+// Tags remembered per Dockerfile for the build form
+pub const MAX_RECENT_TAGS: usize = 10;
+
+// A label filter kept under a name, see `label_filter`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedFilter {
+    pub name: String,
+    pub expression: String,
+}
+
+// Settings kept between sessions, read from config.yaml at startup
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub saved_filters: Vec<SavedFilter>,
+    pub alerts: AlertSettings,
+    // Names of the containers restarted when they crash
+    pub keep_alive: Vec<String>,
+    // Image tags used to build each Dockerfile, keyed by its path, most recent first
+    pub build_tags: BTreeMap<PathBuf, Vec<String>>,
+    // Overridden by --theme, unset follows NO_COLOR
+    pub theme: Option<ThemeName>,
+    // Container row columns from most to least important, empty is the default order
+    pub column_priority: Vec<RowColumn>,
+    // Container row columns picked in the column chooser, unset is all but the created time
+    pub shown_columns: Option<BTreeSet<RowColumn>>,
+    // Unused image tags younger than this many days are kept by the cleanup
+    pub keep_unused_images_days: Option<u64>,
+    // Like --read-only, for machines where changes should never be made by accident
+    pub read_only: bool,
+}
+
+// $XDG_CONFIG_HOME/dockerrs/config.yaml, or ~/.config/dockerrs/config.yaml
+pub fn default_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join("dockerrs").join("config.yaml"))
+}
+
+impl Config {
+    // A file that does not exist yet is an empty config
+    pub fn load(path: &Path) -> Result<Self, String> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => serde_yaml::from_str(&text)
+                .map_err(|e| format!("Failed to parse {:?}: {}", path, e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("Failed to read {:?}: {}", path, e)),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {:?}: {}", dir, e))?;
+        }
+        let text = serde_yaml::to_string(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, text).map_err(|e| format!("Failed to write {:?}: {}", path, e))
+    }
+
+    // Columns left out of `column_priority` come after the listed ones, in the default order
+    pub fn column_priority(&self) -> Vec<RowColumn> {
+        let mut priority = self.column_priority.clone();
+        for column in DEFAULT_COLUMN_PRIORITY {
+            if !priority.contains(&column) {
+                priority.push(column);
+            }
+        }
+        priority
+    }
+
+    pub fn keep_unused_images_days(&self) -> u64 {
+        self.keep_unused_images_days.unwrap_or(DEFAULT_KEEP_DAYS)
+    }
+
+    pub fn shown_columns(&self) -> BTreeSet<RowColumn> {
+        match &self.shown_columns {
+            Some(shown) => shown.clone(),
+            None => ALL_COLUMNS
+                .into_iter()
+                .filter(|column| column.shown_by_default())
+                .collect(),
+        }
+    }
+
+    pub fn toggle_column(&mut self, column: RowColumn) {
+        let mut shown = self.shown_columns();
+        if !shown.remove(&column) {
+            shown.insert(column);
+        }
+        self.shown_columns = Some(shown);
+    }
+
+    pub fn recent_tags(&self, dockerfile: &Path) -> &[String] {
+        self.build_tags
+            .get(dockerfile)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    // Moves `tag` to the front of the Dockerfile's recent tags
+    pub fn remember_tag(&mut self, dockerfile: &Path, tag: &str) {
+        let tags = self.build_tags.entry(dockerfile.to_path_buf()).or_default();
+        tags.retain(|recent| recent != tag);
+        tags.insert(0, tag.to_string());
+        tags.truncate(MAX_RECENT_TAGS);
+    }
+
+    // Replaces a saved filter with the same name
+    pub fn save_filter(&mut self, name: &str, expression: &str) {
+        let filter = SavedFilter {
+            name: name.to_string(),
+            expression: expression.to_string(),
+        };
+        match self
+            .saved_filters
+            .iter_mut()
+            .find(|saved| saved.name == name)
+        {
+            Some(saved) => *saved = filter,
+            None => self.saved_filters.push(filter),
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use bollard::{Docker, API_DEFAULT_VERSION};
+
+// Seconds a request may take, the same as bollard's defaults
+const TIMEOUT: u64 = 120;
+
+pub const DEFAULT_SOCKET: &str = "/var/run/docker.sock";
+// Where Docker Desktop on macOS puts its socket, under the home directory. The link from
+// /var/run is only there when Desktop was allowed to create it.
+pub const DESKTOP_SOCKET: &str = ".docker/run/docker.sock";
+pub const DEFAULT_PIPE: &str = r"\\.\pipe\docker_engine";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Platform {
+    Unix,
+    MacOs,
+    Windows,
+}
+
+impl Platform {
+    pub fn current() -> Self {
+        if cfg!(windows) {
+            Platform::Windows
+        } else if cfg!(target_os = "macos") {
+            Platform::MacOs
+        } else {
+            Platform::Unix
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Endpoint {
+    Socket(PathBuf),
+    NamedPipe(String),
+    // A DOCKER_HOST dockerrs cannot connect to, like tcp:// or ssh://
+    Unsupported(String),
+}
+
+// Written the way DOCKER_HOST takes it
+impl fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Endpoint::Socket(path) => write!(f, "unix://{}", path.display()),
+            Endpoint::NamedPipe(pipe) => write!(f, "npipe://{}", pipe.replace('\\', "/")),
+            Endpoint::Unsupported(host) => write!(f, "{}", host),
+        }
+    }
+}
+
+// An endpoint and why it is tried, e.g. "DOCKER_HOST environment variable"
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Candidate {
+    pub endpoint: Endpoint,
+    pub source: &'static str,
+}
+
+impl fmt::Display for Candidate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.endpoint, self.source)
+    }
+}
+
+// Endpoints to try, in order. A DOCKER_HOST is the only one, like for the docker CLI, also when
+// it names a scheme such as tcp:// that is not supported. Falling back to the local daemon
+// would act on another host than the one asked for.
+pub fn candidates(
+    platform: Platform,
+    docker_host: Option<&str>,
+    home: Option<&Path>,
+) -> Vec<Candidate> {
+    let from_env = "DOCKER_HOST environment variable";
+    if let Some(host) = docker_host.filter(|host| !host.is_empty()) {
+        let endpoint = if let Some(path) = host.strip_prefix("unix://") {
+            Endpoint::Socket(PathBuf::from(path))
+        } else if let Some(pipe) = host.strip_prefix("npipe://") {
+            Endpoint::NamedPipe(pipe.replace('/', "\\"))
+        } else {
+            Endpoint::Unsupported(host.to_string())
+        };
+        return vec![Candidate {
+            endpoint,
+            source: from_env,
+        }];
+    }
+    let default_socket = Candidate {
+        endpoint: Endpoint::Socket(PathBuf::from(DEFAULT_SOCKET)),
+        source: "default",
+    };
+    match platform {
+        Platform::Windows => vec![Candidate {
+            endpoint: Endpoint::NamedPipe(DEFAULT_PIPE.to_string()),
+            source: "default",
+        }],
+        Platform::MacOs => home
+            .map(|home| Candidate {
+                endpoint: Endpoint::Socket(home.join(DESKTOP_SOCKET)),
+                source: "Docker Desktop",
+            })
+            .into_iter()
+            .chain([default_socket])
+            .collect(),
+        Platform::Unix => vec![default_socket],
+    }
+}
+
+// The first candidate that exists, or an error naming every one that was tried
+pub fn select(
+    candidates: Vec<Candidate>,
+    exists: impl Fn(&Endpoint) -> bool,
+) -> Result<Candidate, String> {
+    let tried: Vec<String> = candidates.iter().map(Candidate::to_string).collect();
+    let unsupported = candidates
+        .iter()
+        .find_map(|candidate| match &candidate.endpoint {
+            Endpoint::Unsupported(host) => Some(host),
+            _ => None,
+        });
+    if let Some(host) = unsupported {
+        let scheme = match host.split_once("://") {
+            Some((scheme, _)) => format!("scheme {}://", scheme),
+            None => format!("value {}", host),
+        };
+        return Err(format!(
+            "DOCKER_HOST {} is not supported, tried {}",
+            scheme,
+            tried.join(", ")
+        ));
+    }
+    candidates
+        .into_iter()
+        .find(|candidate| exists(&candidate.endpoint))
+        .ok_or_else(|| format!("No Docker daemon found, tried {}", tried.join(", ")))
+}
+
+// Where this machine's daemon listens
+pub fn target() -> Result<Candidate, String> {
+    let docker_host = std::env::var("DOCKER_HOST").ok();
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    let candidates = candidates(Platform::current(), docker_host.as_deref(), home.as_deref());
+    select(candidates, |endpoint| match endpoint {
+        Endpoint::Socket(path) => path.exists(),
+        Endpoint::NamedPipe(pipe) => Path::new(pipe).exists(),
+        Endpoint::Unsupported(_) => false,
+    })
+}
+
+// Sockets on unix, named pipes on Windows
+pub fn connect_to(candidate: &Candidate) -> Result<Docker, bollard::errors::Error> {
+    let path = match &candidate.endpoint {
+        Endpoint::Socket(path) => path.to_string_lossy().into_owned(),
+        Endpoint::NamedPipe(pipe) => pipe.clone(),
+        Endpoint::Unsupported(host) => {
+            return Err(bollard::errors::Error::UnsupportedURISchemeError { uri: host.clone() })
+        }
+    };
+    Docker::connect_with_socket(&path, TIMEOUT, API_DEFAULT_VERSION)
+}
+
+// The client every feature talks to the daemon with
+pub fn connect() -> Result<Docker, String> {
+    let candidate = target()?;
+    connect_to(&candidate).map_err(|e| format!("Cannot use {}: {}", candidate, e))
+}
@@ -0,0 +1,60 @@
+use crate::health::Health;
+use crate::poller::ContainerSnapshot;
+
+// How many containers are in each state, for the window title and the Containers tab
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ContainerCounts {
+    pub running: usize,
+    // Running with a failing HEALTHCHECK, these count as running too
+    pub unhealthy: usize,
+    pub exited: usize,
+    pub total: usize,
+}
+
+impl ContainerCounts {
+    pub fn of(containers: &ContainerSnapshot) -> Self {
+        let mut counts = Self {
+            total: containers.len(),
+            ..Default::default()
+        };
+        for (summary, _) in containers.values() {
+            match summary.state.as_deref() {
+                Some("running") => counts.running += 1,
+                Some("exited") | Some("dead") => counts.exited += 1,
+                _ => {}
+            }
+            let health = summary.status.as_deref().and_then(Health::from_status);
+            if health == Some(Health::Unhealthy) {
+                counts.unhealthy += 1;
+            }
+        }
+        counts
+    }
+
+    // e.g. "dockerrs — 7 running, 1 unhealthy, 2 exited", leaving out what there is none of
+    pub fn window_title(&self) -> String {
+        let parts: Vec<String> = [
+            (self.running, "running"),
+            (self.unhealthy, "unhealthy"),
+            (self.exited, "exited"),
+        ]
+        .iter()
+        .filter(|(count, _)| *count > 0)
+        .map(|(count, state)| format!("{} {}", count, state))
+        .collect();
+        if parts.is_empty() {
+            "dockerrs".to_string()
+        } else {
+            format!("dockerrs — {}", parts.join(", "))
+        }
+    }
+
+    // Running out of all containers, e.g. "Containers (7/10)"
+    pub fn tab_label(&self) -> String {
+        if self.total == 0 {
+            "Containers".to_string()
+        } else {
+            format!("Containers ({}/{})", self.running, self.total)
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use bollard::secret::ChangeType;
+
+use crate::connection;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ChangeKind {
+    Added,
+    Changed,
+    Deleted,
+}
+
+impl ChangeKind {
+    pub const ALL: [ChangeKind; 3] = [ChangeKind::Added, ChangeKind::Changed, ChangeKind::Deleted];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ChangeKind::Added => "Added",
+            ChangeKind::Changed => "Changed",
+            ChangeKind::Deleted => "Deleted",
+        }
+    }
+
+    // The letter `docker diff` prints in front of the path
+    pub fn symbol(self) -> char {
+        match self {
+            ChangeKind::Added => 'A',
+            ChangeKind::Changed => 'C',
+            ChangeKind::Deleted => 'D',
+        }
+    }
+
+    fn from_change_type(kind: ChangeType) -> Self {
+        match kind {
+            ChangeType::_0 => ChangeKind::Changed,
+            ChangeType::_1 => ChangeKind::Added,
+            ChangeType::_2 => ChangeKind::Deleted,
+        }
+    }
+}
+
+// Kind and path of every change in a container
+pub type ContainerChanges = Vec<(ChangeKind, String)>;
+
+// A line of the diff view, paths are grouped under a header with their count
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DiffRow {
+    Header(ChangeKind, usize),
+    Path(ChangeKind, String),
+}
+
+// Rows of the changes whose path contains `search` (case-insensitive), grouped by kind
+// and sorted by path. Kinds without a matching path get no header.
+pub fn diff_rows(changes: &[(ChangeKind, String)], search: &str) -> Vec<DiffRow> {
+    let search = search.to_lowercase();
+    let mut rows = Vec::new();
+    for kind in ChangeKind::ALL {
+        let mut paths: Vec<&String> = changes
+            .iter()
+            .filter(|(change, path)| *change == kind && path.to_lowercase().contains(&search))
+            .map(|(_, path)| path)
+            .collect();
+
+        if paths.is_empty() {
+            continue;
+        }
+        paths.sort();
+        rows.push(DiffRow::Header(kind, paths.len()));
+        rows.extend(
+            paths
+                .into_iter()
+                .map(|path| DiffRow::Path(kind, path.clone())),
+        );
+    }
+    rows
+}
+
+// Files added, changed or deleted in the container's writable layer, like `docker diff`
+pub async fn container_changes(container_id: String) -> Result<ContainerChanges, String> {
+    let docker = connection::connect()?;
+    let changes = docker
+        .container_changes(&container_id)
+        .await
+        .map_err(|e| format!("Failed to diff container {}: {}", container_id, e))?;
+    Ok(changes
+        .unwrap_or_default()
+        .into_iter()
+        .map(|change| (ChangeKind::from_change_type(change.kind), change.path))
+        .collect())
+}
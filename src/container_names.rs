@@ -0,0 +1,45 @@
+use std::collections::HashMap;
+
+use crate::poller::{display_name, ContainerSnapshot};
+use crate::utils::compose_project;
+
+// Names for the containers whose display name another container in the snapshot shares, by
+// container ID. Those get their compose project in front, `proj1/app-1`, and when that is
+// not enough either, their short ID behind, `app-1 (0123456789ab)`. Every other container
+// keeps its display name and is left out.
+pub fn disambiguated_names(containers: &ContainerSnapshot) -> HashMap<String, String> {
+    let mut by_name: HashMap<String, Vec<&String>> = HashMap::new();
+    for (id, (summary, _)) in containers {
+        by_name.entry(display_name(summary)).or_default().push(id);
+    }
+    let mut names = HashMap::new();
+    for (name, ids) in by_name.into_iter().filter(|(_, ids)| ids.len() > 1) {
+        let name = name.trim_start_matches('/');
+        let with_project: Vec<(&String, String)> = ids
+            .into_iter()
+            .map(|id| {
+                let project = compose_project(&containers[id].0);
+                let name = match project {
+                    Some(project) => format!("{}/{}", project, name),
+                    None => name.to_string(),
+                };
+                (id, name)
+            })
+            .collect();
+        for (id, name) in &with_project {
+            let unique = with_project
+                .iter()
+                .filter(|(_, other)| other == name)
+                .count()
+                == 1;
+            let name = if unique {
+                name.clone()
+            } else {
+                let short_id: String = id.chars().take(12).collect();
+                format!("{} ({})", name, short_id)
+            };
+            names.insert(id.to_string(), name);
+        }
+    }
+    names
+}
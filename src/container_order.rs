@@ -0,0 +1,74 @@
+use std::collections::HashSet;
+
+use bollard::secret::ContainerSummary;
+
+use crate::poller::ContainerSnapshot;
+use crate::utils::{ContainerSort, COMPOSE_PROJECT_LABEL};
+
+// Above one in this many containers moving, the list is sorted again rather than patched
+const RESORT_FRACTION: usize = 4;
+
+// Containers sharing a name are ordered by ID so they do not swap places every poll
+pub fn sorted_ids(containers: &ContainerSnapshot, sort: ContainerSort) -> Vec<String> {
+    let mut ids: Vec<String> = containers.keys().cloned().collect();
+    ids.sort_by_cached_key(|id| (sort.key(&containers[id].0), id.clone()));
+    ids
+}
+
+// Whether the container keeps its place in the list and its compose project group
+fn same_place(before: &ContainerSummary, after: &ContainerSummary) -> bool {
+    fn project(summary: &ContainerSummary) -> Option<&String> {
+        summary
+            .labels
+            .as_ref()
+            .and_then(|labels| labels.get(COMPOSE_PROJECT_LABEL))
+    }
+    before.names == after.names
+        && before.created == after.created
+        && project(before) == project(after)
+}
+
+// Updates `ids`, sorted for `previous`, to the order of `current`. Only containers that
+// came, went or were renamed move, so a poll where nothing but statuses and logs changed
+// costs a pass over the snapshot rather than a sort. Returns whether anything moved.
+pub fn reorder(
+    ids: &mut Vec<String>,
+    previous: &ContainerSnapshot,
+    current: &ContainerSnapshot,
+    sort: ContainerSort,
+) -> bool {
+    if ids.len() != previous.len() {
+        *ids = sorted_ids(current, sort);
+        return true;
+    }
+    let mut stale: HashSet<&String> = previous
+        .keys()
+        .filter(|id| !current.contains_key(*id))
+        .collect();
+    let mut moved: Vec<&String> = Vec::new();
+    for (id, (summary, _)) in current {
+        match previous.get(id) {
+            Some((before, _)) if same_place(before, summary) => {}
+            Some(_) => {
+                stale.insert(id);
+                moved.push(id);
+            }
+            None => moved.push(id),
+        }
+    }
+    if stale.is_empty() && moved.is_empty() {
+        return false;
+    }
+    if (stale.len() + moved.len()) * RESORT_FRACTION > current.len() {
+        *ids = sorted_ids(current, sort);
+        return true;
+    }
+    ids.retain(|id| !stale.contains(id));
+    let key = |id: &String| (sort.key(&current[id].0), id.clone());
+    for id in moved {
+        let new_key = key(id);
+        let position = ids.partition_point(|other| key(other) < new_key);
+        ids.insert(position, id.clone());
+    }
+    true
+}
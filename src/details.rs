@@ -0,0 +1,173 @@
+use bollard::container::InspectContainerOptions;
+use bollard::secret::{ContainerSummary, MountPointTypeEnum};
+
+use crate::connection;
+
+// Variables whose name contains one of these are masked until revealed
+const SECRET_MARKERS: [&str; 3] = ["PASSWORD", "SECRET", "TOKEN"];
+
+// Shown instead of a secret, always the same length so it does not leak the real one
+pub const MASK: &str = "••••••••";
+
+// What a container was started with, from its inspect
+#[derive(Clone, Debug, Default)]
+pub struct ContainerDetails {
+    pub env: Vec<(String, String)>,
+    pub entrypoint: Vec<String>,
+    pub cmd: Vec<String>,
+    // RFC 3339, the zero time when the container never started
+    pub started_at: Option<String>,
+    // "no", "always", "unless-stopped" or "on-failure"
+    pub restart_policy: String,
+    // Only for on-failure, zero is unlimited
+    pub restart_max_retries: i64,
+}
+
+// Splits KEY=value entries, a bare KEY has an empty value
+pub fn parse_env(env: &[String]) -> Vec<(String, String)> {
+    env.iter()
+        .map(|entry| match entry.split_once('=') {
+            Some((key, value)) => (key.to_string(), value.to_string()),
+            None => (entry.clone(), String::new()),
+        })
+        .collect()
+}
+
+// Arguments as they would be typed in a shell, quoting the ones with spaces or quotes
+pub fn command_line(args: &[String]) -> String {
+    args.iter()
+        .map(|arg| {
+            if arg.is_empty() || arg.contains([' ', '\t', '"', '\'']) {
+                format!("'{}'", arg.replace('\'', "'\\''"))
+            } else {
+                arg.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Published ports as host address, container port and protocol, sorted by container port.
+// Unpublished ports have no host address.
+pub fn port_rows(summary: &ContainerSummary) -> Vec<(String, String, String)> {
+    let mut rows: Vec<(u16, String, String, String)> = summary
+        .ports
+        .iter()
+        .flatten()
+        .map(|port| {
+            let host = match (port.ip.as_deref(), port.public_port) {
+                (Some(ip), Some(public)) if ip.contains(':') => format!("[{}]:{}", ip, public),
+                (Some(ip), Some(public)) => format!("{}:{}", ip, public),
+                (None, Some(public)) => public.to_string(),
+                (_, None) => "-".to_string(),
+            };
+
+            let protocol = port
+                .typ
+                .map(|typ| typ.to_string())
+                .filter(|typ| !typ.is_empty())
+                .unwrap_or_else(|| "tcp".to_string());
+            (
+                port.private_port,
+                host,
+                port.private_port.to_string(),
+                protocol,
+            )
+        })
+        .collect();
+    rows.sort();
+    rows.dedup();
+    rows.into_iter()
+        .map(|(_, host, container, protocol)| (host, container, protocol))
+        .collect()
+}
+
+// Mounts as source, destination and mode, volumes by name and binds by host path
+pub fn mount_rows(summary: &ContainerSummary) -> Vec<(String, String, String)> {
+    summary
+        .mounts
+        .iter()
+        .flatten()
+        .map(|mount| {
+            let source = match mount.typ {
+                Some(MountPointTypeEnum::VOLUME) => mount.name.clone(),
+                _ => mount.source.clone(),
+            }
+            .unwrap_or_default();
+            let mode = if mount.rw.unwrap_or(true) { "rw" } else { "ro" };
+            (
+                source,
+                mount.destination.clone().unwrap_or_default(),
+                mode.to_string(),
+            )
+        })
+        .collect()
+}
+
+// Networks the container is on with its address and gateway there, sorted by name
+pub fn network_rows(summary: &ContainerSummary) -> Vec<(String, String, String)> {
+    let mut rows: Vec<(String, String, String)> = summary
+        .network_settings
+        .as_ref()
+        .and_then(|settings| settings.networks.as_ref())
+        .into_iter()
+        .flatten()
+        .map(|(name, endpoint)| {
+            let address = |value: &Option<String>| {
+                value
+                    .clone()
+                    .filter(|value| !value.is_empty())
+                    .unwrap_or_else(|| "-".to_string())
+            };
+            (
+                name.clone(),
+                address(&endpoint.ip_address),
+                address(&endpoint.gateway),
+            )
+        })
+        .collect();
+    rows.sort();
+    rows
+}
+
+// Labels sorted by key
+pub fn label_rows(summary: &ContainerSummary) -> Vec<(String, String)> {
+    let mut rows: Vec<(String, String)> = summary
+        .labels
+        .iter()
+        .flatten()
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    rows.sort();
+    rows
+}
+
+pub fn is_secret(key: &str) -> bool {
+    let key = key.to_uppercase();
+    SECRET_MARKERS.iter().any(|marker| key.contains(marker))
+}
+
+pub async fn container_details(container_id: String) -> Result<ContainerDetails, String> {
+    let docker = connection::connect()?;
+    let inspect = docker
+        .inspect_container(&container_id, None::<InspectContainerOptions>)
+        .await
+        .map_err(|e| format!("Failed to inspect container {}: {}", container_id, e))?;
+    let config = inspect.config.unwrap_or_default();
+    let restart_policy = inspect
+        .host_config
+        .and_then(|host_config| host_config.restart_policy)
+        .unwrap_or_default();
+    Ok(ContainerDetails {
+        env: parse_env(&config.env.unwrap_or_default()),
+        entrypoint: config.entrypoint.unwrap_or_default(),
+        cmd: config.cmd.unwrap_or_default(),
+        started_at: inspect.state.and_then(|state| state.started_at),
+        restart_policy: restart_policy
+            .name
+            .map(|name| name.to_string())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| "no".to_string()),
+        restart_max_retries: restart_policy.maximum_retry_count.unwrap_or_default(),
+    })
+}
@@ -0,0 +1,326 @@
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use bollard::Docker;
+use serde::Serialize;
+use tokio::process::Command;
+
+use crate::config::Config;
+use crate::connection;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl CheckStatus {
+    pub fn label(self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "PASS",
+            CheckStatus::Warn => "WARN",
+            CheckStatus::Fail => "FAIL",
+        }
+    }
+}
+
+// Outcome of a single self-diagnostic check, with a remediation hint when it did not pass
+#[derive(Clone, Debug, Serialize)]
+pub struct CheckResult {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+    pub hint: Option<String>,
+}
+
+impl CheckResult {
+    fn pass(name: &'static str, detail: String) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Pass,
+            detail,
+            hint: None,
+        }
+    }
+
+    fn warn(name: &'static str, detail: String, hint: &str) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Warn,
+            detail,
+            hint: Some(hint.to_string()),
+        }
+    }
+
+    fn fail(name: &'static str, detail: String, hint: &str) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Fail,
+            detail,
+            hint: Some(hint.to_string()),
+        }
+    }
+}
+
+pub fn check_connection_target() -> CheckResult {
+    match connection::target() {
+        Ok(target) => CheckResult::pass("connection target", target.to_string()),
+        Err(e) => CheckResult::fail(
+            "connection target",
+            e,
+            "Start Docker, or point DOCKER_HOST at the socket it listens on, only unix:// and \
+             npipe:// are supported",
+        ),
+    }
+}
+
+pub async fn check_reachable(docker: &Docker) -> CheckResult {
+    let started = Instant::now();
+    match docker.ping().await {
+        Ok(_) => CheckResult::pass(
+            "daemon reachable",
+            format!("ping answered in {} ms", started.elapsed().as_millis()),
+        ),
+        Err(e) => CheckResult::fail(
+            "daemon reachable",
+            e.to_string(),
+            "Check that the Docker daemon is running and that you may access its socket",
+        ),
+    }
+}
+
+pub async fn check_version(docker: &Docker) -> CheckResult {
+    match docker.version().await {
+        Ok(version) => CheckResult::pass(
+            "daemon version",
+            format!(
+                "Docker {} (API {})",
+                version.version.unwrap_or_default(),
+                version.api_version.unwrap_or_default()
+            ),
+        ),
+        Err(e) => CheckResult::fail(
+            "daemon version",
+            e.to_string(),
+            "The daemon did not answer the version request, see 'daemon reachable'",
+        ),
+    }
+}
+
+pub async fn check_docker_group() -> CheckResult {
+    if !cfg!(unix) {
+        return CheckResult::pass("docker group", "not applicable on this platform".into());
+    }
+    match Command::new("id").arg("-Gn").output().await {
+        Ok(output) if output.status.success() => {
+            let groups = String::from_utf8_lossy(&output.stdout).to_string();
+            if groups.split_whitespace().any(|group| group == "docker") {
+                CheckResult::pass("docker group", "current user is in the docker group".into())
+            } else {
+                CheckResult::warn(
+                    "docker group",
+                    format!("current user groups: {}", groups.trim()),
+                    "Add yourself with 'sudo usermod -aG docker $USER' and log in again, unless \
+                     you run rootless Docker",
+                )
+            }
+        }
+        _ => CheckResult::warn(
+            "docker group",
+            "could not determine the groups of the current user".into(),
+            "Run 'id -Gn' to check whether you are in the docker group",
+        ),
+    }
+}
+
+pub async fn check_rootless(docker: &Docker) -> CheckResult {
+    let target = connection::target()
+        .map(|target| target.endpoint.to_string())
+        .unwrap_or_default();
+    let user_socket = std::env::var("XDG_RUNTIME_DIR")
+        .map(|dir| PathBuf::from(dir).join("docker.sock"))
+        .ok();
+    let security_options = docker
+        .info()
+        .await
+        .ok()
+        .and_then(|info| info.security_options)
+        .unwrap_or_default();
+
+    if security_options
+        .iter()
+        .any(|option| option.contains("rootless"))
+    {
+        CheckResult::pass("rootless mode", "daemon runs rootless".into())
+    } else if user_socket.as_ref().is_some_and(|socket| socket.exists())
+        && !target.contains("/run/user/")
+    {
+        CheckResult::warn(
+            "rootless mode",
+            format!(
+                "a rootless socket exists at {:?} but {} is used",
+                user_socket.unwrap_or_default(),
+                target
+            ),
+            "Set DOCKER_HOST=unix://$XDG_RUNTIME_DIR/docker.sock to use the rootless daemon",
+        )
+    } else {
+        CheckResult::pass("rootless mode", "not detected".into())
+    }
+}
+
+pub fn check_config(path: Option<&Path>) -> CheckResult {
+    let Some(path) = path else {
+        return CheckResult::warn(
+            "config file",
+            "no config file, neither XDG_CONFIG_HOME nor HOME is set".into(),
+            "Pass --config to keep saved filters, alerts and keep-alive between sessions",
+        );
+    };
+    if !path.exists() {
+        return CheckResult::pass(
+            "config file",
+            format!(
+                "{} does not exist yet, the defaults are used",
+                path.display()
+            ),
+        );
+    }
+    match Config::load(path) {
+        Ok(_) => CheckResult::pass("config file", format!("{} parsed", path.display())),
+        Err(e) => CheckResult::fail(
+            "config file",
+            e,
+            "Fix or remove the file, dockerrs starts with the defaults while it does not parse",
+        ),
+    }
+}
+
+// Saved filters, keep-alive, build tags and column choices are written back to the config file,
+// so it, or the directory it would be created in, has to be writable
+pub fn check_state_writable(path: Option<&Path>) -> CheckResult {
+    let Some(path) = path else {
+        return CheckResult::warn(
+            "state file",
+            "nowhere to save settings changed in the window".into(),
+            "Pass --config to keep saved filters, alerts and keep-alive between sessions",
+        );
+    };
+    let writable = if path.exists() {
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(path)
+            .map(|_| path.to_path_buf())
+    } else {
+        // The directories are created on the first save, a probe file checks the closest one
+        // that exists already
+        let dir = path
+            .ancestors()
+            .skip(1)
+            .find(|dir| dir.is_dir())
+            .unwrap_or(Path::new("."));
+        let probe = dir.join(format!(".dockerrs-probe-{}", std::process::id()));
+        std::fs::write(&probe, b"")
+            .and_then(|()| std::fs::remove_file(&probe))
+            .map(|()| dir.to_path_buf())
+    };
+    match writable {
+        Ok(writable) => {
+            CheckResult::pass("state file", format!("{} is writable", writable.display()))
+        }
+        Err(e) => CheckResult::warn(
+            "state file",
+            format!("cannot write {}: {}", path.display(), e),
+            "Fix the permissions or pass --config with a writable path, changes made in the \
+             window are lost otherwise",
+        ),
+    }
+}
+
+// egui talks to the clipboard through the display server on Linux and the BSDs
+pub fn check_clipboard() -> CheckResult {
+    if !cfg!(unix) || cfg!(target_os = "macos") {
+        return CheckResult::pass("clipboard", "system clipboard".into());
+    }
+    let display = ["WAYLAND_DISPLAY", "DISPLAY"]
+        .into_iter()
+        .find(|name| std::env::var_os(name).is_some_and(|value| !value.is_empty()));
+    match display {
+        Some(name) => CheckResult::pass("clipboard", format!("through {}", name)),
+        None => CheckResult::warn(
+            "clipboard",
+            "neither WAYLAND_DISPLAY nor DISPLAY is set".into(),
+            "Copy buttons do nothing without a display server, run dockerrs in a desktop session",
+        ),
+    }
+}
+
+// Desktop alerts are sent with notify-send
+pub fn check_notifications(desktop_alerts: bool) -> CheckResult {
+    if !desktop_alerts {
+        return CheckResult::pass("notifications", "desktop alerts are off".into());
+    }
+    let found = std::env::var_os("PATH").is_some_and(|path| {
+        std::env::split_paths(&path).any(|dir| dir.join("notify-send").is_file())
+    });
+    if found {
+        CheckResult::pass("notifications", "notify-send is installed".into())
+    } else {
+        CheckResult::warn(
+            "notifications",
+            "desktop alerts are on but notify-send is not on PATH".into(),
+            "Install libnotify, or turn off alerts.desktop in the config file",
+        )
+    }
+}
+
+// Runs every check in order, later checks that need the daemon are skipped when connecting fails
+pub async fn run_checks(config_path: Option<PathBuf>) -> Vec<CheckResult> {
+    let mut results = vec![check_connection_target()];
+    match connection::connect() {
+        Ok(docker) => {
+            results.push(check_reachable(&docker).await);
+            results.push(check_version(&docker).await);
+            results.push(check_docker_group().await);
+            results.push(check_rootless(&docker).await);
+        }
+        Err(e) => {
+            results.push(CheckResult::fail(
+                "daemon reachable",
+                e.to_string(),
+                "The connection target could not be used, check DOCKER_HOST",
+            ));
+            results.push(check_docker_group().await);
+        }
+    }
+    let config_path = config_path.as_deref();
+    results.push(check_config(config_path));
+    results.push(check_state_writable(config_path));
+    results.push(check_clipboard());
+    let desktop_alerts = config_path
+        .and_then(|path| Config::load(path).ok())
+        .is_some_and(|config| config.alerts.desktop);
+    results.push(check_notifications(desktop_alerts));
+    results
+}
+
+pub fn format_text(results: &[CheckResult]) -> String {
+    results
+        .iter()
+        .map(|result| {
+            let mut line = format!(
+                "[{}] {}: {}",
+                result.status.label(),
+                result.name,
+                result.detail
+            );
+            if let Some(hint) = &result.hint {
+                line.push_str(&format!("\n       hint: {}", hint));
+            }
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,240 @@
+use std::collections::HashMap;
+
+use bollard::container::PruneContainersOptions;
+use bollard::image::PruneImagesOptions;
+use bollard::secret::SystemDataUsageResponse;
+use bollard::volume::PruneVolumesOptions;
+use tokio::process::Command;
+
+use crate::capabilities::{describe_error, Feature};
+use crate::connection;
+use crate::read_only;
+use crate::utils::{format_size, parse_size};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Category {
+    Images,
+    Containers,
+    Volumes,
+    BuildCache,
+}
+
+impl Category {
+    pub fn label(self) -> &'static str {
+        match self {
+            Category::Images => "Images",
+            Category::Containers => "Containers",
+            Category::Volumes => "Local volumes",
+            Category::BuildCache => "Build cache",
+        }
+    }
+
+    // What pruning the category removes, for the confirmation
+    pub fn prune_description(self) -> &'static str {
+        match self {
+            Category::Images => "every image no container uses",
+            Category::Containers => "every stopped container",
+            Category::Volumes => "every volume no container uses",
+            Category::BuildCache => "the build cache that is not in use",
+        }
+    }
+}
+
+// How much of the build cache `docker builder prune` removes
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BuildCachePrune {
+    // Stop once the cache is down to this many bytes
+    pub keep_storage: Option<u64>,
+    // Every unused entry rather than only the dangling ones
+    pub all: bool,
+}
+
+impl BuildCachePrune {
+    pub fn args(self) -> Vec<String> {
+        let mut args: Vec<String> = ["builder", "prune", "--force"].map(String::from).into();
+        if let Some(bytes) = self.keep_storage {
+            args.push("--keep-storage".to_string());
+            args.push(bytes.to_string());
+        }
+        if self.all {
+            args.push("--all".to_string());
+        }
+        args
+    }
+}
+
+// The reclaimed bytes from the "Total:" line that ends `docker builder prune` output
+pub fn reclaimed_build_cache(stdout: &str) -> Option<i64> {
+    let total = stdout
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("Total:"))?;
+    parse_size(total).ok().map(|bytes| bytes as i64)
+}
+
+// One row of `docker system df`
+#[derive(Clone, Debug, PartialEq)]
+pub struct CategoryUsage {
+    pub category: Category,
+    pub total: usize,
+    pub active: usize,
+    pub size: i64,
+    pub reclaimable: i64,
+}
+
+// Sizes and reclaimable space per category, counted the way `docker system df` does
+pub fn summarize(usage: &SystemDataUsageResponse) -> Vec<CategoryUsage> {
+    let images = usage.images.as_deref().unwrap_or_default();
+    let in_use: Vec<_> = images.iter().filter(|image| image.containers > 0).collect();
+    let images_size = usage
+        .layers_size
+        .unwrap_or_else(|| images.iter().map(|image| image.size).sum());
+    // Layers shared with other images stay when an unused image is removed
+    let used: i64 = in_use
+        .iter()
+        .map(|image| image.size - image.shared_size.max(0))
+        .sum();
+
+    let containers = usage.containers.as_deref().unwrap_or_default();
+    let running = |state: &Option<String>| state.as_deref() == Some("running");
+    let container_size = |size: Option<i64>| size.unwrap_or_default().max(0);
+
+    let volumes = usage.volumes.as_deref().unwrap_or_default();
+    let volume_usage = |volume: &bollard::secret::Volume| {
+        volume
+            .usage_data
+            .as_ref()
+            .map_or((0, 0), |data| (data.size.max(0), data.ref_count))
+    };
+
+    let cache = usage.build_cache.as_deref().unwrap_or_default();
+    let cache_in_use = |entry: &bollard::secret::BuildCache| {
+        entry.in_use.unwrap_or_default() || entry.shared.unwrap_or_default()
+    };
+
+    vec![
+        CategoryUsage {
+            category: Category::Images,
+            total: images.len(),
+            active: in_use.len(),
+            size: images_size,
+            reclaimable: (images_size - used).max(0),
+        },
+        CategoryUsage {
+            category: Category::Containers,
+            total: containers.len(),
+            active: containers.iter().filter(|c| running(&c.state)).count(),
+            size: containers.iter().map(|c| container_size(c.size_rw)).sum(),
+            reclaimable: containers
+                .iter()
+                .filter(|c| !running(&c.state))
+                .map(|c| container_size(c.size_rw))
+                .sum(),
+        },
+        CategoryUsage {
+            category: Category::Volumes,
+            total: volumes.len(),
+            active: volumes.iter().filter(|v| volume_usage(v).1 > 0).count(),
+            size: volumes.iter().map(|v| volume_usage(v).0).sum(),
+            reclaimable: volumes
+                .iter()
+                .map(volume_usage)
+                .filter(|(_, references)| *references == 0)
+                .map(|(size, _)| size)
+                .sum(),
+        },
+        CategoryUsage {
+            category: Category::BuildCache,
+            total: cache.len(),
+            active: cache.iter().filter(|entry| cache_in_use(entry)).count(),
+            size: cache
+                .iter()
+                .map(|entry| entry.size.unwrap_or_default())
+                .sum(),
+            reclaimable: cache
+                .iter()
+                .filter(|entry| !cache_in_use(entry))
+                .map(|entry| entry.size.unwrap_or_default())
+                .sum(),
+        },
+    ]
+}
+
+// Walks every image, container and volume, so it is only fetched on demand
+pub async fn disk_usage() -> Result<Vec<CategoryUsage>, String> {
+    let docker = connection::connect()?;
+    let usage = docker
+        .df()
+        .await
+        .map_err(|e| format!("Failed to get disk usage: {}", e))?;
+    Ok(summarize(&usage))
+}
+
+fn reclaimed(category: Category, space: Option<i64>) -> String {
+    format!(
+        "Pruned {}: {} reclaimed",
+        category.label().to_lowercase(),
+        format_size(space.unwrap_or_default())
+    )
+}
+
+// Removes what `category` can reclaim. Since API 1.42 volume prune only takes anonymous
+// volumes unless `all_volumes` is set, older daemons ignore the filter. `build_cache` is only
+// used for the build cache.
+pub async fn prune(
+    category: Category,
+    all_volumes: bool,
+    build_cache: BuildCachePrune,
+) -> Result<String, String> {
+    read_only::check()?;
+    let docker = connection::connect()?;
+    let failed = |e: bollard::errors::Error| format!("Failed to prune: {}", e);
+    match category {
+        Category::Images => {
+            let filters = HashMap::from([("dangling", vec!["false"])]);
+            let response = docker
+                .prune_images(Some(PruneImagesOptions { filters }))
+                .await
+                .map_err(failed)?;
+            Ok(reclaimed(category, response.space_reclaimed))
+        }
+        Category::Containers => {
+            let response = docker
+                .prune_containers(None::<PruneContainersOptions<String>>)
+                .await
+                .map_err(failed)?;
+            Ok(reclaimed(category, response.space_reclaimed))
+        }
+        Category::Volumes => {
+            let mut filters = HashMap::new();
+            if all_volumes {
+                filters.insert("all", vec!["true"]);
+            }
+            let response = docker
+                .prune_volumes(Some(PruneVolumesOptions { filters }))
+                .await
+                .map_err(|e| {
+                    format!(
+                        "Failed to prune: {}",
+                        describe_error(Feature::VolumePruneAll, &e)
+                    )
+                })?;
+            Ok(reclaimed(category, response.space_reclaimed))
+        }
+        // bollard has no endpoint for it, the docker CLI calls the daemon's
+        Category::BuildCache => {
+            let output = Command::new("docker")
+                .args(build_cache.args())
+                .output()
+                .await
+                .map_err(|e| format!("Failed to run docker builder prune: {}", e))?;
+            if !output.status.success() {
+                return Err(format!(
+                    "docker builder prune failed: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ));
+            }
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            Ok(reclaimed(category, reclaimed_build_cache(&stdout)))
+        }
+    }
+}
@@ -0,0 +1,457 @@
+use std::collections::HashMap;
+use std::future::Future;
+
+use bollard::container::{
+    Config, CreateContainerOptions, InspectContainerOptions, KillContainerOptions,
+    ListContainersOptions, LogsOptions, NetworkingConfig, RemoveContainerOptions,
+    RenameContainerOptions, StartContainerOptions, StopContainerOptions,
+};
+use bollard::image::ListImagesOptions;
+use bollard::network::ListNetworksOptions;
+use bollard::secret::{
+    ContainerInspectResponse, ContainerSummary, EndpointSettings, ImageSummary, Network,
+};
+use bollard::system::Version;
+use bollard::Docker;
+use futures_util::stream::StreamExt;
+
+use crate::connection;
+use crate::poller::ALL_LOGS;
+use crate::read_only;
+
+// The daemon calls the poller and container actions need, so they can run against a mock
+pub trait DockerApi: Send + Sync {
+    fn version(&self) -> impl Future<Output = Result<Version, String>> + Send;
+    // `filters` as the daemon takes them, e.g. "label" to ["env=staging"]
+    fn list_containers(
+        &self,
+        all: bool,
+        filters: HashMap<String, Vec<String>>,
+    ) -> impl Future<Output = Result<Vec<ContainerSummary>, String>> + Send;
+    fn list_images(&self) -> impl Future<Output = Result<Vec<ImageSummary>, String>> + Send;
+    fn list_networks(&self) -> impl Future<Output = Result<Vec<Network>, String>> + Send;
+    // The last `tail` lines of stdout and stderr, all of them for `poller::ALL_LOGS`
+    fn logs(&self, id: &str, tail: usize) -> impl Future<Output = Result<String, String>> + Send;
+    fn start(&self, id: &str) -> impl Future<Output = Result<(), String>> + Send;
+    fn stop(&self, id: &str) -> impl Future<Output = Result<(), String>> + Send;
+    // `signal` by name, e.g. "SIGTERM"
+    fn kill(&self, id: &str, signal: &str) -> impl Future<Output = Result<(), String>> + Send;
+    fn remove(
+        &self,
+        id: &str,
+        options: RemoveOptions,
+    ) -> impl Future<Output = Result<(), String>> + Send;
+    fn inspect(
+        &self,
+        id: &str,
+    ) -> impl Future<Output = Result<ContainerInspectResponse, String>> + Send;
+    // Creates a container called `name` and returns its ID
+    fn create(
+        &self,
+        name: &str,
+        config: Config<String>,
+    ) -> impl Future<Output = Result<String, String>> + Send;
+    fn rename(&self, id: &str, name: &str) -> impl Future<Output = Result<(), String>> + Send;
+}
+
+pub struct BollardDocker(pub Docker);
+
+impl BollardDocker {
+    pub fn connect() -> Result<Self, String> {
+        connection::connect()
+            .map(BollardDocker)
+            .map_err(|e| format!("Failed to connect to Docker: {}", e))
+    }
+}
+
+impl DockerApi for BollardDocker {
+    async fn version(&self) -> Result<Version, String> {
+        self.0.version().await.map_err(|e| e.to_string())
+    }
+
+    async fn list_containers(
+        &self,
+        all: bool,
+        filters: HashMap<String, Vec<String>>,
+    ) -> Result<Vec<ContainerSummary>, String> {
+        self.0
+            .list_containers(Some(ListContainersOptions::<String> {
+                all,
+                filters,
+                ..Default::default()
+            }))
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn list_images(&self) -> Result<Vec<ImageSummary>, String> {
+        self.0
+            .list_images(Some(ListImagesOptions::<String> {
+                all: false,
+                ..Default::default()
+            }))
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn list_networks(&self) -> Result<Vec<Network>, String> {
+        self.0
+            .list_networks(None::<ListNetworksOptions<String>>)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    // Chunks that fail to decode are skipped rather than failing the whole tail
+    async fn logs(&self, id: &str, tail: usize) -> Result<String, String> {
+        let options = LogsOptions::<String> {
+            follow: false,
+            stdout: true,
+            stderr: true,
+            tail: if tail == ALL_LOGS {
+                "all".to_string()
+            } else {
+                tail.to_string()
+            },
+            ..Default::default()
+        };
+        let mut logs = String::new();
+        let mut log_stream = self.0.logs(id, Some(options));
+        while let Some(chunk) = log_stream.next().await {
+            if let Ok(log) = chunk {
+                logs.push_str(&String::from_utf8_lossy(&log.into_bytes()));
+            }
+        }
+        Ok(logs)
+    }
+
+    async fn start(&self, id: &str) -> Result<(), String> {
+        self.0
+            .start_container(id, None::<StartContainerOptions<String>>)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn stop(&self, id: &str) -> Result<(), String> {
+        self.0
+            .stop_container(id, None::<StopContainerOptions>)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn kill(&self, id: &str, signal: &str) -> Result<(), String> {
+        self.0
+            .kill_container(id, Some(KillContainerOptions { signal }))
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn remove(&self, id: &str, options: RemoveOptions) -> Result<(), String> {
+        self.0
+            .remove_container(id, Some(options.to_bollard()))
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn inspect(&self, id: &str) -> Result<ContainerInspectResponse, String> {
+        self.0
+            .inspect_container(id, None::<InspectContainerOptions>)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn create(&self, name: &str, config: Config<String>) -> Result<String, String> {
+        let options = CreateContainerOptions {
+            name,
+            platform: None,
+        };
+        self.0
+            .create_container(Some(options), config)
+            .await
+            .map(|created| created.id)
+            .map_err(|e| e.to_string())
+    }
+
+    async fn rename(&self, id: &str, name: &str) -> Result<(), String> {
+        self.0
+            .rename_container(id, RenameContainerOptions { name })
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+// How a container is removed. Without `force` the daemon refuses to remove a running
+// container, and without `volumes` its anonymous volumes are left behind.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RemoveOptions {
+    pub force: bool,
+    pub volumes: bool,
+}
+
+impl RemoveOptions {
+    // What Remove All uses, it stops nothing first
+    pub const FORCE: RemoveOptions = RemoveOptions {
+        force: true,
+        volumes: false,
+    };
+
+    pub fn to_bollard(self) -> RemoveContainerOptions {
+        RemoveContainerOptions {
+            force: self.force,
+            v: self.volumes,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContainerAction {
+    Start,
+    Stop,
+    // With the signal's name, `signals::SIGKILL` unless another one was picked
+    Kill(&'static str),
+    Remove(RemoveOptions),
+    // A new container from the same settings in place of the old one, see `recreate`
+    Recreate,
+}
+
+impl ContainerAction {
+    pub fn name(self) -> &'static str {
+        match self {
+            ContainerAction::Start => "start",
+            ContainerAction::Stop => "stop",
+            ContainerAction::Kill(_) => "kill",
+            ContainerAction::Remove(_) => "remove",
+            ContainerAction::Recreate => "recreate",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ContainerAction::Start => "Start",
+            ContainerAction::Stop => "Stop",
+            ContainerAction::Kill(_) => "Kill",
+            ContainerAction::Remove(_) => "Remove",
+            ContainerAction::Recreate => "Recreate",
+        }
+    }
+
+    // What was done to `target`, for the action history, e.g. "Kill web with SIGTERM"
+    pub fn describe(self, target: &str) -> String {
+        match self {
+            ContainerAction::Kill(signal) => format!("Kill {} with {}", target, signal),
+            ContainerAction::Remove(options) => {
+                let flags: Vec<&str> = [(options.force, "force"), (options.volumes, "volumes")]
+                    .into_iter()
+                    .filter_map(|(set, flag)| set.then_some(flag))
+                    .collect();
+                if flags.is_empty() {
+                    format!("Remove {}", target)
+                } else {
+                    format!("Remove {} ({})", target, flags.join(", "))
+                }
+            }
+            _ => format!("{} {}", self.label(), target),
+        }
+    }
+
+    // Shown next to a container while the action runs
+    pub fn progress(self) -> &'static str {
+        match self {
+            ContainerAction::Start => "starting…",
+            ContainerAction::Stop => "stopping…",
+            ContainerAction::Kill(_) => "killing…",
+            ContainerAction::Remove(_) => "removing…",
+            ContainerAction::Recreate => "recreating…",
+        }
+    }
+}
+
+// Runs one action and describes the outcome for the status bar
+pub async fn run_action<D: DockerApi>(
+    docker: &D,
+    action: ContainerAction,
+    id: &str,
+) -> Result<String, String> {
+    read_only::check()?;
+    let short_id: String = id.chars().take(12).collect();
+    let result = match action {
+        ContainerAction::Start => docker.start(id).await,
+        ContainerAction::Stop => docker.stop(id).await,
+        ContainerAction::Kill(signal) => docker.kill(id, signal).await,
+        ContainerAction::Remove(options) => docker.remove(id, options).await,
+        ContainerAction::Recreate => {
+            return recreate(docker, id).await.map(|new_id| {
+                let new_short_id: String = new_id.chars().take(12).collect();
+                format!("Container {}: recreated as {}", short_id, new_short_id)
+            });
+        }
+    };
+    match (result, action) {
+        (Ok(()), ContainerAction::Kill(signal)) => {
+            Ok(format!("Container {}: sent {}", short_id, signal))
+        }
+        (Ok(()), _) => Ok(format!("Container {}: {} done", short_id, action.name())),
+        (Err(e), ContainerAction::Kill(signal)) => Err(format!(
+            "Failed to send {} to container {}: {}",
+            signal, short_id, e
+        )),
+        (Err(e), _) => Err(format!(
+            "Failed to {} container {}: {}",
+            action.name(),
+            short_id,
+            e
+        )),
+    }
+}
+
+// Replaces a container with a new one created from its settings, e.g. once its image was
+// rebuilt, and returns the new ID. The old container is stopped and renamed out of the way so
+// the new one can take its name. If the new one cannot be created the old one gets its name
+// back and is started again if it ran, only once the new one exists is the old one removed.
+pub async fn recreate<D: DockerApi>(docker: &D, id: &str) -> Result<String, String> {
+    let inspected = docker.inspect(id).await?;
+    let name = inspected
+        .name
+        .as_deref()
+        .unwrap_or_default()
+        .trim_start_matches('/')
+        .to_string();
+    if name.is_empty() {
+        return Err("The container has no name to keep".to_string());
+    }
+    let running = inspected
+        .state
+        .as_ref()
+        .and_then(|state| state.running)
+        .unwrap_or(false);
+    let config = recreate_config(inspected);
+
+    if running {
+        docker.stop(id).await?;
+    }
+    let set_aside = format!("{}_dockerrs_old", name);
+    if let Err(e) = docker.rename(id, &set_aside).await {
+        let restored = if running {
+            docker.start(id).await
+        } else {
+            Ok(())
+        };
+        return Err(rolled_back(
+            format!("Failed to rename the old container: {}", e),
+            restored,
+        ));
+    }
+    let new_id = match docker.create(&name, config).await {
+        Ok(new_id) => new_id,
+        Err(e) => {
+            let restored = match docker.rename(id, &name).await {
+                Ok(()) if running => docker.start(id).await,
+                restored => restored,
+            };
+            return Err(rolled_back(
+                format!("Failed to create the new container: {}", e),
+                restored,
+            ));
+        }
+    };
+    docker
+        .remove(id, RemoveOptions::default())
+        .await
+        .map_err(|e| {
+            format!(
+                "Created {} but failed to remove the old container: {}",
+                name, e
+            )
+        })?;
+    docker
+        .start(&new_id)
+        .await
+        .map_err(|e| format!("Created {} but failed to start it: {}", name, e))?;
+    Ok(new_id)
+}
+
+fn rolled_back(error: String, restored: Result<(), String>) -> String {
+    match restored {
+        Ok(()) => format!("{}, the old container was kept", error),
+        Err(e) => format!("{}, restoring the old container failed too: {}", error, e),
+    }
+}
+
+// What the daemon needs to create the same container again. Values it filled in for the old
+// container, like the hostname it derived from the ID and the addresses it handed out, are
+// left for it to fill in again.
+pub fn recreate_config(inspected: ContainerInspectResponse) -> Config<String> {
+    let short_id: String = inspected
+        .id
+        .as_deref()
+        .unwrap_or_default()
+        .chars()
+        .take(12)
+        .collect();
+    let mut config: Config<String> = inspected.config.unwrap_or_default().into();
+    if config.hostname.as_deref() == Some(short_id.as_str()) {
+        config.hostname = None;
+    }
+    config.host_config = inspected.host_config;
+    let endpoints: HashMap<String, EndpointSettings> = inspected
+        .network_settings
+        .and_then(|settings| settings.networks)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(network, endpoint)| {
+            let aliases = endpoint.aliases.map(|aliases| {
+                aliases
+                    .into_iter()
+                    .filter(|alias| *alias != short_id)
+                    .collect()
+            });
+            let endpoint = EndpointSettings {
+                aliases,
+                ipam_config: endpoint.ipam_config,
+                links: endpoint.links,
+                driver_opts: endpoint.driver_opts,
+                ..Default::default()
+            };
+            (network, endpoint)
+        })
+        .collect();
+    if !endpoints.is_empty() {
+        config.networking_config = Some(NetworkingConfig {
+            endpoints_config: endpoints,
+        });
+    }
+    config
+}
+
+// Runs one action on every container in turn, a failure does not stop the rest. The outcome
+// counts successes and failures and names the first failure.
+pub async fn run_bulk<D: DockerApi>(
+    docker: &D,
+    action: ContainerAction,
+    ids: &[String],
+) -> Result<String, String> {
+    read_only::check()?;
+    let mut failures = Vec::new();
+    for id in ids {
+        if let Err(e) = run_action(docker, action, id).await {
+            failures.push(e);
+        }
+    }
+    let done = ids.len() - failures.len();
+    match failures.first() {
+        None => Ok(format!(
+            "{}: {} of {} containers done",
+            action.label(),
+            done,
+            ids.len()
+        )),
+        Some(first) => Err(format!(
+            "{}: {} of {} containers done, {} failed ({})",
+            action.label(),
+            done,
+            ids.len(),
+            failures.len(),
+            first
+        )),
+    }
+}
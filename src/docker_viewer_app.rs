@@ -1,192 +1,7340 @@
-use std::collections::HashMap;
-use std::thread::sleep;
-use std::time::Duration;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
-use bollard::secret::ContainerSummary;
+use bollard::auth::DockerCredentials;
+use bollard::secret::{
+    ContainerSummary, HealthcheckResult, HistoryResponseItem, ImageSummary, Network, Volume,
+};
 use eframe::{egui, App};
 
 use std::path::{Path, PathBuf};
-use tokio::sync::mpsc;
-use walkdir::WalkDir;
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
 
+use crate::accessibility::{self, name_button, name_selectable};
+use crate::action_queue::{ActionQueue, QueuedAction};
+use crate::alerts::{
+    self, detect_transitions, watch_name, Alert, AlertLimiter, Transition, FLASH_FRAMES, MAX_ALERTS,
+};
+use crate::attach::{self, AttachSession, DEFAULT_DETACH_KEYS};
+use crate::audit::{ActionHistory, AuditKind};
+use crate::builds::{BuildHistory, BuildJob, BuildStatus};
+use crate::capabilities::{gated_button, Capabilities, Feature};
+use crate::commands::{self, Binding, Command, CommandPalette, Scope, COMMANDS};
+use crate::compose_file::{self, error_lines, ComposeFile};
+use crate::compose_logs::ComposeLogs;
+use crate::config::Config;
+use crate::container_counts::ContainerCounts;
+use crate::container_diff::{self, diff_rows, ChangeKind, ContainerChanges, DiffRow};
+use crate::container_names::disambiguated_names;
+use crate::container_order;
+use crate::details::{
+    self, command_line, is_secret, label_rows, mount_rows, network_rows, port_rows,
+    ContainerDetails, MASK,
+};
+use crate::diagnostics::{self, CheckResult, CheckStatus};
+use crate::disk_usage::{self, BuildCachePrune, Category, CategoryUsage};
+use crate::docker_api::{
+    run_action, run_bulk, BollardDocker, ContainerAction, DockerApi, RemoveOptions,
+};
+use crate::dockerfile::{highlighted_lines, lint};
+use crate::doctor::{self, FixStep};
+use crate::error::{report, DockerrsError, ErrorSender};
+use crate::files::{self, EntryKind, FileEntry};
+use crate::graph;
+use crate::health::{self, Health};
+use crate::image_analysis::{self, AnalysisReport};
+use crate::image_build::{self, BuildSettings};
+use crate::image_updates::{outdated_label, ImageUpdates, UpdateStatus};
+use crate::interpolation::{self, Piece};
+use crate::keep_alive::{KeepAlive, KeepAliveEvent, RESTART_DELAYS};
+use crate::label_filter::LabelFilter;
+use crate::layout::{alerts_label, undo_hint, visible_columns, LayoutMode, RowColumn, ALL_COLUMNS};
+use crate::log_layout::{
+    format_prefix, h_offset_label, prefix_color, prefix_width, scroll_offset, truncate_line,
+    wrap_message, LogScroll, MAX_LINE_CHARS, MAX_PREFIX_WIDTH, PREFIX_SEPARATOR,
+};
+use crate::log_level::{classify_lines, LogLevel};
+use crate::log_range::{self, LogRange};
+use crate::log_selection::{line_count, selected_text, LogSelection, SelectionRequest};
+use crate::log_stream::{
+    adjust_scroll, FollowedLogs, MergedLogs, StoppedLogs, DEFAULT_MAX_LOG_LINES,
+};
+use crate::networks::{
+    self, connected_containers, driver_options, ipam_rows, BUILTIN_NETWORKS, NETWORK_DRIVERS,
+};
+use crate::notifications::Notifications;
+use crate::poller::{
+    display_name, Connection, ContainerSnapshot, LOG_TAIL, LOG_TAIL_CHOICES, RETRY_INTERVAL,
+};
+use crate::port_conflicts::{find_conflicts, in_use_on_host, requested_ports, PendingUp};
+use crate::ps::{self, container_row};
+use crate::read_only::{self, READ_ONLY_MESSAGE};
+use crate::registry;
+use crate::resources::{self, ResourceLimits, RESTART_POLICIES};
+use crate::restarts::{self, RestartInfo, RestartTracker};
+use crate::scan::{
+    compose_file_set, compose_sort_key, is_compose_file, is_compose_override, is_dockerfile, Scan,
+    ScanSettings,
+};
+use crate::signals::{parse_signal, COMMON_SIGNALS, SIGKILL};
+use crate::split_view::{Pane, SplitView};
+use crate::stats::{self, StatsHistory, StatsStream, DEFAULT_STATS_SAMPLES};
+use crate::system_info::{self, DaemonInfo};
+use crate::tarballs;
+use crate::theme::Theme;
+use crate::time_format::{format_age, format_timestamp, unix_now, uptime};
+use crate::top::{self, Resort, Resource};
+use crate::ui_state::{restore_scroll, ScrollRestore, UiStateCache};
+use crate::unused_images::{is_removable, unused_tags};
 use crate::utils::{
-    build_docker_image, kill_container, kill_containers, remove_container, remove_containers,
-    run_docker_compose_up,
+    build_docker_image, check_image_tag, commit_container, compose_project,
+    compose_project_location, compose_service, container_matches, create_and_start_container,
+    dockerfile_stages, format_size, image_history, is_dangling, parse_port_mappings, parse_size,
+    run_docker_compose, run_docker_compose_project, scale_compose_service, service_replicas,
+    suggested_tag, tag_image, ComposeCommand, ComposeProject, ContainerSort,
 };
+use crate::volumes::{self, mounting_containers, VolumeListing};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AppView {
+    Containers,
+    Composes,
+    Dockerfiles,
+    Images,
+    Networks,
+    Volumes,
+}
+
+#[derive(Clone)]
+pub struct ContainerGroup {
+    // None for containers that were not started by docker compose
+    pub project: Option<String>,
+    pub ids: Vec<String>,
+}
+
+// Which containers the "All" buttons act on
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BulkScope {
+    All,
+    // The containers the list filter shows, everything while it is empty
+    Filter,
+    Project(String),
+}
+
+impl BulkScope {
+    pub fn label(&self) -> String {
+        match self {
+            BulkScope::All => "all containers".to_string(),
+            BulkScope::Filter => "shown containers".to_string(),
+            BulkScope::Project(project) => format!("project {}", project),
+        }
+    }
+}
+
+pub struct DoctorStep {
+    pub step: FixStep,
+    pub selected: bool,
+    pub result: Option<Result<String, String>>,
+}
+
+// Diagnosis and repair of a compose project left behind by an interrupted run
+pub struct DoctorState {
+    pub project: String,
+    pub plan: Option<oneshot::Receiver<Result<Vec<FixStep>, String>>>,
+    pub steps: Vec<DoctorStep>,
+    pub error: Option<String>,
+    pub results: Option<mpsc::UnboundedReceiver<(usize, Result<String, String>)>>,
+}
+
+// Self-diagnostics overlay, the same checks as `dockerrs doctor`
+#[derive(Default)]
+pub struct DiagnosticsState {
+    pub pending: Option<oneshot::Receiver<Vec<CheckResult>>>,
+    pub results: Vec<CheckResult>,
+}
+
+// Layers of one image, to find out which step made it big
+pub struct ImageHistoryState {
+    pub image: String,
+    pub pending: Option<oneshot::Receiver<Result<Vec<HistoryResponseItem>, String>>>,
+    pub layers: Vec<HistoryResponseItem>,
+    pub selected: Option<usize>,
+    pub error: Option<String>,
+}
+
+impl ImageHistoryState {
+    pub fn load(image: String) -> Self {
+        let (sender, receiver) = oneshot::channel();
+        let name = image.clone();
+        tokio::spawn(async move {
+            let _ = sender.send(image_history(name).await);
+        });
+        Self {
+            image,
+            pending: Some(receiver),
+            layers: Vec::new(),
+            selected: None,
+            error: None,
+        }
+    }
+}
+
+// Env, entrypoint and command of a container, kept until its state changes
+pub struct DetailsState {
+    pub state: Option<String>,
+    pub pending: Option<oneshot::Receiver<Result<ContainerDetails, String>>>,
+    pub details: ContainerDetails,
+    pub error: Option<String>,
+    // The restart policy as picked in the host config section, applied with its button
+    pub restart_policy: String,
+    pub restart_max_retries: i64,
+    pub restart_update: Option<oneshot::Receiver<Result<String, String>>>,
+}
+
+impl DetailsState {
+    pub fn load(container_id: String, state: Option<String>) -> Self {
+        let (sender, receiver) = oneshot::channel();
+        tokio::spawn(async move {
+            let _ = sender.send(details::container_details(container_id).await);
+        });
+        Self {
+            state,
+            pending: Some(receiver),
+            details: ContainerDetails::default(),
+            error: None,
+            restart_policy: String::new(),
+            restart_max_retries: 0,
+            restart_update: None,
+        }
+    }
+}
+
+// Wasted space per layer of an image, read from its save tarball. Dropping it stops the
+// analysis.
+pub struct ImageAnalysisState {
+    pub image: String,
+    pub pending: Option<oneshot::Receiver<Result<AnalysisReport, String>>>,
+    pub progress_receiver: mpsc::UnboundedReceiver<String>,
+    pub progress: String,
+    pub report: Option<AnalysisReport>,
+    pub selected_layer: Option<usize>,
+    pub error: Option<String>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl ImageAnalysisState {
+    pub fn start(image: String) -> Self {
+        let (sender, receiver) = oneshot::channel();
+        let (progress_sender, progress_receiver) = mpsc::unbounded_channel();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let name = image.clone();
+        let flag = cancelled.clone();
+        tokio::spawn(async move {
+            let _ = sender.send(image_analysis::analyze_image(name, progress_sender, flag).await);
+        });
+        Self {
+            image,
+            pending: Some(receiver),
+            progress_receiver,
+            progress: "Exporting the image".to_string(),
+            report: None,
+            selected_layer: None,
+            error: None,
+            cancelled,
+        }
+    }
+}
+
+impl Drop for ImageAnalysisState {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+// The attach window of one container, the session arrives once the daemon accepted it
+pub struct AttachState {
+    pub name: String,
+    pub pending: Option<oneshot::Receiver<Result<AttachSession, String>>>,
+    pub session: Option<AttachSession>,
+    pub error: Option<String>,
+}
+
+impl AttachState {
+    pub fn start(container_id: String, name: String, detach_keys: String) -> Self {
+        let (sender, receiver) = oneshot::channel();
+        let session_name = name.clone();
+        tokio::spawn(async move {
+            let _ = sender.send(attach::attach(container_id, session_name, detach_keys).await);
+        });
+        Self {
+            name,
+            pending: Some(receiver),
+            session: None,
+            error: None,
+        }
+    }
+}
+
+// `docker system df`, fetched when the window opens or on Refresh since it is expensive
+pub struct DiskUsageState {
+    pub pending: Option<oneshot::Receiver<Result<Vec<CategoryUsage>, String>>>,
+    pub usage: Vec<CategoryUsage>,
+    pub error: Option<String>,
+    pub confirm: Option<Category>,
+    pub pruning: Option<(Category, oneshot::Receiver<Result<String, String>>)>,
+    // Build cache prune settings, the size to keep as typed, like "10GB", empty for none
+    pub keep_storage: String,
+    pub prune_all_cache: bool,
+}
+
+impl DiskUsageState {
+    pub fn load() -> Self {
+        let mut state = Self {
+            pending: None,
+            usage: Vec::new(),
+            error: None,
+            confirm: None,
+            pruning: None,
+            keep_storage: String::new(),
+            prune_all_cache: false,
+        };
+        state.refresh();
+        state
+    }
+
+    pub fn refresh(&mut self) {
+        let (sender, receiver) = oneshot::channel();
+        tokio::spawn(async move {
+            let _ = sender.send(disk_usage::disk_usage().await);
+        });
+        self.pending = Some(receiver);
+    }
+}
+
+// Daemon version and `docker info`, fetched again whenever the window opens or on Refresh
+pub struct SystemInfoState {
+    pub pending: Option<oneshot::Receiver<Result<DaemonInfo, String>>>,
+    pub info: Option<DaemonInfo>,
+    pub error: Option<String>,
+}
+
+impl SystemInfoState {
+    pub fn load() -> Self {
+        let mut state = Self {
+            pending: None,
+            info: None,
+            error: None,
+        };
+        state.refresh();
+        state
+    }
+
+    pub fn refresh(&mut self) {
+        let (sender, receiver) = oneshot::channel();
+        tokio::spawn(async move {
+            let _ = sender.send(system_info::daemon_info().await);
+        });
+        self.pending = Some(receiver);
+    }
+}
+
+// The volumes view, listed when it is first shown and on Refresh. Sizes come separately
+// since the daemon walks every volume for them.
+pub struct VolumesState {
+    pub pending: Option<oneshot::Receiver<Result<Vec<Volume>, String>>>,
+    pub volumes: Vec<Volume>,
+    pub sizes_pending: Option<oneshot::Receiver<Result<HashMap<String, i64>, String>>>,
+    pub sizes: HashMap<String, i64>,
+    pub error: Option<String>,
+    pub selected: Option<String>,
+    pub browse: Option<VolumeBrowse>,
+}
+
+impl VolumesState {
+    pub fn load() -> Self {
+        let mut state = Self {
+            pending: None,
+            volumes: Vec::new(),
+            sizes_pending: None,
+            sizes: HashMap::new(),
+            error: None,
+            selected: None,
+            browse: None,
+        };
+        state.refresh();
+        state
+    }
+
+    pub fn refresh(&mut self) {
+        let (sender, receiver) = oneshot::channel();
+        tokio::spawn(async move {
+            let _ = sender.send(volumes::list_volumes().await);
+        });
+        self.pending = Some(receiver);
+        let (sender, receiver) = oneshot::channel();
+        tokio::spawn(async move {
+            let _ = sender.send(volumes::volume_sizes().await);
+        });
+        self.sizes_pending = Some(receiver);
+    }
+}
+
+// Top-level entries of a volume, listed from a helper container. The helper is cleaned up
+// by the task, also when this is dropped before it is done.
+pub struct VolumeBrowse {
+    pub volume: String,
+    pub progress: mpsc::UnboundedReceiver<String>,
+    pub step: String,
+    pub pending: Option<oneshot::Receiver<Result<VolumeListing, String>>>,
+    pub listing: Option<VolumeListing>,
+    pub error: Option<String>,
+}
+
+impl VolumeBrowse {
+    pub fn start(volume: String, errors: ErrorSender) -> Self {
+        let (progress_sender, progress) = mpsc::unbounded_channel();
+        let (sender, receiver) = oneshot::channel();
+        let name = volume.clone();
+        tokio::spawn(async move {
+            let _ = sender.send(volumes::browse_volume(name, progress_sender, errors).await);
+        });
+        Self {
+            volume,
+            progress,
+            step: String::new(),
+            pending: Some(receiver),
+            listing: None,
+            error: None,
+        }
+    }
+}
+
+// The selected compose file, parsed once and checked with `docker compose config` in the
+// background
+pub struct ComposePreview {
+    pub path: PathBuf,
+    pub content: String,
+    pub parsed: Result<ComposeFile, String>,
+    pub pending: Option<oneshot::Receiver<Result<(), String>>>,
+    pub validation: Option<Result<(), String>>,
+    // Shows the file with its variables substituted, the file itself is left alone
+    pub interpolated: bool,
+    // From the .env next to the file and the environment, read when the file is selected
+    pub variables: HashMap<String, String>,
+}
+
+impl ComposePreview {
+    pub fn load(path: PathBuf) -> Self {
+        let (content, parsed) = match std::fs::read_to_string(&path) {
+            Ok(content) => {
+                let parsed = ComposeFile::parse(&content);
+                (content, parsed)
+            }
+            Err(e) => (
+                String::new(),
+                Err(format!("Failed to read {:?}: {}", path, e)),
+            ),
+        };
+        let (sender, receiver) = oneshot::channel();
+        let file = path.clone();
+        tokio::spawn(async move {
+            let _ = sender.send(compose_file::validate(&file).await);
+        });
+        let variables = interpolation::compose_variables(&path);
+        Self {
+            path,
+            content,
+            parsed,
+            pending: Some(receiver),
+            validation: None,
+            interpolated: false,
+            variables,
+        }
+    }
+
+    // Lines the YAML parser or compose reported an error on
+    pub fn error_lines(&self) -> Vec<usize> {
+        let mut lines = Vec::new();
+        if let Err(e) = &self.parsed {
+            lines.extend(error_lines(e));
+        }
+        if let Some(Err(e)) = &self.validation {
+            lines.extend(error_lines(e));
+        }
+        lines
+    }
+}
+
+// The since/until prompt of the logs panel, it stays open until the range parses
+#[derive(Default)]
+pub struct LogRangeForm {
+    pub since: String,
+    pub until: String,
+    pub error: Option<String>,
+}
+
+// Logs of one container within a time range, shown instead of the polled tail
+pub struct RangeLogs {
+    pub container_id: String,
+    pub range: LogRange,
+    pub pending: Option<oneshot::Receiver<Result<String, String>>>,
+    pub logs: String,
+    pub error: Option<String>,
+}
+
+impl RangeLogs {
+    pub fn load(container_id: String, range: LogRange) -> Self {
+        let (sender, receiver) = oneshot::channel();
+        let (id, fetched) = (container_id.clone(), range.clone());
+        tokio::spawn(async move {
+            let _ = sender.send(log_range::fetch_logs(id, fetched).await);
+        });
+        Self {
+            container_id,
+            range,
+            pending: Some(receiver),
+            logs: String::new(),
+            error: None,
+        }
+    }
+}
+
+// Sections of the details panel, one is shown at a time
+const DETAIL_FIELDS: &[&str] = &[
+    "Command",
+    "Environment",
+    "Ports",
+    "Mounts",
+    "Networks",
+    "Labels",
+];
+
+// Keys in the disk usage window that ask to prune a category
+const PRUNE_KEYS: [(egui::Key, Category); 4] = [
+    (egui::Key::I, Category::Images),
+    (egui::Key::C, Category::Containers),
+    (egui::Key::V, Category::Volumes),
+    (egui::Key::B, Category::BuildCache),
+];
+
+// Files changed in a container's writable layer, like `docker diff`
+pub struct DiffState {
+    pub name: String,
+    pub pending: Option<oneshot::Receiver<Result<ContainerChanges, String>>>,
+    pub changes: ContainerChanges,
+    pub search: String,
+    // Grouped rows for `search`, rebuilt only when it changes since a diff can be huge
+    pub rows: Vec<DiffRow>,
+    pub rows_search: Option<String>,
+    pub error: Option<String>,
+}
+
+impl DiffState {
+    pub fn load(container_id: String, name: String) -> Self {
+        let (sender, receiver) = oneshot::channel();
+        tokio::spawn(async move {
+            let _ = sender.send(container_diff::container_changes(container_id).await);
+        });
+        Self {
+            name,
+            pending: Some(receiver),
+            changes: Vec::new(),
+            search: String::new(),
+            rows: Vec::new(),
+            rows_search: None,
+            error: None,
+        }
+    }
+}
+
+// A start, stop or kill sent for a container, shown on its row until it is done
+pub struct PendingAction {
+    pub action: ContainerAction,
+    pub since: Instant,
+    // The container state when the action was sent, a different one means it took effect
+    pub state: Option<String>,
+    pub done: oneshot::Receiver<()>,
+}
+
+// Actions without an outcome after this long stop being shown as pending
+pub const ACTION_TIMEOUT: Duration = Duration::from_secs(30);
+
+// Recent health probes of a container, fetched on demand
+pub struct HealthLogState {
+    pub container_id: String,
+    pub pending: Option<oneshot::Receiver<Result<Vec<HealthcheckResult>, String>>>,
+    pub entries: Vec<HealthcheckResult>,
+    pub error: Option<String>,
+}
+
+impl HealthLogState {
+    pub fn load(container_id: String) -> Self {
+        let (sender, receiver) = oneshot::channel();
+        let id = container_id.clone();
+        tokio::spawn(async move {
+            let _ = sender.send(health::health_log(id).await);
+        });
+        Self {
+            container_id,
+            pending: Some(receiver),
+            entries: Vec::new(),
+            error: None,
+        }
+    }
+}
+
+// Memory, CPU and restart policy of a running container, as `docker update` changes them
+pub struct ResourceForm {
+    pub container_id: String,
+    pub name: String,
+    pub loading: Option<oneshot::Receiver<Result<ResourceLimits, String>>>,
+    // As loaded, to tell which fields were changed
+    pub current: ResourceLimits,
+    pub memory: String,
+    pub cpus: String,
+    pub restart_policy: String,
+    pub error: Option<String>,
+    pub result: Option<oneshot::Receiver<Result<String, String>>>,
+}
+
+impl ResourceForm {
+    pub fn load(container_id: String, name: String) -> Self {
+        let (sender, receiver) = oneshot::channel();
+        let id = container_id.clone();
+        tokio::spawn(async move {
+            let _ = sender.send(resources::current_limits(id).await);
+        });
+        Self {
+            container_id,
+            name,
+            loading: Some(receiver),
+            current: ResourceLimits::default(),
+            memory: String::new(),
+            cpus: String::new(),
+            restart_policy: String::new(),
+            error: None,
+            result: None,
+        }
+    }
+}
+
+// Read-only walk through a container's filesystem
+pub struct FileBrowser {
+    pub container_id: String,
+    pub name: String,
+    pub path: String,
+    pub pending: Option<oneshot::Receiver<Result<Vec<FileEntry>, String>>>,
+    pub entries: Vec<FileEntry>,
+    pub error: Option<String>,
+    // Host directory downloads are extracted into and the host path to upload
+    pub host_dir: String,
+    pub upload_path: String,
+    // Container path and host destination of a download that would overwrite something
+    pub confirm_overwrite: Option<(String, PathBuf)>,
+}
+
+impl FileBrowser {
+    pub fn new(container_id: String, name: String) -> Self {
+        let host_dir = std::env::current_dir()
+            .map(|dir| dir.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let mut browser = Self {
+            container_id,
+            name,
+            path: "/".to_string(),
+            pending: None,
+            entries: Vec::new(),
+            error: None,
+            host_dir,
+            upload_path: String::new(),
+            confirm_overwrite: None,
+        };
+        browser.open("/".to_string());
+        browser
+    }
+
+    pub fn open(&mut self, path: String) {
+        let (sender, receiver) = oneshot::channel();
+        let id = self.container_id.clone();
+        let listed = path.clone();
+        tokio::spawn(async move {
+            let _ = sender.send(files::list_path(id, listed).await);
+        });
+        self.path = path;
+        self.pending = Some(receiver);
+        self.error = None;
+    }
+}
+
+// Repository, tag and comment for `docker commit` of a container
+pub struct CommitForm {
+    pub container_id: String,
+    pub name: String,
+    pub repo: String,
+    pub tag: String,
+    pub comment: String,
+    pub error: Option<String>,
+    pub result: Option<oneshot::Receiver<Result<String, String>>>,
+}
+
+impl CommitForm {
+    pub fn new(container_id: String, name: String) -> Self {
+        Self {
+            container_id,
+            name,
+            repo: String::new(),
+            tag: String::new(),
+            comment: String::new(),
+            error: None,
+            result: None,
+        }
+    }
+}
+
+// Replica count for a compose service, applied with `docker compose up --scale`
+pub struct ScaleForm {
+    pub project: ComposeProject,
+    pub service: String,
+    pub replicas: u32,
+}
+
+// Tarball transfers that need a path on the host first
+pub enum TarballAction {
+    ExportContainer { id: String, name: String },
+    SaveImage(String),
+    LoadImage,
+}
+
+pub struct TarballPrompt {
+    pub action: TarballAction,
+    pub path: String,
+}
+
+impl TarballPrompt {
+    pub fn new(action: TarballAction) -> Self {
+        // Suggest a file name in the working directory for exports
+        let path = match &action {
+            TarballAction::ExportContainer { name, .. } => format!("{}.tar", name),
+            TarballAction::SaveImage(image) => {
+                format!("{}.tar", image.replace(['/', ':'], "_"))
+            }
+            TarballAction::LoadImage => String::new(),
+        };
+        Self { action, path }
+    }
+}
+
+// Credentials for a push, empty fields mean the ones stored by `docker login` are used
+pub struct PushForm {
+    pub image: String,
+    pub username: String,
+    pub password: String,
+}
+
+impl PushForm {
+    pub fn new(image: String) -> Self {
+        Self {
+            image,
+            username: String::new(),
+            password: String::new(),
+        }
+    }
+}
+
+// Create form of the networks view, which also shows errors of removals in-line
+pub struct NetworkForm {
+    pub name: String,
+    pub driver: String,
+    pub error: Option<String>,
+    pub result: Option<oneshot::Receiver<Result<String, String>>>,
+}
+
+impl Default for NetworkForm {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            driver: NETWORK_DRIVERS[0].to_string(),
+            error: None,
+            result: None,
+        }
+    }
+}
+
+// Form state for creating and starting a container from an image
+pub struct RunImageForm {
+    pub image: String,
+    pub name: String,
+    pub ports: String,
+    pub env: String,
+    pub error: Option<String>,
+    pub result: Option<oneshot::Receiver<Result<String, String>>>,
+}
+
+impl RunImageForm {
+    pub fn new(image: String) -> Self {
+        Self {
+            image,
+            name: String::new(),
+            ports: String::new(),
+            env: String::new(),
+            error: None,
+            result: None,
+        }
+    }
+}
+
+// Build options for the selected Dockerfile, reset whenever another one is selected
+pub struct BuildForm {
+    pub dockerfile: PathBuf,
+    pub tag: String,
+    pub args: Vec<(String, String)>,
+    pub target: Option<String>,
+    // Empty means the directory containing the Dockerfile
+    pub context: String,
+    pub error: Option<String>,
+}
+
+impl BuildForm {
+    pub fn new(dockerfile: PathBuf, tag: String) -> Self {
+        Self {
+            dockerfile,
+            tag,
+            args: Vec::new(),
+            target: None,
+            context: String::new(),
+            error: None,
+        }
+    }
+}
+
+pub struct DockerViewerApp {
+    pub receiver: mpsc::Receiver<ContainerSnapshot>,
+    pub containers: ContainerSnapshot,
+    // Counted once per snapshot, for the window title and the Containers tab
+    pub container_counts: ContainerCounts,
+    // Names of the containers whose display name is not unique, by ID, from each snapshot
+    pub unique_names: HashMap<String, String>,
+    // The title last sent to the window, it is only sent again when it changes
+    pub window_title: Option<String>,
+    // Container ID, so the selection survives a rename
+    pub selected_container: Option<String>,
+    // Scroll position, follow flag and search per container ID
+    pub container_ui_state: UiStateCache,
+    pub logs_shown_for: Option<String>,
+    pub log_range_form: Option<LogRangeForm>,
+    pub range_logs: Option<RangeLogs>,
+    pub scroll_notice: Option<String>,
+    // Set by the arrow and page keys, applied to the logs panel on the next frame
+    pub log_scroll_request: Option<LogScroll>,
+    // Shift+V picks log lines of the selected container to copy with Y
+    pub log_selection: Option<LogSelection>,
+    pub log_selection_request: Option<SelectionRequest>,
+    // Log lines the poller fetches per container, shared with it
+    pub log_tail: Arc<AtomicUsize>,
+    // Container IDs sorted by name, and the same IDs grouped by compose project
+    pub container_ids: Vec<String>,
+    pub container_groups: Vec<ContainerGroup>,
+    pub group_by_project: bool,
+    // Only containers matching this are listed, see `container_matches`
+    pub container_filter: String,
+    // Label conditions the poller lists containers by, shared with it
+    pub label_filter: Arc<Mutex<LabelFilter>>,
+    pub label_filter_input: String,
+    // Whether the poller reaches the daemon, shared with it
+    pub connection: Arc<Mutex<Connection>>,
+    // Quick switch between the saved label filters, with the name to save the active one as
+    pub filter_switcher: Option<String>,
+    // Open with Ctrl+P or `:`
+    pub command_palette: Option<CommandPalette>,
+    pub config: Config,
+    pub config_path: Option<PathBuf>,
+    pub selected_project: Option<String>,
+    // Container ID to select once it shows up in the next poll
+    pub pending_selection: Option<String>,
+    // Name or ID prefix from --follow, whose logs are opened with the first containers
+    pub follow_on_start: Option<String>,
+    // Set by --check-updates, the image update check starts with the first containers
+    pub check_updates_on_start: bool,
+    // ID of the container to remove once the Delete key is confirmed
+    pub confirm_remove: Option<String>,
+    pub confirm_recreate: Option<String>,
+    // Stop, kill or remove every container in `bulk_scope` once confirmed
+    pub confirm_bulk: Option<ContainerAction>,
+    // Days of the unused image cleanup being confirmed, younger unused tags are kept
+    pub confirm_image_cleanup: Option<u64>,
+    pub bulk_scope: BulkScope,
+    // Set when the keyboard moved the selection, the row is scrolled into view once
+    pub scroll_to_selection: bool,
+    pub images_receiver: mpsc::Receiver<Vec<ImageSummary>>,
+    pub images: Vec<ImageSummary>,
+    pub networks_receiver: mpsc::Receiver<Vec<Network>>,
+    pub networks: Vec<Network>,
+    // Network ID, so the selection survives refreshes
+    pub selected_network: Option<String>,
+    // Container ID of the selected row in the network details
+    pub selected_network_container: Option<String>,
+    pub network_form: NetworkForm,
+    pub selected_image: Option<String>,
+    pub retag_input: String,
+    pub image_history: Option<ImageHistoryState>,
+    pub image_analysis: Option<ImageAnalysisState>,
+    pub attach: Option<AttachState>,
+    pub disk_usage: Option<DiskUsageState>,
+    pub container_diff: Option<DiffState>,
+    pub volumes: Option<VolumesState>,
+    // Key sequence that detaches an attached container, in Docker's format
+    pub detach_keys: String,
+    pub push_form: Option<PushForm>,
+    pub health_log: Option<HealthLogState>,
+    // Interleaved logs of every container of a compose project
+    pub merged_logs: Option<MergedLogs>,
+    // Streamed logs of the selected container while Follow is on in the logs panel
+    pub followed_logs: Option<FollowedLogs>,
+    // Where the streams of containers followed before stopped, by container ID
+    pub stopped_logs: HashMap<String, StoppedLogs>,
+    // `|` shows the list and the live logs of the selected container side by side
+    pub split_view: SplitView,
+    // Lines the merged logs window keeps before dropping the oldest
+    pub max_log_lines: usize,
+    // Tasks that live as long as the window, aborted on exit
+    pub background_tasks: Vec<JoinHandle<()>>,
+    // Cancelled on exit, which stops the poller
+    pub shutdown: CancellationToken,
+    // Details panel of the selected container, inspected once per container and state
+    pub show_details: bool,
+    // Which of `DETAIL_FIELDS` the details panel shows
+    pub details_field: &'static str,
+    // Network name selected in the details, N opens it
+    pub details_network: Option<String>,
+    pub reveal_secrets: bool,
+    pub container_details: HashMap<String, DetailsState>,
+    pub pending_actions: HashMap<String, PendingAction>,
+    // CPU and memory of containers whose details were shown, only the selected one is sampled
+    pub stats_history: HashMap<String, StatsHistory>,
+    pub stats_streams: HashMap<String, StatsStream>,
+    // The top CPU and memory users, Shift+T
+    pub top_leaderboard: bool,
+    pub top_resort: Resort,
+    pub stats_samples: usize,
+    pub resource_form: Option<ResourceForm>,
+    pub file_browser: Option<FileBrowser>,
+    pub commit_form: Option<CommitForm>,
+    pub scale_form: Option<ScaleForm>,
+    pub tarball_prompt: Option<TarballPrompt>,
+    pub run_image_form: Option<RunImageForm>,
+    pub doctor: Option<DoctorState>,
+    pub diagnostics: Option<DiagnosticsState>,
+    pub system_info: Option<SystemInfoState>,
+    pub compose_files: Vec<PathBuf>,
+    pub selected_compose_for_preview: Option<PathBuf>,
+    pub compose_preview: Option<ComposePreview>,
+    // Compose commands whose child process is still alive, per compose file
+    pub running_compose_commands: HashMap<PathBuf, (ComposeCommand, JoinHandle<()>)>,
+    pub confirm_compose_down: Option<PathBuf>,
+    // A compose up waiting on ports other containers or programs hold
+    pub pending_up: Option<PendingUp>,
+    pub current_view: AppView,
+    pub dockerfiles: Vec<PathBuf>,
+    // Where to look for compose files and Dockerfiles, and the walks in flight
+    pub scan_settings: ScanSettings,
+    pub compose_scan: Option<Scan>,
+    pub dockerfile_scan: Option<Scan>,
+    pub selected_dockerfile_for_preview: Option<PathBuf>,
+    pub build_form: Option<BuildForm>,
+    pub builds: BuildHistory,
+    // Dockerfile and start of the build whose output replaces the preview
+    pub shown_build: Option<(PathBuf, Instant)>,
+    pub build_settings: BuildSettings,
+    pub capabilities_receiver: oneshot::Receiver<Capabilities>,
+    pub capabilities: Capabilities,
+    // Results of background actions, shown in the status bar
+    pub result_sender: mpsc::UnboundedSender<Result<String, String>>,
+    pub result_receiver: mpsc::UnboundedReceiver<Result<String, String>>,
+    pub status_message: Option<Result<String, String>>,
+    // Output lines of external docker commands, streamed as they run
+    pub output_sender: mpsc::UnboundedSender<String>,
+    pub output_receiver: mpsc::UnboundedReceiver<String>,
+    pub command_output: Vec<String>,
+    // Outcomes of actions on one container, by container ID
+    pub action_result_sender: mpsc::UnboundedSender<(String, Result<String, String>)>,
+    pub action_result_receiver: mpsc::UnboundedReceiver<(String, Result<String, String>)>,
+    pub error_sender: ErrorSender,
+    pub error_receiver: mpsc::UnboundedReceiver<DockerrsError>,
+    // Results and failures of background work, shown in the corner of the window
+    pub notifications: Notifications,
+    // Container state changes become notifications too, for screen readers to announce
+    pub screen_reader: bool,
+    pub show_notification_history: bool,
+    // Labels of the queued actions dropped on exit, for the binary to warn about
+    pub dropped_on_exit: Arc<Mutex<Vec<String>>>,
+    pub grace_period_enabled: bool,
+    pub action_queue: ActionQueue,
+    // Names of the containers whose crashes are alerted, and the alerts so far
+    pub watched: HashSet<String>,
+    pub alerts: Vec<Alert>,
+    pub alert_limiter: AlertLimiter,
+    // Whether the window had focus last frame, crashes only ring and flash while it has not
+    pub window_focused: bool,
+    // Frames left of the tab bar flash after a crash
+    pub crash_flash_frames: u32,
+    pub alert_sender: mpsc::UnboundedSender<Alert>,
+    pub alert_receiver: mpsc::UnboundedReceiver<Alert>,
+    pub show_alerts: bool,
+    pub keep_alive: KeepAlive,
+    // Whether the images of running containers are behind their registry, checked on U
+    pub image_updates: ImageUpdates,
+    // Colors everything is drawn with, see --theme
+    pub theme: Theme,
+    // From the window width, updated every frame
+    pub layout: LayoutMode,
+    pub container_sort: ContainerSort,
+    // Restart counts and OOM kills, inspected when a container's status changes
+    pub restarts: RestartTracker,
+    pub restart_sender: mpsc::UnboundedSender<(String, Result<RestartInfo, String>)>,
+    pub restart_receiver: mpsc::UnboundedReceiver<(String, Result<RestartInfo, String>)>,
+    // The popup picking the container row columns, V opens it
+    pub column_chooser: bool,
+    // Typed into the signal picker next to Kill
+    pub kill_signal_input: String,
+    // Toggles of Remove and its confirmation, Force is cleared after each removal
+    pub remove_options: RemoveOptions,
+    // What was sent to the daemon this session and what came back, H shows it
+    pub action_history: ActionHistory,
+    pub show_action_history: bool,
+}
+
+impl DockerViewerApp {
+    // Takes the receiving ends of the poller channels, nothing is scanned or polled until
+    // the caller starts it
+    pub fn new(
+        receiver: mpsc::Receiver<ContainerSnapshot>,
+        images_receiver: mpsc::Receiver<Vec<ImageSummary>>,
+        networks_receiver: mpsc::Receiver<Vec<Network>>,
+        capabilities_receiver: oneshot::Receiver<Capabilities>,
+        scan_settings: ScanSettings,
+        build_settings: BuildSettings,
+    ) -> Self {
+        let (result_sender, result_receiver) = mpsc::unbounded_channel();
+        let (output_sender, output_receiver) = mpsc::unbounded_channel();
+        let (alert_sender, alert_receiver) = mpsc::unbounded_channel();
+        let (error_sender, error_receiver) = mpsc::unbounded_channel();
+        let (action_result_sender, action_result_receiver) = mpsc::unbounded_channel();
+        let (restart_sender, restart_receiver) = mpsc::unbounded_channel();
+        Self {
+            receiver,
+            containers: HashMap::new(),
+            container_counts: ContainerCounts::default(),
+            window_title: None,
+            unique_names: HashMap::new(),
+            selected_container: None,
+            container_ui_state: UiStateCache::default(),
+            logs_shown_for: None,
+            log_range_form: None,
+            range_logs: None,
+            scroll_notice: None,
+            log_tail: Arc::new(AtomicUsize::new(LOG_TAIL)),
+            container_ids: Vec::new(),
+            container_groups: Vec::new(),
+            group_by_project: false,
+            container_filter: String::new(),
+            label_filter: Arc::new(Mutex::new(LabelFilter::default())),
+            connection: Arc::new(Mutex::new(Connection::default())),
+            label_filter_input: String::new(),
+            filter_switcher: None,
+            command_palette: None,
+            config: Config::default(),
+            config_path: None,
+            selected_project: None,
+            pending_selection: None,
+            follow_on_start: None,
+            check_updates_on_start: false,
+            confirm_remove: None,
+            confirm_recreate: None,
+            confirm_bulk: None,
+            confirm_image_cleanup: None,
+            bulk_scope: BulkScope::Filter,
+            scroll_to_selection: false,
+            log_scroll_request: None,
+            log_selection: None,
+            log_selection_request: None,
+            images_receiver,
+            networks_receiver,
+            networks: Vec::new(),
+            selected_network: None,
+            selected_network_container: None,
+            network_form: NetworkForm::default(),
+            images: Vec::new(),
+            selected_image: None,
+            retag_input: String::new(),
+            image_history: None,
+            image_analysis: None,
+            attach: None,
+            disk_usage: None,
+            container_diff: None,
+            volumes: None,
+            detach_keys: DEFAULT_DETACH_KEYS.to_string(),
+            push_form: None,
+            health_log: None,
+            merged_logs: None,
+            followed_logs: None,
+            stopped_logs: HashMap::new(),
+            split_view: SplitView::default(),
+            max_log_lines: DEFAULT_MAX_LOG_LINES,
+            background_tasks: Vec::new(),
+            shutdown: CancellationToken::new(),
+            show_details: false,
+            details_field: DETAIL_FIELDS[0],
+            details_network: None,
+            reveal_secrets: false,
+            container_details: HashMap::new(),
+            pending_actions: HashMap::new(),
+            stats_history: HashMap::new(),
+            stats_streams: HashMap::new(),
+            top_leaderboard: false,
+            top_resort: Resort::default(),
+            stats_samples: DEFAULT_STATS_SAMPLES,
+            resource_form: None,
+            file_browser: None,
+            commit_form: None,
+            scale_form: None,
+            tarball_prompt: None,
+            run_image_form: None,
+            doctor: None,
+            diagnostics: None,
+            system_info: None,
+            current_view: AppView::Containers,
+            selected_compose_for_preview: None,
+            compose_preview: None,
+            running_compose_commands: HashMap::new(),
+            confirm_compose_down: None,
+            pending_up: None,
+            compose_files: Vec::new(),
+            dockerfiles: Vec::new(),
+            scan_settings,
+            compose_scan: None,
+            dockerfile_scan: None,
+            selected_dockerfile_for_preview: None,
+            build_form: None,
+            builds: BuildHistory::default(),
+            shown_build: None,
+            build_settings,
+            capabilities_receiver,
+            capabilities: Capabilities::default(),
+            result_sender,
+            result_receiver,
+            status_message: None,
+            output_sender,
+            output_receiver,
+            command_output: Vec::new(),
+            error_sender,
+            error_receiver,
+            action_result_sender,
+            action_result_receiver,
+            notifications: Notifications::default(),
+            screen_reader: false,
+            show_notification_history: false,
+            dropped_on_exit: Arc::default(),
+            grace_period_enabled: false,
+            action_queue: ActionQueue::default(),
+            watched: HashSet::new(),
+            alerts: Vec::new(),
+            alert_limiter: AlertLimiter::default(),
+            window_focused: true,
+            crash_flash_frames: 0,
+            alert_sender,
+            alert_receiver,
+            show_alerts: false,
+            keep_alive: KeepAlive::default(),
+            image_updates: ImageUpdates::default(),
+            theme: Theme::default(),
+            layout: LayoutMode::default(),
+            container_sort: ContainerSort::default(),
+            restarts: RestartTracker::default(),
+            restart_sender,
+            restart_receiver,
+            column_chooser: false,
+            kill_signal_input: String::new(),
+            remove_options: RemoveOptions::default(),
+            action_history: ActionHistory::default(),
+            show_action_history: false,
+        }
+    }
+
+    // Drains everything background tasks sent since the last frame, without drawing
+    pub fn receive_updates(&mut self) {
+        while let Ok(containers) = self.receiver.try_recv() {
+            self.update_containers(containers);
+        }
+        while let Ok(alert) = self.alert_receiver.try_recv() {
+            self.record_alert(alert, Instant::now());
+        }
+        while let Ok(images) = self.images_receiver.try_recv() {
+            self.images = images;
+        }
+        while let Ok(mut networks) = self.networks_receiver.try_recv() {
+            networks.sort_by(|a, b| a.name.cmp(&b.name));
+            self.networks = networks;
+        }
+        self.receive_scans();
+        self.clear_finished_actions(Instant::now());
+        self.running_compose_commands
+            .retain(|_, (_, handle)| !handle.is_finished());
+        while let Ok(line) = self.output_receiver.try_recv() {
+            self.command_output.push(line);
+        }
+        while let Ok(result) = self.result_receiver.try_recv() {
+            self.report_result(result, None);
+        }
+        while let Ok((id, result)) = self.action_result_receiver.try_recv() {
+            self.report_result(result, Some(id));
+        }
+        while let Ok(error) = self.error_receiver.try_recv() {
+            let error = Err(error.to_string());
+            self.action_history.result(&error, unix_now());
+            self.notifications.push(error, None, Instant::now());
+        }
+        let (lines, results) = self.builds.receive(Instant::now());
+        self.command_output.extend(lines);
+        for result in results {
+            self.report_result(result, None);
+        }
+        if let Ok(capabilities) = self.capabilities_receiver.try_recv() {
+            self.capabilities = capabilities;
+        }
+        self.image_updates.receive(Instant::now());
+    }
+
+    // The outcome of background work goes to the status bar, a notification and the history
+    fn report_result(&mut self, result: Result<String, String>, container: Option<String>) {
+        self.action_history.result(&result, unix_now());
+        self.notifications
+            .push(result.clone(), container, Instant::now());
+        self.status_message = Some(result);
+    }
+
+    // Samples the selected container while its details are shown and it runs, and every
+    // running container while the list is sorted by CPU or memory or the leaderboard is open.
+    // Forgets the history of containers that are gone.
+    pub fn update_stats(&mut self) {
+        let running = |id: &String| {
+            self.containers
+                .get(id)
+                .is_some_and(|(summary, _)| summary.state.as_deref() == Some("running"))
+        };
+        let mut sampled: HashSet<String> = self
+            .selected_container
+            .iter()
+            .filter(|_| self.show_details)
+            .filter(|id| running(id))
+            .cloned()
+            .collect();
+        if self.container_sort.resource().is_some() || self.top_leaderboard {
+            sampled.extend(self.containers.keys().filter(|id| running(id)).cloned());
+        }
+        self.stats_streams.retain(|id, _| sampled.contains(id));
+        for id in sampled {
+            self.stats_streams
+                .entry(id.clone())
+                .or_insert_with(|| StatsStream::start(id, self.error_sender.clone()));
+        }
+        let mut received = false;
+        for (id, stream) in &mut self.stats_streams {
+            let history = self
+                .stats_history
+                .entry(id.clone())
+                .or_insert_with(|| StatsHistory::new(self.stats_samples));
+            while let Ok(sample) = stream.receiver.try_recv() {
+                history.push(sample);
+                received = true;
+            }
+        }
+        self.stats_history
+            .retain(|id, _| self.containers.contains_key(id));
+        // The selection stays on its container while the rows move, once a second at most
+        if received
+            && self.container_sort.resource().is_some()
+            && self.top_resort.due(Instant::now())
+        {
+            self.order_containers();
+        }
+    }
+
+    // Too narrow a window has no room for two halves and keeps the usual layout
+    pub fn split_active(&self) -> bool {
+        self.split_view.enabled && self.layout == LayoutMode::Wide
+    }
+
+    // Streams the logs of the selected container while Follow is on for it and no time range
+    // is shown, or always in the split view. Turning Follow off or selecting another
+    // container stops the old stream, following it again continues with its lines.
+    pub fn update_followed_logs(&mut self) {
+        let range = self.range_logs.is_some();
+        let followed = match &self.selected_container {
+            // The split view streams whatever is selected, once the selection settles
+            _ if self.split_active() => self
+                .split_view
+                .streamed(self.selected_container.as_ref(), Instant::now())
+                .filter(|_| !range)
+                .cloned(),
+            Some(id) if !range && self.container_ui_state.get_mut(id).follow => Some(id.clone()),
+            _ => None,
+        };
+        let current = self.followed_logs.as_ref().map(|logs| &logs.container_id);
+        if current != followed.as_ref() {
+            if let Some(stopped) = self.followed_logs.take() {
+                self.stopped_logs
+                    .insert(stopped.container_id.clone(), stopped.stop());
+            }
+            let tail = self.log_tail.load(Ordering::Relaxed);
+            self.followed_logs = followed.map(|id| {
+                let stopped = self.stopped_logs.remove(&id);
+                FollowedLogs::resume(id, tail, self.max_log_lines, stopped)
+            });
+        }
+        self.stopped_logs
+            .retain(|id, _| self.containers.contains_key(id));
+        if let Some(logs) = &mut self.followed_logs {
+            logs.receive();
+        }
+    }
+
+    // Inspects the containers whose status changed since their last inspect and takes in
+    // the answers, sorting again when sorted by restarts
+    pub fn update_restarts(&mut self) {
+        for id in self.restarts.due(&self.containers) {
+            let sender = self.restart_sender.clone();
+            tokio::spawn(async move {
+                let info = restarts::inspect_restarts(id.clone()).await;
+                let _ = sender.send((id, info));
+            });
+        }
+        let mut changed = false;
+        while let Ok((id, info)) = self.restart_receiver.try_recv() {
+            changed |= self.restarts.record(id, info);
+        }
+        if changed && self.container_sort == ContainerSort::Restarts {
+            self.order_containers();
+        }
+    }
+
+    // Runs an action on a container unless one is still pending for it
+    pub fn start_action(&mut self, container_id: String, action: ContainerAction) {
+        self.start_action_with(BollardDocker::connect(), container_id, action);
+    }
+
+    // `start_action` against any daemon, the outcome goes to the status bar and a notification
+    // that selects the container
+    pub fn start_action_with<D: DockerApi + 'static>(
+        &mut self,
+        docker: Result<D, String>,
+        container_id: String,
+        action: ContainerAction,
+    ) {
+        if self.pending_actions.contains_key(&container_id) {
+            return;
+        }
+        if matches!(
+            action,
+            ContainerAction::Stop | ContainerAction::Kill(_) | ContainerAction::Recreate
+        ) {
+            self.suspend_keep_alive(&container_id);
+        }
+        let state = self
+            .containers
+            .get(&container_id)
+            .and_then(|(summary, _)| summary.state.clone());
+        let target = match self.containers.get(&container_id) {
+            Some((summary, _)) => display_name(summary),
+            None => container_id.clone(),
+        };
+        self.action_history
+            .sent(action.describe(&target), unix_now());
+        let (done_sender, done) = oneshot::channel();
+        let id = container_id.clone();
+        let results = self.action_result_sender.clone();
+        tokio::spawn(async move {
+            let result = match docker {
+                Ok(docker) => run_action(&docker, action, &id).await,
+                Err(e) => Err(e),
+            };
+            let _ = done_sender.send(());
+            let _ = results.send((id, result));
+        });
+        self.pending_actions.insert(
+            container_id,
+            PendingAction {
+                action,
+                since: Instant::now(),
+                state,
+                done,
+            },
+        );
+    }
+
+    // Forgets actions that finished, changed the container's state or timed out
+    pub fn clear_finished_actions(&mut self, now: Instant) {
+        let containers = &self.containers;
+        let result_sender = &self.result_sender;
+        self.pending_actions.retain(|id, pending| {
+            let running = matches!(
+                pending.done.try_recv(),
+                Err(oneshot::error::TryRecvError::Empty)
+            );
+            let state = containers.get(id).map(|(summary, _)| &summary.state);
+            if !running || state != Some(&pending.state) {
+                return false;
+            }
+            if now.saturating_duration_since(pending.since) >= ACTION_TIMEOUT {
+                let short_id: String = id.chars().take(12).collect();
+                let _ = result_sender.send(Err(format!(
+                    "No response to {} of {} after {}s",
+                    pending.action.name(),
+                    short_id,
+                    ACTION_TIMEOUT.as_secs()
+                )));
+                return false;
+            }
+            true
+        });
+    }
+
+    // Lists the alert and notifies as configured, at most once per minute per container
+    pub fn record_alert(&mut self, alert: Alert, now: Instant) {
+        if self.alert_limiter.allow(&alert.container, now) {
+            let settings = &self.config.alerts;
+            let unnoticed = alert.transition.is_crash() && !self.window_focused;
+            if settings.bell && unnoticed {
+                eprint!("\x07");
+            }
+            if settings.flash && unnoticed {
+                self.crash_flash_frames = FLASH_FRAMES;
+            }
+            if settings.desktop {
+                let notification = alerts::notify_desktop(
+                    format!("{} {}", alert.container, alert.transition.describe()),
+                    format!("at {}", format_timestamp(alert.at)),
+                );
+                let errors = self.error_sender.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = notification.await {
+                        report(&errors, e);
+                    }
+                });
+            }
+        }
+        self.alerts.push(alert);
+        if self.alerts.len() > MAX_ALERTS {
+            self.alerts.remove(0);
+        }
+    }
+
+    // Restarts kept-alive containers whose backoff is over
+    pub fn restart_crashed(&mut self, now: Instant) {
+        for event in self.keep_alive.due(now) {
+            self.report_keep_alive(event);
+        }
+    }
+
+    fn report_keep_alive(&mut self, event: KeepAliveEvent) {
+        match event {
+            KeepAliveEvent::Restart { id, name, attempt } => {
+                self.start_action(id, ContainerAction::Start);
+                self.status_message = Some(Ok(format!(
+                    "Restarting {} after a crash (attempt {} of {})",
+                    name,
+                    attempt,
+                    RESTART_DELAYS.len()
+                )));
+            }
+            KeepAliveEvent::GaveUp { name } => {
+                self.status_message = Some(Err(format!(
+                    "Gave up restarting {} after {} attempts",
+                    name,
+                    RESTART_DELAYS.len()
+                )));
+            }
+        }
+    }
+
+    // A container stopped by hand is not restarted until it runs again
+    fn suspend_keep_alive(&mut self, container_id: &str) {
+        if let Some(name) = watch_name(&self.containers, container_id) {
+            self.keep_alive.suspend(&name);
+        }
+    }
+
+    // Keeps the container alive or stops doing so, saved in the config file by name
+    pub fn toggle_keep_alive(&mut self, container_id: &str) {
+        let Some(name) = watch_name(&self.containers, container_id) else {
+            return;
+        };
+        let kept = self.keep_alive.toggle(&name);
+        let mut names: Vec<String> = self.keep_alive.names.iter().cloned().collect();
+        names.sort();
+        self.config.keep_alive = names;
+        let message = if kept {
+            format!("Restarting {} when it crashes", name)
+        } else {
+            format!("No longer restarting {}", name)
+        };
+        self.status_message = Some(self.save_config().map(|()| message));
+    }
+
+    // Switches to the Containers view with the container selected, false when it is gone
+    // The container's name in the list, told apart from containers with the same name
+    pub fn row_name(&self, id: &str, summary: &ContainerSummary) -> String {
+        match self.unique_names.get(id) {
+            Some(name) => name.clone(),
+            None => display_name(summary),
+        }
+    }
+
+    pub fn select_container_by_id(&mut self, id: &str) -> bool {
+        if !self.containers.contains_key(id) {
+            self.status_message = Some(Err(format!("Container {} no longer exists", id)));
+            return false;
+        }
+        self.selected_container = Some(id.to_string());
+        self.scroll_to_selection = true;
+        self.current_view = AppView::Containers;
+        true
+    }
+
+    // Switches to the Networks view with the network selected, by ID or name as containers
+    // list their networks by name
+    pub fn select_network_by_id(&mut self, id_or_name: &str) -> bool {
+        let Some(id) = self
+            .networks
+            .iter()
+            .find(|network| {
+                network.id.as_deref() == Some(id_or_name)
+                    || network.name.as_deref() == Some(id_or_name)
+            })
+            .and_then(|network| network.id.clone())
+        else {
+            self.status_message = Some(Err(format!("Network {} not found", id_or_name)));
+            return false;
+        };
+        self.selected_network = Some(id);
+        self.selected_network_container = None;
+        self.current_view = AppView::Networks;
+        true
+    }
+
+    // Switches to the Images view with the image selected
+    pub fn select_image_by_id(&mut self, id: &str) -> bool {
+        if !self.images.iter().any(|image| image.id == id) {
+            self.status_message = Some(Err(format!("Image {} not found", id)));
+            return false;
+        }
+        self.selected_image = Some(id.to_string());
+        self.retag_input.clear();
+        self.current_view = AppView::Images;
+        true
+    }
+
+    // Starts or stops alerting on crashes of the container, by name
+    pub fn toggle_watch(&mut self, container_id: &str) {
+        let Some(name) = watch_name(&self.containers, container_id) else {
+            return;
+        };
+        let message = if self.watched.remove(&name) {
+            format!("Stopped watching {}", name)
+        } else {
+            self.watched.insert(name.clone());
+            format!("Watching {} for crashes", name)
+        };
+        self.status_message = Some(Ok(message));
+    }
+
+    // Lists containers by the label expression from the next poll on. An invalid expression
+    // is reported and the active filter stays.
+    pub fn apply_label_filter(&mut self, expression: &str) {
+        match LabelFilter::parse(expression) {
+            Ok(filter) => {
+                self.label_filter_input = filter.to_string();
+                *self.label_filter.lock().unwrap() = filter;
+            }
+            Err(e) => self.status_message = Some(Err(format!("Label filter: {}", e))),
+        }
+    }
+
+    pub fn save_config(&self) -> Result<(), String> {
+        match &self.config_path {
+            Some(path) => self.config.save(path),
+            None => Err("No config file, set one with --config".to_string()),
+        }
+    }
+
+    // Saves the active label filter under `name` in the config file
+    pub fn save_label_filter(&mut self, name: &str) {
+        let expression = self.label_filter.lock().unwrap().to_string();
+        self.config.save_filter(name, &expression);
+        let result = self
+            .save_config()
+            .map(|()| format!("Saved filter {}", name));
+        self.status_message = Some(result);
+    }
+}
+
+// Buttons that change something on the daemon, disabled in read-only mode
+fn mutating_button(
+    ui: &mut egui::Ui,
+    enabled: bool,
+    text: impl Into<egui::WidgetText>,
+) -> egui::Response {
+    let read_only = read_only::is_enabled();
+    let response = ui.add_enabled(enabled && !read_only, egui::Button::new(text));
+    if read_only {
+        response.on_disabled_hover_text(READ_ONLY_MESSAGE)
+    } else {
+        response
+    }
+}
+
+// Whether the binding was pressed this frame, taking it out of the input so nothing else
+// reacts to it. Typed characters are removed too, or the palette opened by `:` would start
+// with a `:` in its field.
+fn consume_binding(ctx: &egui::Context, binding: Binding) -> bool {
+    match binding {
+        Binding::Key(modifiers, key) => ctx.input_mut(|i| i.consume_key(modifiers, key)),
+        Binding::Text(wanted) => ctx.input_mut(|i| {
+            let before = i.events.len();
+            i.events
+                .retain(|event| !matches!(event, egui::Event::Text(text) if text == wanted));
+            i.events.len() != before
+        }),
+    }
+}
+
+const SPARKLINE_SIZE: egui::Vec2 = egui::vec2(240.0, 28.0);
+
+// A line of `values` scaled to the height of the widget, the largest value at the top
+fn sparkline(ui: &mut egui::Ui, values: &[f64], size: egui::Vec2, color: egui::Color32) {
+    let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+    ui.painter()
+        .rect_stroke(rect, 2.0, ui.visuals().widgets.noninteractive.bg_stroke);
+    if values.len() < 2 {
+        return;
+    }
+    let max = values
+        .iter()
+        .copied()
+        .fold(0.0, f64::max)
+        .max(f64::MIN_POSITIVE);
+    let step = rect.width() / (values.len() - 1) as f32;
+    let points = values
+        .iter()
+        .enumerate()
+        .map(|(index, value)| {
+            let y = rect.bottom() - (value / max) as f32 * rect.height();
+            egui::pos2(rect.left() + index as f32 * step, y)
+        })
+        .collect();
+    ui.painter()
+        .add(egui::Shape::line(points, egui::Stroke::new(1.5, color)));
+}
+
+// How often the UI redraws while background work may still deliver results
+const BUSY_REPAINT_INTERVAL: Duration = Duration::from_millis(100);
+
+// Runs a background action and reports its outcome to the status bar
+// Toggles of how a container is removed, with what a running container needs
+fn remove_options_ui(ui: &mut egui::Ui, options: &mut RemoveOptions, running: bool) {
+    ui.checkbox(&mut options.force, "Force (F)")
+        .on_hover_text("Remove it even while it runs, the daemon refuses otherwise");
+    ui.checkbox(&mut options.volumes, "Remove anonymous volumes (V)");
+    if running && !options.force {
+        ui.weak("It is running, removing fails without Force");
+    }
+}
+
+// The ▾ next to Kill: the common signals, or any known one typed in, returned when picked.
+// SIGKILL is the Kill button itself.
+fn signal_menu(ui: &mut egui::Ui, input: &mut String, theme: &Theme) -> Option<&'static str> {
+    let mut picked = None;
+    let menu = ui.menu_button("▾", |ui| {
+        for signal in COMMON_SIGNALS {
+            if ui.button(signal).clicked() {
+                picked = Some(signal);
+            }
+        }
+        ui.separator();
+        let parsed = parse_signal(input);
+        ui.horizontal(|ui| {
+            let response = ui.add(
+                egui::TextEdit::singleline(input)
+                    .hint_text("e.g. SIGWINCH or 28")
+                    .desired_width(140.0),
+            );
+            let entered = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            let send = ui.add_enabled(parsed.is_ok(), egui::Button::new("Send"));
+            if let Ok(signal) = parsed {
+                if send.clicked() || entered {
+                    picked = Some(signal);
+                }
+            }
+        });
+        if let (Err(e), false) = (&parsed, input.trim().is_empty()) {
+            ui.colored_label(theme.error, e);
+        }
+        if picked.is_some() {
+            ui.close_menu();
+        }
+    });
+    name_button(menu.response, "Send another signal").on_hover_text("Send another signal");
+    picked
+}
+
+fn spawn_reported<F>(
+    sender: &mpsc::UnboundedSender<Result<String, String>>,
+    task: F,
+) -> JoinHandle<()>
+where
+    F: Future<Output = Result<String, String>> + Send + 'static,
+{
+    let sender = sender.clone();
+    tokio::spawn(async move {
+        let _ = sender.send(task.await);
+    })
+}
+
+impl App for DockerViewerApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.receive_updates();
+
+        let now = Instant::now();
+        for action in self.action_queue.take_due(now) {
+            self.dispatch(action);
+        }
+        self.restart_crashed(now);
+        if ctx.input(|i| i.modifiers.command && i.key_pressed(egui::Key::Z)) {
+            self.action_queue.undo_last();
+        }
+        self.layout = LayoutMode::for_width(ctx.screen_rect().width());
+        self.window_focused = ctx.input(|i| i.focused);
+        self.handle_keys(ctx);
+        self.update_stats();
+        self.update_followed_logs();
+        self.update_restarts();
+        self.statusbar(ctx, now);
+        // Window managers redraw the title bar on every title, so it is only sent on a change
+        let title = self.container_counts.window_title();
+        if self.window_title.as_ref() != Some(&title) {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Title(title.clone()));
+            self.window_title = Some(title);
+        }
+
+        let flashing = self.crash_flash_frames > 0;
+        if flashing {
+            self.crash_flash_frames -= 1;
+            ctx.request_repaint();
+        }
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            // Inverted colors for a few frames after a crash
+            let mut tab_bar = egui::Frame::none();
+            if flashing {
+                tab_bar = tab_bar.fill(ui.visuals().text_color());
+            }
+            tab_bar.show(ui, |ui| {
+                if flashing {
+                    ui.visuals_mut().override_text_color = Some(ui.visuals().panel_fill);
+                }
+                ui.horizontal(|ui| {
+                    // Red while a container is unhealthy
+                    let counts = self.container_counts;
+                    let mut containers_tab = egui::RichText::new(counts.tab_label());
+                    if counts.unhealthy > 0 {
+                        containers_tab = containers_tab.color(self.theme.error);
+                    }
+                    let mut containers_tab = ui.button(containers_tab);
+                    if counts.unhealthy > 0 {
+                        containers_tab =
+                            containers_tab.on_hover_text(format!("{} unhealthy", counts.unhealthy));
+                    }
+                    if containers_tab.clicked() {
+                        self.current_view = AppView::Containers;
+                    }
+                    if ui.button("Composes").clicked() {
+                        self.current_view = AppView::Composes;
+                    }
+                    if ui.button("Dockerfiles").clicked() {
+                        self.current_view = AppView::Dockerfiles;
+                    }
+                    if ui.button("Images").clicked() {
+                        self.current_view = AppView::Images;
+                    }
+                    if ui.button("Networks").clicked() {
+                        self.current_view = AppView::Networks;
+                    }
+                    if ui.button("Volumes").clicked() {
+                        self.current_view = AppView::Volumes;
+                    }
+                    if ui
+                        .button("Disk usage")
+                        .on_hover_text("Ctrl+D, like docker system df")
+                        .clicked()
+                    {
+                        self.disk_usage = Some(DiskUsageState::load());
+                    }
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if mutating_button(ui, true, "Remove All").clicked() {
+                            self.confirm_bulk = Some(ContainerAction::Remove(RemoveOptions::FORCE));
+                        }
+                        if mutating_button(ui, true, "Kill All").clicked() {
+                            self.confirm_bulk = Some(ContainerAction::Kill(SIGKILL));
+                        }
+                        if mutating_button(ui, true, "Stop All").clicked() {
+                            self.confirm_bulk = Some(ContainerAction::Stop);
+                        }
+                        self.bulk_scope_ui(ui);
+                        ui.checkbox(&mut self.grace_period_enabled, "Grace period")
+                            .on_hover_text("Delay removals by 5 seconds so they can be undone");
+                    });
+                });
+            });
+
+            match self.current_view {
+                AppView::Containers => {
+                    self.containers_appview(ui);
+                }
+                AppView::Composes => {
+                    self.composes_appview(ui);
+                }
+                AppView::Dockerfiles => {
+                    self.dockerfiles_appview(ui);
+                }
+                AppView::Images => {
+                    self.images_appview(ui);
+                }
+                AppView::Networks => {
+                    self.networks_appview(ui);
+                }
+                AppView::Volumes => {
+                    self.volumes_appview(ui);
+                }
+            }
+        });
+        self.run_image_window(ctx);
+        self.image_history_window(ctx);
+        self.image_analysis_window(ctx);
+        self.attach_window(ctx);
+        self.disk_usage_window(ctx);
+        self.top_window(ctx);
+        self.diff_window(ctx);
+        self.filter_switcher_window(ctx);
+        self.command_palette_window(ctx);
+        self.column_chooser_window(ctx);
+        self.push_window(ctx);
+        self.resource_window(ctx);
+        self.file_browser_window(ctx);
+        self.commit_window(ctx);
+        self.alerts_window(ctx);
+        self.log_range_window(ctx);
+        self.scale_window(ctx);
+        self.tarball_window(ctx);
+        self.doctor_window(ctx);
+        self.diagnostics_window(ctx);
+        self.system_info_window(ctx);
+        self.merged_logs_window(ctx);
+        self.confirm_remove_window(ctx);
+        self.confirm_recreate_window(ctx);
+        self.confirm_bulk_window(ctx);
+        self.image_cleanup_window(ctx);
+        self.notifications_area(ctx);
+        self.notification_history_window(ctx);
+        self.action_history_window(ctx);
+
+        // Container and image updates wake the UI from the poller, anything else running in
+        // the background is picked up by redrawing a few times per second until it is done
+        if self.is_busy() {
+            ctx.request_repaint_after(BUSY_REPAINT_INTERVAL);
+        }
+    }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        let dropped = self.action_queue.drop_pending();
+        if let Ok(mut dropped_on_exit) = self.dropped_on_exit.lock() {
+            dropped_on_exit.extend(dropped);
+        }
+        self.shutdown.cancel();
+        for task in self.background_tasks.drain(..) {
+            task.abort();
+        }
+        // Dropping the merged and followed logs aborts their streams
+        self.merged_logs = None;
+        self.followed_logs = None;
+    }
+}
+
+impl DockerViewerApp {
+    // Whether a background task, scan, command or grace period may still change what is shown
+    pub fn is_busy(&self) -> bool {
+        // Every running reported task and streamed command holds a clone of these senders
+        self.result_sender.strong_count() > 1
+            || self.output_sender.strong_count() > 1
+            || self.alert_sender.strong_count() > 1
+            || self.keep_alive.is_waiting()
+            || self.image_updates.pending.is_some()
+            || self.builds.is_running()
+            || self
+                .range_logs
+                .as_ref()
+                .is_some_and(|logs| logs.pending.is_some())
+            || self.compose_scan.is_some()
+            || self.dockerfile_scan.is_some()
+            || !self.running_compose_commands.is_empty()
+            || !self.action_queue.is_empty()
+            || !self.pending_actions.is_empty()
+            || !self.stats_streams.is_empty()
+            || self.restarts.is_pending()
+            || self.split_view.is_switching()
+            || self
+                .disk_usage
+                .as_ref()
+                .is_some_and(|state| state.pending.is_some() || state.pruning.is_some())
+            || self
+                .container_diff
+                .as_ref()
+                .is_some_and(|state| state.pending.is_some())
+            || self
+                .compose_preview
+                .as_ref()
+                .is_some_and(|preview| preview.pending.is_some())
+            || self.volumes.as_ref().is_some_and(|state| {
+                state.pending.is_some()
+                    || state.sizes_pending.is_some()
+                    || state.browse.as_ref().is_some_and(|b| b.pending.is_some())
+            })
+            || self
+                .run_image_form
+                .as_ref()
+                .is_some_and(|form| form.result.is_some())
+            || self
+                .image_history
+                .as_ref()
+                .is_some_and(|state| state.pending.is_some())
+            || self
+                .image_analysis
+                .as_ref()
+                .is_some_and(|state| state.pending.is_some())
+            || self.attach.as_ref().is_some_and(|state| {
+                state.pending.is_some() || state.session.as_ref().is_some_and(|s| !s.ended)
+            })
+            || self
+                .merged_logs
+                .as_ref()
+                .is_some_and(MergedLogs::is_streaming)
+            || self
+                .followed_logs
+                .as_ref()
+                .is_some_and(FollowedLogs::is_streaming)
+            || self
+                .container_details
+                .values()
+                .any(|state| state.pending.is_some() || state.restart_update.is_some())
+            || self
+                .health_log
+                .as_ref()
+                .is_some_and(|state| state.pending.is_some())
+            || self
+                .resource_form
+                .as_ref()
+                .is_some_and(|form| form.loading.is_some() || form.result.is_some())
+            || self
+                .file_browser
+                .as_ref()
+                .is_some_and(|browser| browser.pending.is_some())
+            || self.network_form.result.is_some()
+            || self
+                .commit_form
+                .as_ref()
+                .is_some_and(|form| form.result.is_some())
+            || self.doctor.as_ref().is_some_and(|state| {
+                state.plan.is_some()
+                    || state
+                        .results
+                        .as_ref()
+                        .is_some_and(|results| !results.is_closed() || !results.is_empty())
+            })
+            || self
+                .diagnostics
+                .as_ref()
+                .is_some_and(|state| state.pending.is_some())
+    }
+
+    // Replaces the snapshot, sorts and groups it and resolves a pending selection once its
+    // container shows up
+    pub fn update_containers(&mut self, containers: ContainerSnapshot) {
+        let transitions = detect_transitions(&self.containers, &containers, &self.watched);
+        let previous = std::mem::replace(&mut self.containers, containers);
+        if self.screen_reader {
+            for (id, message) in accessibility::state_changes(&previous, &self.containers) {
+                let container = self.containers.contains_key(&id).then_some(id);
+                self.notifications
+                    .push(Ok(message), container, Instant::now());
+            }
+        }
+        self.container_counts = ContainerCounts::of(&self.containers);
+        self.unique_names = disambiguated_names(&self.containers);
+        for event in self.keep_alive.observe(&self.containers, Instant::now()) {
+            self.report_keep_alive(event);
+        }
+        for (id, container, transition) in transitions {
+            if let Transition::Exited(code) = transition {
+                // Whether it was an OOM kill takes an inspect
+                let sender = self.alert_sender.clone();
+                tokio::spawn(async move {
+                    let transition = alerts::exit_transition(id, code).await;
+                    let _ = sender.send(Alert {
+                        at: unix_now(),
+                        container,
+                        transition,
+                    });
+                });
+            } else {
+                let alert = Alert {
+                    at: unix_now(),
+                    container,
+                    transition,
+                };
+                self.record_alert(alert, Instant::now());
+            }
+        }
+        let sort = self.container_sort;
+        if sort == ContainerSort::Restarts {
+            self.order_containers();
+        } else if container_order::reorder(
+            &mut self.container_ids,
+            &previous,
+            &self.containers,
+            sort,
+        ) {
+            // New containers are put in by name, usage sorts them again
+            if sort.resource().is_some() {
+                self.order_containers();
+            } else {
+                self.regroup_containers();
+            }
+        }
+
+        if let Some(id) = &self.pending_selection {
+            if self.containers.contains_key(id) {
+                self.selected_container = self.pending_selection.take();
+            }
+        }
+        if let Some(query) = self.follow_on_start.take() {
+            self.follow_container(&query);
+        }
+        if std::mem::take(&mut self.check_updates_on_start) {
+            self.check_image_updates();
+        }
+    }
+
+    // Asks the registries about the images of running containers that were not checked
+    // within the hour
+    pub fn check_image_updates(&mut self) {
+        if self.image_updates.pending.is_some() {
+            return;
+        }
+        let message = match self.image_updates.start(&self.containers, Instant::now()) {
+            0 => "The images of running containers were checked within the hour".to_string(),
+            1 => "Checking 1 image for updates".to_string(),
+            count => format!("Checking {} images for updates", count),
+        };
+        self.status_message = Some(Ok(message));
+    }
+
+    // Selects the container `ps::resolve_container` finds and follows its logs. When there is
+    // none, or several, the containers view stays as it is and says why.
+    pub fn follow_container(&mut self, query: &str) {
+        let found =
+            ps::resolve_container(self.containers.values().map(|(summary, _)| summary), query)
+                .map(|summary| summary.id.clone());
+        match found {
+            Ok(Some(id)) => {
+                self.current_view = AppView::Containers;
+                self.container_ui_state.get_mut(&id).follow = true;
+                self.selected_container = Some(id);
+                self.scroll_to_selection = true;
+            }
+            Ok(None) => {}
+            Err(e) => self.status_message = Some(Err(e)),
+        }
+    }
+
+    // Sorts the containers by `container_sort` and regroups them by compose project
+    pub fn order_containers(&mut self) {
+        self.container_ids = container_order::sorted_ids(&self.containers, self.container_sort);
+        if self.container_sort == ContainerSort::Restarts {
+            self.restarts.sort(&mut self.container_ids);
+        }
+        if let Some(resource) = self.container_sort.resource() {
+            top::sort_by_usage(&mut self.container_ids, &self.stats_history, resource);
+        }
+        self.regroup_containers();
+    }
+
+    // Groups the sorted containers by compose project, standalone containers come last
+    fn regroup_containers(&mut self) {
+        let mut projects: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        let mut standalone = Vec::new();
+        for id in &self.container_ids {
+            match compose_project(&self.containers[id].0) {
+                Some(project) => projects.entry(project).or_default().push(id.clone()),
+                None => standalone.push(id.clone()),
+            }
+        }
+        self.container_groups = projects
+            .into_iter()
+            .map(|(project, ids)| ContainerGroup {
+                project: Some(project),
+                ids,
+            })
+            .collect();
+        if !standalone.is_empty() {
+            self.container_groups.push(ContainerGroup {
+                project: None,
+                ids: standalone,
+            });
+        }
+
+        if self.selected_project.as_ref().is_some_and(|project| {
+            !self
+                .container_groups
+                .iter()
+                .any(|group| group.project.as_ref() == Some(project))
+        }) {
+            self.selected_project = None;
+        }
+    }
+
+    // Container IDs in the order they are listed, with or without project groups, leaving
+    // out the ones the filter hides
+    pub fn visible_container_ids(&self) -> Vec<String> {
+        let ids: Vec<&String> = if self.group_by_project {
+            self.container_groups
+                .iter()
+                .flat_map(|group| &group.ids)
+                .collect()
+        } else {
+            self.container_ids.iter().collect()
+        };
+        // The lists are rebuilt with every poll, so without a filter all of them are shown
+        if self.container_filter.trim().is_empty() {
+            return ids.into_iter().cloned().collect();
+        }
+        ids.into_iter()
+            .filter(|id| self.is_shown(id))
+            .cloned()
+            .collect()
+    }
+
+    fn is_shown(&self, id: &str) -> bool {
+        self.containers
+            .get(id)
+            .is_some_and(|(summary, _)| container_matches(summary, &self.container_filter))
+    }
+
+    // The keys of the commands in `commands::COMMANDS`, the ones scoped to containers only in
+    // the containers view. Up/Down move the selection there, Left and Right scroll unwrapped
+    // logs sideways and Page Up and Page Down scroll them by a page, Tab switches between the
+    // halves of the split view. Keys are left alone while
+    // a widget such as the image tag field has focus, or while a confirmation is open.
+    fn handle_keys(&mut self, ctx: &egui::Context) {
+        if ctx.wants_keyboard_input()
+            || self.confirm_remove.is_some()
+            || self.confirm_recreate.is_some()
+            || self.confirm_bulk.is_some()
+            || self.confirm_image_cleanup.is_some()
+            || self.filter_switcher.is_some()
+            || self.command_palette.is_some()
+            || self.scale_form.is_some()
+            || self.log_range_form.is_some()
+            || self.column_chooser
+            || self
+                .disk_usage
+                .as_ref()
+                .is_some_and(|state| state.confirm.is_some())
+        {
+            return;
+        }
+        // While log lines are selected the arrows, J and K move its end and Esc drops it
+        if matches!(self.current_view, AppView::Containers) && self.log_selection.is_some() {
+            let request = ctx.input_mut(|i| {
+                [
+                    (egui::Key::Escape, SelectionRequest::Clear),
+                    (egui::Key::ArrowUp, SelectionRequest::Up),
+                    (egui::Key::K, SelectionRequest::Up),
+                    (egui::Key::ArrowDown, SelectionRequest::Down),
+                    (egui::Key::J, SelectionRequest::Down),
+                ]
+                .into_iter()
+                .find(|(key, _)| i.consume_key(egui::Modifiers::NONE, *key))
+                .map(|(_, request)| request)
+            });
+            if request.is_some() {
+                self.log_selection_request = request;
+                return;
+            }
+        }
+        // Full-screen details in the narrow layout go back to the list
+        if matches!(self.current_view, AppView::Containers)
+            && self.layout == LayoutMode::Narrow
+            && self.show_details
+            && ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Escape))
+        {
+            self.show_details = false;
+            return;
+        }
+        for info in COMMANDS {
+            if info.scope == Scope::Containers && !matches!(self.current_view, AppView::Containers)
+            {
+                continue;
+            }
+            if info
+                .bindings
+                .iter()
+                .any(|binding| consume_binding(ctx, *binding))
+            {
+                self.run_command(info.command, None);
+            }
+        }
+        if !matches!(self.current_view, AppView::Containers) {
+            return;
+        }
+        // In the split view only the focused half takes the arrow and page keys
+        let split = self.split_active();
+        if split && ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Tab)) {
+            self.split_view.focus = self.split_view.focus.other();
+        }
+        let list_keys = !split || self.split_view.focus == Pane::List;
+        let logs_keys = !split || self.split_view.focus == Pane::Logs;
+
+        let (up, down, scroll) = ctx.input_mut(|i| {
+            let scroll = [
+                (egui::Key::ArrowLeft, LogScroll::Left),
+                (egui::Key::ArrowRight, LogScroll::Right),
+                (egui::Key::PageUp, LogScroll::PageUp),
+                (egui::Key::PageDown, LogScroll::PageDown),
+            ]
+            .into_iter()
+            .find(|(key, _)| logs_keys && i.consume_key(egui::Modifiers::NONE, *key));
+            (
+                list_keys && i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+                list_keys && i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+                scroll.map(|(_, scroll)| scroll),
+            )
+        });
+        if up || down {
+            let ids = self.visible_container_ids();
+            let current = self
+                .selected_container
+                .as_ref()
+                .and_then(|selected| ids.iter().position(|id| id == selected));
+            let next = match (current, down) {
+                (None, true) => Some(0),
+                (None, false) => ids.len().checked_sub(1),
+                (Some(index), true) => Some((index + 1).min(ids.len() - 1)),
+                (Some(index), false) => Some(index.saturating_sub(1)),
+            };
+            if let Some(id) = next.and_then(|index| ids.get(index)) {
+                self.selected_container = Some(id.clone());
+                self.scroll_to_selection = true;
+            }
+        }
+        if scroll.is_some() && self.selected_container.is_some() {
+            self.log_scroll_request = scroll;
+        }
+    }
+
+    // What a key or the command palette asked for. `argument` is what was typed for commands
+    // with a prompt. Commands on the selected container do nothing without one.
+    pub fn run_command(&mut self, command: Command, argument: Option<String>) {
+        match command {
+            Command::Palette => self.command_palette = Some(CommandPalette::default()),
+            Command::ShowView(view) => self.current_view = view,
+            Command::DiskUsage => self.disk_usage = Some(DiskUsageState::load()),
+            Command::Alerts => self.show_alerts = !self.show_alerts,
+            Command::SystemInfo => self.system_info = Some(SystemInfoState::load()),
+            Command::ActionHistory => self.show_action_history = !self.show_action_history,
+            Command::NotificationHistory => {
+                self.show_notification_history = !self.show_notification_history
+            }
+            Command::Diagnostics => self.run_diagnostics(),
+            Command::RemoveDanglingImages => {
+                let dangling: Vec<String> = self
+                    .images
+                    .iter()
+                    .filter(|image| is_dangling(image))
+                    .map(|image| image.id.clone())
+                    .collect();
+                self.queue_or_dispatch(
+                    "Remove dangling images".to_string(),
+                    QueuedAction::RemoveImages(dangling),
+                );
+            }
+            Command::CleanUpUnusedImages => {
+                self.current_view = AppView::Images;
+                if read_only::is_enabled() {
+                    self.status_message = Some(Err(READ_ONLY_MESSAGE.to_string()));
+                } else {
+                    self.confirm_image_cleanup = Some(self.config.keep_unused_images_days());
+                }
+            }
+            Command::FollowLogs => self.follow_container(argument.unwrap_or_default().trim()),
+            Command::FilterByLabels => {
+                self.current_view = AppView::Containers;
+                self.apply_label_filter(argument.unwrap_or_default().trim());
+            }
+            Command::Bulk(action) => {
+                self.current_view = AppView::Containers;
+                if read_only::is_enabled() {
+                    self.status_message = Some(Err(READ_ONLY_MESSAGE.to_string()));
+                } else {
+                    self.confirm_bulk = Some(action);
+                }
+            }
+            Command::SwitchLabelFilter => {
+                self.current_view = AppView::Containers;
+                self.filter_switcher = Some(String::new());
+            }
+            Command::ChooseColumns => {
+                self.current_view = AppView::Containers;
+                self.column_chooser = true;
+            }
+            Command::CycleSort => self.set_container_sort(self.container_sort.next()),
+            Command::SortByUsage => {
+                self.current_view = AppView::Containers;
+                let sort = match self.container_sort {
+                    ContainerSort::Cpu => ContainerSort::Memory,
+                    _ => ContainerSort::Cpu,
+                };
+                self.set_container_sort(sort);
+            }
+            Command::TopConsumers => self.top_leaderboard = !self.top_leaderboard,
+            Command::CheckImageUpdates => self.check_image_updates(),
+            Command::SplitView => {
+                self.current_view = AppView::Containers;
+                self.split_view.toggle();
+            }
+            _ => {
+                if let Some(id) = self.selected_container.clone() {
+                    self.run_container_command(command, id);
+                }
+            }
+        }
+    }
+
+    fn run_container_command(&mut self, command: Command, id: String) {
+        // Pressing again while an action runs would only fail once it is done
+        let pending = self.pending_actions.contains_key(&id);
+        match command {
+            Command::StartOrStop | Command::Remove | Command::Recreate
+                if read_only::is_enabled() =>
+            {
+                self.status_message = Some(Err(READ_ONLY_MESSAGE.to_string()));
+            }
+            Command::StartOrStop if !pending => {
+                if let Some((summary, _)) = self.containers.get(&id) {
+                    let action = if summary.state.as_deref() == Some("running") {
+                        ContainerAction::Stop
+                    } else {
+                        ContainerAction::Start
+                    };
+                    self.start_action(id, action);
+                }
+            }
+            Command::Remove if !pending && self.containers.contains_key(&id) => {
+                self.confirm_remove = Some(id);
+            }
+            Command::Recreate if !pending && self.containers.contains_key(&id) => {
+                self.confirm_recreate = Some(id);
+            }
+            Command::KeepAlive => self.toggle_keep_alive(&id),
+            Command::CycleLogLevel => {
+                let state = self.container_ui_state.get_mut(&id);
+                state.level_filter = state.level_filter.next();
+            }
+            Command::ChangedFiles => {
+                if let Some((summary, _)) = self.containers.get(&id) {
+                    self.container_diff = Some(DiffState::load(id, display_name(summary)));
+                }
+            }
+            Command::Watch => self.toggle_watch(&id),
+            Command::LogTimeRange => {
+                self.current_view = AppView::Containers;
+                self.log_range_form = Some(LogRangeForm::default());
+            }
+            Command::OpenNetwork => self.open_container_network(&id),
+            Command::OpenImage => {
+                let image_id = self
+                    .containers
+                    .get(&id)
+                    .and_then(|(summary, _)| summary.image_id.clone());
+                if let Some(image_id) = image_id {
+                    self.select_image_by_id(&image_id);
+                }
+            }
+            Command::WrapLogs => {
+                let state = self.container_ui_state.get_mut(&id);
+                state.wrap = !state.wrap;
+            }
+            Command::SelectLogLines => {
+                self.current_view = AppView::Containers;
+                self.log_selection_request = Some(SelectionRequest::Start);
+            }
+            Command::CopyLogLines => self.log_selection_request = Some(SelectionRequest::Copy),
+            _ => {}
+        }
+    }
+
+    fn run_diagnostics(&mut self) {
+        let (sender, receiver) = oneshot::channel();
+        let config_path = self.config_path.clone();
+        tokio::spawn(async move {
+            let _ = sender.send(diagnostics::run_checks(config_path).await);
+        });
+        self.diagnostics = Some(DiagnosticsState {
+            pending: Some(receiver),
+            results: Vec::new(),
+        });
+    }
+
+    // Fuzzy-matched list of every command, Up/Down pick one and Enter runs it. A command
+    // with a prompt asks for its argument in the same field first.
+    fn command_palette_window(&mut self, ctx: &egui::Context) {
+        let Some(palette) = self.command_palette.as_mut() else {
+            return;
+        };
+        let (enter, escape, up, down) = ctx.input_mut(|i| {
+            (
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Escape),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+            )
+        });
+        let matches = match palette.prompting {
+            Some(_) => Vec::new(),
+            None => commands::matching_commands(&palette.query),
+        };
+        if down {
+            palette.selected += 1;
+        }
+        if up {
+            palette.selected = palette.selected.saturating_sub(1);
+        }
+        palette.selected = palette.selected.min(matches.len().saturating_sub(1));
+        let mut picked = enter
+            .then(|| matches.get(palette.selected))
+            .flatten()
+            .copied();
+        let mut open = !escape;
+        egui::Window::new("Commands")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_TOP, [0.0, 40.0])
+            .show(ctx, |ui| {
+                let hint = match palette.prompting.and_then(commands::info) {
+                    Some(info) => info.prompt.unwrap_or_default(),
+                    None => "Type a command",
+                };
+                let field = ui.add(
+                    egui::TextEdit::singleline(&mut palette.query)
+                        .hint_text(hint)
+                        .desired_width(360.0),
+                );
+                field.request_focus();
+                if field.changed() {
+                    palette.selected = 0;
+                }
+                if let Some(info) = palette.prompting.and_then(commands::info) {
+                    ui.label(egui::RichText::new(info.name).weak());
+                    return;
+                }
+                if matches.is_empty() {
+                    ui.label(egui::RichText::new("No matching command").weak());
+                }
+                egui::ScrollArea::vertical()
+                    .max_height(320.0)
+                    .show(ui, |ui| {
+                        for (index, info) in matches.iter().enumerate() {
+                            ui.horizontal(|ui| {
+                                let row = ui.selectable_label(index == palette.selected, info.name);
+                                if index == palette.selected && (up || down) {
+                                    row.scroll_to_me(None);
+                                }
+                                if row.clicked() {
+                                    picked = Some(*info);
+                                }
+                                let keys: Vec<String> = info
+                                    .bindings
+                                    .iter()
+                                    .map(|binding| binding.label())
+                                    .collect();
+                                ui.label(egui::RichText::new(keys.join(" or ")).weak());
+                            });
+                        }
+                    });
+            });
+
+        let palette = self.command_palette.as_mut().unwrap();
+        if let Some(command) = palette.prompting.filter(|_| enter) {
+            let argument = std::mem::take(&mut palette.query);
+            self.command_palette = None;
+            self.run_command(command, Some(argument));
+        } else if let Some(info) = picked {
+            if info.prompt.is_some() {
+                palette.prompting = Some(info.command);
+                palette.query.clear();
+            } else {
+                self.command_palette = None;
+                self.run_command(info.command, None);
+            }
+        } else if !open {
+            self.command_palette = None;
+        }
+    }
+
+    // Opens the network selected in the container's details, or its first one
+    fn open_container_network(&mut self, container_id: &str) {
+        let Some((summary, _)) = self.containers.get(container_id) else {
+            return;
+        };
+        let names: Vec<String> = network_rows(summary)
+            .into_iter()
+            .map(|(name, _, _)| name)
+            .collect();
+        let name = self
+            .details_network
+            .clone()
+            .filter(|focused| names.contains(focused))
+            .or_else(|| names.into_iter().next());
+        if let Some(name) = name {
+            self.select_network_by_id(&name);
+        }
+    }
+
+    fn confirm_remove_window(&mut self, ctx: &egui::Context) {
+        let Some((summary, _)) = self
+            .confirm_remove
+            .as_ref()
+            .and_then(|id| self.containers.get(id))
+        else {
+            // The container went away while the confirmation was open
+            self.confirm_remove = None;
+            return;
+        };
+        let summary = summary.clone();
+        let name = display_name(&summary);
+        let mut confirmed =
+            ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Enter));
+        let mut cancelled =
+            ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Escape));
+        // Besides Tab and Space, F and V flip the toggles
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::F)) {
+            self.remove_options.force = !self.remove_options.force;
+        }
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::V)) {
+            self.remove_options.volumes = !self.remove_options.volumes;
+        }
+        let running = summary.state.as_deref() == Some("running");
+        egui::Window::new("Confirm remove")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(format!("Remove container {}? (Enter / Esc)", name));
+                remove_options_ui(ui, &mut self.remove_options, running);
+                ui.horizontal(|ui| {
+                    confirmed |= ui.button("Remove").clicked();
+                    cancelled |= ui.button("Cancel").clicked();
+                });
+            });
+        if confirmed {
+            self.remove_container(name, summary);
+        }
+        if confirmed || cancelled {
+            self.confirm_remove = None;
+        }
+    }
+
+    fn confirm_recreate_window(&mut self, ctx: &egui::Context) {
+        let Some((id, summary)) = self
+            .confirm_recreate
+            .as_ref()
+            .and_then(|id| Some((id.clone(), self.containers.get(id)?.0.clone())))
+        else {
+            // The container went away while the confirmation was open
+            self.confirm_recreate = None;
+            return;
+        };
+        let name = display_name(&summary);
+        let mut confirmed =
+            ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Enter));
+        let mut cancelled =
+            ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Escape));
+        egui::Window::new("Confirm recreate")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(format!("Recreate container {}? (Enter / Esc)", name));
+                ui.label(format!(
+                    "It is stopped and replaced by a new container with the same name and \
+                     settings, from the current {} image. The container ID changes, and \
+                     anything written inside the old container outside of volumes is lost.",
+                    summary.image.as_deref().unwrap_or("unknown")
+                ));
+                ui.horizontal(|ui| {
+                    confirmed |= ui.button("Recreate").clicked();
+                    cancelled |= ui.button("Cancel").clicked();
+                });
+            });
+        if confirmed {
+            self.start_action(id, ContainerAction::Recreate);
+        }
+        if confirmed || cancelled {
+            self.confirm_recreate = None;
+        }
+    }
+
+    // Containers the bulk actions act on, sorted like the list
+    pub fn bulk_targets(&self) -> Vec<ContainerSummary> {
+        let ids = match &self.bulk_scope {
+            BulkScope::All => self.container_ids.clone(),
+            BulkScope::Filter => self.visible_container_ids(),
+            BulkScope::Project(project) => self
+                .container_ids
+                .iter()
+                .filter(|id| compose_project(&self.containers[*id].0).as_ref() == Some(project))
+                .cloned()
+                .collect(),
+        };
+        ids.iter()
+            .filter_map(|id| self.containers.get(id))
+            .map(|(summary, _)| summary.clone())
+            .collect()
+    }
+
+    fn bulk_scope_ui(&mut self, ui: &mut egui::Ui) {
+        let projects: Vec<String> = self
+            .container_groups
+            .iter()
+            .filter_map(|group| group.project.clone())
+            .collect();
+        // A project that went away falls back to the filter rather than to everything
+        if let BulkScope::Project(project) = &self.bulk_scope {
+            if !projects.contains(project) {
+                self.bulk_scope = BulkScope::Filter;
+            }
+        }
+        egui::ComboBox::from_id_source("bulk_scope")
+            .selected_text(self.bulk_scope.label())
+            .show_ui(ui, |ui| {
+                ui.selectable_value(
+                    &mut self.bulk_scope,
+                    BulkScope::Filter,
+                    BulkScope::Filter.label(),
+                );
+                ui.selectable_value(&mut self.bulk_scope, BulkScope::All, BulkScope::All.label());
+                for project in projects {
+                    let scope = BulkScope::Project(project);
+                    let label = scope.label();
+                    ui.selectable_value(&mut self.bulk_scope, scope, label);
+                }
+            })
+            .response
+            .on_hover_text("Containers that Stop All, Kill All and Remove All act on");
+    }
+
+    fn confirm_bulk_window(&mut self, ctx: &egui::Context) {
+        let Some(action) = self.confirm_bulk else {
+            return;
+        };
+        let in_scope = self.bulk_targets();
+        let with_state = |states: &[&str]| -> Vec<ContainerSummary> {
+            in_scope
+                .iter()
+                .filter(|summary| states.contains(&summary.state.as_deref().unwrap_or_default()))
+                .cloned()
+                .collect()
+        };
+        // Only running containers can be stopped or killed, removing usually means cleaning
+        // up the exited ones
+        let (targets, exited) = match action {
+            ContainerAction::Remove(_) => (in_scope.clone(), with_state(&["exited"])),
+            _ => (with_state(&["running"]), Vec::new()),
+        };
+
+        let mut chosen = None;
+        let mut cancelled =
+            ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Escape));
+        let mut action = action;
+        egui::Window::new(format!("{} containers", action.label()))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "{} {} containers in scope {}:",
+                    action.label(),
+                    targets.len(),
+                    self.bulk_scope.label()
+                ));
+                // Exactly what will be affected, exited ones are marked for the removal choice
+                egui::ScrollArea::vertical()
+                    .id_source("bulk_targets")
+                    .max_height(200.0)
+                    .show(ui, |ui| {
+                        for summary in &targets {
+                            let state = summary.state.as_deref().unwrap_or("unknown");
+                            ui.label(format!("{}  ({})", display_name(summary), state));
+                        }
+                    });
+                if let ContainerAction::Remove(options) = &mut action {
+                    ui.checkbox(&mut options.volumes, "Also remove their anonymous volumes");
+                }
+                ui.horizontal(|ui| {
+                    if matches!(action, ContainerAction::Remove(_))
+                        && ui
+                            .add_enabled(
+                                !exited.is_empty(),
+                                egui::Button::new(format!("Remove {} exited", exited.len())),
+                            )
+                            .clicked()
+                    {
+                        chosen = Some(exited.clone());
+                    }
+                    let all = format!("{} all {}", action.label(), targets.len());
+                    if ui
+                        .add_enabled(!targets.is_empty(), egui::Button::new(all))
+                        .clicked()
+                    {
+                        chosen = Some(targets.clone());
+                    }
+                    cancelled |= ui.button("Cancel").clicked();
+                });
+            });
+
+        self.confirm_bulk = Some(action);
+        if chosen.is_some() || cancelled {
+            self.confirm_bulk = None;
+        }
+        if let Some(summaries) = chosen {
+            if let (ContainerAction::Remove(options), true) = (action, self.grace_period_enabled) {
+                self.queue_or_dispatch(
+                    format!("Remove {} containers", summaries.len()),
+                    QueuedAction::RemoveContainers(summaries, options),
+                );
+            } else {
+                let names: Vec<String> = summaries.iter().map(display_name).collect();
+                let target = format!("{} containers: {}", names.len(), names.join(", "));
+                self.action_history
+                    .sent(action.describe(&target), unix_now());
+                let ids: Vec<String> = summaries
+                    .into_iter()
+                    .filter_map(|summary| summary.id)
+                    .collect();
+                for id in &ids {
+                    self.suspend_keep_alive(id);
+                }
+                spawn_reported(&self.result_sender, async move {
+                    let docker = BollardDocker::connect()?;
+                    run_bulk(&docker, action, &ids).await
+                });
+            }
+        }
+    }
+
+    // Removes with the options picked, forcing only applies to this removal
+    fn remove_container(&mut self, name: String, summary: ContainerSummary) {
+        let options = self.remove_options;
+        self.remove_options.force = false;
+        self.queue_or_dispatch(
+            format!("Remove {}", name),
+            QueuedAction::RemoveContainer(Box::new(summary), options),
+        );
+    }
+
+    // Destructive actions go through the queue when the grace period is enabled
+    fn queue_or_dispatch(&mut self, label: String, action: QueuedAction) {
+        if self.grace_period_enabled {
+            self.action_queue.push(label, action, Instant::now());
+        } else {
+            self.dispatch(action);
+        }
+    }
+
+    fn dispatch(&mut self, action: QueuedAction) {
+        self.action_history.sent(action.describe(), unix_now());
+        action.dispatch(&self.error_sender, &self.result_sender);
+    }
+
+    fn statusbar(&mut self, ctx: &egui::Context, now: Instant) {
+        let theme = self.theme;
+        let mut cancelled = Vec::new();
+        egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                if ui.small_button("Diagnostics").clicked() {
+                    self.run_diagnostics();
+                }
+                if ui
+                    .small_button("System info")
+                    .on_hover_text("!, daemon version and docker info")
+                    .clicked()
+                {
+                    self.system_info = Some(SystemInfoState::load());
+                }
+                if self.layout == LayoutMode::Wide {
+                    ui.label(self.capabilities.describe());
+                }
+                let alerts = alerts_label(self.alerts.len(), self.layout);
+                if ui
+                    .small_button(alerts)
+                    .on_hover_text("Shift+A, crashes of watched containers")
+                    .clicked()
+                {
+                    self.show_alerts = !self.show_alerts;
+                }
+                if ui
+                    .small_button(format!(
+                        "Notifications ({})",
+                        self.notifications.history.len()
+                    ))
+                    .on_hover_text("The last results and errors of background work")
+                    .clicked()
+                {
+                    self.show_notification_history = !self.show_notification_history;
+                }
+                if self.image_updates.pending.is_some() {
+                    ui.spinner().on_hover_text("Checking images for updates");
+                }
+                let outdated = self.image_updates.outdated_containers(&self.containers);
+                let skipped = self.image_updates.skipped();
+                if outdated > 0 || !skipped.is_empty() {
+                    let label = if outdated > 0 {
+                        ui.colored_label(theme.warning, outdated_label(outdated))
+                    } else {
+                        ui.weak("Image updates unknown")
+                    };
+                    if !skipped.is_empty() {
+                        label.on_hover_text(format!("Not checked:\n{}", skipped.join("\n")));
+                    }
+                }
+                if read_only::is_enabled() {
+                    ui.label(
+                        egui::RichText::new(" RO ")
+                            .strong()
+                            .color(theme.on_warning)
+                            .background_color(theme.warning),
+                    )
+                    .on_hover_text("Started with --read-only, nothing can be changed");
+                }
+                match &self.status_message {
+                    Some(Ok(message)) => {
+                        ui.separator();
+                        ui.label(message);
+                    }
+                    Some(Err(message)) => {
+                        ui.separator();
+                        ui.colored_label(theme.error, message);
+                    }
+                    None => {}
+                }
+                if let Some(last) = self.action_queue.pending().last() {
+                    ui.separator();
+                    ui.label(undo_hint(
+                        &last.label,
+                        last.remaining(now).as_secs() + 1,
+                        self.layout,
+                    ));
+                    if ui.button("Undo").clicked() {
+                        cancelled.push(last.id);
+                    }
+                }
+            });
+        });
+
+        // Stacked actions are listed in an overlay so each one can be undone separately
+        if self.action_queue.pending().len() > 1 {
+            egui::Window::new("Queued actions")
+                .anchor(egui::Align2::RIGHT_BOTTOM, [-10.0, -40.0])
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    for pending in self.action_queue.pending() {
+                        ui.horizontal(|ui| {
+                            ui.label(format!(
+                                "{} in {}s",
+                                pending.label,
+                                pending.remaining(now).as_secs() + 1
+                            ));
+                            if ui.button("Undo").clicked() {
+                                cancelled.push(pending.id);
+                            }
+                        });
+                    }
+                });
+        }
+
+        for id in cancelled {
+            self.action_queue.cancel(id);
+        }
+    }
+
+    fn run_project_command(&self, group: &ContainerGroup, command: ComposeCommand) {
+        // Compose records where it was run from on every container, so use the first one
+        let Some(project) = group
+            .ids
+            .iter()
+            .filter_map(|id| self.containers.get(id))
+            .find_map(|(summary, _)| compose_project_location(summary))
+        else {
+            let _ = self.result_sender.send(Err(format!(
+                "Cannot locate the compose file for project {}",
+                group.project.as_deref().unwrap_or_default()
+            )));
+            return;
+        };
+        let output = self.output_sender.clone();
+        spawn_reported(&self.result_sender, async move {
+            run_docker_compose_project(&project, command, output).await
+        });
+    }
+
+    // Asks for the replica count of the container's compose service, starting from how many
+    // there are now. Containers compose did not create cannot be scaled.
+    pub fn open_scale_form(&mut self, container_id: &str) {
+        let Some((summary, _)) = self.containers.get(container_id) else {
+            return;
+        };
+        let (Some(project), Some(service)) =
+            (compose_project_location(summary), compose_service(summary))
+        else {
+            self.status_message = Some(Err(format!(
+                "{} is not part of a compose project, only compose services can be scaled",
+                display_name(summary)
+            )));
+            return;
+        };
+        let replicas = self
+            .containers
+            .values()
+            .filter(|(other, _)| {
+                compose_project(other).as_ref() == Some(&project.name)
+                    && compose_service(other).as_ref() == Some(&service)
+            })
+            .count();
+        self.scale_form = Some(ScaleForm {
+            project,
+            service,
+            replicas: replicas as u32,
+        });
+    }
+
+    fn follow_project_logs(&mut self, group: &ContainerGroup) {
+        let containers = group
+            .ids
+            .iter()
+            .filter_map(|id| self.containers.get(id))
+            .map(|(summary, _)| {
+                let id = summary.id.clone().unwrap_or_default();
+                (
+                    id,
+                    display_name(summary).trim_start_matches('/').to_string(),
+                )
+            })
+            .collect();
+        let title = group.project.as_deref().unwrap_or("standalone containers");
+        self.merged_logs = Some(MergedLogs::follow(
+            title.to_string(),
+            containers,
+            self.log_tail
+                .load(Ordering::Relaxed)
+                .min(self.max_log_lines),
+            self.max_log_lines,
+        ));
+    }
+
+    // Runs `docker compose logs -f` for the project instead of following its containers one
+    // by one, so services whose containers lost their labels are still in it
+    fn follow_compose_logs(&mut self, group: &ContainerGroup) {
+        let project = group
+            .ids
+            .iter()
+            .filter_map(|id| self.containers.get(id))
+            .find_map(|(summary, _)| compose_project_location(summary));
+        let Some(project) = project else {
+            self.status_message = Some(Err(format!(
+                "Cannot locate the compose file for project {}",
+                group.project.as_deref().unwrap_or_default()
+            )));
+            return;
+        };
+        match ComposeLogs::spawn(&project) {
+            Ok(logs) => {
+                self.merged_logs = Some(MergedLogs::compose(
+                    format!("{} (docker compose logs)", project.name),
+                    logs,
+                    self.max_log_lines,
+                ));
+            }
+            Err(e) => self.status_message = Some(Err(e)),
+        }
+    }
+
+    fn merged_logs_window(&mut self, ctx: &egui::Context) {
+        let theme = self.theme;
+        let Some(logs) = &mut self.merged_logs else {
+            return;
+        };
+        logs.receive();
+        let width = prefix_width(logs.names.iter().map(String::as_str), MAX_PREFIX_WIDTH);
+        let mut open = true;
+        let mut title = format!("Logs of {}", logs.title);
+        if logs.lines.dropped() > 0 {
+            title.push_str(&format!(
+                " - {} lines buffered (oldest dropped)",
+                logs.lines.len()
+            ));
+        }
+        egui::Window::new(title)
+            .id(egui::Id::new("merged_logs_window"))
+            .open(&mut open)
+            .default_size([900.0, 500.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut logs.wrap, "Wrap");
+                    if !logs.is_streaming() {
+                        ui.label("All streams ended");
+                    }
+                });
+                let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+                let char_width = ui.fonts(|fonts| {
+                    fonts.glyph_width(&egui::TextStyle::Monospace.resolve(ui.style()), 'M')
+                });
+                // Lines dropped from the top since the last frame would otherwise shift the
+                // view down by as many rows
+                let newly_dropped = logs.lines.dropped() - logs.dropped_seen;
+                logs.dropped_seen = logs.lines.dropped();
+                let spaced_row = row_height + ui.spacing().item_spacing.y;
+                let scroll_area = if logs.wrap {
+                    egui::ScrollArea::vertical()
+                } else {
+                    egui::ScrollArea::both()
+                };
+                let mut scroll_area = scroll_area
+                    .id_source("merged_logs")
+                    .auto_shrink([false, false])
+                    .stick_to_bottom(true);
+                if newly_dropped > 0 {
+                    scroll_area = scroll_area.vertical_scroll_offset(adjust_scroll(
+                        logs.scroll_offset,
+                        newly_dropped,
+                        spaced_row,
+                    ));
+                }
+                let colors = &logs.colors;
+                // `columns` is the window width in characters while wrapping
+                let log_line = |ui: &mut egui::Ui,
+                                name: &str,
+                                line: &str,
+                                columns: Option<usize>| {
+                    let truncated = truncate_line(line, MAX_LINE_CHARS);
+                    let message = truncated.as_deref().unwrap_or(line);
+                    let rows = match columns {
+                        Some(columns) => wrap_message(message, width, columns),
+                        None => vec![message.to_string()],
+                    };
+                    let mut rows = rows.into_iter();
+                    ui.horizontal(|ui| {
+                        ui.spacing_mut().item_spacing.x = 0.0;
+                        let prefix = format!("{}{}", format_prefix(name, width), PREFIX_SEPARATOR);
+                        ui.label(egui::RichText::new(prefix).monospace().color(
+                            match colors.get(name) {
+                                Some(color) => theme.prefixes[*color],
+                                None => prefix_color(name, &theme),
+                            },
+                        ));
+                        let first = rows.next().unwrap_or_default();
+                        let response = ui.add(
+                            egui::Label::new(egui::RichText::new(first).monospace()).wrap(false),
+                        );
+                        if truncated.is_some() {
+                            response.on_hover_text(line);
+                        }
+                    });
+                    for row in rows {
+                        ui.add(egui::Label::new(egui::RichText::new(row).monospace()).wrap(false));
+                    }
+                };
+                // Unwrapped lines are one row high each, so only the visible ones are laid out
+                let output = if logs.wrap {
+                    scroll_area.show(ui, |ui| {
+                        let columns = (ui.available_width() / char_width.max(1.0)) as usize;
+                        for (name, line) in logs.lines.iter() {
+                            log_line(ui, name, line, Some(columns));
+                        }
+                    })
+                } else {
+                    scroll_area.show_rows(ui, row_height, logs.lines.len(), |ui, rows| {
+                        for (name, line) in logs.lines.range(rows) {
+                            log_line(ui, name, line, None);
+                        }
+                    })
+                };
+                logs.scroll_offset = output.state.offset.y;
+            });
+        // Closing the window drops the streams
+        if !open {
+            self.merged_logs = None;
+        }
+    }
+
+    fn start_doctor(&mut self, group: &ContainerGroup) {
+        let containers: Vec<ContainerSummary> = group
+            .ids
+            .iter()
+            .filter_map(|id| self.containers.get(id))
+            .map(|(summary, _)| summary.clone())
+            .collect();
+        let Some(project) = containers.iter().find_map(compose_project_location) else {
+            let _ = self.result_sender.send(Err(format!(
+                "Cannot locate the compose file for project {}",
+                group.project.as_deref().unwrap_or_default()
+            )));
+            return;
+        };
+
+        let (sender, receiver) = oneshot::channel();
+        self.doctor = Some(DoctorState {
+            project: project.name.clone(),
+            plan: Some(receiver),
+            steps: Vec::new(),
+            error: None,
+            results: None,
+        });
+        tokio::spawn(async move {
+            let _ = sender.send(doctor::plan(&project, &containers).await);
+        });
+    }
+
+    fn doctor_window(&mut self, ctx: &egui::Context) {
+        let theme = self.theme;
+        let Some(state) = &mut self.doctor else {
+            return;
+        };
+
+        if let Some(plan) = &mut state.plan {
+            match plan.try_recv() {
+                Ok(Ok(steps)) => {
+                    state.steps = steps
+                        .into_iter()
+                        .map(|step| DoctorStep {
+                            step,
+                            selected: true,
+                            result: None,
+                        })
+                        .collect();
+                    state.plan = None;
+                }
+                Ok(Err(e)) => {
+                    state.error = Some(e);
+                    state.plan = None;
+                }
+                Err(oneshot::error::TryRecvError::Empty) => {}
+                Err(oneshot::error::TryRecvError::Closed) => state.plan = None,
+            }
+        }
+        if let Some(results) = &mut state.results {
+            while let Ok((index, result)) = results.try_recv() {
+                if let Some(step) = state.steps.get_mut(index) {
+                    step.result = Some(result);
+                }
+            }
+        }
+
+        let mut open = true;
+        let mut execute = false;
+        egui::Window::new(format!("Doctor: {}", state.project))
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                if state.plan.is_some() {
+                    ui.label("Diagnosing…");
+                    return;
+                }
+                if let Some(error) = &state.error {
+                    ui.colored_label(theme.error, error);
+                    return;
+                }
+                if state.steps.is_empty() {
+                    ui.label("No problems found");
+                    return;
+                }
+                for step in &mut state.steps {
+                    ui.horizontal(|ui| {
+                        ui.add_enabled(
+                            state.results.is_none(),
+                            egui::Checkbox::new(&mut step.selected, step.step.describe()),
+                        );
+                        match &step.result {
+                            Some(Ok(message)) => {
+                                ui.colored_label(theme.success, message);
+                            }
+                            Some(Err(message)) => {
+                                ui.colored_label(theme.error, message);
+                            }
+                            None => {}
+                        }
+                    });
+                }
+                if state.results.is_none() && mutating_button(ui, true, "Fix selected").clicked() {
+                    execute = true;
+                }
+            });
+
+        if execute {
+            let steps: Vec<(usize, FixStep)> = state
+                .steps
+                .iter()
+                .enumerate()
+                .filter(|(_, step)| step.selected)
+                .map(|(index, step)| (index, step.step.clone()))
+                .collect();
+            let (sender, receiver) = mpsc::unbounded_channel();
+            state.results = Some(receiver);
+            tokio::spawn(doctor::execute_plan(state.project.clone(), steps, sender));
+        }
+        if !open {
+            self.doctor = None;
+        }
+    }
+
+    fn diagnostics_window(&mut self, ctx: &egui::Context) {
+        let theme = self.theme;
+        let Some(state) = &mut self.diagnostics else {
+            return;
+        };
+        if let Some(pending) = &mut state.pending {
+            if let Ok(results) = pending.try_recv() {
+                state.results = results;
+                state.pending = None;
+            }
+        }
+
+        let mut open = true;
+        egui::Window::new("Diagnostics")
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                if state.pending.is_some() {
+                    ui.spinner();
+                    return;
+                }
+                egui::Grid::new("diagnostics")
+                    .num_columns(3)
+                    .show(ui, |ui| {
+                        for result in &state.results {
+                            let color = match result.status {
+                                CheckStatus::Pass => theme.success,
+                                CheckStatus::Warn => theme.warning,
+                                CheckStatus::Fail => theme.error,
+                            };
+                            ui.colored_label(color, result.status.label());
+                            ui.label(result.name);
+                            let detail = ui.label(&result.detail);
+                            if let Some(hint) = &result.hint {
+                                detail.on_hover_text(hint);
+                            }
+                            ui.end_row();
+                        }
+                    });
+                if ui.button("Copy as text").clicked() {
+                    ui.output_mut(|output| {
+                        output.copied_text = diagnostics::format_text(&state.results)
+                    });
+                }
+            });
+        if !open {
+            self.diagnostics = None;
+        }
+    }
+
+    fn system_info_window(&mut self, ctx: &egui::Context) {
+        let theme = self.theme;
+        let Some(state) = &mut self.system_info else {
+            return;
+        };
+        if let Some(pending) = &mut state.pending {
+            match pending.try_recv() {
+                Ok(Ok(info)) => {
+                    state.info = Some(info);
+                    state.error = None;
+                    state.pending = None;
+                }
+                Ok(Err(e)) => {
+                    // Stale numbers would hide that the daemon is gone
+                    state.info = None;
+                    state.error = Some(e);
+                    state.pending = None;
+                }
+                Err(oneshot::error::TryRecvError::Empty) => {}
+                Err(oneshot::error::TryRecvError::Closed) => state.pending = None,
+            }
+        }
+
+        let mut open = true;
+        egui::Window::new("System info")
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(state.pending.is_none(), egui::Button::new("Refresh"))
+                        .clicked()
+                    {
+                        state.refresh();
+                    }
+                    if state.pending.is_some() {
+                        ui.spinner();
+                    }
+                });
+                if let Some(error) = &state.error {
+                    ui.label(egui::RichText::new(error).color(theme.error).strong());
+                }
+                let Some(info) = &state.info else {
+                    return;
+                };
+                egui::Grid::new("system_info")
+                    .num_columns(2)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (label, value) in &info.rows {
+                            ui.label(*label);
+                            ui.label(value);
+                            ui.end_row();
+                        }
+                    });
+                if !info.warnings.is_empty() {
+                    ui.separator();
+                    ui.label(egui::RichText::new("Daemon warnings").strong());
+                    for warning in &info.warnings {
+                        ui.colored_label(theme.warning, warning);
+                    }
+                }
+            });
+        if !open {
+            self.system_info = None;
+        }
+    }
+
+    // Streamed output of the last compose or build command
+    fn command_output_panel(&mut self, ui: &mut egui::Ui) {
+        if self.command_output.is_empty() {
+            return;
+        }
+        ui.group(|ui| {
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new("Output").strong());
+                if ui.button("Clear").clicked() {
+                    self.command_output.clear();
+                }
+            });
+            egui::ScrollArea::vertical()
+                .id_source("command_output")
+                .max_height(200.0)
+                .stick_to_bottom(true)
+                .auto_shrink([false, true])
+                .show(ui, |ui| {
+                    for line in &self.command_output {
+                        ui.monospace(line);
+                    }
+                });
+        });
+    }
+
+    // Runs the command for the whole file, or only for `services` when there are any
+    fn run_compose_file(&mut self, path: PathBuf, command: ComposeCommand, services: Vec<String>) {
+        self.run_compose_file_after_stopping(path, command, services, Vec::new());
+    }
+
+    // `run_compose_file` once the containers, given as ID and name, are stopped
+    fn run_compose_file_after_stopping(
+        &mut self,
+        path: PathBuf,
+        command: ComposeCommand,
+        services: Vec<String>,
+        containers: Vec<(String, String)>,
+    ) {
+        let Some(parent) = path.parent().map(Path::to_owned) else {
+            let _ = self.result_sender.send(Err(format!(
+                "Cannot determine the parent directory for {:?}",
+                path
+            )));
+            return;
+        };
+        let output = self.output_sender.clone();
+        let compose_files = compose_file_set(&path, &self.compose_files);
+        self.command_output.clear();
+        for (id, name) in &containers {
+            self.suspend_keep_alive(id);
+            self.action_history
+                .sent(ContainerAction::Stop.describe(name), unix_now());
+        }
+        let handle = spawn_reported(&self.result_sender, async move {
+            if !containers.is_empty() {
+                let docker = BollardDocker::connect()?;
+                for (id, _) in &containers {
+                    run_action(&docker, ContainerAction::Stop, id).await?;
+                }
+            }
+            run_docker_compose(&parent, None, &compose_files, command, &services, output).await
+        });
+        self.running_compose_commands
+            .insert(path, (command, handle));
+    }
+
+    // Compose up, unless ports it publishes are taken. Then the user picks whether to go ahead
+    // or stop the containers holding them first.
+    fn request_compose_up(&mut self, path: PathBuf, services: Vec<String>) {
+        let compose_files = compose_file_set(&path, &self.compose_files);
+        let working_dir = path.parent().map(Path::to_owned).unwrap_or_default();
+        // A file that does not load is left for compose to report
+        let conflicts = match ComposeFile::load(&compose_files, &working_dir) {
+            Ok(compose) => find_conflicts(
+                &requested_ports(&compose, &services),
+                &self.containers,
+                &working_dir,
+                in_use_on_host,
+            ),
+            Err(_) => Vec::new(),
+        };
+        if conflicts.is_empty() {
+            self.run_compose_file(path, ComposeCommand::Up, services);
+        } else {
+            self.pending_up = Some(PendingUp {
+                path,
+                services,
+                conflicts,
+            });
+        }
+    }
+
+    fn port_conflicts_window(&mut self, ctx: &egui::Context) {
+        let theme = self.theme;
+        let Some(pending) = &self.pending_up else {
+            return;
+        };
+        let containers = pending.containers();
+        let mut proceed = false;
+        let mut stop_first = false;
+        let mut cancelled = false;
+        egui::Window::new("Ports in use")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "docker compose up for {:?} publishes ports that are already in use:",
+                    pending.path
+                ));
+                for conflict in &pending.conflicts {
+                    ui.colored_label(theme.warning, conflict.describe());
+                }
+                ui.horizontal(|ui| {
+                    proceed = mutating_button(ui, true, "Up anyway").clicked();
+                    let names: Vec<&str> =
+                        containers.iter().map(|(_, name)| name.as_str()).collect();
+                    stop_first =
+                        mutating_button(ui, !containers.is_empty(), "Stop containers and up")
+                            .on_hover_text(format!("Stops {}", names.join(", ")))
+                            .clicked();
+                    cancelled = ui.button("Cancel").clicked();
+                });
+            });
+        if !(proceed || stop_first || cancelled) {
+            return;
+        }
+        let Some(pending) = self.pending_up.take() else {
+            return;
+        };
+        if proceed {
+            self.run_compose_file(pending.path, ComposeCommand::Up, pending.services);
+        } else if stop_first {
+            self.run_compose_file_after_stopping(
+                pending.path,
+                ComposeCommand::Up,
+                pending.services,
+                containers,
+            );
+        }
+    }
+
+    fn confirm_compose_down_window(&mut self, ctx: &egui::Context) {
+        let Some(path) = self.confirm_compose_down.clone() else {
+            return;
+        };
+        let mut confirmed = false;
+        let mut cancelled = false;
+        egui::Window::new("Confirm docker compose down")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "This stops and removes the containers and networks of {:?}.",
+                    path
+                ));
+                ui.horizontal(|ui| {
+                    confirmed = ui.button("Down").clicked();
+                    cancelled = ui.button("Cancel").clicked();
+                });
+            });
+        if confirmed {
+            self.run_compose_file(path, ComposeCommand::Down, Vec::new());
+        }
+        if confirmed || cancelled {
+            self.confirm_compose_down = None;
+        }
+    }
+
+    fn composes_appview(&mut self, ui: &mut egui::Ui) {
+        if Self::scan_header(
+            ui,
+            self.compose_files.len(),
+            &self.scan_settings.roots,
+            self.compose_scan.is_some(),
+        ) {
+            self.rescan_compose_files();
+        }
+
+        let mut requested = None;
+        // Path and Docker containers separation line
+        ui.vertical(|ui| {
+            for path in &self.compose_files {
+                ui.separator();
+                ui.horizontal(|ui| {
+                    // Override files are listed right below their base file
+                    if is_compose_override(path) {
+                        ui.add_space(16.0);
+                        ui.label("↳");
+                    }
+                    // Extract the last three folders from the path
+                    let folders: Vec<_> = path.iter().rev().collect();
+                    let display_path = folders
+                        .iter()
+                        .rev()
+                        .map(|p| p.to_string_lossy())
+                        .collect::<Vec<_>>()
+                        .join("/");
+                    if ui
+                        .selectable_label(
+                            self.selected_compose_for_preview == Some(path.clone()),
+                            display_path,
+                        )
+                        .clicked()
+                    {
+                        self.selected_compose_for_preview = Some(path.clone())
+                    }
+
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if let Some((running, _)) = self.running_compose_commands.get(path) {
+                            ui.spinner();
+                            ui.label(format!("{} running…", running.name()));
+                            return;
+                        }
+                        let commands: &[ComposeCommand] =
+                            if self.selected_compose_for_preview.as_ref() == Some(path) {
+                                &[
+                                    ComposeCommand::Down,
+                                    ComposeCommand::Stop,
+                                    ComposeCommand::Restart,
+                                    ComposeCommand::Up,
+                                ]
+                            } else {
+                                &[ComposeCommand::Up]
+                            };
+                        for command in commands {
+                            if mutating_button(ui, true, command.label()).clicked() {
+                                requested = Some((path.clone(), *command));
+                            }
+                        }
+                    });
+                });
+            }
+        });
+
+        match requested {
+            // Down removes containers, so it needs to be confirmed first
+            Some((path, ComposeCommand::Down)) => self.confirm_compose_down = Some(path),
+            Some((path, ComposeCommand::Up)) => self.request_compose_up(path, Vec::new()),
+            Some((path, command)) => self.run_compose_file(path, command, Vec::new()),
+            None => {}
+        }
+        self.confirm_compose_down_window(ui.ctx());
+        self.port_conflicts_window(ui.ctx());
+        self.command_output_panel(ui);
+
+        self.compose_preview(ui);
+    }
+
+    // Summary of the selected compose file above its text, with the lines compose or the
+    // YAML parser complained about in red
+    fn compose_preview(&mut self, ui: &mut egui::Ui) {
+        let theme = self.theme;
+        let Some(selected) = &self.selected_compose_for_preview else {
+            self.compose_preview = None;
+            return;
+        };
+        if self
+            .compose_preview
+            .as_ref()
+            .is_none_or(|preview| &preview.path != selected)
+        {
+            self.compose_preview = Some(ComposePreview::load(selected.clone()));
+        }
+        let Some(preview) = &mut self.compose_preview else {
+            return;
+        };
+        if let Some(pending) = &mut preview.pending {
+            match pending.try_recv() {
+                Ok(result) => {
+                    preview.validation = Some(result);
+                    preview.pending = None;
+                }
+                Err(oneshot::error::TryRecvError::Empty) => {}
+                Err(oneshot::error::TryRecvError::Closed) => preview.pending = None,
+            }
+        }
+
+        // One command at a time per file, its output goes to the shared panel
+        let idle = !self.running_compose_commands.contains_key(&preview.path);
+        let mut requested = None;
+        let red = theme.error;
+        ui.group(|ui| {
+            match &preview.parsed {
+                Ok(compose) => {
+                    egui::Grid::new("compose_services")
+                        .striped(true)
+                        .num_columns(5)
+                        .show(ui, |ui| {
+                            ui.strong("Service");
+                            ui.strong("Image / build");
+                            ui.strong("Ports");
+                            ui.strong("Depends on");
+                            ui.label("");
+                            ui.end_row();
+                            for (name, service) in &compose.services {
+                                ui.label(name);
+                                match (&service.image, service.build_context()) {
+                                    (Some(image), _) => ui.label(image),
+                                    (None, Some(context)) => ui.label(format!("build {}", context)),
+                                    (None, None) => ui.weak("none"),
+                                };
+                                ui.label(service.published_ports().join(", "));
+                                ui.label(service.depends_on().join(", "));
+                                ui.horizontal(|ui| {
+                                    for command in [ComposeCommand::Up, ComposeCommand::Stop] {
+                                        if mutating_button(ui, idle, command.label()).clicked() {
+                                            requested = Some((command, name.clone()));
+                                        }
+                                    }
+                                    if ui.add_enabled(idle, egui::Button::new("Logs")).clicked() {
+                                        requested = Some((ComposeCommand::Logs, name.clone()));
+                                    }
+                                });
+                                ui.end_row();
+                            }
+                        });
+                    if !compose.volumes.is_empty() {
+                        let volumes: Vec<&str> =
+                            compose.volumes.keys().map(String::as_str).collect();
+                        ui.label(format!("Volumes: {}", volumes.join(", ")));
+                    }
+                    match compose.start_order() {
+                        Ok(order) => ui.label(format!("Start order: {}", order.join(" → "))),
+                        Err(e) => ui.colored_label(red, e),
+                    };
+                }
+                Err(e) => {
+                    ui.colored_label(red, format!("Invalid YAML: {}", e));
+                }
+            }
+            match &preview.validation {
+                None if preview.pending.is_some() => {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label("Validating with docker compose config…");
+                    });
+                }
+                Some(Ok(())) => {
+                    ui.label("docker compose config: valid");
+                }
+                Some(Err(e)) => {
+                    ui.colored_label(red, e);
+                }
+                None => {}
+            }
+        });
+
+        if let Some((command, service)) = requested {
+            let path = preview.path.clone();
+            match command {
+                ComposeCommand::Up => self.request_compose_up(path, vec![service]),
+                _ => self.run_compose_file(path, command, vec![service]),
+            }
+            return;
+        }
+
+        let marked = preview.error_lines();
+        let interpolated = preview.interpolated.then(|| {
+            interpolation::interpolate(&preview.content, &|name: &str| {
+                preview.variables.get(name).cloned()
+            })
+        });
+        ui.group(|ui| {
+            ui.horizontal(|ui| {
+                ui.selectable_value(&mut preview.interpolated, false, "Raw");
+                ui.selectable_value(&mut preview.interpolated, true, "Interpolated")
+                    .on_hover_text("Variables substituted from .env and the environment");
+                let missing = interpolated
+                    .as_deref()
+                    .map(interpolation::missing)
+                    .unwrap_or_default();
+                if !missing.is_empty() {
+                    let described: Vec<String> = missing.iter().map(|m| m.describe()).collect();
+                    ui.colored_label(red, format!("{} unresolved", missing.len()))
+                        .on_hover_text(described.join("\n"));
+                }
+            });
+            egui::ScrollArea::vertical()
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    if let Some(lines) = &interpolated {
+                        for (index, pieces) in lines.iter().enumerate() {
+                            ui.horizontal(|ui| {
+                                ui.spacing_mut().item_spacing.x = 0.0;
+                                ui.monospace(format!("{:>4}  ", index + 1));
+                                for piece in pieces {
+                                    match piece {
+                                        Piece::Text(text) => {
+                                            ui.monospace(text);
+                                        }
+                                        Piece::Unresolved { original, missing } => {
+                                            let text = egui::RichText::new(original)
+                                                .monospace()
+                                                .color(red);
+                                            let described: Vec<String> =
+                                                missing.iter().map(|m| m.describe()).collect();
+                                            ui.label(text).on_hover_text(described.join("\n"));
+                                        }
+                                    }
+                                }
+                            });
+                        }
+                        return;
+                    }
+                    // Without a model the text is all there is, so it is shown as is
+                    if preview.parsed.is_err() && marked.is_empty() {
+                        ui.label(&preview.content);
+                        return;
+                    }
+                    for (index, line) in preview.content.lines().enumerate() {
+                        let text =
+                            egui::RichText::new(format!("{:>4}  {}", index + 1, line)).monospace();
+                        if marked.contains(&(index + 1)) {
+                            ui.label(text.color(red));
+                        } else {
+                            ui.label(text);
+                        }
+                    }
+                });
+        });
+    }
+
+    fn containers_appview(&mut self, ui: &mut egui::Ui) {
+        let theme = self.theme;
+        // Until the daemon answers, an empty list would look like there are no containers
+        let connection = self.connection.lock().unwrap().clone();
+        match connection {
+            Connection::Connecting if self.containers.is_empty() => {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("Connecting to Docker…");
+                });
+                return;
+            }
+            Connection::Failed(error) => {
+                ui.colored_label(theme.error, error);
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(format!("Retrying every {}s", RETRY_INTERVAL.as_secs()));
+                });
+                if self.containers.is_empty() {
+                    return;
+                }
+                ui.weak("The containers below are from before the connection was lost");
+            }
+            _ => {}
+        }
+        let shown = self.visible_container_ids();
+        // Too narrow to show the list next to the details, so they take its place
+        let full_screen_details = self.layout == LayoutMode::Narrow
+            && self.show_details
+            && self
+                .selected_container
+                .as_ref()
+                .is_some_and(|id| shown.contains(id));
+        if full_screen_details {
+            if ui.button("← Containers (Esc)").clicked() {
+                self.show_details = false;
+            }
+            self.details_panel(ui);
+            self.health_log_panel(ui);
+            self.logs_panel(ui);
+            return;
+        }
+        if self.split_active() {
+            self.split_panes(ui, &shown);
+            return;
+        }
+        self.container_list(ui, &shown, 300.0);
+        self.selected_container_panels(ui, &shown);
+    }
+
+    // The list on the left and the selected container on the right, each half as wide. The
+    // half the scroll keys act on is outlined and Tab or a click moves there.
+    fn split_panes(&mut self, ui: &mut egui::Ui, shown: &[String]) {
+        let margin = egui::Frame::group(ui.style()).inner_margin.sum();
+        let width = (ui.available_width() - ui.spacing().item_spacing.x) / 2.0 - margin.x;
+        let height = ui.available_height() - margin.y;
+        let focused = ui.visuals().selection.stroke;
+        let unfocused = ui.visuals().widgets.noninteractive.bg_stroke;
+        ui.horizontal_top(|ui| {
+            for pane in [Pane::List, Pane::Logs] {
+                let stroke = if self.split_view.focus == pane {
+                    focused
+                } else {
+                    unfocused
+                };
+                let frame = egui::Frame::group(ui.style())
+                    .stroke(stroke)
+                    .show(ui, |ui| {
+                        ui.set_width(width);
+                        ui.set_min_height(height);
+                        ui.vertical(|ui| match pane {
+                            Pane::List => {
+                                // Room for the filter rows above the list
+                                let list_height = height - 4.0 * ui.spacing().interact_size.y;
+                                self.container_list(ui, shown, list_height.max(100.0));
+                            }
+                            Pane::Logs => self.selected_container_panels(ui, shown),
+                        });
+                    });
+                let clicked = ui.input(|i| i.pointer.primary_clicked());
+                if clicked && ui.rect_contains_pointer(frame.response.rect) {
+                    self.split_view.focus = pane;
+                }
+            }
+        });
+    }
+
+    fn container_list(&mut self, ui: &mut egui::Ui, shown: &[String], list_height: f32) {
+        let theme = self.theme;
+        ui.horizontal(|ui| {
+            ui.label("Filter");
+            ui.add(
+                egui::TextEdit::singleline(&mut self.container_filter)
+                    .hint_text("name, image, status or project"),
+            );
+            if ui.small_button("✕").clicked() {
+                self.container_filter.clear();
+            }
+            ui.label(format!(
+                "{} of {} shown",
+                shown.len(),
+                self.containers.len()
+            ));
+            ui.checkbox(&mut self.group_by_project, "Group by compose project");
+            let mut sort = self.container_sort;
+            egui::ComboBox::from_id_source("container_sort")
+                .selected_text(format!("Sort: {}", sort.label()))
+                .show_ui(ui, |ui| {
+                    for option in [
+                        ContainerSort::Name,
+                        ContainerSort::Created,
+                        ContainerSort::Restarts,
+                        ContainerSort::Cpu,
+                        ContainerSort::Memory,
+                    ] {
+                        ui.selectable_value(&mut sort, option, option.label());
+                    }
+                })
+                .response
+                .on_hover_text("O cycles the order, % sorts by CPU and then memory");
+            if sort != self.container_sort {
+                self.set_container_sort(sort);
+            }
+            if ui
+                .button("Columns")
+                .on_hover_text("V, pick the columns of the list")
+                .clicked()
+            {
+                self.column_chooser = true;
+            }
+            if self.layout == LayoutMode::Wide
+                && ui
+                    .selectable_label(self.split_view.enabled, "Split")
+                    .on_hover_text("|, the list next to the live logs of the selected container")
+                    .clicked()
+            {
+                self.split_view.toggle();
+            }
+            if ui
+                .add_enabled(
+                    self.image_updates.pending.is_none(),
+                    egui::Button::new("Check updates"),
+                )
+                .on_hover_text("U, compare the images of running containers with their registry")
+                .clicked()
+            {
+                self.check_image_updates();
+            }
+        });
+        let mut apply_labels = false;
+        ui.horizontal(|ui| {
+            ui.label("Labels");
+            let input = ui.add(
+                egui::TextEdit::singleline(&mut self.label_filter_input)
+                    .hint_text("env=staging,tier!=db,traefik.enable"),
+            );
+            apply_labels = input.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            apply_labels |= ui.button("Apply").clicked();
+            if ui
+                .button("Saved filters")
+                .on_hover_text("F, switch between the filters in the config file")
+                .clicked()
+            {
+                self.filter_switcher = Some(String::new());
+            }
+        });
+        if apply_labels {
+            let expression = self.label_filter_input.clone();
+            self.apply_label_filter(&expression);
+        }
+
+        let shown_set: HashSet<&String> = shown.iter().collect();
+        let groups: Vec<ContainerGroup> = if self.group_by_project {
+            self.container_groups.clone()
+        } else {
+            vec![ContainerGroup {
+                project: None,
+                ids: self.container_ids.clone(),
+            }]
+        }
+        .into_iter()
+        .map(|group| ContainerGroup {
+            ids: group
+                .ids
+                .into_iter()
+                .filter(|id| shown_set.contains(id))
+                .collect(),
+            ..group
+        })
+        .filter(|group| !group.ids.is_empty())
+        .collect();
+
+        let columns = visible_columns(
+            ui.available_width(),
+            &self.config.column_priority(),
+            &self.config.shown_columns(),
+        );
+        let now = unix_now();
+        let mut to_remove = None;
+        let mut to_start = None;
+        let mut to_recreate = None;
+        let mut to_scale = None;
+        // One line per project header and container, only the ones scrolled into view are laid
+        // out so thousands of containers cost no more per frame than a screenful
+        let lines: Vec<(usize, Option<&String>)> = groups
+            .iter()
+            .enumerate()
+            .flat_map(|(index, group)| {
+                let header = self.group_by_project.then_some((index, None));
+                header
+                    .into_iter()
+                    .chain(group.ids.iter().map(move |id| (index, Some(id))))
+            })
+            .collect();
+        let row_height = ui.spacing().interact_size.y;
+        let mut scroll_area = egui::ScrollArea::vertical()
+            .id_source("container_list")
+            .max_height(list_height)
+            .auto_shrink([false, true]);
+        if self.scroll_to_selection {
+            let selected = lines
+                .iter()
+                .position(|(_, id)| id.is_some() && *id == self.selected_container.as_ref());
+            if let Some(index) = selected {
+                let line_height = row_height + ui.spacing().item_spacing.y;
+                let offset = index as f32 * line_height - (list_height - line_height) / 2.0;
+                scroll_area = scroll_area.vertical_scroll_offset(offset.max(0.0));
+            }
+            self.scroll_to_selection = false;
+        }
+        let mut replicas = HashMap::new();
+        scroll_area.show_rows(ui, row_height, lines.len(), |ui, range| {
+            for &(group_index, line) in &lines[range] {
+                let group = &groups[group_index];
+                let Some(container_id) = line else {
+                    let header = group.project.as_deref().unwrap_or("(standalone)");
+                    let selected =
+                        group.project.is_some() && self.selected_project == group.project;
+                    ui.horizontal(|ui| {
+                        if ui
+                            .selectable_label(selected, egui::RichText::new(header).strong())
+                            .clicked()
+                            && group.project.is_some()
+                        {
+                            self.selected_project = group.project.clone();
+                        }
+                        if selected {
+                            ui.with_layout(
+                                egui::Layout::right_to_left(egui::Align::Center),
+                                |ui| {
+                                    if ui.button("doctor").clicked() {
+                                        self.start_doctor(group);
+                                    }
+                                    if ui.button("logs").clicked() {
+                                        self.follow_project_logs(group);
+                                    }
+                                    if ui
+                                        .button("compose logs")
+                                        .on_hover_text(
+                                            "Follow docker compose logs in the project directory",
+                                        )
+                                        .clicked()
+                                    {
+                                        self.follow_compose_logs(group);
+                                    }
+                                    for command in [
+                                        ComposeCommand::Restart,
+                                        ComposeCommand::Down,
+                                        ComposeCommand::Up,
+                                    ] {
+                                        if mutating_button(ui, true, command.name()).clicked() {
+                                            self.run_project_command(group, command);
+                                        }
+                                    }
+                                },
+                            );
+                        }
+                    });
+                    continue;
+                };
+                let Some((summary, _logs)) = self.containers.get(container_id) else {
+                    continue;
+                };
+                let name = self.row_name(container_id, summary);
+                let health = summary.status.as_deref().and_then(Health::from_status);
+                let selected = self.selected_container.as_ref() == Some(container_id);
+                let pending = self.pending_actions.get(container_id);
+                ui.horizontal(|ui| {
+                    if self.group_by_project {
+                        ui.add_space(ui.spacing().indent);
+                    }
+                    let spoken = accessibility::container_name(
+                        &name,
+                        summary.state.as_deref(),
+                        summary.status.as_deref(),
+                    );
+                    let row = ui.selectable_label(selected, &name);
+                    if name_selectable(row, selected, &spoken).clicked() {
+                        self.selected_container = Some(container_id.clone());
+                    }
+                    // Replicas are counted over the whole group, once per frame it is in view
+                    let service = compose_service(summary)
+                        .filter(|_| self.group_by_project && columns.contains(&RowColumn::Service))
+                        .and_then(|service| {
+                            let replicas = replicas.entry(group_index).or_insert_with(|| {
+                                service_replicas(
+                                    group
+                                        .ids
+                                        .iter()
+                                        .filter_map(|id| self.containers.get(id))
+                                        .map(|(summary, _)| summary),
+                                )
+                            });
+                            let (running, total) = replicas.get(&service)?;
+                            Some(format!("{} ({}/{})", service, running, total))
+                        });
+                    if let Some(service) = service {
+                        ui.weak(service).on_hover_text("Running of all replicas");
+                    }
+                    if let Some(pending) = pending {
+                        ui.spinner();
+                        ui.weak(pending.action.progress());
+                    }
+                    let watched = watch_name(&self.containers, container_id)
+                        .is_some_and(|name| self.watched.contains(&name));
+                    if watched {
+                        ui.weak("watched")
+                            .on_hover_text("Crashes are alerted, A stops watching");
+                    }
+                    let kept = watch_name(&self.containers, container_id)
+                        .is_some_and(|name| self.keep_alive.is_kept(&name));
+                    if kept {
+                        ui.label("↻")
+                            .on_hover_text("Restarted when it crashes, Shift+K stops it");
+                    }
+                    let restarts = self.restarts.get(container_id);
+                    if let Some(badge) = restarts.badge() {
+                        let hover = format!(
+                            "Restarted {} times by its restart policy",
+                            restarts.restart_count
+                        );
+                        if restarts.is_looping() {
+                            ui.colored_label(theme.error, badge).on_hover_text(hover);
+                        } else {
+                            ui.weak(badge).on_hover_text(hover);
+                        }
+                    }
+                    if restarts.oom_killed {
+                        ui.colored_label(theme.error, "OOM")
+                            .on_hover_text("Its last exit was an OOM kill");
+                    }
+                    let outdated = summary.image.as_deref().filter(|image| {
+                        self.image_updates.status(image) == Some(&UpdateStatus::Outdated)
+                    });
+                    if let Some(image) = outdated {
+                        ui.colored_label(theme.warning, "⟳").on_hover_text(format!(
+                            "The registry has a newer {}, pull it and recreate the container",
+                            image
+                        ));
+                    }
+                    if columns.contains(&RowColumn::Health) {
+                        match health {
+                            Some(health) => ui.colored_label(health.color(&theme), health.label()),
+                            None => ui.label("-"),
+                        };
+                    }
+                    // What the list is sorted by, like top
+                    let usage = self
+                        .container_sort
+                        .resource()
+                        .and_then(|_| top::latest(&self.stats_history, container_id));
+                    if let Some(sample) = usage {
+                        ui.weak(format!(
+                            "{:.1}% CPU, {}",
+                            sample.cpu_percent,
+                            format_size(sample.memory as i64)
+                        ));
+                    }
+                    if columns.contains(&RowColumn::Image) {
+                        ui.weak(summary.image.as_deref().unwrap_or_default());
+                    }
+                    if columns.contains(&RowColumn::Created) {
+                        if let Some(created) = summary.created {
+                            ui.weak(format_age(created, now))
+                                .on_hover_text(format_timestamp(created));
+                        }
+                    }
+                    if columns.contains(&RowColumn::Ports) {
+                        let ports = container_row(summary).ports;
+                        if !ports.is_empty() {
+                            ui.weak(ports.join(", "));
+                        }
+                    }
+                    if columns.contains(&RowColumn::Id) {
+                        let id = summary.id.as_deref().unwrap_or_default();
+                        ui.weak(id.chars().take(12).collect::<String>());
+                    }
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        let logs = ui.button("Logs");
+                        if name_button(logs, &format!("Logs of {}", name)).clicked() {
+                            self.selected_container = Some(container_id.clone());
+                        }
+                    });
+                });
+            }
+        });
+
+        // Actions of the selected container, below the list rather than in its row so every
+        // row has the same height
+        let selected = self
+            .selected_container
+            .as_ref()
+            .filter(|id| shown_set.contains(id))
+            .and_then(|id| self.containers.get(id));
+        if let Some((summary, _)) = selected {
+            let name = self.row_name(summary.id.as_deref().unwrap_or_default(), summary);
+            let health = summary.status.as_deref().and_then(Health::from_status);
+            let pending = self
+                .selected_container
+                .as_ref()
+                .and_then(|id| self.pending_actions.get(id));
+            // Screen readers hear which container a button acts on, e.g. "Stop web"
+            let on_container = |text: &str| format!("{} {}", text, name);
+            ui.group(|ui| {
+                ui.horizontal_wrapped(|ui| {
+                    let remove = mutating_button(ui, pending.is_none(), "Remove");
+                    if name_button(remove, &on_container("Remove")).clicked() {
+                        to_remove = Some((name.clone(), summary.clone()));
+                    }
+                    let running = summary.state.as_deref() == Some("running");
+                    let options = &mut self.remove_options;
+                    ui.add_enabled_ui(pending.is_none() && !read_only::is_enabled(), |ui| {
+                        let menu =
+                            ui.menu_button("▾", |ui| remove_options_ui(ui, options, running));
+                        name_button(menu.response, "Remove options")
+                            .on_hover_text("How Remove removes");
+                    });
+                    if let Some(id) = &summary.id {
+                        let running = summary.state.as_deref() == Some("running");
+                        let actions = if running {
+                            [ContainerAction::Kill(SIGKILL), ContainerAction::Stop]
+                        } else {
+                            [ContainerAction::Kill(SIGKILL), ContainerAction::Start]
+                        };
+                        for action in actions {
+                            let button = mutating_button(ui, pending.is_none(), action.label());
+                            if name_button(button, &on_container(action.label())).clicked() {
+                                to_start = Some((id.clone(), action));
+                            }
+                            if matches!(action, ContainerAction::Kill(_)) {
+                                let enabled = pending.is_none() && !read_only::is_enabled();
+                                let input = &mut self.kill_signal_input;
+                                ui.add_enabled_ui(enabled, |ui| {
+                                    let signal = signal_menu(ui, input, &theme);
+                                    if let Some(signal) = signal {
+                                        to_start =
+                                            Some((id.clone(), ContainerAction::Kill(signal)));
+                                    }
+                                });
+                            }
+                        }
+                        let recreate = mutating_button(ui, pending.is_none(), "Recreate");
+                        if name_button(recreate, &on_container("Recreate"))
+                            .on_hover_text("Replace it with a new container from the same settings")
+                            .clicked()
+                        {
+                            to_recreate = Some(id.clone());
+                        }
+                        let details = ui.selectable_label(self.show_details, "Details");
+                        let details = name_selectable(
+                            details,
+                            self.show_details,
+                            &on_container("Details of"),
+                        );
+                        if details.clicked() {
+                            self.show_details = !self.show_details;
+                        }
+                        if mutating_button(ui, true, "Scale")
+                            .on_hover_text("Replicas of the compose service")
+                            .clicked()
+                        {
+                            to_scale = Some(id.clone());
+                        }
+                        if mutating_button(ui, true, "Limits").clicked() {
+                            self.resource_form = Some(ResourceForm::load(id.clone(), name.clone()));
+                        }
+                        if ui
+                            .button("Diff")
+                            .on_hover_text("Files changed in the container (D)")
+                            .clicked()
+                        {
+                            self.container_diff = Some(DiffState::load(id.clone(), name.clone()));
+                        }
+                        if ui.button("Files").clicked() {
+                            self.file_browser = Some(FileBrowser::new(id.clone(), name.clone()));
+                        }
+                        if running
+                            && mutating_button(ui, true, "Attach")
+                                .on_hover_text(
+                                    "Connect to the stdin and output of the main process",
+                                )
+                                .clicked()
+                        {
+                            self.attach = Some(AttachState::start(
+                                id.clone(),
+                                name.clone(),
+                                self.detach_keys.clone(),
+                            ));
+                        }
+                        if mutating_button(ui, true, "Commit").clicked() {
+                            self.commit_form = Some(CommitForm::new(id.clone(), name.clone()));
+                        }
+                        if ui.button("Export").clicked() {
+                            self.tarball_prompt =
+                                Some(TarballPrompt::new(TarballAction::ExportContainer {
+                                    id: id.clone(),
+                                    name: name.clone(),
+                                }));
+                        }
+                    }
+                    // Only containers with a healthcheck have probes to inspect
+                    if let (Some(_), Some(id)) = (health, &summary.id) {
+                        if ui.button("Health log").clicked() {
+                            self.health_log = Some(HealthLogState::load(id.clone()));
+                        }
+                    }
+                });
+            });
+        }
+        if let Some(id) = to_scale {
+            self.open_scale_form(&id);
+        }
+        if let Some((name, summary)) = to_remove {
+            self.remove_container(name, summary);
+        }
+        if let Some((id, action)) = to_start {
+            self.start_action(id, action);
+        }
+        if to_recreate.is_some() {
+            self.confirm_recreate = to_recreate;
+        }
+
+        // Output of project commands and scaling
+        self.command_output_panel(ui);
+    }
+
+    fn selected_container_panels(&mut self, ui: &mut egui::Ui, shown: &[String]) {
+        // Keep the selection while it is filtered out, but do not show its stale logs
+        if let Some(id) = &self.selected_container {
+            if self.containers.contains_key(id) && !shown.contains(id) {
+                ui.separator();
+                ui.label(
+                    "The selected container is hidden by the filter, clear it to see its logs.",
+                );
+                return;
+            }
+        }
+        self.details_panel(ui);
+        self.health_log_panel(ui);
+        self.logs_panel(ui);
+    }
+
+    fn details_panel(&mut self, ui: &mut egui::Ui) {
+        let theme = self.theme;
+        if !self.show_details {
+            return;
+        }
+        let Some((id, (summary, _))) = self
+            .selected_container
+            .as_ref()
+            .and_then(|id| self.containers.get_key_value(id))
+        else {
+            return;
+        };
+        // Starting or stopping a container can change what it runs with, e.g. after an update
+        let stale = self
+            .container_details
+            .get(id)
+            .is_none_or(|cached| cached.state != summary.state);
+        if stale {
+            self.container_details.insert(
+                id.clone(),
+                DetailsState::load(id.clone(), summary.state.clone()),
+            );
+        }
+        // Forget containers that are gone
+        self.container_details
+            .retain(|id, _| self.containers.contains_key(id));
+        let Some(state) = self.container_details.get_mut(id) else {
+            return;
+        };
+        if let Some(pending) = &mut state.pending {
+            match pending.try_recv() {
+                Ok(Ok(details)) => {
+                    state.restart_policy = details.restart_policy.clone();
+                    state.restart_max_retries = details.restart_max_retries;
+                    state.details = details;
+                    state.pending = None;
+                }
+                Ok(Err(e)) => {
+                    state.error = Some(e);
+                    state.pending = None;
+                }
+                Err(oneshot::error::TryRecvError::Empty) => {}
+                Err(oneshot::error::TryRecvError::Closed) => state.pending = None,
+            }
+        }
+        if let Some(update) = &mut state.restart_update {
+            match update.try_recv() {
+                Ok(result) => {
+                    state.restart_update = None;
+                    // Reloaded so the details show what the daemon now has
+                    *state = DetailsState::load(id.clone(), summary.state.clone());
+                    self.status_message = Some(result);
+                }
+                Err(oneshot::error::TryRecvError::Empty) => {}
+                Err(oneshot::error::TryRecvError::Closed) => state.restart_update = None,
+            }
+        }
+
+        let history = self.stats_history.get(id);
+        let reveal = &mut self.reveal_secrets;
+        let field = &mut self.details_field;
+        let focused_network = &mut self.details_network;
+        let image_id = summary.image_id.clone();
+        let mut open_network = None;
+        let mut open_image = false;
+        let created = summary.created;
+        let running = summary.state.as_deref() == Some("running");
+        ui.group(|ui| {
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new("Details").strong());
+                ui.checkbox(reveal, "Reveal secrets").on_hover_text(
+                    "Show values of variables with PASSWORD, SECRET or TOKEN in their name",
+                );
+            });
+            if state.pending.is_some() {
+                ui.spinner();
+                return;
+            }
+            if let Some(error) = &state.error {
+                ui.colored_label(theme.error, error);
+                return;
+            }
+            let details = &state.details;
+            egui::Grid::new("container_details")
+                .num_columns(2)
+                .striped(true)
+                .show(ui, |ui| {
+                    let now = unix_now();
+                    if let Some(created) = created {
+                        ui.label("Created");
+                        ui.label(format!(
+                            "{} ({})",
+                            format_timestamp(created),
+                            format_age(created, now)
+                        ));
+                        ui.end_row();
+                    }
+                    if let Some(image) = &summary.image {
+                        ui.label("Image");
+                        open_image = ui.link(image).on_hover_text("Open in Images, I").clicked();
+                        ui.end_row();
+                    }
+                    let started_at = details.started_at.as_deref().unwrap_or_default();
+                    if let Some(uptime) = uptime(started_at, now).filter(|_| running) {
+                        ui.label("Uptime");
+                        ui.label(uptime);
+                        ui.end_row();
+                    }
+                });
+            ui.separator();
+            ui.label(egui::RichText::new("Host config").strong());
+            ui.horizontal(|ui| {
+                ui.label("Restart policy");
+                let current = resources::describe_restart_policy(
+                    &state.details.restart_policy,
+                    state.details.restart_max_retries,
+                );
+                egui::ComboBox::from_id_source("details_restart_policy")
+                    .selected_text(state.restart_policy.as_str())
+                    .show_ui(ui, |ui| {
+                        for policy in RESTART_POLICIES {
+                            ui.selectable_value(
+                                &mut state.restart_policy,
+                                policy.to_string(),
+                                policy,
+                            );
+                        }
+                    })
+                    .response
+                    .on_hover_text(format!("Currently {}", current));
+                if state.restart_policy == "on-failure" {
+                    ui.label("Max retries");
+                    ui.add(
+                        egui::DragValue::new(&mut state.restart_max_retries).clamp_range(0..=100),
+                    )
+                    .on_hover_text("0 retries without limit");
+                } else {
+                    state.restart_max_retries = 0;
+                }
+                let changed = state.restart_policy != state.details.restart_policy
+                    || state.restart_max_retries != state.details.restart_max_retries;
+                let apply = mutating_button(ui, changed && state.restart_update.is_none(), "Apply")
+                    .on_hover_text("Takes effect without restarting the container");
+                if apply.clicked() {
+                    let (sender, receiver) = oneshot::channel();
+                    let container_id = id.clone();
+                    let policy = state.restart_policy.clone();
+                    let max_retries = state.restart_max_retries;
+                    tokio::spawn(async move {
+                        let _ = sender.send(
+                            resources::update_restart_policy(container_id, policy, max_retries)
+                                .await,
+                        );
+                    });
+                    state.restart_update = Some(receiver);
+                }
+                if state.restart_update.is_some() {
+                    ui.spinner();
+                }
+            });
+            ui.horizontal(|ui| {
+                for name in DETAIL_FIELDS {
+                    if ui.selectable_label(*field == *name, *name).clicked() {
+                        *field = name;
+                    }
+                }
+            });
+            // Host, container and protocol columns and the like, "-" when there are no rows
+            let table = |ui: &mut egui::Ui, id: &str, headers: [&str; 3], rows: Vec<_>| {
+                if rows.is_empty() {
+                    ui.label("-");
+                    return;
+                }
+                egui::Grid::new(id)
+                    .num_columns(3)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for header in headers {
+                            ui.strong(header);
+                        }
+                        ui.end_row();
+                        for (a, b, c) in rows {
+                            ui.monospace(a);
+                            ui.monospace(b);
+                            ui.monospace(c);
+                            ui.end_row();
+                        }
+                    });
+            };
+            match *field {
+                "Command" => {
+                    egui::Grid::new("container_command")
+                        .num_columns(2)
+                        .show(ui, |ui| {
+                            ui.label("Entrypoint");
+                            ui.monospace(command_line(&details.entrypoint));
+                            ui.end_row();
+                            ui.label("Cmd");
+                            ui.monospace(command_line(&details.cmd));
+                            ui.end_row();
+                        });
+                }
+                "Environment" => {
+                    egui::Grid::new("container_env")
+                        .num_columns(2)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for (key, value) in &details.env {
+                                ui.monospace(key);
+                                if is_secret(key) && !*reveal {
+                                    ui.monospace(MASK);
+                                } else {
+                                    ui.monospace(value);
+                                }
+                                ui.end_row();
+                            }
+                        });
+                }
+                "Ports" => table(
+                    ui,
+                    "container_ports",
+                    ["Host", "Container", "Protocol"],
+                    port_rows(summary),
+                ),
+                "Mounts" => table(
+                    ui,
+                    "container_mounts",
+                    ["Source", "Destination", "Mode"],
+                    mount_rows(summary),
+                ),
+                // Names are selectable, N or a double-click opens the network
+                "Networks" => {
+                    let rows = network_rows(summary);
+                    if rows.is_empty() {
+                        ui.label("-");
+                    }
+                    egui::Grid::new("container_networks")
+                        .num_columns(3)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.strong("Network");
+                            ui.strong("IP");
+                            ui.strong("Gateway");
+                            ui.end_row();
+                            for (name, ip, gateway) in rows {
+                                let selected = focused_network.as_ref() == Some(&name);
+                                let response = ui
+                                    .selectable_label(selected, &name)
+                                    .on_hover_text("N or double-click opens it in Networks");
+                                if response.clicked() {
+                                    *focused_network = Some(name.clone());
+                                }
+                                if response.double_clicked() {
+                                    open_network = Some(name.clone());
+                                }
+                                ui.monospace(ip);
+                                ui.monospace(gateway);
+                                ui.end_row();
+                            }
+                        });
+                }
+                _ => {
+                    egui::Grid::new("container_labels")
+                        .num_columns(3)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for (key, value) in label_rows(summary) {
+                                ui.monospace(key);
+                                ui.monospace(&value);
+                                if ui.small_button("Copy").clicked() {
+                                    ui.output_mut(|o| o.copied_text = value);
+                                }
+                                ui.end_row();
+                            }
+                        });
+                }
+            }
+            let Some(history) = history.filter(|_| running) else {
+                return;
+            };
+            ui.separator();
+            let cpu = history.cpu();
+            let memory = history.memory();
+            egui::Grid::new("container_stats")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    if let Some((current, min, max)) = stats::summary(&cpu) {
+                        sparkline(ui, &cpu, SPARKLINE_SIZE, theme.cpu);
+                        ui.label(format!(
+                            "CPU {:.1}% (min {:.1}%, max {:.1}%)",
+                            current, min, max
+                        ));
+                        ui.end_row();
+                    }
+                    if let Some((current, min, max)) = stats::summary(&memory) {
+                        sparkline(ui, &memory, SPARKLINE_SIZE, theme.memory);
+                        ui.label(format!(
+                            "Memory {} (min {}, max {})",
+                            format_size(current as i64),
+                            format_size(min as i64),
+                            format_size(max as i64)
+                        ));
+                        ui.end_row();
+                    }
+                });
+        });
+        if let Some(network) = open_network {
+            self.select_network_by_id(&network);
+        }
+        if let (true, Some(image_id)) = (open_image, image_id) {
+            self.select_image_by_id(&image_id);
+        }
+    }
+
+    fn health_log_panel(&mut self, ui: &mut egui::Ui) {
+        let theme = self.theme;
+        let selected_id = self
+            .selected_container
+            .as_ref()
+            .and_then(|id| self.containers.get(id))
+            .and_then(|(summary, _)| summary.id.clone());
+        let Some(state) = &mut self.health_log else {
+            return;
+        };
+        if selected_id.as_ref() != Some(&state.container_id) {
+            self.health_log = None;
+            return;
+        }
+        if let Some(pending) = &mut state.pending {
+            match pending.try_recv() {
+                Ok(Ok(entries)) => {
+                    state.entries = entries;
+                    state.pending = None;
+                }
+                Ok(Err(e)) => {
+                    state.error = Some(e);
+                    state.pending = None;
+                }
+                Err(oneshot::error::TryRecvError::Empty) => {}
+                Err(oneshot::error::TryRecvError::Closed) => state.pending = None,
+            }
+        }
+
+        let mut close = false;
+        ui.group(|ui| {
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new("Health log").strong());
+                if ui.button("Refresh").clicked() {
+                    *state = HealthLogState::load(state.container_id.clone());
+                }
+                close = ui.button("Close").clicked();
+            });
+            if state.pending.is_some() {
+                ui.spinner();
+                return;
+            }
+            if let Some(error) = &state.error {
+                ui.colored_label(theme.error, error);
+                return;
+            }
+            egui::Grid::new("health_log")
+                .num_columns(3)
+                .striped(true)
+                .show(ui, |ui| {
+                    for entry in state.entries.iter().rev() {
+                        let exit_code = entry.exit_code.unwrap_or(-1);
+                        let color = if exit_code == 0 {
+                            theme.success
+                        } else {
+                            theme.error
+                        };
+                        ui.label(entry.start.as_deref().unwrap_or("-"));
+                        ui.colored_label(color, format!("exit {}", exit_code));
+                        ui.label(entry.output.as_deref().unwrap_or_default().trim());
+                        ui.end_row();
+                    }
+                });
+        });
+        if close {
+            self.health_log = None;
+        }
+    }
+
+    fn logs_panel(&mut self, ui: &mut egui::Ui) {
+        let theme = self.theme;
+        let Some((summary, logs)) = self
+            .selected_container
+            .as_ref()
+            .and_then(|id| self.containers.get(id))
+        else {
+            return;
+        };
+        let Some(id) = summary.id.clone() else {
+            return;
+        };
+
+        // Restore the saved scroll position once, right after switching containers
+        let switched = self.logs_shown_for.as_ref() != Some(&id);
+        if switched {
+            self.logs_shown_for = Some(id.clone());
+            self.scroll_notice = None;
+        }
+        // A fetched time range replaces the polled tail until it is closed
+        if let Some(range_logs) = &mut self.range_logs {
+            if range_logs.container_id != id {
+                self.range_logs = None;
+            } else if let Some(pending) = &mut range_logs.pending {
+                match pending.try_recv() {
+                    Ok(Ok(logs)) => {
+                        range_logs.logs = logs;
+                        range_logs.pending = None;
+                    }
+                    Ok(Err(e)) => {
+                        range_logs.error = Some(e);
+                        range_logs.pending = None;
+                    }
+                    Err(oneshot::error::TryRecvError::Empty) => {}
+                    Err(oneshot::error::TryRecvError::Closed) => range_logs.pending = None,
+                }
+            }
+        }
+        let mut close_range = false;
+        let mut open_range = false;
+        let scroll_request = self.log_scroll_request.take();
+        let selection_request = self.log_selection_request.take();
+        // A time range wins over the followed stream, which wins over the polled tail. Lines
+        // are numbered from the start of the stream, `first` is the oldest one still kept.
+        let (source, first): (Vec<&str>, usize) = match (&self.range_logs, &self.followed_logs) {
+            (Some(range_logs), _) => (range_logs.logs.lines().collect(), 0),
+            (None, Some(followed)) if followed.container_id == id => (
+                followed.lines.iter().map(String::as_str).collect(),
+                followed.lines.dropped(),
+            ),
+            _ => (logs.lines().collect(), 0),
+        };
+        // A selection belongs to one container and shrinks with the lines it covers
+        self.log_selection = self
+            .log_selection
+            .take()
+            .filter(|selection| selection.container_id == id)
+            .and_then(|selection| selection.clamp(first));
+        let state = self.container_ui_state.get_mut(&id);
+        let log_tail = &self.log_tail;
+
+        ui.group(|ui| {
+            let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+            let char_width = ui.fonts(|fonts| {
+                fonts.glyph_width(&egui::TextStyle::Monospace.resolve(ui.style()), 'M')
+            });
+            if let Some(range_logs) = &self.range_logs {
+                ui.horizontal(|ui| {
+                    ui.strong(format!("Logs {}", range_logs.range.label));
+                    if range_logs.pending.is_some() {
+                        ui.spinner();
+                    }
+                    close_range = ui.button("Back to live logs").clicked();
+                });
+                if let Some(error) = &range_logs.error {
+                    ui.colored_label(theme.error, error);
+                }
+            }
+            ui.horizontal(|ui| {
+                open_range = ui
+                    .button("Time range")
+                    .on_hover_text("Fetch the logs between two points in time, T")
+                    .clicked();
+                ui.checkbox(&mut state.follow, "Follow")
+                    .on_hover_text("Stream new lines as they are written");
+                ui.checkbox(&mut state.wrap, "Wrap")
+                    .on_hover_text("W, without it Left and Right scroll sideways");
+                if let Some(label) = h_offset_label(state.log_h_scroll, char_width) {
+                    if !state.wrap {
+                        ui.weak(label);
+                    }
+                }
+                if ui
+                    .button(format!("Level: {}", state.level_filter.label()))
+                    .on_hover_text("Minimum level shown, L cycles it")
+                    .clicked()
+                {
+                    state.level_filter = state.level_filter.next();
+                }
+                let mut tail = log_tail.load(Ordering::Relaxed);
+                egui::ComboBox::from_id_source("log_tail")
+                    .selected_text(
+                        LOG_TAIL_CHOICES
+                            .iter()
+                            .find(|(lines, _)| *lines == tail)
+                            .map_or_else(|| tail.to_string(), |(_, label)| label.to_string()),
+                    )
+                    .show_ui(ui, |ui| {
+                        for (lines, label) in LOG_TAIL_CHOICES {
+                            ui.selectable_value(&mut tail, lines, label);
+                        }
+                    })
+                    .response
+                    .on_hover_text("Lines fetched per container");
+                log_tail.store(tail, Ordering::Relaxed);
+                ui.label("Search");
+                ui.text_edit_singleline(&mut state.search);
+                if let Some(notice) = &self.scroll_notice {
+                    ui.label(notice);
+                }
+                if let Some(selection) = &self.log_selection {
+                    ui.label(format!(
+                        "{} selected, Y copies, Esc clears",
+                        line_count(selection.range().count())
+                    ));
+                }
+            });
+
+            // Unwrapped lines run past the right edge, so they scroll sideways too
+            let scroll_area = if state.wrap {
+                egui::ScrollArea::vertical()
+            } else {
+                egui::ScrollArea::both()
+            };
+            let mut scroll_area = scroll_area
+                .id_source("container_logs")
+                .auto_shrink([false, false])
+                .stick_to_bottom(state.follow && self.log_selection.is_none());
+            if switched && !state.follow {
+                scroll_area = scroll_area.vertical_scroll_offset(state.log_scroll);
+            }
+            let search = state.search.to_lowercase();
+            let levels = classify_lines(source.iter().copied());
+            let lines: Vec<(usize, &str, Option<LogLevel>)> = (first..)
+                .zip(source.iter().copied())
+                .zip(levels)
+                .map(|((number, line), level)| (number, line, level))
+                .filter(|(_, line, level)| {
+                    state.level_filter.allows(*level)
+                        && (search.is_empty() || line.to_lowercase().contains(&search))
+                })
+                .collect();
+
+            let mut cursor_moved = false;
+            match selection_request {
+                Some(SelectionRequest::Start) => {
+                    // The newest line while following, otherwise the one at the top
+                    let top = (state.log_scroll / row_height) as usize;
+                    let line = if state.follow {
+                        lines.last()
+                    } else {
+                        lines.get(top).or(lines.last())
+                    };
+                    self.log_selection =
+                        line.map(|(number, _, _)| LogSelection::new(id.clone(), *number));
+                }
+                Some(request @ (SelectionRequest::Up | SelectionRequest::Down)) => {
+                    if let Some(selection) = &mut self.log_selection {
+                        let shown: Vec<usize> =
+                            lines.iter().map(|(number, _, _)| *number).collect();
+                        selection.step(&shown, request == SelectionRequest::Down);
+                        cursor_moved = true;
+                    }
+                }
+                Some(SelectionRequest::Copy) => match self.log_selection.take() {
+                    Some(selection) => {
+                        let shown = lines.iter().map(|(number, line, _)| (*number, *line));
+                        let (text, count) = selected_text(shown, &selection);
+                        ui.ctx().output_mut(|output| output.copied_text = text);
+                        self.status_message = Some(Ok(format!("Copied {}", line_count(count))));
+                    }
+                    None => {
+                        self.status_message =
+                            Some(Err("Select log lines with Shift+V first".to_string()));
+                    }
+                },
+                Some(SelectionRequest::Clear) => self.log_selection = None,
+                None => {}
+            }
+            // Keep the moving end in view, rows are only a line high without wrapping
+            let cursor = self
+                .log_selection
+                .as_ref()
+                .map(|selection| selection.cursor);
+            let row = lines
+                .iter()
+                .position(|(number, _, _)| Some(*number) == cursor);
+            if let (true, false, Some(row)) = (cursor_moved, state.wrap, row) {
+                let row_step = row_height + ui.spacing().item_spacing.y;
+                let view = ui.available_height();
+                let top = row as f32 * row_step;
+                let offset = if top < state.log_scroll {
+                    top
+                } else if top + row_step > state.log_scroll + view {
+                    top + row_step - view
+                } else {
+                    state.log_scroll
+                };
+                scroll_area = scroll_area.vertical_scroll_offset(offset);
+            }
+
+            let selection = self.log_selection.as_ref();
+            let selected_fill = ui.visuals().selection.bg_fill;
+            let log_line = |ui: &mut egui::Ui,
+                            (number, line, level): (usize, &str, Option<LogLevel>),
+                            wrap: bool| {
+                let truncated = truncate_line(line, MAX_LINE_CHARS);
+                let mut text =
+                    egui::RichText::new(truncated.as_deref().unwrap_or(line)).monospace();
+                if let Some(color) = level.and_then(|level| level.color(&theme)) {
+                    text = text.color(color);
+                }
+                if selection.is_some_and(|selection| selection.contains(number)) {
+                    text = text.background_color(selected_fill);
+                }
+                let response = ui.add(egui::Label::new(text).wrap(wrap));
+                if truncated.is_some() {
+                    response.on_hover_text(line);
+                }
+            };
+            // Without wrapping every line is one row high, so only the visible ones are laid out
+            let output = if state.wrap {
+                scroll_area.show(ui, |ui| {
+                    for line in &lines {
+                        log_line(ui, *line, true);
+                    }
+                })
+            } else {
+                scroll_area.show_rows(ui, row_height, lines.len(), |ui, rows| {
+                    for line in &lines[rows] {
+                        log_line(ui, *line, false);
+                    }
+                })
+            };
+
+            let max_offset = (output.content_size.y - output.inner_rect.height()).max(0.0);
+            if switched && !state.follow {
+                if let ScrollRestore::Clamp(offset) = restore_scroll(state.log_scroll, max_offset) {
+                    self.scroll_notice =
+                        Some("Older lines are gone, scrolled to the oldest available".to_string());
+                    state.log_scroll = offset;
+                    return;
+                }
+            }
+            let mut scroll_state = output.state;
+            if let Some(scroll) = scroll_request {
+                let max = output.content_size - output.inner_rect.size();
+                scroll_state.offset = scroll_offset(
+                    scroll,
+                    scroll_state.offset,
+                    output.inner_rect.size(),
+                    max,
+                    row_height,
+                    char_width,
+                );
+                scroll_state.store(ui.ctx(), output.id);
+                ui.ctx().request_repaint();
+            }
+            state.log_scroll = scroll_state.offset.y;
+            state.log_h_scroll = scroll_state.offset.x;
+        });
+        if close_range {
+            self.range_logs = None;
+        }
+        if open_range {
+            self.log_range_form = Some(LogRangeForm::default());
+        }
+    }
+
+    fn networks_appview(&mut self, ui: &mut egui::Ui) {
+        let theme = self.theme;
+        let form = &mut self.network_form;
+        if let Some(result) = &mut form.result {
+            match result.try_recv() {
+                Ok(Ok(message)) => {
+                    form.result = None;
+                    form.error = None;
+                    let _ = self.result_sender.send(Ok(message));
+                }
+                Ok(Err(e)) => {
+                    form.error = Some(e);
+                    form.result = None;
+                }
+                Err(oneshot::error::TryRecvError::Empty) => {}
+                Err(oneshot::error::TryRecvError::Closed) => form.result = None,
+            }
+        }
+
+        ui.horizontal(|ui| {
+            ui.label(format!("{} networks", self.networks.len()));
+            ui.separator();
+            ui.label("Name");
+            ui.text_edit_singleline(&mut form.name);
+            egui::ComboBox::from_id_source("network_driver")
+                .selected_text(form.driver.as_str())
+                .show_ui(ui, |ui| {
+                    for driver in NETWORK_DRIVERS {
+                        ui.selectable_value(&mut form.driver, driver.to_string(), driver);
+                    }
+                });
+            let name = form.name.trim().to_string();
+            if mutating_button(ui, !name.is_empty() && form.result.is_none(), "Create").clicked() {
+                let (sender, receiver) = oneshot::channel();
+                let driver = form.driver.clone();
+                tokio::spawn(async move {
+                    let _ = sender.send(networks::create_network(name, driver).await);
+                });
+                form.result = Some(receiver);
+                form.name.clear();
+            }
+            ui.separator();
+            if ui
+                .add_enabled(form.result.is_none(), egui::Button::new("Export graph"))
+                .on_hover_text(format!(
+                    "Write the networks and their containers to {} and an SVG of it",
+                    graph::DEFAULT_OUTPUT
+                ))
+                .clicked()
+            {
+                let (sender, receiver) = oneshot::channel();
+                let networks = self.networks.clone();
+                let containers: Vec<ContainerSummary> = self
+                    .containers
+                    .values()
+                    .map(|(summary, _)| summary.clone())
+                    .collect();
+                tokio::spawn(async move {
+                    let output = Path::new(graph::DEFAULT_OUTPUT);
+                    let _ = sender.send(graph::export(output, &networks, &containers, true).await);
+                });
+                form.result = Some(receiver);
+            }
+            if form.result.is_some() {
+                ui.spinner();
+            }
+        });
+        if let Some(error) = &form.error {
+            ui.colored_label(theme.error, error);
+        }
+
+        // A removed network disappears with the next poll, which drops the selection
+        if self.selected_network.as_ref().is_some_and(|id| {
+            !self
+                .networks
+                .iter()
+                .any(|network| network.id.as_ref() == Some(id))
+        }) {
+            self.selected_network = None;
+        }
+
+        let mut remove = None;
+        egui::ScrollArea::vertical()
+            .id_source("network_list")
+            .max_height(300.0)
+            .auto_shrink([false, true])
+            .show(ui, |ui| {
+                egui::Grid::new("networks")
+                    .num_columns(4)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for network in &self.networks {
+                            let (Some(id), Some(name)) = (&network.id, &network.name) else {
+                                continue;
+                            };
+                            let selected = self.selected_network.as_ref() == Some(id);
+                            if ui.selectable_label(selected, name).clicked() {
+                                self.selected_network = Some(id.clone());
+                            }
+                            ui.label(network.driver.as_deref().unwrap_or("-"));
+                            ui.label(network.scope.as_deref().unwrap_or("-"));
+                            let connected = connected_containers(network, &self.containers);
+                            ui.label(format!("{} containers", connected.len()));
+                            ui.end_row();
+                            if selected && !BUILTIN_NETWORKS.contains(&name.as_str()) {
+                                remove = Some((id.clone(), name.clone()));
+                            }
+                        }
+                    });
+            });
+
+        let Some(network) = self.selected_network.as_ref().and_then(|id| {
+            self.networks
+                .iter()
+                .find(|network| network.id.as_ref() == Some(id))
+        }) else {
+            return;
+        };
+        ui.separator();
+        let mut removed = None;
+        ui.horizontal(|ui| {
+            ui.label(egui::RichText::new(network.name.as_deref().unwrap_or_default()).strong());
+            if let Some((id, name)) = remove {
+                if mutating_button(ui, self.network_form.result.is_none(), "Remove").clicked() {
+                    removed = Some((
+                        format!("Remove network {}", name),
+                        QueuedAction::RemoveNetwork { id, name },
+                    ));
+                }
+            }
+        });
+        if let Some((label, action)) = removed {
+            return self.queue_or_dispatch(label, action);
+        }
+        let connected = connected_containers(network, &self.containers);
+        let mut jump = ui.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Enter))
+            && !ui.ctx().wants_keyboard_input();
+        let none = || egui::RichText::new("(none)").weak();
+        egui::Grid::new("network_details")
+            .num_columns(2)
+            .show(ui, |ui| {
+                let flag = |value: Option<bool>| {
+                    if value.unwrap_or_default() {
+                        "yes"
+                    } else {
+                        "no"
+                    }
+                };
+                ui.label("ID");
+                ui.monospace(network.id.as_deref().unwrap_or_default());
+                ui.end_row();
+                ui.label("IPAM");
+                let ipam = ipam_rows(network);
+                if ipam.is_empty() {
+                    ui.label(none());
+                } else {
+                    // One row per config, so long lists stay aligned instead of wrapping
+                    egui::Grid::new("network_ipam")
+                        .num_columns(3)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.strong("Subnet");
+                            ui.strong("Gateway");
+                            ui.strong("IP range");
+                            ui.end_row();
+                            for row in &ipam {
+                                for value in [&row.subnet, &row.gateway, &row.ip_range] {
+                                    ui.monospace(if value.is_empty() { "-" } else { value });
+                                }
+                                ui.end_row();
+                            }
+                        });
+                }
+                ui.end_row();
+                ui.label("Driver options");
+                let options = driver_options(network);
+                if options.is_empty() {
+                    ui.label(none());
+                } else {
+                    ui.vertical(|ui| {
+                        for option in &options {
+                            ui.monospace(option);
+                        }
+                    });
+                }
+                ui.end_row();
+                ui.label("Internal");
+                ui.label(flag(network.internal));
+                ui.end_row();
+                ui.label("Attachable");
+                ui.label(flag(network.attachable));
+                ui.end_row();
+                ui.label("IPv6");
+                ui.label(flag(network.enable_ipv6));
+                ui.end_row();
+                ui.label("Containers");
+                if connected.is_empty() {
+                    ui.label(none());
+                } else {
+                    egui::Grid::new("network_containers")
+                        .num_columns(4)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.strong("Name");
+                            ui.strong("IPv4");
+                            ui.strong("IPv6");
+                            ui.strong("MAC");
+                            ui.end_row();
+                            for endpoint in &connected {
+                                let selected = self.selected_network_container.as_ref()
+                                    == Some(&endpoint.container_id);
+                                let response = ui
+                                    .selectable_label(selected, &endpoint.name)
+                                    .on_hover_text("Enter or double-click opens it in Containers");
+                                if response.clicked() {
+                                    self.selected_network_container =
+                                        Some(endpoint.container_id.clone());
+                                }
+                                if response.double_clicked() {
+                                    jump = true;
+                                }
+                                for value in [&endpoint.ipv4, &endpoint.ipv6, &endpoint.mac] {
+                                    ui.monospace(if value.is_empty() { "-" } else { value });
+                                }
+                                ui.end_row();
+                            }
+                        });
+                }
+                ui.end_row();
+            });
+
+        let target = self.selected_network_container.clone().filter(|id| {
+            connected
+                .iter()
+                .any(|endpoint| &endpoint.container_id == id)
+        });
+        if let (true, Some(id)) = (jump, target) {
+            self.select_container_by_id(&id);
+        }
+    }
+
+    fn volumes_appview(&mut self, ui: &mut egui::Ui) {
+        let theme = self.theme;
+        let state = self.volumes.get_or_insert_with(VolumesState::load);
+        if let Some(pending) = &mut state.pending {
+            match pending.try_recv() {
+                Ok(Ok(volumes)) => {
+                    state.volumes = volumes;
+                    state.error = None;
+                    state.pending = None;
+                }
+                Ok(Err(e)) => {
+                    state.error = Some(e);
+                    state.pending = None;
+                }
+                Err(oneshot::error::TryRecvError::Empty) => {}
+                Err(oneshot::error::TryRecvError::Closed) => state.pending = None,
+            }
+        }
+        if let Some(pending) = &mut state.sizes_pending {
+            match pending.try_recv() {
+                Ok(Ok(sizes)) => {
+                    state.sizes = sizes;
+                    state.sizes_pending = None;
+                }
+                Ok(Err(e)) => {
+                    report(
+                        &self.error_sender,
+                        DockerrsError::docker("Volume sizes unavailable", e),
+                    );
+                    state.sizes_pending = None;
+                }
+                Err(oneshot::error::TryRecvError::Empty) => {}
+                Err(oneshot::error::TryRecvError::Closed) => state.sizes_pending = None,
+            }
+        }
+        if let Some(browse) = &mut state.browse {
+            while let Ok(step) = browse.progress.try_recv() {
+                browse.step = step;
+            }
+            if let Some(pending) = &mut browse.pending {
+                match pending.try_recv() {
+                    Ok(Ok(listing)) => {
+                        browse.listing = Some(listing);
+                        browse.pending = None;
+                    }
+                    Ok(Err(e)) => {
+                        browse.error = Some(e);
+                        browse.pending = None;
+                    }
+                    Err(oneshot::error::TryRecvError::Empty) => {}
+                    Err(oneshot::error::TryRecvError::Closed) => browse.pending = None,
+                }
+            }
+        }
+
+        ui.horizontal(|ui| {
+            ui.label(format!("{} volumes", state.volumes.len()));
+            if ui
+                .add_enabled(state.pending.is_none(), egui::Button::new("Refresh"))
+                .clicked()
+            {
+                state.refresh();
+            }
+            if state.pending.is_some() {
+                ui.spinner();
+            } else if state.sizes_pending.is_some() {
+                ui.spinner();
+                ui.weak("measuring sizes");
+            }
+        });
+        if let Some(error) = &state.error {
+            ui.colored_label(theme.error, error);
+        }
+
+        egui::ScrollArea::vertical()
+            .id_source("volume_list")
+            .max_height(300.0)
+            .auto_shrink([false, true])
+            .show(ui, |ui| {
+                egui::Grid::new("volumes")
+                    .num_columns(4)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for volume in &state.volumes {
+                            let selected = state.selected.as_ref() == Some(&volume.name);
+                            if ui.selectable_label(selected, &volume.name).clicked() {
+                                state.selected = Some(volume.name.clone());
+                            }
+                            ui.label(&volume.driver);
+                            match state.sizes.get(&volume.name) {
+                                Some(size) => ui.label(format_size(*size)),
+                                None => ui.label("-"),
+                            };
+                            let mounts = mounting_containers(&volume.name, &self.containers);
+                            if mounts.is_empty() {
+                                ui.weak("unused");
+                            } else {
+                                ui.label(format!("{} containers", mounts.len()));
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+
+        let Some(volume) = state
+            .selected
+            .as_ref()
+            .and_then(|name| state.volumes.iter().find(|volume| &volume.name == name))
+        else {
+            return;
+        };
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label(egui::RichText::new(&volume.name).strong());
+            let browsing = state.browse.as_ref().is_some_and(|b| b.pending.is_some());
+            if mutating_button(ui, !browsing, "Browse")
+                .on_hover_text("Lists the volume from a temporary read-only alpine container")
+                .clicked()
+            {
+                state.browse = Some(VolumeBrowse::start(
+                    volume.name.clone(),
+                    self.error_sender.clone(),
+                ));
+            }
+        });
+        let mounts = mounting_containers(&volume.name, &self.containers);
+        egui::Grid::new("volume_details")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("Mountpoint");
+                ui.monospace(&volume.mountpoint);
+                ui.end_row();
+                ui.label("Mounted by");
+                ui.vertical(|ui| {
+                    if mounts.is_empty() {
+                        ui.label("-");
+                    }
+                    for mount in &mounts {
+                        let mode = if mount.read_write { "rw" } else { "ro" };
+                        ui.label(format!(
+                            "{}  {}  {}",
+                            mount.container, mount.destination, mode
+                        ));
+                    }
+                });
+                ui.end_row();
+            });
+
+        let Some(browse) = state
+            .browse
+            .as_ref()
+            .filter(|browse| browse.volume == volume.name)
+        else {
+            return;
+        };
+        ui.separator();
+        if browse.pending.is_some() {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label(&browse.step);
+            });
+        }
+        if let Some(error) = &browse.error {
+            ui.colored_label(theme.error, error);
+        }
+        if let Some(listing) = &browse.listing {
+            if let Some(size) = listing.size {
+                ui.label(format!("{} in total", format_size(size as i64)));
+            }
+            egui::ScrollArea::vertical()
+                .id_source("volume_entries")
+                .auto_shrink([false, true])
+                .show(ui, |ui| {
+                    egui::Grid::new("volume_entries_grid")
+                        .num_columns(3)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for entry in &listing.entries {
+                                let name = if entry.kind == EntryKind::Directory {
+                                    format!("{}/", entry.name)
+                                } else {
+                                    entry.name.clone()
+                                };
+                                ui.monospace(name);
+                                ui.label(&entry.size);
+                                ui.label(&entry.modified);
+                                ui.end_row();
+                            }
+                        });
+                });
+        }
+    }
+
+    fn images_appview(&mut self, ui: &mut egui::Ui) {
+        let theme = self.theme;
+        let dangling: Vec<String> = self
+            .images
+            .iter()
+            .filter(|image| is_dangling(image))
+            .map(|image| image.id.clone())
+            .collect();
+        // Images no container uses, running or stopped
+        let used: HashSet<String> = self
+            .containers
+            .values()
+            .filter_map(|(summary, _)| summary.image_id.clone())
+            .collect();
+        let unused = self
+            .images
+            .iter()
+            .filter(|image| !used.contains(&image.id) && !is_dangling(image))
+            .count();
+        // Queued once the panel is drawn, removals go through the grace period
+        let mut remove = None;
+        ui.horizontal(|ui| {
+            ui.label(format!(
+                "{} images, {} dangling, {} unused",
+                self.images.len(),
+                dangling.len(),
+                unused
+            ));
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if mutating_button(ui, !dangling.is_empty(), "Remove dangling").clicked() {
+                    remove = Some((
+                        "Remove dangling images".to_string(),
+                        QueuedAction::RemoveImages(dangling),
+                    ));
+                }
+                if mutating_button(ui, unused > 0, "Clean up unused")
+                    .on_hover_text("Remove old tags no container uses, listed first")
+                    .clicked()
+                {
+                    self.confirm_image_cleanup = Some(self.config.keep_unused_images_days());
+                }
+                if mutating_button(ui, true, "Load").clicked() {
+                    self.tarball_prompt = Some(TarballPrompt::new(TarballAction::LoadImage));
+                }
+            });
+        });
+
+        // Push progress
+        self.command_output_panel(ui);
+
+        let now = unix_now();
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for image in &self.images {
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let selected = self.selected_image.as_ref() == Some(&image.id);
+                    let label = if is_dangling(image) {
+                        egui::RichText::new("<none> (dangling)").color(theme.warning)
+                    } else {
+                        egui::RichText::new(image.repo_tags.join(", "))
+                    };
+                    if ui.selectable_label(selected, label).clicked() {
+                        self.selected_image = Some(image.id.clone());
+                        self.retag_input.clear();
+                    }
+                    ui.label(format_size(image.size));
+                    ui.label(format_age(image.created, now));
+                    if !used.contains(&image.id) && !is_dangling(image) {
+                        ui.weak("unused");
+                    }
+
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        // Prefer the tag so the created container shows a readable image
+                        let image_ref = image
+                            .repo_tags
+                            .first()
+                            .filter(|_| !is_dangling(image))
+                            .cloned()
+                            .unwrap_or_else(|| image.id.clone());
+                        if mutating_button(ui, true, "Run").clicked() {
+                            self.run_image_form = Some(RunImageForm::new(image_ref.clone()));
+                        }
+                        if selected {
+                            if mutating_button(ui, true, "Remove").clicked() {
+                                remove = Some((
+                                    format!("Remove {}", image_ref),
+                                    QueuedAction::RemoveImage(image_ref.clone()),
+                                ));
+                            }
+                            if mutating_button(ui, !is_dangling(image), "Push").clicked() {
+                                self.push_form = Some(PushForm::new(image_ref.clone()));
+                            }
+                            if ui.button("Save").clicked() {
+                                self.tarball_prompt = Some(TarballPrompt::new(
+                                    TarballAction::SaveImage(image_ref.clone()),
+                                ));
+                            }
+                            if ui.button("History").clicked() {
+                                self.image_history =
+                                    Some(ImageHistoryState::load(image_ref.clone()));
+                            }
+                            if ui
+                                .button("Analyze")
+                                .on_hover_text("Find space wasted on files later layers replace")
+                                .clicked()
+                            {
+                                self.image_analysis =
+                                    Some(ImageAnalysisState::start(image_ref.clone()));
+                            }
+                            if mutating_button(ui, true, "Tag").clicked() {
+                                let target = std::mem::take(&mut self.retag_input);
+                                spawn_reported(
+                                    &self.result_sender,
+                                    tag_image(image.id.clone(), target),
+                                );
+                            }
+                            ui.add(
+                                egui::TextEdit::singleline(&mut self.retag_input)
+                                    .hint_text("repo:tag")
+                                    .desired_width(160.0),
+                            );
+                        }
+                    });
+                });
+            }
+        });
+        if let Some((label, action)) = remove {
+            self.queue_or_dispatch(label, action);
+        }
+    }
+
+    // Lists every unused tag by repository before anything is removed. Tags younger than the
+    // chosen number of days are listed as kept.
+    fn image_cleanup_window(&mut self, ctx: &egui::Context) {
+        let Some(mut keep_days) = self.confirm_image_cleanup else {
+            return;
+        };
+        let groups = unused_tags(&self.images, &self.containers);
+        let now = unix_now();
+        let removable: Vec<String> = groups
+            .values()
+            .flatten()
+            .filter(|tag| is_removable(tag, keep_days, now))
+            .map(|tag| tag.tag.clone())
+            .collect();
+
+        let mut confirmed = false;
+        let mut cancelled =
+            ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Escape));
+        egui::Window::new("Clean up unused images")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Keep tags newer than");
+                    ui.add(egui::DragValue::new(&mut keep_days).clamp_range(0..=3650));
+                    ui.label("days");
+                });
+                egui::ScrollArea::vertical()
+                    .id_source("unused_tags")
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        for (repository, tags) in &groups {
+                            ui.strong(format!("{} ({} unused)", repository, tags.len()));
+                            for tag in tags {
+                                let line = format!(
+                                    "  {}  {}  {}",
+                                    tag.tag,
+                                    format_size(tag.size),
+                                    format_age(tag.created, now)
+                                );
+                                if is_removable(tag, keep_days, now) {
+                                    ui.label(line);
+                                } else {
+                                    ui.weak(format!("{}  (kept)", line));
+                                }
+                            }
+                        }
+                    });
+                ui.horizontal(|ui| {
+                    confirmed = mutating_button(
+                        ui,
+                        !removable.is_empty(),
+                        format!("Remove {} tags", removable.len()),
+                    )
+                    .clicked();
+                    cancelled |= ui.button("Cancel").clicked();
+                });
+            });
+
+        self.confirm_image_cleanup = Some(keep_days);
+        if confirmed || cancelled {
+            self.confirm_image_cleanup = None;
+        }
+        if !confirmed {
+            return;
+        }
+        if keep_days != self.config.keep_unused_images_days() {
+            self.config.keep_unused_images_days = Some(keep_days);
+            if self.config_path.is_some() {
+                if let Err(e) = self.save_config() {
+                    self.status_message = Some(Err(e));
+                }
+            }
+        }
+        self.queue_or_dispatch(
+            format!("Remove {} unused image tags", removable.len()),
+            QueuedAction::RemoveUnusedTags(removable),
+        );
+    }
+
+    fn image_history_window(&mut self, ctx: &egui::Context) {
+        let theme = self.theme;
+        let Some(state) = &mut self.image_history else {
+            return;
+        };
+        if let Some(pending) = &mut state.pending {
+            match pending.try_recv() {
+                Ok(Ok(layers)) => {
+                    state.layers = layers;
+                    state.pending = None;
+                }
+                Ok(Err(e)) => {
+                    state.error = Some(e);
+                    state.pending = None;
+                }
+                Err(oneshot::error::TryRecvError::Empty) => {}
+                Err(oneshot::error::TryRecvError::Closed) => state.pending = None,
+            }
+        }
+
+        let now = unix_now();
+        let mut open = true;
+        egui::Window::new(format!("History of {}", state.image))
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(700.0)
+            .show(ctx, |ui| {
+                if state.pending.is_some() {
+                    ui.spinner();
+                    return;
+                }
+                if let Some(error) = &state.error {
+                    ui.colored_label(theme.error, error);
+                    return;
+                }
+                egui::ScrollArea::vertical()
+                    .max_height(400.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("image_history")
+                            .num_columns(3)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for (index, layer) in state.layers.iter().enumerate() {
+                                    // Long RUN lines are cut off here, the full text is shown below
+                                    let mut text =
+                                        egui::RichText::new(&layer.created_by).monospace();
+                                    if state.selected == Some(index) {
+                                        text = text.strong().color(theme.emphasis);
+                                    }
+                                    ui.scope(|ui| {
+                                        ui.set_max_width(480.0);
+                                        let created_by = egui::Label::new(text)
+                                            .truncate(true)
+                                            .sense(egui::Sense::click());
+                                        if ui.add(created_by).clicked() {
+                                            state.selected = Some(index);
+                                        }
+                                    });
+                                    ui.label(format_size(layer.size));
+                                    ui.label(format_age(layer.created, now));
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                ui.separator();
+                let total: i64 = state.layers.iter().map(|layer| layer.size).sum();
+                ui.label(
+                    egui::RichText::new(format!(
+                        "{} layers, {} in total",
+                        state.layers.len(),
+                        format_size(total)
+                    ))
+                    .strong(),
+                );
+                if let Some(layer) = state.selected.and_then(|index| state.layers.get(index)) {
+                    ui.separator();
+                    ui.add(
+                        egui::Label::new(egui::RichText::new(&layer.created_by).monospace())
+                            .wrap(true),
+                    );
+                }
+            });
+        if !open {
+            self.image_history = None;
+        }
+    }
+
+    // Saved label filters to switch to, 1-9 pick one while the name field is not focused
+    pub fn set_container_sort(&mut self, sort: ContainerSort) {
+        self.container_sort = sort;
+        self.order_containers();
+        self.scroll_to_selection = true;
+    }
+
+    // 1-6 toggle the columns, Esc or V closes it. Choices are saved in the config file.
+    fn column_chooser_window(&mut self, ctx: &egui::Context) {
+        if !self.column_chooser {
+            return;
+        }
+        let mut open = !ctx.input_mut(|i| {
+            i.consume_key(egui::Modifiers::NONE, egui::Key::Escape)
+                || i.consume_key(egui::Modifiers::NONE, egui::Key::V)
+        });
+        let digits = [
+            egui::Key::Num1,
+            egui::Key::Num2,
+            egui::Key::Num3,
+            egui::Key::Num4,
+            egui::Key::Num5,
+            egui::Key::Num6,
+        ];
+        let mut toggled: Vec<RowColumn> = digits
+            .into_iter()
+            .zip(ALL_COLUMNS)
+            .filter(|(key, _)| ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, *key)))
+            .map(|(_, column)| column)
+            .collect();
+        let shown = self.config.shown_columns();
+        egui::Window::new("Columns")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                for (index, column) in ALL_COLUMNS.into_iter().enumerate() {
+                    let mut checked = shown.contains(&column);
+                    let label = format!("{} {}", index + 1, column.label());
+                    if ui.checkbox(&mut checked, label).changed() {
+                        toggled.push(column);
+                    }
+                }
+                ui.weak("Columns that do not fit the window are left out");
+            });
+        if !toggled.is_empty() {
+            for column in toggled {
+                self.config.toggle_column(column);
+            }
+            if let Err(e) = self.save_config() {
+                self.status_message = Some(Err(e));
+            }
+        }
+        if !open {
+            self.column_chooser = false;
+        }
+    }
+
+    fn filter_switcher_window(&mut self, ctx: &egui::Context) {
+        let Some(name) = &mut self.filter_switcher else {
+            return;
+        };
+        let mut open = !ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Escape));
+        let mut chosen = None;
+        let mut delete = None;
+        let mut save = false;
+        if !ctx.wants_keyboard_input() {
+            let digits = [
+                egui::Key::Num1,
+                egui::Key::Num2,
+                egui::Key::Num3,
+                egui::Key::Num4,
+                egui::Key::Num5,
+                egui::Key::Num6,
+                egui::Key::Num7,
+                egui::Key::Num8,
+                egui::Key::Num9,
+            ];
+            for (key, saved) in digits.into_iter().zip(&self.config.saved_filters) {
+                if ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, key)) {
+                    chosen = Some(saved.expression.clone());
+                }
+            }
+        }
+        let active = self.label_filter.lock().unwrap().to_string();
+        egui::Window::new("Saved filters")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                if ui
+                    .selectable_label(active.is_empty(), "No filter")
+                    .clicked()
+                {
+                    chosen = Some(String::new());
+                }
+                for (index, saved) in self.config.saved_filters.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        let label = format!("{} {}", index + 1, saved.name);
+                        if ui
+                            .selectable_label(saved.expression == active, label)
+                            .clicked()
+                        {
+                            chosen = Some(saved.expression.clone());
+                        }
+                        ui.weak(&saved.expression);
+                        if ui.small_button("✕").on_hover_text("Delete").clicked() {
+                            delete = Some(index);
+                        }
+                    });
+                }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let field = ui.add(egui::TextEdit::singleline(name).hint_text("name"));
+                    let enter = field.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    let button = ui.add_enabled(
+                        !name.trim().is_empty() && !active.is_empty(),
+                        egui::Button::new("Save active filter"),
+                    );
+                    save = (enter || button.clicked())
+                        && !name.trim().is_empty()
+                        && !active.is_empty();
+                });
+            });
+
+        if save {
+            let name = name.trim().to_string();
+            self.save_label_filter(&name);
+            self.filter_switcher = Some(String::new());
+        }
+        if let Some(index) = delete {
+            let removed = self.config.saved_filters.remove(index);
+            let result = self
+                .save_config()
+                .map(|()| format!("Deleted filter {}", removed.name));
+            self.status_message = Some(result);
+        }
+        if let Some(expression) = chosen {
+            self.apply_label_filter(&expression);
+            open = false;
+        }
+        if !open {
+            self.filter_switcher = None;
+        }
+    }
+
+    fn diff_window(&mut self, ctx: &egui::Context) {
+        let theme = self.theme;
+        let Some(state) = &mut self.container_diff else {
+            return;
+        };
+        if let Some(pending) = &mut state.pending {
+            match pending.try_recv() {
+                Ok(Ok(changes)) => {
+                    state.changes = changes;
+                    state.pending = None;
+                }
+                Ok(Err(e)) => {
+                    state.error = Some(e);
+                    state.pending = None;
+                }
+                Err(oneshot::error::TryRecvError::Empty) => {}
+                Err(oneshot::error::TryRecvError::Closed) => state.pending = None,
+            }
+        }
+        if state.pending.is_none() && state.rows_search.as_ref() != Some(&state.search) {
+            state.rows = diff_rows(&state.changes, &state.search);
+            state.rows_search = Some(state.search.clone());
+        }
+
+        let mut open = true;
+        egui::Window::new(format!("Changes in {}", state.name))
+            .open(&mut open)
+            .default_size([700.0, 500.0])
+            .show(ctx, |ui| {
+                if state.pending.is_some() {
+                    ui.spinner();
+                    return;
+                }
+                if let Some(error) = &state.error {
+                    ui.colored_label(theme.error, error);
+                    return;
+                }
+                let counts: Vec<String> = ChangeKind::ALL
+                    .iter()
+                    .map(|kind| {
+                        let count = state.changes.iter().filter(|(k, _)| k == kind).count();
+                        format!("{} {}", count, kind.label().to_lowercase())
+                    })
+                    .collect();
+                ui.label(egui::RichText::new(counts.join(", ")).strong());
+                ui.horizontal(|ui| {
+                    ui.label("Search");
+                    let search = ui.text_edit_singleline(&mut state.search);
+                    if !search.has_focus()
+                        && ui.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Slash))
+                    {
+                        search.request_focus();
+                    }
+                });
+                ui.separator();
+                let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+                egui::ScrollArea::vertical()
+                    .id_source("container_diff")
+                    .auto_shrink([false, false])
+                    .show_rows(ui, row_height, state.rows.len(), |ui, rows| {
+                        for row in &state.rows[rows] {
+                            match row {
+                                DiffRow::Header(kind, count) => {
+                                    ui.strong(format!("{} ({})", kind.label(), count));
+                                }
+                                DiffRow::Path(kind, path) => {
+                                    let color = match kind {
+                                        ChangeKind::Added => theme.success,
+                                        ChangeKind::Changed => theme.warning,
+                                        ChangeKind::Deleted => theme.error,
+                                    };
+                                    ui.label(
+                                        egui::RichText::new(format!("{} {}", kind.symbol(), path))
+                                            .monospace()
+                                            .color(color),
+                                    );
+                                }
+                            }
+                        }
+                    });
+            });
+        if !open {
+            self.container_diff = None;
+        }
+    }
+
+    // The running containers using the most CPU and memory, with their recent trend
+    fn top_window(&mut self, ctx: &egui::Context) {
+        if !self.top_leaderboard {
+            return;
+        }
+        let theme = self.theme;
+        let running: Vec<&String> = self
+            .container_ids
+            .iter()
+            .filter(|id| {
+                self.containers
+                    .get(*id)
+                    .is_some_and(|(summary, _)| summary.state.as_deref() == Some("running"))
+            })
+            .collect();
+        let mut open = true;
+        let mut selected = None;
+        egui::Window::new("Top consumers")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                for (resource, title, color) in [
+                    (Resource::Cpu, "CPU", theme.cpu),
+                    (Resource::Memory, "Memory", theme.memory),
+                ] {
+                    ui.label(egui::RichText::new(title).strong());
+                    let leaders =
+                        top::leaders(running.iter().copied(), &self.stats_history, resource);
+                    if leaders.is_empty() {
+                        ui.weak("Waiting for stats…");
+                        continue;
+                    }
+                    egui::Grid::new(("top", title))
+                        .num_columns(3)
+                        .show(ui, |ui| {
+                            for (id, sample) in leaders {
+                                let name = match self.containers.get(&id) {
+                                    Some((summary, _)) => self.row_name(&id, summary),
+                                    None => id.clone(),
+                                };
+                                let is_selected = self.selected_container.as_ref() == Some(&id);
+                                if ui.selectable_label(is_selected, name).clicked() {
+                                    selected = Some(id.clone());
+                                }
+                                let (usage, trend) = match resource {
+                                    Resource::Cpu => (
+                                        format!("{:.1}%", sample.cpu_percent),
+                                        self.stats_history.get(&id).map(StatsHistory::cpu),
+                                    ),
+                                    Resource::Memory => (
+                                        format_size(sample.memory as i64),
+                                        self.stats_history.get(&id).map(StatsHistory::memory),
+                                    ),
+                                };
+                                ui.label(usage);
+                                sparkline(
+                                    ui,
+                                    &trend.unwrap_or_default(),
+                                    egui::vec2(120.0, 20.0),
+                                    color,
+                                );
+                                ui.end_row();
+                            }
+                        });
+                }
+            });
+        if let Some(id) = selected {
+            self.select_container_by_id(&id);
+        }
+        if !open {
+            self.top_leaderboard = false;
+        }
+    }
+
+    fn disk_usage_window(&mut self, ctx: &egui::Context) {
+        let theme = self.theme;
+        let Some(state) = &mut self.disk_usage else {
+            return;
+        };
+        if let Some(pending) = &mut state.pending {
+            match pending.try_recv() {
+                Ok(Ok(usage)) => {
+                    state.usage = usage;
+                    state.error = None;
+                    state.pending = None;
+                }
+                Ok(Err(e)) => {
+                    state.error = Some(e);
+                    state.pending = None;
+                }
+                Err(oneshot::error::TryRecvError::Empty) => {}
+                Err(oneshot::error::TryRecvError::Closed) => state.pending = None,
+            }
+        }
+        if let Some((_, pruning)) = &mut state.pruning {
+            match pruning.try_recv() {
+                Ok(result) => {
+                    let _ = self.result_sender.send(result);
+                    state.pruning = None;
+                    state.refresh();
+                }
+                Err(oneshot::error::TryRecvError::Empty) => {}
+                Err(oneshot::error::TryRecvError::Closed) => state.pruning = None,
+            }
+        }
+
+        let can_prune = |category: Category| match category {
+            Category::BuildCache => self.capabilities.supports(Feature::BuildCachePrune),
+            _ => true,
+        };
+        if state.confirm.is_none() && state.pruning.is_none() && !ctx.wants_keyboard_input() {
+            for (key, category) in PRUNE_KEYS {
+                if !ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, key)) {
+                    continue;
+                }
+                if read_only::is_enabled() {
+                    let _ = self.result_sender.send(Err(READ_ONLY_MESSAGE.to_string()));
+                } else if can_prune(category) {
+                    state.confirm = Some(category);
+                }
+            }
+        }
+
+        let mut open = true;
+        egui::Window::new("Disk usage")
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(600.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(state.pending.is_none(), egui::Button::new("Refresh"))
+                        .clicked()
+                    {
+                        state.refresh();
+                    }
+                    if state.pending.is_some() {
+                        ui.spinner();
+                    }
+                    if let Some((category, _)) = &state.pruning {
+                        ui.spinner();
+                        ui.label(format!("Pruning {}", category.label().to_lowercase()));
+                    }
+                });
+                if let Some(error) = &state.error {
+                    ui.colored_label(theme.error, error);
+                }
+                egui::Grid::new("disk_usage")
+                    .num_columns(6)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for header in ["Type", "Total", "Active", "Size", "Reclaimable", ""] {
+                            ui.strong(header);
+                        }
+                        ui.end_row();
+                        for usage in &state.usage {
+                            ui.label(usage.category.label());
+                            ui.label(usage.total.to_string());
+                            ui.label(usage.active.to_string());
+                            ui.label(format_size(usage.size));
+                            let percent = if usage.size > 0 {
+                                usage.reclaimable as f64 / usage.size as f64 * 100.0
+                            } else {
+                                0.0
+                            };
+                            ui.label(format!(
+                                "{} ({:.0}%)",
+                                format_size(usage.reclaimable),
+                                percent
+                            ));
+                            let key = PRUNE_KEYS
+                                .iter()
+                                .find(|(_, category)| *category == usage.category)
+                                .map(|(key, _)| key.name())
+                                .unwrap_or_default();
+                            let text = format!("Prune ({})", key);
+                            let idle = state.pruning.is_none()
+                                && usage.reclaimable > 0
+                                && !read_only::is_enabled();
+                            let clicked = if usage.category == Category::BuildCache {
+                                ui.add_enabled_ui(idle, |ui| {
+                                    gated_button(
+                                        ui,
+                                        &self.capabilities,
+                                        Feature::BuildCachePrune,
+                                        &text,
+                                    )
+                                })
+                                .inner
+                                .clicked()
+                            } else {
+                                ui.add_enabled(idle, egui::Button::new(text)).clicked()
+                            };
+                            if clicked {
+                                state.confirm = Some(usage.category);
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+
+        if let Some(category) = state.confirm {
+            let keep_storage = match state.keep_storage.trim() {
+                "" => Ok(None),
+                size => parse_size(size).map(Some),
+            };
+            let mut confirmed =
+                ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Enter));
+            let mut cancelled =
+                ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Escape));
+            egui::Window::new("Confirm prune")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    let description = if category == Category::BuildCache && state.prune_all_cache {
+                        "all build cache that is not in use"
+                    } else {
+                        category.prune_description()
+                    };
+                    ui.label(format!("Remove {}? (Enter / Esc)", description));
+                    if category == Category::BuildCache {
+                        ui.horizontal(|ui| {
+                            ui.label("Keep");
+                            ui.add(
+                                egui::TextEdit::singleline(&mut state.keep_storage)
+                                    .hint_text("e.g. 10GB")
+                                    .desired_width(80.0),
+                            );
+                            ui.label("of cache");
+                        });
+                        if let Err(e) = &keep_storage {
+                            ui.colored_label(theme.error, e);
+                        }
+                        ui.checkbox(
+                            &mut state.prune_all_cache,
+                            "Not only dangling cache, everything unused",
+                        );
+                    }
+                    ui.horizontal(|ui| {
+                        confirmed |= ui
+                            .add_enabled(keep_storage.is_ok(), egui::Button::new("Prune"))
+                            .clicked();
+                        cancelled |= ui.button("Cancel").clicked();
+                    });
+                });
+            confirmed &= keep_storage.is_ok();
+            if confirmed {
+                let all_volumes = self.capabilities.supports(Feature::VolumePruneAll);
+                let build_cache = BuildCachePrune {
+                    keep_storage: keep_storage.ok().flatten(),
+                    all: state.prune_all_cache,
+                };
+                let (sender, receiver) = oneshot::channel();
+                tokio::spawn(async move {
+                    let result = disk_usage::prune(category, all_volumes, build_cache).await;
+                    let _ = sender.send(result);
+                });
+                state.pruning = Some((category, receiver));
+            }
+            if confirmed || cancelled {
+                state.confirm = None;
+            }
+        }
+        if !open {
+            self.disk_usage = None;
+        }
+    }
+
+    // Closing the window detaches, the container keeps running
+    fn attach_window(&mut self, ctx: &egui::Context) {
+        let theme = self.theme;
+        let Some(state) = &mut self.attach else {
+            return;
+        };
+        if let Some(pending) = &mut state.pending {
+            match pending.try_recv() {
+                Ok(Ok(session)) => {
+                    state.session = Some(session);
+                    state.pending = None;
+                }
+                Ok(Err(e)) => {
+                    state.error = Some(e);
+                    state.pending = None;
+                }
+                Err(oneshot::error::TryRecvError::Empty) => {}
+                Err(oneshot::error::TryRecvError::Closed) => state.pending = None,
+            }
+        }
+
+        let mut open = true;
+        let mut detach = false;
+        egui::Window::new(format!("Attached to {}", state.name))
+            .open(&mut open)
+            .default_size([800.0, 450.0])
+            .show(ctx, |ui| {
+                if state.pending.is_some() {
+                    ui.spinner();
+                    return;
+                }
+                if let Some(error) = &state.error {
+                    ui.colored_label(theme.error, error);
+                    return;
+                }
+                let Some(session) = &mut state.session else {
+                    return;
+                };
+                session.receive();
+                if !session.open_stdin {
+                    ui.colored_label(
+                        theme.warning,
+                        "The container was started without -i, input is ignored",
+                    );
+                } else if !session.tty {
+                    ui.colored_label(
+                        theme.warning,
+                        "The container has no TTY, input may not be echoed",
+                    );
+                }
+                egui::ScrollArea::vertical()
+                    .id_source("attach_output")
+                    .auto_shrink([false, false])
+                    .stick_to_bottom(true)
+                    .max_height(ui.available_height() - 40.0)
+                    .show(ui, |ui| {
+                        ui.add(
+                            egui::Label::new(egui::RichText::new(&session.output).monospace())
+                                .wrap(true),
+                        );
+                    });
+                if session.ended {
+                    ui.label("The container's streams ended");
+                }
+                ui.horizontal(|ui| {
+                    let response = ui.add_enabled(
+                        !session.ended,
+                        egui::TextEdit::singleline(&mut session.input)
+                            .font(egui::TextStyle::Monospace)
+                            .hint_text("input, Enter sends")
+                            .desired_width(500.0),
+                    );
+                    if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                        let mut line = std::mem::take(&mut session.input);
+                        line.push('\n');
+                        session.send(line.into_bytes());
+                        response.request_focus();
+                    }
+                    if ui.button("Ctrl-C").clicked() {
+                        session.send(vec![3]);
+                    }
+                    detach = ui.button("Detach").clicked();
+                });
+            });
+        // Dropping the session sends the detach keys
+        if !open || detach {
+            self.attach = None;
+        }
+    }
+
+    fn image_analysis_window(&mut self, ctx: &egui::Context) {
+        let theme = self.theme;
+        let Some(state) = &mut self.image_analysis else {
+            return;
+        };
+        while let Ok(progress) = state.progress_receiver.try_recv() {
+            state.progress = progress;
+        }
+        if let Some(pending) = &mut state.pending {
+            match pending.try_recv() {
+                Ok(Ok(report)) => {
+                    state.report = Some(report);
+                    state.pending = None;
+                }
+                Ok(Err(e)) => {
+                    state.error = Some(e);
+                    state.pending = None;
+                }
+                Err(oneshot::error::TryRecvError::Empty) => {}
+                Err(oneshot::error::TryRecvError::Closed) => state.pending = None,
+            }
+        }
+
+        // Esc cancels a running analysis, closing the window does too
+        let mut open = !(state.pending.is_some()
+            && ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Escape)));
+        egui::Window::new(format!("Analysis of {}", state.image))
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(700.0)
+            .show(ctx, |ui| {
+                if state.pending.is_some() {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(&state.progress);
+                    });
+                    ui.label("Esc cancels");
+                    return;
+                }
+                if let Some(error) = &state.error {
+                    ui.colored_label(theme.error, error);
+                    return;
+                }
+                let Some(report) = &state.report else {
+                    return;
+                };
+                ui.label(
+                    egui::RichText::new(format!(
+                        "Efficiency {:.1}%, {} wasted of {}",
+                        report.efficiency() * 100.0,
+                        format_size(report.wasted() as i64),
+                        format_size(report.total_size() as i64)
+                    ))
+                    .strong(),
+                );
+                egui::ScrollArea::vertical()
+                    .id_source("analysis_layers")
+                    .max_height(250.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("analysis_layers")
+                            .num_columns(3)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                ui.strong("Layer");
+                                ui.strong("Size");
+                                ui.strong("Wasted");
+                                ui.end_row();
+                                for (index, layer) in report.layers.iter().enumerate() {
+                                    let selected = state.selected_layer == Some(index);
+                                    let id = egui::RichText::new(&layer.id).monospace();
+                                    if ui.selectable_label(selected, id).clicked() {
+                                        state.selected_layer = Some(index);
+                                    }
+                                    ui.label(format_size(layer.size as i64));
+                                    if layer.wasted > 0 {
+                                        ui.colored_label(
+                                            theme.warning,
+                                            format_size(layer.wasted as i64),
+                                        );
+                                    } else {
+                                        ui.label("-");
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                let Some(layer) = state
+                    .selected_layer
+                    .and_then(|index| report.layers.get(index))
+                else {
+                    ui.label("Select a layer to see its biggest files");
+                    return;
+                };
+                ui.separator();
+                egui::Grid::new("analysis_files")
+                    .num_columns(2)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for file in &layer.biggest_files {
+                            let mut path = egui::RichText::new(&file.path).monospace();
+                            if file.wasted {
+                                path = path.color(theme.warning);
+                            }
+                            ui.label(path);
+                            ui.label(format_size(file.size as i64));
+                            ui.end_row();
+                        }
+                    });
+                ui.weak("Yellow files are replaced or deleted by a later layer");
+            });
+        if !open {
+            self.image_analysis = None;
+        }
+    }
+
+    // Stays open on invalid input or a rejected update so the values can be corrected
+    fn resource_window(&mut self, ctx: &egui::Context) {
+        let theme = self.theme;
+        let Some(form) = &mut self.resource_form else {
+            return;
+        };
+        if let Some(loading) = &mut form.loading {
+            match loading.try_recv() {
+                Ok(Ok(limits)) => {
+                    form.memory = resources::format_memory(limits.memory);
+                    form.cpus = resources::format_cpus(limits.nano_cpus);
+                    form.restart_policy = limits.restart_policy.clone();
+                    form.current = limits;
+                    form.loading = None;
+                }
+                Ok(Err(e)) => {
+                    form.error = Some(e);
+                    form.loading = None;
+                }
+                Err(oneshot::error::TryRecvError::Empty) => {}
+                Err(oneshot::error::TryRecvError::Closed) => form.loading = None,
+            }
+        }
+        if let Some(result) = &mut form.result {
+            match result.try_recv() {
+                Ok(Ok(message)) => {
+                    // The details show the restart policy, which the form may have changed
+                    self.container_details.remove(&form.container_id);
+                    self.status_message = Some(Ok(message));
+                    self.resource_form = None;
+                    return;
+                }
+                Ok(Err(e)) => {
+                    form.error = Some(e);
+                    form.result = None;
+                }
+                Err(oneshot::error::TryRecvError::Empty) => {}
+                Err(oneshot::error::TryRecvError::Closed) => {
+                    form.error = Some("The update was aborted".to_string());
+                    form.result = None;
+                }
+            }
+        }
+
+        let mut open = true;
+        let mut submitted = false;
+        egui::Window::new(format!("Limits of {}", form.name))
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                if form.loading.is_some() {
+                    ui.spinner();
+                    return;
+                }
+                egui::Grid::new("resource_form")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Memory");
+                        ui.add(egui::TextEdit::singleline(&mut form.memory).hint_text("unlimited"))
+                            .on_hover_text("e.g. 512m or 1.5g");
+                        ui.end_row();
+                        ui.label("CPUs");
+                        ui.add(egui::TextEdit::singleline(&mut form.cpus).hint_text("unlimited"))
+                            .on_hover_text("e.g. 1.5");
+                        ui.end_row();
+                        ui.label("Restart policy");
+                        // The current policy is shown with its retry count, which is kept unless
+                        // the policy is changed
+                        let selected = if form.restart_policy == form.current.restart_policy {
+                            resources::describe_restart_policy(
+                                &form.current.restart_policy,
+                                form.current.restart_max_retries,
+                            )
+                        } else {
+                            form.restart_policy.clone()
+                        };
+                        egui::ComboBox::from_id_source("restart_policy")
+                            .selected_text(selected)
+                            .show_ui(ui, |ui| {
+                                for policy in RESTART_POLICIES {
+                                    ui.selectable_value(
+                                        &mut form.restart_policy,
+                                        policy.to_string(),
+                                        policy,
+                                    );
+                                }
+                            });
+                        ui.end_row();
+                    });
+
+                if let Some(error) = &form.error {
+                    ui.colored_label(theme.error, error);
+                }
+                if form.result.is_some() {
+                    ui.label("Updating…");
+                } else if ui.button("Update").clicked() {
+                    submitted = true;
+                }
+            });
+
+        if submitted {
+            // Empty fields leave the current limit alone
+            let memory = Some(form.memory.trim())
+                .filter(|memory| !memory.is_empty())
+                .map(resources::parse_memory)
+                .transpose();
+            let cpus = Some(form.cpus.trim())
+                .filter(|cpus| !cpus.is_empty())
+                .map(resources::parse_cpus)
+                .transpose();
+            let restart_policy =
+                resources::restart_policy_change(&form.restart_policy, &form.current);
+            match (memory, cpus, restart_policy) {
+                (Ok(memory), Ok(nano_cpus), Ok(restart_policy)) => {
+                    let id = form.container_id.clone();
+                    let (sender, receiver) = oneshot::channel();
+                    form.error = None;
+                    form.result = Some(receiver);
+                    tokio::spawn(async move {
+                        let result =
+                            resources::update_resources(id, memory, nano_cpus, restart_policy)
+                                .await;
+                        let _ = sender.send(result);
+                    });
+                }
+                (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => form.error = Some(e),
+            }
+        }
+        if !open {
+            self.resource_form = None;
+        }
+    }
+
+    fn file_browser_window(&mut self, ctx: &egui::Context) {
+        let theme = self.theme;
+        let Some(browser) = &mut self.file_browser else {
+            return;
+        };
+        if let Some(pending) = &mut browser.pending {
+            match pending.try_recv() {
+                Ok(Ok(entries)) => {
+                    browser.entries = entries;
+                    browser.pending = None;
+                }
+                Ok(Err(e)) => {
+                    browser.entries.clear();
+                    browser.error = Some(e);
+                    browser.pending = None;
+                }
+                Err(oneshot::error::TryRecvError::Empty) => {}
+                Err(oneshot::error::TryRecvError::Closed) => browser.pending = None,
+            }
+        }
+
+        let mut open = true;
+        let mut navigate = None;
+        let mut download = None;
+        let mut upload = false;
+        let mut cancel_overwrite = false;
+        egui::Window::new(format!("Files in {}", browser.name))
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(600.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(browser.path != "/", egui::Button::new("⬆ Up"))
+                        .clicked()
+                    {
+                        navigate = Some(files::join_path(&browser.path, ".."));
+                    }
+                    ui.label(egui::RichText::new(&browser.path).monospace());
+                    if browser.pending.is_some() {
+                        ui.spinner();
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Copy to host directory");
+                    ui.text_edit_singleline(&mut browser.host_dir);
+                });
+                ui.separator();
+                if let Some(error) = &browser.error {
+                    ui.colored_label(theme.warning, error);
+                }
+                egui::ScrollArea::vertical()
+                    .max_height(400.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("file_browser")
+                            .num_columns(5)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for entry in &browser.entries {
+                                    ui.label(egui::RichText::new(&entry.permissions).monospace());
+                                    match &entry.kind {
+                                        EntryKind::Directory => {
+                                            if ui.link(format!("{}/", entry.name)).clicked() {
+                                                navigate = Some(files::join_path(
+                                                    &browser.path,
+                                                    &entry.name,
+                                                ));
+                                            }
+                                        }
+                                        // Links are followed, listing tells whether they
+                                        // point to a directory
+                                        EntryKind::Symlink(target) => {
+                                            if ui
+                                                .link(format!("{} → {}", entry.name, target))
+                                                .clicked()
+                                            {
+                                                navigate =
+                                                    Some(files::join_path(&browser.path, target));
+                                            }
+                                        }
+                                        EntryKind::File | EntryKind::Other => {
+                                            ui.label(&entry.name);
+                                        }
+                                    }
+                                    ui.label(&entry.size);
+                                    ui.label(&entry.modified);
+                                    if ui.small_button("Copy to host").clicked() {
+                                        download =
+                                            Some(files::join_path(&browser.path, &entry.name));
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut browser.upload_path)
+                            .hint_text("host file or directory"),
+                    );
+                    upload = mutating_button(
+                        ui,
+                        !browser.upload_path.trim().is_empty(),
+                        format!("Copy into {}", browser.path),
+                    )
+                    .clicked();
+                });
+
+                if let Some((container_path, destination)) = &browser.confirm_overwrite {
+                    ui.separator();
+                    ui.colored_label(
+                        theme.warning,
+                        format!("{:?} already exists, overwrite it?", destination),
+                    );
+                    ui.horizontal(|ui| {
+                        if ui.button("Overwrite").clicked() {
+                            download = Some(container_path.clone());
+                        }
+                        cancel_overwrite = ui.button("Cancel").clicked();
+                    });
+                }
+            });
+
+        if cancel_overwrite {
+            browser.confirm_overwrite = None;
+        }
+        if let Some(container_path) = download {
+            let host_dir = PathBuf::from(browser.host_dir.trim());
+            let destination = files::download_destination(&container_path, &host_dir);
+            // The second click on a path that exists comes from the Overwrite button
+            let confirmed = browser
+                .confirm_overwrite
+                .take()
+                .is_some_and(|(confirmed, _)| confirmed == container_path);
+            if destination.exists() && !confirmed {
+                browser.confirm_overwrite = Some((container_path, destination));
+            } else {
+                let id = browser.container_id.clone();
+                let progress = self.result_sender.clone();
+                spawn_reported(
+                    &self.result_sender,
+                    files::copy_from_container(id, container_path, host_dir, progress),
+                );
+            }
+        }
+        if upload {
+            let id = browser.container_id.clone();
+            let host_path = PathBuf::from(browser.upload_path.trim());
+            let progress = self.result_sender.clone();
+            spawn_reported(
+                &self.result_sender,
+                files::copy_to_container(id, host_path, browser.path.clone(), progress),
+            );
+        }
+        if let Some(path) = navigate {
+            browser.open(path);
+        }
+        if !open {
+            self.file_browser = None;
+        }
+    }
+
+    fn scale_window(&mut self, ctx: &egui::Context) {
+        let Some(form) = &mut self.scale_form else {
+            return;
+        };
+        let (mut submitted, cancelled) = ctx.input_mut(|i| {
+            (
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Escape),
+            )
+        });
+        let mut open = !cancelled;
+        egui::Window::new(format!("Scale {}", form.service))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(format!("Project {}", form.project.name));
+                ui.horizontal(|ui| {
+                    ui.label("Replicas");
+                    ui.add(egui::DragValue::new(&mut form.replicas).clamp_range(0..=100));
+                });
+                submitted |= ui.button("Scale").clicked();
+            });
 
-pub enum AppView {
-    Containers,
-    Composes,
-    Dockerfiles,
-}
+        if submitted {
+            if let Some(form) = self.scale_form.take() {
+                let output = self.output_sender.clone();
+                spawn_reported(&self.result_sender, async move {
+                    scale_compose_service(&form.project, &form.service, form.replicas, output).await
+                });
+            }
+        } else if !open {
+            self.scale_form = None;
+        }
+    }
 
-pub struct DockerViewerApp {
-    pub receiver: mpsc::Receiver<HashMap<String, (ContainerSummary, String)>>,
-    pub containers: HashMap<String, (ContainerSummary, String)>,
-    pub selected_container: Option<String>,
-    pub compose_files: Vec<PathBuf>,
-    pub selected_compose_for_preview: Option<PathBuf>,
-    pub current_view: AppView,
-    pub dockerfiles: Vec<PathBuf>,
-    pub selected_dockerfile_for_preview: Option<PathBuf>,
-}
+    // Asks for the since/until of the logs to fetch for the selected container, a range
+    // that does not parse keeps the prompt open with the error
+    fn log_range_window(&mut self, ctx: &egui::Context) {
+        let theme = self.theme;
+        let Some(form) = &mut self.log_range_form else {
+            return;
+        };
+        let mut fetch = ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Enter));
+        let mut cancelled =
+            ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Escape));
+        egui::Window::new("Logs time range")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label("Durations like 15m or 2h count back from now, timestamps are RFC 3339");
+                egui::Grid::new("log_range").num_columns(2).show(ui, |ui| {
+                    ui.label("Since");
+                    let since =
+                        ui.add(egui::TextEdit::singleline(&mut form.since).hint_text("15m"));
+                    if ui.memory(|memory| memory.focus().is_none()) {
+                        since.request_focus();
+                    }
+                    ui.end_row();
+                    ui.label("Until");
+                    ui.add(egui::TextEdit::singleline(&mut form.until).hint_text("now"));
+                    ui.end_row();
+                });
+                if let Some(error) = &form.error {
+                    ui.colored_label(theme.error, error);
+                }
+                ui.horizontal(|ui| {
+                    fetch |= ui.button("Fetch").clicked();
+                    cancelled |= ui.button("Cancel").clicked();
+                });
+            });
+        if cancelled {
+            self.log_range_form = None;
+            return;
+        }
+        if !fetch {
+            return;
+        }
+        let Some(id) = self.selected_container.clone() else {
+            self.log_range_form = None;
+            return;
+        };
+        match LogRange::parse(&form.since, &form.until, unix_now()) {
+            Ok(range) => {
+                self.range_logs = Some(RangeLogs::load(id, range));
+                self.log_range_form = None;
+            }
+            Err(e) => form.error = Some(e),
+        }
+    }
 
-impl App for DockerViewerApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        egui::CentralPanel::default().show(ctx, |ui| {
-            ui.horizontal(|ui| {
-                if ui.button("Containers").clicked() {
-                    self.current_view = AppView::Containers;
+    // Results and errors of background work stacked in the top right corner, each until it
+    // expires or is dismissed. Those about a container select it when clicked.
+    fn notifications_area(&mut self, ctx: &egui::Context) {
+        let Some(next_expiry) = self.notifications.expire(Instant::now()) else {
+            return;
+        };
+        ctx.request_repaint_after(next_expiry);
+        let theme = self.theme;
+        let mut dismissed = None;
+        let mut selected = None;
+        egui::Area::new(egui::Id::new("notifications"))
+            .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-8.0, 40.0))
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                ui.set_max_width(400.0);
+                for (index, notification) in self.notifications.shown.iter().enumerate() {
+                    let color = if notification.success {
+                        theme.success
+                    } else {
+                        theme.error
+                    };
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            let dismiss = ui.small_button("✕");
+                            if name_button(dismiss, "Dismiss notification")
+                                .on_hover_text("Dismiss")
+                                .clicked()
+                            {
+                                dismissed = Some(index);
+                            }
+                            let text = egui::RichText::new(notification.label()).color(color);
+                            let label = egui::Label::new(text).wrap(true);
+                            let spoken = accessibility::notification_name(notification);
+                            let response = match &notification.container {
+                                Some(id) => {
+                                    let response = ui
+                                        .add(label.sense(egui::Sense::click()))
+                                        .on_hover_text("Select the container");
+                                    if response.clicked() {
+                                        selected = Some(id.clone());
+                                    }
+                                    name_button(response, &spoken)
+                                }
+                                None => {
+                                    let response = ui.add(label);
+                                    response.widget_info(|| {
+                                        egui::WidgetInfo::labeled(egui::WidgetType::Label, &spoken)
+                                    });
+                                    response
+                                }
+                            };
+                            accessibility::announce(ui.ctx(), &response, !notification.success);
+                        });
+                    });
                 }
-                if ui.button("Composes").clicked() {
-                    self.current_view = AppView::Composes;
+            });
+        if let Some(index) = dismissed {
+            self.notifications.dismiss(index);
+        }
+        if let Some(id) = selected {
+            self.select_container_by_id(&id);
+        }
+    }
+
+    // The last notifications, newest first, from the status bar
+    fn notification_history_window(&mut self, ctx: &egui::Context) {
+        if !self.show_notification_history {
+            return;
+        }
+        let theme = self.theme;
+        let now = Instant::now();
+        let mut open = true;
+        let mut selected = None;
+        egui::Window::new("Notifications")
+            .open(&mut open)
+            .default_width(500.0)
+            .show(ctx, |ui| {
+                if self.notifications.history.is_empty() {
+                    ui.label("Nothing happened yet");
                 }
-                if ui.button("Dockerfiles").clicked() {
-                    self.current_view = AppView::Dockerfiles;
+                egui::ScrollArea::vertical()
+                    .max_height(400.0)
+                    .show(ui, |ui| {
+                        for notification in self.notifications.history.iter().rev() {
+                            ui.horizontal(|ui| {
+                                let elapsed = now.saturating_duration_since(notification.at);
+                                ui.weak(format_age(0, elapsed.as_secs() as i64));
+                                let color = if notification.success {
+                                    theme.success
+                                } else {
+                                    theme.error
+                                };
+                                ui.colored_label(color, notification.label());
+                                if let Some(id) = &notification.container {
+                                    if ui.small_button("Select").clicked() {
+                                        selected = Some(id.clone());
+                                    }
+                                }
+                            });
+                        }
+                    });
+            });
+        if !open {
+            self.show_notification_history = false;
+        }
+        if let Some(id) = selected {
+            self.select_container_by_id(&id);
+        }
+    }
+
+    fn action_history_window(&mut self, ctx: &egui::Context) {
+        if !self.show_action_history {
+            return;
+        }
+        let theme = self.theme;
+        let mut open = true;
+        egui::Window::new("Action history")
+            .open(&mut open)
+            .default_width(600.0)
+            .show(ctx, |ui| {
+                if self.action_history.entries.is_empty() {
+                    ui.label("Nothing was done yet this session");
                 }
-                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                    if ui.button("Remove All").clicked() {
-                        let all_summaries: Vec<ContainerSummary> = self
-                            .containers
-                            .values()
-                            .cloned()
-                            .into_iter()
-                            .map(|a| a.0)
-                            .collect();
-                        tokio::spawn(async move { remove_containers(all_summaries).await });
+                egui::ScrollArea::vertical()
+                    .max_height(400.0)
+                    .stick_to_bottom(true)
+                    .show(ui, |ui| {
+                        for entry in &self.action_history.entries {
+                            ui.horizontal(|ui| {
+                                ui.weak(format_timestamp(entry.at));
+                                let kind = entry.kind.label();
+                                match entry.kind {
+                                    AuditKind::Sent => ui.label(kind),
+                                    AuditKind::Succeeded => ui.colored_label(theme.success, kind),
+                                    AuditKind::Failed => ui.colored_label(theme.error, kind),
+                                };
+                                ui.label(&entry.message);
+                            });
+                        }
+                    });
+            });
+        if !open {
+            self.show_action_history = false;
+        }
+    }
+
+    fn alerts_window(&mut self, ctx: &egui::Context) {
+        let theme = self.theme;
+        if !self.show_alerts {
+            return;
+        }
+        let mut open = true;
+        egui::Window::new("Alerts")
+            .open(&mut open)
+            .default_width(500.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let mut watched: Vec<&String> = self.watched.iter().collect();
+                    watched.sort();
+                    if watched.is_empty() {
+                        ui.label("No containers watched, A watches the selected one");
+                    } else {
+                        ui.label(format!(
+                            "Watching {}",
+                            watched
+                                .iter()
+                                .map(|name| name.as_str())
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        ));
                     }
-                    if ui.button("Kill All").clicked() {
-                        let all_summaries: Vec<ContainerSummary> = self
-                            .containers
-                            .values()
-                            .cloned()
-                            .into_iter()
-                            .map(|a| a.0)
-                            .collect();
-                        tokio::spawn(async move { kill_containers(all_summaries).await });
+                    if ui.button("Clear").clicked() {
+                        self.alerts.clear();
                     }
                 });
+                ui.horizontal(|ui| {
+                    let settings = &mut self.config.alerts;
+                    ui.label("On a crash while the window is in the background:");
+                    ui.checkbox(&mut settings.bell, "Bell")
+                        .on_hover_text("A bell on the terminal dockerrs was started from");
+                    ui.checkbox(&mut settings.flash, "Flash")
+                        .on_hover_text("Invert the tab bar for a moment");
+                });
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        egui::Grid::new("alerts")
+                            .num_columns(3)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for alert in self.alerts.iter().rev() {
+                                    ui.label(format_timestamp(alert.at));
+                                    ui.strong(&alert.container);
+                                    let color = match alert.transition {
+                                        Transition::Unhealthy => theme.warning,
+                                        _ => theme.error,
+                                    };
+                                    ui.colored_label(color, alert.transition.describe());
+                                    ui.end_row();
+                                }
+                            });
+                    });
             });
+        if !open {
+            self.show_alerts = false;
+        }
+    }
 
-            match self.current_view {
-                AppView::Containers => {
-                    self.containers_appview(ui);
+    fn commit_window(&mut self, ctx: &egui::Context) {
+        let theme = self.theme;
+        let Some(form) = &mut self.commit_form else {
+            return;
+        };
+        if let Some(result) = &mut form.result {
+            match result.try_recv() {
+                // The new image shows up in the Images view with the next poll
+                Ok(Ok(message)) => {
+                    self.status_message = Some(Ok(message));
+                    self.commit_form = None;
+                    return;
                 }
-                AppView::Composes => {
-                    self.composes_appview(ui);
+                Ok(Err(e)) => {
+                    form.error = Some(e);
+                    form.result = None;
                 }
-                AppView::Dockerfiles => {
-                    self.dockerfiles_appview(ui);
+                Err(oneshot::error::TryRecvError::Empty) => {}
+                Err(oneshot::error::TryRecvError::Closed) => {
+                    form.error = Some("The commit was aborted".to_string());
+                    form.result = None;
                 }
             }
-        });
-
-        ctx.request_repaint();
-        sleep(Duration::from_millis(50));
-    }
-}
-
-impl DockerViewerApp {
-    fn composes_appview(&mut self, ui: &mut egui::Ui) {
-        // Path and Docker containers separation line
-        ui.vertical(|ui| {
-            for path in &self.compose_files {
-                ui.separator();
-                ui.horizontal(|ui| {
-                    // Extract the last three folders from the path
-                    let folders: Vec<_> = path.iter().rev().collect();
-                    let display_path = folders
-                        .iter()
-                        .rev()
-                        .map(|p| p.to_string_lossy())
-                        .collect::<Vec<_>>()
-                        .join("/");
-                    if ui
-                        .selectable_label(
-                            self.selected_compose_for_preview == Some(path.clone()),
-                            display_path,
-                        )
-                        .clicked()
-                    {
-                        self.selected_compose_for_preview = Some(path.clone())
-                    }
+        }
 
-                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        if ui.button("Run").clicked() {
-                            if let Some(parent) = path.parent() {
-                                let parent_clone = parent.to_owned();
-                                tokio::spawn(async move {
-                                    run_docker_compose_up(&parent_clone).await;
-                                });
-                            } else {
-                                eprintln!(
-                                    "Error: Cannot determine the parent directory for {:?}",
-                                    path
-                                );
-                            }
-                        }
+        let mut open = true;
+        let mut submitted = false;
+        egui::Window::new(format!("Commit {}", form.name))
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                egui::Grid::new("commit_form")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Repository");
+                        ui.text_edit_singleline(&mut form.repo);
+                        ui.end_row();
+                        ui.label("Tag");
+                        ui.add(egui::TextEdit::singleline(&mut form.tag).hint_text("latest"));
+                        ui.end_row();
+                        ui.label("Comment");
+                        ui.text_edit_singleline(&mut form.comment);
+                        ui.end_row();
                     });
+                if let Some(error) = &form.error {
+                    ui.colored_label(theme.error, error);
+                }
+                if form.result.is_some() {
+                    ui.label("Committing…");
+                } else if ui.button("Commit").clicked() {
+                    submitted = true;
+                }
+            });
+
+        if submitted {
+            if form.repo.trim().is_empty() {
+                form.error = Some("Enter a repository name for the new image".to_string());
+            } else {
+                let (sender, receiver) = oneshot::channel();
+                let id = form.container_id.clone();
+                let repo = form.repo.clone();
+                let tag = form.tag.clone();
+                let comment = form.comment.clone();
+                form.error = None;
+                form.result = Some(receiver);
+                tokio::spawn(async move {
+                    let _ = sender.send(commit_container(id, repo, tag, comment).await);
                 });
             }
-        });
-        // Display compose preview if a file is selected
-        if let Some(selected_compose) = &self.selected_compose_for_preview {
-            if let Ok(file_content) = std::fs::read_to_string(selected_compose) {
-                ui.group(|ui| {
-                    egui::ScrollArea::vertical()
-                        .auto_shrink([false, false])
-                        .show(ui, |ui| {
-                            ui.label(file_content);
-                        });
-                });
-            }
+        }
+        if !open {
+            self.commit_form = None;
         }
     }
 
-    fn containers_appview(&mut self, ui: &mut egui::Ui) {
-        for (name, (summary, logs)) in &self.containers {
-            ui.horizontal(|ui| {
-                ui.label(name);
-                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                    if ui.button("Logs").clicked() {
-                        self.selected_container = Some(name.clone());
-                    }
+    fn tarball_window(&mut self, ctx: &egui::Context) {
+        let Some(prompt) = &mut self.tarball_prompt else {
+            return;
+        };
+        let (title, button) = match &prompt.action {
+            TarballAction::ExportContainer { name, .. } => (format!("Export {}", name), "Export"),
+            TarballAction::SaveImage(image) => (format!("Save {}", image), "Save"),
+            TarballAction::LoadImage => ("Load image".to_string(), "Load"),
+        };
+
+        let mut open = true;
+        let mut submitted = false;
+        egui::Window::new(title)
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Tarball");
+                    ui.text_edit_singleline(&mut prompt.path);
                 });
+                submitted = ui
+                    .add_enabled(!prompt.path.trim().is_empty(), egui::Button::new(button))
+                    .clicked();
             });
 
-            ui.group(|ui| {
-                if self.selected_container.as_ref() == Some(name) {
-                    if ui.button("Remove").clicked() {
-                        let summary_clone = summary.clone();
-                        tokio::spawn(async move { remove_container(&summary_clone).await });
+        if submitted {
+            let path = PathBuf::from(prompt.path.trim());
+            let progress = self.result_sender.clone();
+            match &prompt.action {
+                TarballAction::ExportContainer { id, .. } => spawn_reported(
+                    &self.result_sender,
+                    tarballs::export_container(id.clone(), path, progress),
+                ),
+                TarballAction::SaveImage(image) => spawn_reported(
+                    &self.result_sender,
+                    tarballs::save_image(image.clone(), path, progress),
+                ),
+                // The loaded images show up in the Images view with the next poll
+                TarballAction::LoadImage => {
+                    spawn_reported(&self.result_sender, tarballs::load_image(path, progress))
+                }
+            };
+            self.tarball_prompt = None;
+        } else if !open {
+            self.tarball_prompt = None;
+        }
+    }
+
+    fn push_window(&mut self, ctx: &egui::Context) {
+        let theme = self.theme;
+        let Some(form) = &mut self.push_form else {
+            return;
+        };
+
+        let registry = registry::registry_of(&form.image);
+        let mut open = true;
+        let mut submitted = false;
+        egui::Window::new(format!("Push {}", form.image))
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                match registry {
+                    Some(registry) => {
+                        ui.label(format!("Pushing to {}", registry));
                     }
-                    if ui.button("Kill").clicked() {
-                        let summary_clone = summary.clone();
-                        tokio::spawn(async move { kill_container(&summary_clone).await });
+                    None => {
+                        ui.colored_label(
+                            theme.warning,
+                            "This image has no registry prefix, it will be pushed to Docker Hub",
+                        );
                     }
                 }
+                egui::Grid::new("push_form").num_columns(2).show(ui, |ui| {
+                    ui.label("Username");
+                    ui.text_edit_singleline(&mut form.username);
+                    ui.end_row();
+                    ui.label("Password");
+                    ui.add(egui::TextEdit::singleline(&mut form.password).password(true));
+                    ui.end_row();
+                });
+                ui.label("Leave empty to use the credentials stored by docker login");
+                submitted = ui.button("Push").clicked();
+            });
+
+        if submitted {
+            let image = form.image.clone();
+            let username = form.username.trim().to_string();
+            let password = std::mem::take(&mut form.password);
+            let output = self.output_sender.clone();
+            self.command_output.clear();
+            spawn_reported(&self.result_sender, async move {
+                let credentials = if username.is_empty() {
+                    registry::stored_credentials(registry::registry_of(&image)).await?
+                } else {
+                    Some(DockerCredentials {
+                        username: Some(username),
+                        password: Some(password),
+                        serveraddress: registry::registry_of(&image).map(str::to_string),
+                        ..Default::default()
+                    })
+                };
+                registry::push_image(image, credentials, output).await
             });
+            self.push_form = None;
+        } else if !open {
+            self.push_form = None;
         }
+    }
 
-        if let Some(name) = &self.selected_container {
-            if let Some((_summary, logs)) = self.containers.get(name) {
-                ui.group(|ui| {
-                    egui::ScrollArea::vertical()
-                        .auto_shrink([false, false])
-                        .show(ui, |ui| {
-                            ui.label(logs);
-                        });
+    fn run_image_window(&mut self, ctx: &egui::Context) {
+        let theme = self.theme;
+        let Some(form) = &mut self.run_image_form else {
+            return;
+        };
+
+        if let Some(result) = &mut form.result {
+            match result.try_recv() {
+                Ok(Ok(id)) => {
+                    self.pending_selection = Some(id);
+                    self.current_view = AppView::Containers;
+                    self.run_image_form = None;
+                    return;
+                }
+                Ok(Err(e)) => {
+                    form.error = Some(e);
+                    form.result = None;
+                }
+                Err(oneshot::error::TryRecvError::Empty) => {}
+                Err(oneshot::error::TryRecvError::Closed) => {
+                    form.error = Some("Container creation was aborted".to_string());
+                    form.result = None;
+                }
+            }
+        }
+
+        let mut open = true;
+        let mut submitted = false;
+        egui::Window::new(format!("Run {}", form.image))
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                egui::Grid::new("run_image_form")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Name");
+                        ui.text_edit_singleline(&mut form.name);
+                        ui.end_row();
+                        ui.label("Ports");
+                        ui.text_edit_singleline(&mut form.ports)
+                            .on_hover_text("Comma separated host:container pairs, e.g. 8080:80");
+                        ui.end_row();
+                        ui.label("Env");
+                        ui.text_edit_multiline(&mut form.env)
+                            .on_hover_text("One KEY=value per line");
+                        ui.end_row();
+                    });
+
+                if let Some(error) = &form.error {
+                    ui.colored_label(theme.error, error);
+                }
+                if form.result.is_some() {
+                    ui.label("Starting…");
+                } else if ui.button("Create and start").clicked() {
+                    submitted = true;
+                }
+            });
+
+        if submitted {
+            let name = form.name.trim().to_string();
+            let duplicate = !name.is_empty()
+                && self.containers.values().any(|(summary, _)| {
+                    summary
+                        .names
+                        .as_ref()
+                        .is_some_and(|names| names.contains(&format!("/{}", name)))
                 });
+            if duplicate {
+                form.error = Some(format!("A container named '{}' already exists", name));
+            } else {
+                match parse_port_mappings(&form.ports) {
+                    Ok(ports) => {
+                        let env = form
+                            .env
+                            .lines()
+                            .map(str::trim)
+                            .filter(|line| !line.is_empty())
+                            .map(str::to_string)
+                            .collect();
+                        let image = form.image.clone();
+                        let name = (!name.is_empty()).then_some(name);
+                        let (sender, receiver) = oneshot::channel();
+                        form.error = None;
+                        form.result = Some(receiver);
+                        tokio::spawn(async move {
+                            let result = create_and_start_container(image, name, ports, env).await;
+                            let _ = sender.send(result);
+                        });
+                    }
+                    Err(e) => form.error = Some(e),
+                }
             }
         }
+        if !open {
+            self.run_image_form = None;
+        }
     }
 
     fn dockerfiles_appview(&mut self, ui: &mut egui::Ui) {
+        let theme = self.theme;
+        if Self::scan_header(
+            ui,
+            self.dockerfiles.len(),
+            &self.scan_settings.roots,
+            self.dockerfile_scan.is_some(),
+        ) {
+            self.rescan_dockerfiles();
+        }
+
+        let mut build_requested = None;
+        let mut selected = None;
+        let mut shown = None;
+        let now = Instant::now();
         ui.vertical(|ui| {
             for dockerfile in &self.dockerfiles {
                 ui.separator();
                 ui.horizontal(|ui| {
-                    let display_path = dockerfile.to_string_lossy();
+                    // Lead with the file name so variants in one directory can be told apart
+                    let display_path = match (dockerfile.file_name(), dockerfile.parent()) {
+                        (Some(file_name), Some(parent)) => format!(
+                            "{}  ({})",
+                            file_name.to_string_lossy(),
+                            parent.to_string_lossy()
+                        ),
+                        _ => dockerfile.to_string_lossy().to_string(),
+                    };
                     if ui
                         .selectable_label(
                             self.selected_dockerfile_for_preview == Some(dockerfile.clone()),
@@ -194,110 +7342,413 @@ impl DockerViewerApp {
                         )
                         .clicked()
                     {
-                        self.selected_dockerfile_for_preview = Some(dockerfile.clone())
+                        selected = Some(dockerfile.clone());
                     }
 
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        if ui.button("Build").clicked() {
-                            if let Some(parent) = dockerfile.parent() {
-                                let parent_clone = parent.to_owned();
-                                tokio::spawn(async move {
-                                    build_docker_image(&parent_clone).await;
-                                });
-                            } else {
-                                eprintln!(
-                                    "Error: Cannot determine the parent directory for {:?}",
-                                    dockerfile
-                                );
-                            }
+                        if mutating_button(ui, true, "Build").clicked() {
+                            build_requested = Some(dockerfile.clone());
+                        }
+                        let Some(job) = self.builds.latest(dockerfile) else {
+                            return;
+                        };
+                        let color = match job.status {
+                            BuildStatus::Running => ui.visuals().text_color(),
+                            BuildStatus::Succeeded => theme.success,
+                            BuildStatus::Failed(_) => theme.error,
+                        };
+                        let summary =
+                            egui::Label::new(egui::RichText::new(job.summary(now)).color(color))
+                                .sense(egui::Sense::click());
+                        if ui
+                            .add(summary)
+                            .on_hover_text("Show the build output")
+                            .clicked()
+                        {
+                            shown = Some((dockerfile.clone(), job.started));
+                        }
+                        if job.is_running() {
+                            ui.spinner();
                         }
                     });
                 });
             }
         });
+        if let Some((dockerfile, _)) = &shown {
+            if self.selected_dockerfile_for_preview.as_ref() != Some(dockerfile) {
+                selected = Some(dockerfile.clone());
+            }
+        }
+        if let Some(dockerfile) = selected {
+            // Prefilled with the tag this Dockerfile was built with last time
+            let tag = self.last_build_tag(&dockerfile).unwrap_or_default();
+            self.build_form = Some(BuildForm::new(dockerfile.clone(), tag));
+            self.selected_dockerfile_for_preview = Some(dockerfile);
+            self.shown_build = None;
+        }
+        if shown.is_some() {
+            self.shown_build = shown;
+        }
 
-        if let Some(selected_dockerfile) = &self.selected_dockerfile_for_preview {
-            if let Ok(file_content) = std::fs::read_to_string(selected_dockerfile) {
-                ui.group(|ui| {
-                    egui::ScrollArea::vertical()
-                        .auto_shrink([false, false])
-                        .show(ui, |ui| {
-                            ui.label(file_content);
-                        });
-                });
+        let file_content = self
+            .selected_dockerfile_for_preview
+            .as_ref()
+            .and_then(|selected| std::fs::read_to_string(selected).ok());
+        if let (Some(form), Some(content)) = (&mut self.build_form, &file_content) {
+            let recent = self.config.recent_tags(&form.dockerfile);
+            Self::build_form_ui(ui, form, &dockerfile_stages(content), recent, &theme);
+        }
+        if let Some(dockerfile) = build_requested {
+            self.build_dockerfile(dockerfile);
+        }
+
+        self.command_output_panel(ui);
+
+        let Some(selected) = self.selected_dockerfile_for_preview.clone() else {
+            return;
+        };
+        // Recent builds of the selected file, one of them can replace the preview
+        let mut toggled = None;
+        let mut jobs = self.builds.jobs(&selected).peekable();
+        if jobs.peek().is_some() {
+            ui.horizontal_wrapped(|ui| {
+                ui.label("Builds:");
+                for job in jobs {
+                    let build = (selected.clone(), job.started);
+                    let is_shown = self.shown_build.as_ref() == Some(&build);
+                    if ui.selectable_label(is_shown, job.summary(now)).clicked() {
+                        toggled = Some((!is_shown).then_some(build));
+                    }
+                }
+            });
+        }
+        if let Some(build) = toggled {
+            self.shown_build = build;
+        }
+
+        let shown_job = self
+            .shown_build
+            .as_ref()
+            .and_then(|(dockerfile, started)| self.builds.find(dockerfile, *started));
+        if let Some(job) = shown_job {
+            if Self::build_output(ui, job, now, &theme) {
+                self.shown_build = None;
             }
+        } else if let Some(file_content) = file_content {
+            Self::dockerfile_preview(ui, &file_content, &theme);
         }
     }
 
-    pub fn load_dockerfiles(&mut self, directory: &Path) {
-        println!("Loading dockerfiles");
-        let walker = WalkDir::new(directory).into_iter();
-        self.dockerfiles = walker
-            .filter_map(|entry| {
-                match entry {
-                    Ok(entry) if entry.path().is_file() => {
-                        let file_name = entry.file_name().to_str();
-                        if file_name == Some("Dockerfile") {
-                            // Resolve the path to an absolute path
-                            let abs_path = entry.path().canonicalize();
-                            match abs_path {
-                                Ok(path) => {
-                                    println!("File found: {:?}", path);
-                                    Some(path)
-                                }
-                                Err(e) => {
-                                    eprintln!("Error resolving path {:?}: {}", entry.path(), e);
-                                    None
+    // Everything a build printed, returns whether to go back to the Dockerfile
+    fn build_output(ui: &mut egui::Ui, job: &BuildJob, now: Instant, theme: &Theme) -> bool {
+        let mut back = false;
+        ui.group(|ui| {
+            ui.horizontal(|ui| {
+                ui.strong(format!("Build of {}", job.tag));
+                ui.label(job.summary(now));
+                back = ui.button("Back to Dockerfile").clicked();
+            });
+            if let BuildStatus::Failed(e) = &job.status {
+                ui.colored_label(theme.error, e);
+            }
+            egui::ScrollArea::vertical()
+                .id_source("build_output")
+                .auto_shrink([false, false])
+                .stick_to_bottom(job.is_running())
+                .show(ui, |ui| {
+                    for line in &job.output {
+                        ui.monospace(line);
+                    }
+                });
+        });
+        back
+    }
+
+    // Highlighted Dockerfile with lint warnings in the gutter, explained on hover and listed
+    // below the text
+    fn dockerfile_preview(ui: &mut egui::Ui, content: &str, theme: &Theme) {
+        let warnings = lint(content);
+        let font = egui::TextStyle::Monospace.resolve(ui.style());
+        let lines = highlighted_lines(content, font, ui.visuals().text_color(), theme);
+        ui.group(|ui| {
+            egui::ScrollArea::vertical()
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    egui::Grid::new("dockerfile_preview")
+                        .num_columns(3)
+                        .spacing([6.0, 0.0])
+                        .show(ui, |ui| {
+                            for (index, line) in lines.into_iter().enumerate() {
+                                let number = index + 1;
+                                let here: Vec<_> =
+                                    warnings.iter().filter(|w| w.line == number).collect();
+                                if here.is_empty() {
+                                    ui.label("");
+                                } else {
+                                    let explanation = here
+                                        .iter()
+                                        .map(|w| {
+                                            format!(
+                                                "{}: {}",
+                                                w.lint.message(),
+                                                w.lint.explanation()
+                                            )
+                                        })
+                                        .collect::<Vec<_>>()
+                                        .join("\n\n");
+                                    ui.colored_label(theme.warning, "⚠")
+                                        .on_hover_text(explanation);
                                 }
+                                ui.weak(egui::RichText::new(number.to_string()).monospace());
+                                ui.label(line);
+                                ui.end_row();
                             }
-                        } else {
-                            None
+                        });
+                    if !warnings.is_empty() {
+                        ui.separator();
+                        for warning in &warnings {
+                            ui.label(format!(
+                                "⚠ line {}: {}",
+                                warning.line,
+                                warning.lint.message()
+                            ))
+                            .on_hover_text(warning.lint.explanation());
                         }
                     }
-                    Ok(_) => None,
-                    Err(e) => {
-                        eprintln!("Error walking directory: {}", e);
-                        None
-                    }
-                }
-            })
-            .collect();
+                });
+        });
     }
 
-    pub fn load_compose_files(&mut self, directory: &Path) {
-        println!("Loading compose files");
-        let walker = WalkDir::new(directory).into_iter();
-        self.compose_files = walker
-            .filter_map(|entry| {
-                match entry {
-                    Ok(entry) if entry.path().is_file() => {
-                        let file_name = entry.file_name().to_str();
-                        if file_name == Some("docker_compose.yaml")
-                            || file_name == Some("docker-compose.yaml")
-                        {
-                            // Resolve the path to an absolute path
-                            let abs_path = entry.path().canonicalize();
-                            match abs_path {
-                                Ok(path) => {
-                                    println!("File found: {:?}", path);
-                                    Some(path)
-                                }
-                                Err(e) => {
-                                    eprintln!("Error resolving path {:?}: {}", entry.path(), e);
-                                    None
-                                }
+    // Tag, build args, target stage and context override for the selected Dockerfile
+    fn build_form_ui(
+        ui: &mut egui::Ui,
+        form: &mut BuildForm,
+        stages: &[String],
+        recent: &[String],
+        theme: &Theme,
+    ) {
+        let suggested = suggested_tag(&form.dockerfile);
+        ui.group(|ui| {
+            egui::Grid::new("build_form").num_columns(2).show(ui, |ui| {
+                ui.label("Tag");
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut form.tag)
+                            .hint_text(suggested.as_deref().unwrap_or("myapp:latest")),
+                    );
+                    egui::ComboBox::from_id_source("build_tag")
+                        .selected_text("Recent")
+                        .show_ui(ui, |ui| {
+                            let suggestion = suggested.iter().filter(|tag| !recent.contains(tag));
+                            for tag in recent.iter().chain(suggestion) {
+                                ui.selectable_value(&mut form.tag, tag.clone(), tag);
                             }
-                        } else {
-                            None
+                        });
+                });
+                ui.end_row();
+
+                ui.label("Context");
+                ui.add(
+                    egui::TextEdit::singleline(&mut form.context)
+                        .hint_text("directory containing the Dockerfile"),
+                );
+                ui.end_row();
+
+                ui.label("Target");
+                egui::ComboBox::from_id_source("build_target")
+                    .selected_text(form.target.as_deref().unwrap_or("(final stage)"))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut form.target, None, "(final stage)");
+                        for stage in stages {
+                            ui.selectable_value(&mut form.target, Some(stage.clone()), stage);
                         }
+                    });
+                ui.end_row();
+
+                ui.label("Build args");
+                ui.vertical(|ui| {
+                    let mut removed = None;
+                    for (index, (key, value)) in form.args.iter_mut().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.add(
+                                egui::TextEdit::singleline(key)
+                                    .hint_text("KEY")
+                                    .desired_width(120.0),
+                            );
+                            ui.label("=");
+                            ui.add(egui::TextEdit::singleline(value).hint_text("value"));
+                            if ui.small_button("✖").clicked() {
+                                removed = Some(index);
+                            }
+                        });
+                    }
+                    if let Some(index) = removed {
+                        form.args.remove(index);
                     }
-                    Ok(_) => None,
-                    Err(e) => {
-                        eprintln!("Error reading directory entry: {}", e);
-                        None
+                    if ui.small_button("+ Add").clicked() {
+                        form.args.push((String::new(), String::new()));
                     }
+                });
+                ui.end_row();
+            });
+
+            if let Some(error) = &form.error {
+                ui.colored_label(theme.error, error);
+            }
+        });
+    }
+
+    // The options in the build form only apply to the Dockerfile they were entered for
+    // The tag the Dockerfile was last built with, or the suggested one
+    fn last_build_tag(&self, dockerfile: &Path) -> Option<String> {
+        self.config
+            .recent_tags(dockerfile)
+            .first()
+            .cloned()
+            .or_else(|| suggested_tag(dockerfile))
+    }
+
+    fn build_dockerfile(&mut self, dockerfile: PathBuf) {
+        let Some(mut tag) = self.last_build_tag(&dockerfile) else {
+            self.status_message = Some(Err(format!(
+                "Cannot derive an image name from {:?}",
+                dockerfile
+            )));
+            return;
+        };
+        let mut context = dockerfile
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        let mut args = Vec::new();
+        let mut target = None;
+        if let Some(form) = self
+            .build_form
+            .as_mut()
+            .filter(|form| form.dockerfile == dockerfile)
+        {
+            tag = match check_image_tag(&form.tag) {
+                Ok(tag) => tag,
+                Err(e) => {
+                    form.error = Some(e);
+                    return;
                 }
+            };
+            if form.args.iter().any(|(key, _)| key.trim().is_empty()) {
+                form.error = Some("Build args need a non-empty key".to_string());
+                return;
+            }
+            form.error = None;
+            if !form.context.trim().is_empty() {
+                context = PathBuf::from(form.context.trim());
+            }
+            args = form
+                .args
+                .iter()
+                .map(|(key, value)| (key.trim().to_string(), value.clone()))
+                .collect();
+            target = form.target.clone();
+        }
+
+        self.config.remember_tag(&dockerfile, &tag);
+        if let Err(e) = self.save_config() {
+            let error = DockerrsError::Io {
+                context: "Failed to remember the build tag".to_string(),
+                source: std::io::Error::other(e),
+            };
+            report(&self.error_sender, error);
+        }
+
+        self.command_output.clear();
+        let now = Instant::now();
+        let (file, image) = (dockerfile.clone(), tag.clone());
+        // bollard 0.16 has no build option for the target stage, those builds go through the CLI
+        let job = if self.build_settings.use_cli || target.is_some() {
+            BuildJob::start(file, image, now, move |output| async move {
+                build_docker_image(
+                    &context,
+                    &dockerfile,
+                    &tag,
+                    &args,
+                    target.as_deref(),
+                    output,
+                )
+                .await
             })
-            .collect();
+        } else {
+            let max_file_size = self.build_settings.max_context_file_size;
+            BuildJob::start(file, image, now, move |output| {
+                image_build::build_image(context, dockerfile, tag, args, max_file_size, output)
+            })
+        };
+        self.builds.push(job);
+    }
+
+    // Starting a new scan drops, and thereby cancels, the one in flight
+    pub fn rescan_dockerfiles(&mut self) {
+        self.dockerfiles.clear();
+        self.dockerfile_scan = Some(Scan::start(
+            self.scan_settings.clone(),
+            is_dockerfile,
+            self.error_sender.clone(),
+        ));
+    }
+
+    pub fn rescan_compose_files(&mut self) {
+        self.compose_files.clear();
+        self.compose_scan = Some(Scan::start(
+            self.scan_settings.clone(),
+            is_compose_file,
+            self.error_sender.clone(),
+        ));
+    }
+
+    // Appends files as the walk finds them, returns whether anything was added
+    fn receive_scan(scan: &mut Option<Scan>, files: &mut Vec<PathBuf>) -> bool {
+        let Some(running) = scan else {
+            return false;
+        };
+        let found = files.len();
+        loop {
+            match running.receiver.try_recv() {
+                Ok(path) => files.push(path),
+                Err(mpsc::error::TryRecvError::Empty) => break,
+                Err(mpsc::error::TryRecvError::Disconnected) => {
+                    *scan = None;
+                    break;
+                }
+            }
+        }
+        files.len() != found
+    }
+
+    fn receive_scans(&mut self) {
+        if Self::receive_scan(&mut self.compose_scan, &mut self.compose_files) {
+            self.compose_files
+                .sort_by_key(|path| compose_sort_key(path));
+        }
+        Self::receive_scan(&mut self.dockerfile_scan, &mut self.dockerfiles);
+    }
+
+    // Number of files found, the roots being scanned and a Rescan button
+    fn scan_header(ui: &mut egui::Ui, count: usize, roots: &[PathBuf], scanning: bool) -> bool {
+        let mut rescan = false;
+        ui.horizontal(|ui| {
+            let roots = roots
+                .iter()
+                .map(|root| root.to_string_lossy())
+                .collect::<Vec<_>>()
+                .join(", ");
+            if scanning {
+                ui.spinner();
+                ui.label(format!("scanning… {} files found in {}", count, roots));
+            } else {
+                ui.label(format!("{} files found in {}", count, roots));
+            }
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                rescan = ui.button("Rescan").clicked();
+            });
+        });
+        rescan
     }
 }
@@ -0,0 +1,371 @@
+use eframe::egui::text::{LayoutJob, TextFormat};
+use eframe::egui::{Color32, FontId};
+
+use crate::theme::Theme;
+
+const INSTRUCTIONS: &[&str] = &[
+    "ADD",
+    "ARG",
+    "CMD",
+    "COPY",
+    "ENTRYPOINT",
+    "ENV",
+    "EXPOSE",
+    "FROM",
+    "HEALTHCHECK",
+    "LABEL",
+    "MAINTAINER",
+    "ONBUILD",
+    "RUN",
+    "SHELL",
+    "STOPSIGNAL",
+    "USER",
+    "VOLUME",
+    "WORKDIR",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenKind {
+    Instruction,
+    Comment,
+    // The `\` that continues an instruction on the next line
+    Continuation,
+    // A `<<EOF` marker and the lines up to its terminator
+    Heredoc,
+    Variable,
+    Text,
+}
+
+impl TokenKind {
+    // Text keeps the default color
+    pub fn color(self, theme: &Theme) -> Option<Color32> {
+        match self {
+            TokenKind::Instruction => Some(theme.keyword),
+            TokenKind::Comment => Some(theme.muted),
+            TokenKind::Continuation => Some(theme.continuation),
+            TokenKind::Heredoc => Some(theme.heredoc),
+            TokenKind::Variable => Some(theme.variable),
+            TokenKind::Text => None,
+        }
+    }
+}
+
+// Splits a Dockerfile into highlighted pieces, joined together they are the file again
+pub fn tokenize(content: &str) -> Vec<(TokenKind, &str)> {
+    let mut tokens = Vec::new();
+    let mut continued = false;
+    // Terminators of the heredocs opened on the last instruction line, in order
+    let mut heredocs: Vec<String> = Vec::new();
+    for line in content.split_inclusive('\n') {
+        if let Some(terminator) = heredocs.first() {
+            if line.trim_start_matches('\t').trim_end() == terminator {
+                heredocs.remove(0);
+            }
+            tokens.push((TokenKind::Heredoc, line));
+            continue;
+        }
+        let body = line.trim_start();
+        let indent = &line[..line.len() - body.len()];
+        if !indent.is_empty() {
+            tokens.push((TokenKind::Text, indent));
+        }
+        // Comments may also sit between continued lines
+        if body.starts_with('#') {
+            tokens.push((TokenKind::Comment, body));
+            continue;
+        }
+        let mut rest = body;
+        if !continued {
+            let word_end = rest.find(|c: char| c.is_whitespace()).unwrap_or(rest.len());
+            let word = &rest[..word_end];
+            if INSTRUCTIONS.iter().any(|i| i.eq_ignore_ascii_case(word)) {
+                tokens.push((TokenKind::Instruction, word));
+                rest = &rest[word_end..];
+            }
+        }
+        let trimmed = rest.trim_end();
+        continued = trimmed.ends_with('\\');
+        let (arguments, end) = if continued {
+            rest.split_at(trimmed.len() - 1)
+        } else {
+            rest.split_at(trimmed.len())
+        };
+        arguments_tokens(arguments, &mut tokens, &mut heredocs);
+        if continued {
+            tokens.push((TokenKind::Continuation, &end[..1]));
+            if end.len() > 1 {
+                tokens.push((TokenKind::Text, &end[1..]));
+            }
+        } else if !end.is_empty() {
+            tokens.push((TokenKind::Text, end));
+        }
+    }
+    tokens
+}
+
+// Variables and heredoc markers within the arguments of an instruction
+fn arguments_tokens<'a>(
+    arguments: &'a str,
+    tokens: &mut Vec<(TokenKind, &'a str)>,
+    heredocs: &mut Vec<String>,
+) {
+    let mut text_start = 0;
+    let mut index = 0;
+    let bytes = arguments.as_bytes();
+    while index < bytes.len() {
+        let special = if arguments[index..].starts_with("<<") {
+            heredoc_marker(&arguments[index..]).map(|(length, terminator)| {
+                heredocs.push(terminator);
+                (TokenKind::Heredoc, length)
+            })
+        } else if bytes[index] == b'$' {
+            variable_length(&arguments[index..]).map(|length| (TokenKind::Variable, length))
+        } else {
+            None
+        };
+        match special {
+            Some((kind, length)) => {
+                if text_start < index {
+                    tokens.push((TokenKind::Text, &arguments[text_start..index]));
+                }
+                tokens.push((kind, &arguments[index..index + length]));
+                index += length;
+                text_start = index;
+            }
+            None => index += arguments[index..].chars().next().map_or(1, char::len_utf8),
+        }
+    }
+    if text_start < arguments.len() {
+        tokens.push((TokenKind::Text, &arguments[text_start..]));
+    }
+}
+
+// Length of a `<<EOF`, `<<-EOF` or quoted `<<"EOF"` marker and its terminator
+fn heredoc_marker(text: &str) -> Option<(usize, String)> {
+    let after = text.strip_prefix("<<")?;
+    let after_dash = after.strip_prefix('-').unwrap_or(after);
+    let (quote, name_start) = match after_dash.chars().next()? {
+        quote @ ('"' | '\'') => (Some(quote), 1),
+        _ => (None, 0),
+    };
+    // Not a shift like `$((1<<2))`
+    if !after_dash[name_start..].starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        return None;
+    }
+    let name_length = after_dash[name_start..]
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .unwrap_or(after_dash.len() - name_start);
+    let name = &after_dash[name_start..name_start + name_length];
+    let mut length = text.len() - after_dash.len() + name_start + name_length;
+    if let Some(quote) = quote {
+        if !after_dash[name_start + name_length..].starts_with(quote) {
+            return None;
+        }
+        length += 1;
+    }
+    Some((length, name.to_string()))
+}
+
+// Length of `$NAME` or `${NAME...}`
+fn variable_length(text: &str) -> Option<usize> {
+    let after = &text[1..];
+    if after.starts_with('{') {
+        return after.find('}').map(|end| end + 2);
+    }
+    let name = after
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .unwrap_or(after.len());
+    (name > 0).then_some(name + 1)
+}
+
+// One highlighted job per line of the file, for the preview
+pub fn highlighted_lines(
+    content: &str,
+    font: FontId,
+    default: Color32,
+    theme: &Theme,
+) -> Vec<LayoutJob> {
+    let mut lines = vec![LayoutJob::default()];
+    for (kind, text) in tokenize(content) {
+        let format = TextFormat::simple(font.clone(), kind.color(theme).unwrap_or(default));
+        for (index, piece) in text.split('\n').enumerate() {
+            if index > 0 {
+                lines.push(LayoutJob::default());
+            }
+            if !piece.is_empty() {
+                lines
+                    .last_mut()
+                    .expect("starts with a line")
+                    .append(piece, 0.0, format.clone());
+            }
+        }
+    }
+    if content.ends_with('\n') {
+        lines.pop();
+    }
+    lines
+}
+
+// An instruction with its continued lines joined and comments left out, heredoc bodies are
+// part of the arguments
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Instruction {
+    // 1-based line the instruction starts on
+    pub line: usize,
+    pub keyword: String,
+    pub arguments: String,
+}
+
+pub fn instructions(content: &str) -> Vec<Instruction> {
+    let mut instructions: Vec<Instruction> = Vec::new();
+    let mut line = 1;
+    // A continuation and the whitespace around it become one space
+    let mut continued = false;
+    for (kind, text) in tokenize(content) {
+        match kind {
+            TokenKind::Instruction => instructions.push(Instruction {
+                line,
+                keyword: text.to_uppercase(),
+                arguments: String::new(),
+            }),
+            TokenKind::Comment => {}
+            TokenKind::Continuation => {
+                if let Some(instruction) = instructions.last_mut() {
+                    let kept = instruction.arguments.trim_end().len();
+                    instruction.arguments.truncate(kept);
+                    instruction.arguments.push(' ');
+                }
+                continued = true;
+            }
+            TokenKind::Heredoc | TokenKind::Variable | TokenKind::Text => {
+                let text = if continued { text.trim_start() } else { text };
+                continued &= text.is_empty();
+                if let Some(instruction) = instructions.last_mut() {
+                    instruction.arguments.push_str(text);
+                }
+            }
+        }
+        line += text.matches('\n').count();
+    }
+    for instruction in &mut instructions {
+        instruction.arguments = instruction.arguments.trim().to_string();
+    }
+    instructions
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Lint {
+    UntaggedBaseImage,
+    AddInsteadOfCopy,
+    AptInstallRecommends,
+    MultipleCmd,
+}
+
+impl Lint {
+    pub fn message(self) -> &'static str {
+        match self {
+            Lint::UntaggedBaseImage => "FROM without a tag",
+            Lint::AddInsteadOfCopy => "ADD where COPY suffices",
+            Lint::AptInstallRecommends => "apt-get install without --no-install-recommends",
+            Lint::MultipleCmd => "More than one CMD",
+        }
+    }
+
+    pub fn explanation(self) -> &'static str {
+        match self {
+            Lint::UntaggedBaseImage => {
+                "Without a tag the image is whatever `latest` points to at build time, pin a \
+                 version like `debian:12` so builds are repeatable."
+            }
+            Lint::AddInsteadOfCopy => {
+                "ADD also fetches URLs and unpacks archives. For plain files COPY does the \
+                 same without the surprises."
+            }
+            Lint::AptInstallRecommends => {
+                "apt-get also installs recommended packages by default, which the image rarely \
+                 needs. --no-install-recommends keeps it smaller."
+            }
+            Lint::MultipleCmd => {
+                "Only the last CMD of a stage takes effect, the earlier ones do nothing."
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LintWarning {
+    pub line: usize,
+    pub lint: Lint,
+}
+
+fn is_archive_or_url(source: &str) -> bool {
+    let source = source.to_lowercase();
+    ["http://", "https://", "git@"]
+        .iter()
+        .any(|prefix| source.starts_with(prefix))
+        || [".tar", ".tar.gz", ".tgz", ".tar.bz2", ".tar.xz", ".txz"]
+            .iter()
+            .any(|suffix| source.ends_with(suffix))
+}
+
+pub fn lint(content: &str) -> Vec<LintWarning> {
+    let mut warnings = Vec::new();
+    let mut stages: Vec<String> = Vec::new();
+    let mut cmd_in_stage = false;
+    for instruction in instructions(content) {
+        let words: Vec<&str> = instruction.arguments.split_whitespace().collect();
+        let operands: Vec<&str> = words
+            .iter()
+            .copied()
+            .filter(|word| !word.starts_with("--"))
+            .collect();
+        let lint = match instruction.keyword.as_str() {
+            "FROM" => {
+                cmd_in_stage = false;
+                let image = operands.first().copied().unwrap_or_default();
+                let name = image.rsplit('/').next().unwrap_or(image);
+                // Earlier stages, scratch, digests and ARG images have no tag to pin
+                let untagged = !image.is_empty()
+                    && !name.contains(':')
+                    && !image.contains('@')
+                    && !image.contains('$')
+                    && !image.eq_ignore_ascii_case("scratch")
+                    && !stages.iter().any(|stage| stage == image);
+                if let Some(alias) = words
+                    .iter()
+                    .position(|word| word.eq_ignore_ascii_case("AS"))
+                    .and_then(|index| words.get(index + 1))
+                {
+                    stages.push(alias.to_string());
+                }
+                untagged.then_some(Lint::UntaggedBaseImage)
+            }
+            "ADD" => {
+                let sources = &operands[..operands.len().saturating_sub(1)];
+                let plain = !sources.is_empty() && !sources.iter().any(|s| is_archive_or_url(s));
+                plain.then_some(Lint::AddInsteadOfCopy)
+            }
+            "RUN" => {
+                let installs = words
+                    .iter()
+                    .skip_while(|word| **word != "apt-get")
+                    .any(|word| *word == "install");
+                let recommends = !words.contains(&"--no-install-recommends");
+                (installs && recommends).then_some(Lint::AptInstallRecommends)
+            }
+            "CMD" => {
+                let repeated = cmd_in_stage;
+                cmd_in_stage = true;
+                repeated.then_some(Lint::MultipleCmd)
+            }
+            _ => None,
+        };
+        if let Some(lint) = lint {
+            warnings.push(LintWarning {
+                line: instruction.line,
+                lint,
+            });
+        }
+    }
+    warnings
+}
@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+
+use bollard::container::RemoveContainerOptions;
+use bollard::network::{CreateNetworkOptions, ListNetworksOptions};
+use bollard::secret::{ContainerSummary, Network};
+use bollard::Docker;
+use tokio::sync::mpsc;
+
+use crate::compose_file::ComposeFile;
+use crate::connection;
+use crate::read_only;
+use crate::utils::{ComposeProject, COMPOSE_PROJECT_LABEL, COMPOSE_SERVICE_LABEL};
+
+pub const COMPOSE_NETWORK_LABEL: &str = "com.docker.compose.network";
+
+// One step of the plan to repair a project left behind by an interrupted compose run
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FixStep {
+    // Created but never started, for a service that is still in the compose file
+    StartContainer {
+        id: String,
+        name: String,
+    },
+    // Container of a service that is no longer in the compose file
+    RemoveOrphan {
+        id: String,
+        name: String,
+    },
+    CreateNetwork {
+        key: String,
+        name: String,
+    },
+    // Network exists under the expected name but was not created by compose for this project
+    RecreateNetwork {
+        id: String,
+        key: String,
+        name: String,
+    },
+}
+
+impl FixStep {
+    pub fn describe(&self) -> String {
+        match self {
+            FixStep::StartContainer { name, .. } => {
+                format!("Start {}, it was created but never started", name)
+            }
+            FixStep::RemoveOrphan { name, .. } => {
+                format!(
+                    "Remove {}, its service is no longer in the compose file",
+                    name
+                )
+            }
+            FixStep::CreateNetwork { name, .. } => format!("Create missing network {}", name),
+            FixStep::RecreateNetwork { name, .. } => {
+                format!(
+                    "Recreate network {}, its labels do not match the project",
+                    name
+                )
+            }
+        }
+    }
+}
+
+fn label<'a>(labels: &'a Option<HashMap<String, String>>, key: &str) -> Option<&'a str> {
+    labels.as_ref()?.get(key).map(String::as_str)
+}
+
+fn container_name(container: &ContainerSummary) -> String {
+    container
+        .names
+        .as_ref()
+        .and_then(|names| names.first())
+        .map(|name| name.trim_start_matches('/').to_string())
+        .unwrap_or_else(|| "Unnamed Container".to_string())
+}
+
+pub fn diagnose(
+    project: &str,
+    compose: &ComposeFile,
+    containers: &[ContainerSummary],
+    networks: &[Network],
+) -> Vec<FixStep> {
+    let mut steps = Vec::new();
+
+    for container in containers {
+        if label(&container.labels, COMPOSE_PROJECT_LABEL) != Some(project) {
+            continue;
+        }
+        let Some(id) = container.id.clone() else {
+            continue;
+        };
+        let name = container_name(container);
+        let in_file = label(&container.labels, COMPOSE_SERVICE_LABEL)
+            .is_some_and(|service| compose.services.contains_key(service));
+
+        if !in_file {
+            steps.push(FixStep::RemoveOrphan { id, name });
+        } else if container.state.as_deref() == Some("created") {
+            steps.push(FixStep::StartContainer { id, name });
+        }
+    }
+
+    for (key, name) in compose.project_networks(project) {
+        match networks
+            .iter()
+            .find(|network| network.name.as_deref() == Some(&name))
+        {
+            None => steps.push(FixStep::CreateNetwork { key, name }),
+            Some(network) => {
+                let matches = label(&network.labels, COMPOSE_PROJECT_LABEL) == Some(project)
+                    && label(&network.labels, COMPOSE_NETWORK_LABEL) == Some(&key);
+                if !matches {
+                    steps.push(FixStep::RecreateNetwork {
+                        id: network.id.clone().unwrap_or_else(|| name.clone()),
+                        key,
+                        name,
+                    });
+                }
+            }
+        }
+    }
+
+    steps
+}
+
+async fn create_project_network(
+    docker: &Docker,
+    project: &str,
+    key: &str,
+    name: &str,
+) -> Result<(), String> {
+    let labels = HashMap::from([
+        (COMPOSE_PROJECT_LABEL.to_string(), project.to_string()),
+        (COMPOSE_NETWORK_LABEL.to_string(), key.to_string()),
+    ]);
+    docker
+        .create_network(CreateNetworkOptions {
+            name: name.to_string(),
+            driver: "bridge".to_string(),
+            labels,
+            ..Default::default()
+        })
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("Failed to create network {}: {}", name, e))
+}
+
+pub async fn execute_step(
+    docker: &Docker,
+    project: &str,
+    step: &FixStep,
+) -> Result<String, String> {
+    read_only::check()?;
+    match step {
+        FixStep::StartContainer { id, name } => docker
+            .start_container::<String>(id, None)
+            .await
+            .map(|_| format!("Started {}", name))
+            .map_err(|e| format!("Failed to start {}: {}", name, e)),
+        FixStep::RemoveOrphan { id, name } => docker
+            .remove_container(
+                id,
+                Some(RemoveContainerOptions {
+                    force: true,
+                    ..Default::default()
+                }),
+            )
+            .await
+            .map(|_| format!("Removed {}", name))
+            .map_err(|e| format!("Failed to remove {}: {}", name, e)),
+        FixStep::CreateNetwork { key, name } => {
+            create_project_network(docker, project, key, name).await?;
+            Ok(format!("Created network {}", name))
+        }
+        FixStep::RecreateNetwork { id, key, name } => {
+            docker
+                .remove_network(id)
+                .await
+                .map_err(|e| format!("Failed to remove network {}: {}", name, e))?;
+            create_project_network(docker, project, key, name).await?;
+            Ok(format!("Recreated network {}", name))
+        }
+    }
+}
+
+pub async fn plan(
+    project: &ComposeProject,
+    containers: &[ContainerSummary],
+) -> Result<Vec<FixStep>, String> {
+    let compose = ComposeFile::load(&project.config_files, &project.working_dir)?;
+    let docker = connection::connect()?;
+    let networks = docker
+        .list_networks(None::<ListNetworksOptions<String>>)
+        .await
+        .map_err(|e| format!("Failed to list networks: {}", e))?;
+    Ok(diagnose(&project.name, &compose, containers, &networks))
+}
+
+// Runs the steps one by one, reporting each result by its index in `steps`
+pub async fn execute_plan(
+    project: String,
+    steps: Vec<(usize, FixStep)>,
+    results: mpsc::UnboundedSender<(usize, Result<String, String>)>,
+) {
+    let docker = match connection::connect() {
+        Ok(docker) => docker,
+        Err(e) => {
+            for (index, _) in steps {
+                let _ = results.send((index, Err(e.to_string())));
+            }
+            return;
+        }
+    };
+    for (index, step) in steps {
+        let result = execute_step(&docker, &project, &step).await;
+        if results.send((index, result)).is_err() {
+            break;
+        }
+    }
+}
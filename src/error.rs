@@ -0,0 +1,42 @@
+use std::fmt::Display;
+
+use thiserror::Error;
+use tokio::sync::mpsc;
+
+// Failures of background work that has no caller to return them to, like a queued removal or
+// a stats stream. They go to the app over an `ErrorSender` and show up as notifications.
+#[derive(Debug, Error)]
+pub enum DockerrsError {
+    #[error("{context}: {message}")]
+    Docker { context: String, message: String },
+    #[error("{context}: {source}")]
+    Io {
+        context: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("Failed to run {program}: {source}")]
+    Spawn {
+        program: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("{context}: {message}")]
+    Parse { context: String, message: String },
+}
+
+impl DockerrsError {
+    pub fn docker(context: impl Into<String>, error: impl Display) -> Self {
+        DockerrsError::Docker {
+            context: context.into(),
+            message: error.to_string(),
+        }
+    }
+}
+
+pub type ErrorSender = mpsc::UnboundedSender<DockerrsError>;
+
+// Nobody is left to tell once the app is gone, so a closed channel drops the error
+pub fn report(errors: &ErrorSender, error: DockerrsError) {
+    let _ = errors.send(error);
+}
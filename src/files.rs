@@ -0,0 +1,438 @@
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use bollard::container::{DownloadFromContainerOptions, LogOutput};
+use bollard::exec::{CreateExecOptions, StartExecResults};
+use futures_util::stream::StreamExt;
+use tokio::sync::mpsc;
+use tokio_util::io::{StreamReader, SyncIoBridge};
+
+use crate::connection;
+use crate::read_only;
+use crate::utils::format_size;
+
+// Bytes between two progress updates of a copy
+const PROGRESS_INTERVAL: usize = 1024 * 1024;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EntryKind {
+    Directory,
+    File,
+    Symlink(String),
+    Other,
+}
+
+#[derive(Clone, Debug)]
+pub struct FileEntry {
+    pub name: String,
+    pub kind: EntryKind,
+    pub permissions: String,
+    pub size: String,
+    pub modified: String,
+}
+
+// Splits off `count` whitespace separated fields and returns them with the untouched rest,
+// so file names with spaces survive
+fn split_fields(line: &str, count: usize) -> Option<(Vec<&str>, &str)> {
+    let mut fields = Vec::with_capacity(count);
+    let mut rest = line;
+    for _ in 0..count {
+        rest = rest.trim_start();
+        let end = rest.find(char::is_whitespace)?;
+        fields.push(&rest[..end]);
+        rest = &rest[end..];
+    }
+    Some((fields, rest.trim_start()))
+}
+
+// Parses `ls -la` output from coreutils and busybox, skipping the total and `.`/`..` lines
+pub fn parse_ls(output: &str) -> Vec<FileEntry> {
+    let mut entries: Vec<FileEntry> = output
+        .lines()
+        .filter_map(|line| {
+            let (mut fields, mut rest) = split_fields(line, 8)?;
+            // Device files show "major, minor" instead of a size, which takes one more field
+            if fields[4].ends_with(',') {
+                (fields, rest) = split_fields(line, 9)?;
+            }
+            let date = fields.len() - 3;
+            let permissions = fields[0];
+            let (kind, name) = match permissions.chars().next()? {
+                'd' => (EntryKind::Directory, rest.to_string()),
+                '-' => (EntryKind::File, rest.to_string()),
+                'l' => match rest.split_once(" -> ") {
+                    Some((name, target)) => {
+                        (EntryKind::Symlink(target.to_string()), name.to_string())
+                    }
+                    None => (EntryKind::Symlink(String::new()), rest.to_string()),
+                },
+                _ => (EntryKind::Other, rest.to_string()),
+            };
+            if name == "." || name == ".." || name.is_empty() {
+                return None;
+            }
+            Some(FileEntry {
+                name,
+                kind,
+                permissions: permissions.to_string(),
+                size: fields[4..date].join(" "),
+                modified: fields[date..].join(" "),
+            })
+        })
+        .collect();
+    // Directories first, like most file managers
+    entries.sort_by(|a, b| {
+        (a.kind != EntryKind::Directory)
+            .cmp(&(b.kind != EntryKind::Directory))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    entries
+}
+
+// Joins a path inside the container, resolving `..` without touching the host filesystem
+pub fn join_path(base: &str, name: &str) -> String {
+    let mut parts: Vec<&str> = if name.starts_with('/') {
+        Vec::new()
+    } else {
+        base.split('/').filter(|part| !part.is_empty()).collect()
+    };
+    for part in name.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            _ => parts.push(part),
+        }
+    }
+    format!("/{}", parts.join("/"))
+}
+
+// Lists a directory with a non-interactive `ls -la` exec, the trailing slash makes symlinked
+// directories list their contents
+pub async fn list_path(container_id: String, path: String) -> Result<Vec<FileEntry>, String> {
+    let docker = connection::connect()?;
+    let target = if path.ends_with('/') {
+        path.clone()
+    } else {
+        format!("{}/", path)
+    };
+    let exec = docker
+        .create_exec(
+            &container_id,
+            CreateExecOptions {
+                cmd: Some(vec!["ls", "-la", target.as_str()]),
+                attach_stdout: Some(true),
+                attach_stderr: Some(true),
+                ..Default::default()
+            },
+        )
+        .await
+        .map_err(|e| format!("Failed to list {}: {}", path, e))?;
+    let started = docker.start_exec(&exec.id, None).await;
+    let mut stdout = String::new();
+    let mut stderr = String::new();
+    match started {
+        Ok(StartExecResults::Attached { mut output, .. }) => {
+            while let Some(message) = output.next().await {
+                match message {
+                    Ok(LogOutput::StdOut { message }) => {
+                        stdout.push_str(&String::from_utf8_lossy(&message))
+                    }
+                    Ok(LogOutput::StdErr { message }) => {
+                        stderr.push_str(&String::from_utf8_lossy(&message))
+                    }
+                    Ok(_) => {}
+                    Err(e) => stderr.push_str(&e.to_string()),
+                }
+            }
+        }
+        Ok(StartExecResults::Detached) => {}
+        Err(e) => stderr.push_str(&e.to_string()),
+    }
+
+    let exit_code = docker
+        .inspect_exec(&exec.id)
+        .await
+        .ok()
+        .and_then(|inspect| inspect.exit_code);
+    if exit_code == Some(0) {
+        return Ok(parse_ls(&stdout));
+    }
+    // 126 and 127 are what the runtime returns when `ls` cannot be started at all
+    let lower = stderr.to_lowercase();
+    if matches!(exit_code, Some(126) | Some(127))
+        || lower.contains("executable file not found")
+        || lower.contains("no such file or directory: unknown")
+    {
+        Err(
+            "This container has no `ls` (a distroless or scratch image?), its files cannot be \
+             listed"
+                .to_string(),
+        )
+    } else if lower.contains("permission denied") {
+        Err(format!("Permission denied: {}", path))
+    } else if lower.contains("not a directory") {
+        Err(format!("{} is not a directory", path))
+    } else if lower.contains("no such file") {
+        Err(format!("{} does not exist", path))
+    } else if lower.contains("is not running") {
+        Err("The container is not running".to_string())
+    } else {
+        Err(format!("Failed to list {}: {}", path, stderr.trim()))
+    }
+}
+
+// Checks and unpacks every entry below `destination`
+fn unpack_checked(tarball: impl Read, destination: &Path) -> Result<usize, String> {
+    let mut archive = tar::Archive::new(tarball);
+    let mut extracted = 0;
+    for entry in archive
+        .entries()
+        .map_err(|e| format!("Failed to read the archive: {}", e))?
+    {
+        let mut entry = entry.map_err(|e| format!("Failed to read the archive: {}", e))?;
+        let path = entry
+            .path()
+            .map_err(|e| format!("Invalid path in the archive: {}", e))?
+            .into_owned();
+        let escapes = path.components().any(|component| {
+            matches!(
+                component,
+                Component::ParentDir | Component::RootDir | Component::Prefix(_)
+            )
+        });
+        let refused = || {
+            format!(
+                "Refusing to extract {:?}, it points outside of the destination",
+                path
+            )
+        };
+        if escapes {
+            return Err(refused());
+        }
+        if !entry
+            .unpack_in(destination)
+            .map_err(|e| format!("Failed to extract {:?}: {}", path, e))?
+        {
+            return Err(refused());
+        }
+        extracted += 1;
+    }
+    Ok(extracted)
+}
+
+// Moves `from` to `to`, merging directories into existing ones like `docker cp` does
+fn move_into(from: &Path, to: &Path) -> std::io::Result<()> {
+    let from_dir = std::fs::symlink_metadata(from)?.is_dir();
+    match std::fs::symlink_metadata(to) {
+        Ok(existing) if from_dir && existing.is_dir() => {
+            for entry in std::fs::read_dir(from)? {
+                let entry = entry?;
+                move_into(&entry.path(), &to.join(entry.file_name()))?;
+            }
+            std::fs::remove_dir(from)
+        }
+        Ok(existing) => {
+            if existing.is_dir() {
+                std::fs::remove_dir_all(to)?;
+            } else {
+                std::fs::remove_file(to)?;
+            }
+            std::fs::rename(from, to)
+        }
+        Err(_) => std::fs::rename(from, to),
+    }
+}
+
+// Extracts a tarball below `destination`, refusing entries that would land outside of it. The
+// entries are unpacked into a staging directory first, so a refused archive leaves nothing.
+pub fn extract_tar(tarball: impl Read, destination: &Path) -> Result<usize, String> {
+    static STAGED: AtomicUsize = AtomicUsize::new(0);
+    let staging = destination.join(format!(
+        ".dockerrs-copy-{}-{}",
+        std::process::id(),
+        STAGED.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::create_dir_all(&staging)
+        .map_err(|e| format!("Failed to create {:?}: {}", staging, e))?;
+    let moved = unpack_checked(tarball, &staging).and_then(|extracted| {
+        for entry in std::fs::read_dir(&staging).map_err(|e| e.to_string())? {
+            let entry = entry.map_err(|e| e.to_string())?;
+            let target = destination.join(entry.file_name());
+            move_into(&entry.path(), &target)
+                .map_err(|e| format!("Failed to move {:?} into place: {}", target, e))?;
+        }
+        Ok(extracted)
+    });
+    let _ = std::fs::remove_dir_all(&staging);
+    moved
+}
+
+// Counts the bytes written through it and reports them every `PROGRESS_INTERVAL`
+struct ProgressWriter<W, F> {
+    inner: W,
+    written: usize,
+    reported: usize,
+    report: F,
+}
+
+impl<W: Write, F: FnMut(usize)> Write for ProgressWriter<W, F> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.written += written;
+        if self.written - self.reported >= PROGRESS_INTERVAL {
+            self.reported = self.written;
+            (self.report)(self.written);
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+// Where a download of `container_path` into the host directory ends up, to check for overwrites
+pub fn download_destination(container_path: &str, host_dir: &Path) -> PathBuf {
+    let name = container_path
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .unwrap_or_default();
+    host_dir.join(name)
+}
+
+// Like `docker cp <container>:<path> <dir>`, progress is reported as bytes received so far. The
+// archive is unpacked while it arrives instead of being held in memory.
+pub async fn copy_from_container(
+    container_id: String,
+    container_path: String,
+    host_dir: PathBuf,
+    progress: mpsc::UnboundedSender<Result<String, String>>,
+) -> Result<String, String> {
+    let docker = connection::connect()?;
+    let received = Arc::new(AtomicUsize::new(0));
+    let counted = received.clone();
+    let mut reported = 0;
+    let path = container_path.clone();
+    let stream = docker
+        .download_from_container(
+            &container_id,
+            Some(DownloadFromContainerOptions {
+                path: container_path.clone(),
+            }),
+        )
+        .map(move |chunk| {
+            let chunk = chunk.map_err(std::io::Error::other)?;
+            let total = counted.fetch_add(chunk.len(), Ordering::Relaxed) + chunk.len();
+            if total - reported >= PROGRESS_INTERVAL {
+                reported = total;
+                let _ = progress.send(Ok(format!(
+                    "Copying {}: {} received",
+                    path,
+                    format_size(total as i64)
+                )));
+            }
+            Ok::<_, std::io::Error>(chunk)
+        });
+    let tarball = SyncIoBridge::new(StreamReader::new(stream));
+
+    let extracted = tokio::task::spawn_blocking(move || {
+        std::fs::create_dir_all(&host_dir)
+            .map_err(|e| format!("Failed to create {:?}: {}", host_dir, e))?;
+        extract_tar(tarball, &host_dir)
+    })
+    .await
+    .map_err(|e| format!("Failed to extract {}: {}", container_path, e))?
+    .map_err(|e| {
+        format!(
+            "Failed to copy {} from the container: {}",
+            container_path, e
+        )
+    })?;
+    Ok(format!(
+        "Copied {} ({} files, {}) to the host",
+        container_path,
+        extracted,
+        format_size(received.load(Ordering::Relaxed) as i64)
+    ))
+}
+
+// Like `docker cp <path> <container>:<dir>`, a file or a whole directory. bollard only uploads
+// an archive held in memory, so the archive is streamed into `docker cp -` instead, pointed at
+// the daemon dockerrs is connected to, and progress is reported as bytes sent so far.
+pub async fn copy_to_container(
+    container_id: String,
+    host_path: PathBuf,
+    container_dir: String,
+    progress: mpsc::UnboundedSender<Result<String, String>>,
+) -> Result<String, String> {
+    read_only::check()?;
+    let name = host_path
+        .file_name()
+        .map(|name| name.to_os_string())
+        .ok_or_else(|| format!("Cannot copy {:?}, it has no file name", host_path))?;
+    let target = connection::target()?;
+    let source = host_path.clone();
+    let destination = container_dir.clone();
+    let sent = tokio::task::spawn_blocking(move || -> Result<usize, String> {
+        let mut child = std::process::Command::new("docker")
+            .env("DOCKER_HOST", target.endpoint.to_string())
+            .args(["cp", "-", &format!("{}:{}", container_id, destination)])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to run docker cp: {}", e))?;
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let writer = ProgressWriter {
+            inner: stdin,
+            written: 0,
+            reported: 0,
+            report: |sent: usize| {
+                let _ = progress.send(Ok(format!(
+                    "Copying {:?}: {} sent",
+                    source,
+                    format_size(sent as i64)
+                )));
+            },
+        };
+        let mut builder = tar::Builder::new(writer);
+        builder.follow_symlinks(false);
+        let appended = if source.is_dir() {
+            builder.append_dir_all(&name, &source)
+        } else {
+            builder.append_path_with_name(&source, &name)
+        };
+        // Closing stdin tells docker cp the archive is complete
+        let written = appended
+            .and_then(|_| builder.into_inner())
+            .map(|writer| writer.written);
+        let output = child
+            .wait_with_output()
+            .map_err(|e| format!("Failed to run docker cp: {}", e))?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+        }
+        written.map_err(|e| format!("Failed to read {:?}: {}", source, e))
+    })
+    .await
+    .map_err(|e| e.to_string())
+    .and_then(|sent| sent)
+    .map_err(|e| {
+        format!(
+            "Failed to copy {:?} into {}: {}",
+            host_path, container_dir, e
+        )
+    })?;
+    Ok(format!(
+        "Copied {:?} ({}) to {}",
+        host_path,
+        format_size(sent as i64),
+        container_dir
+    ))
+}
@@ -0,0 +1,124 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Debug, Write};
+use std::hash::{Hash, Hasher};
+
+use bollard::secret::{ContainerSummary, ImageSummary, Network};
+
+use crate::poller::ContainerSnapshot;
+
+// Cheap summaries of what the poller listed, so an unchanged listing is recognised without
+// keeping a copy of it. Every listing deserializes into new hash maps whose iteration order
+// differs, so maps are hashed sorted by key and never through their Debug output.
+
+// Feeds formatted values into a hasher without building a string
+struct HashWriter<'a>(&'a mut DefaultHasher);
+
+impl Write for HashWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0.write(s.as_bytes());
+        Ok(())
+    }
+}
+
+// For values without maps inside, which do not implement Hash
+fn hash_debug(value: &impl Debug, hasher: &mut DefaultHasher) {
+    let _ = write!(HashWriter(hasher), "{:?}", value);
+}
+
+fn hash_map<V>(
+    map: Option<&HashMap<String, V>>,
+    hasher: &mut DefaultHasher,
+    mut hash_value: impl FnMut(&V, &mut DefaultHasher),
+) {
+    let sorted: BTreeMap<&String, &V> = map.into_iter().flatten().collect();
+    sorted.len().hash(hasher);
+    for (key, value) in sorted {
+        key.hash(hasher);
+        hash_value(value, hasher);
+    }
+}
+
+// Everything about a container the app shows, and the tail of its logs
+fn container_fingerprint(summary: &ContainerSummary, logs: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    summary.id.hash(&mut hasher);
+    summary.names.hash(&mut hasher);
+    summary.image.hash(&mut hasher);
+    summary.image_id.hash(&mut hasher);
+    summary.command.hash(&mut hasher);
+    summary.created.hash(&mut hasher);
+    summary.state.hash(&mut hasher);
+    summary.status.hash(&mut hasher);
+    hash_debug(&summary.ports, &mut hasher);
+    hash_debug(&summary.mounts, &mut hasher);
+    hash_map(summary.labels.as_ref(), &mut hasher, |value, hasher| {
+        value.hash(hasher)
+    });
+    let networks = summary
+        .network_settings
+        .as_ref()
+        .and_then(|settings| settings.networks.as_ref());
+    hash_map(networks, &mut hasher, |endpoint, hasher| {
+        endpoint.network_id.hash(hasher);
+        endpoint.ip_address.hash(hasher);
+        endpoint.ip_prefix_len.hash(hasher);
+        endpoint.gateway.hash(hasher);
+        endpoint.global_ipv6_address.hash(hasher);
+        endpoint.global_ipv6_prefix_len.hash(hasher);
+        endpoint.mac_address.hash(hasher);
+    });
+    logs.hash(&mut hasher);
+    hasher.finish()
+}
+
+// The snapshot is a hash map too, so the containers are combined in an order-independent way
+pub fn snapshot_fingerprint(snapshot: &ContainerSnapshot) -> u64 {
+    snapshot
+        .values()
+        .map(|(summary, logs)| container_fingerprint(summary, logs))
+        .fold(snapshot.len() as u64, u64::wrapping_add)
+}
+
+pub fn images_fingerprint(images: &[ImageSummary]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    images.len().hash(&mut hasher);
+    for image in images {
+        image.id.hash(&mut hasher);
+        image.parent_id.hash(&mut hasher);
+        image.repo_tags.hash(&mut hasher);
+        image.repo_digests.hash(&mut hasher);
+        image.created.hash(&mut hasher);
+        image.size.hash(&mut hasher);
+        image.shared_size.hash(&mut hasher);
+        image.containers.hash(&mut hasher);
+        hash_map(Some(&image.labels), &mut hasher, |value, hasher| {
+            value.hash(hasher)
+        });
+    }
+    hasher.finish()
+}
+
+pub fn networks_fingerprint(networks: &[Network]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    networks.len().hash(&mut hasher);
+    for network in networks {
+        network.id.hash(&mut hasher);
+        network.name.hash(&mut hasher);
+        network.created.hash(&mut hasher);
+        network.driver.hash(&mut hasher);
+        network.scope.hash(&mut hasher);
+        network.internal.hash(&mut hasher);
+        network.attachable.hash(&mut hasher);
+        network.ingress.hash(&mut hasher);
+        network.enable_ipv6.hash(&mut hasher);
+        hash_map(network.labels.as_ref(), &mut hasher, |value, hasher| {
+            value.hash(hasher)
+        });
+        hash_map(network.options.as_ref(), &mut hasher, |value, hasher| {
+            value.hash(hasher)
+        });
+        hash_map(network.containers.as_ref(), &mut hasher, hash_debug);
+    }
+    hasher.finish()
+}
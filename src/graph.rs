@@ -0,0 +1,374 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use bollard::secret::{ContainerSummary, Network};
+use tokio::process::Command;
+
+use crate::utils::compose_project;
+
+// Where the Networks view exports to and the CLI does by default
+pub const DEFAULT_OUTPUT: &str = "dockerrs.dot";
+
+// Quotes a DOT identifier, escaping the characters that would end or break the string
+pub fn escape_id(id: &str) -> String {
+    let mut escaped = String::with_capacity(id.len() + 2);
+    escaped.push('"');
+    for c in id.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn container_label(container: &ContainerSummary) -> String {
+    let name = container
+        .names
+        .as_ref()
+        .and_then(|names| names.first())
+        .map(|name| name.trim_start_matches('/').to_string())
+        .unwrap_or_else(|| "Unnamed Container".to_string());
+
+    let published: Vec<String> = container
+        .ports
+        .iter()
+        .flatten()
+        .filter_map(|port| {
+            let public = port.public_port?;
+            let protocol = port
+                .typ
+                .map(|typ| typ.to_string())
+                .unwrap_or_else(|| "tcp".to_string());
+            Some(format!("{}→{}/{}", public, port.private_port, protocol))
+        })
+        .collect();
+
+    if published.is_empty() {
+        name
+    } else {
+        format!("{}\n{}", name, published.join(", "))
+    }
+}
+
+// Containers without an ID are left out, the rest are grouped by compose project with the
+// containers outside any project first
+fn by_project(containers: &[ContainerSummary]) -> BTreeMap<Option<String>, Vec<&ContainerSummary>> {
+    let mut projects: BTreeMap<Option<String>, Vec<&ContainerSummary>> = BTreeMap::new();
+    for container in containers {
+        if container.id.is_some() {
+            projects
+                .entry(compose_project(container))
+                .or_default()
+                .push(container);
+        }
+    }
+    projects
+}
+
+fn attached_networks(container: &ContainerSummary) -> impl Iterator<Item = &String> {
+    container
+        .network_settings
+        .as_ref()
+        .and_then(|settings| settings.networks.as_ref())
+        .into_iter()
+        .flat_map(|networks| networks.keys())
+}
+
+// Containers become boxes, networks ellipses, and compose projects are drawn as clusters
+pub fn network_topology_dot(networks: &[Network], containers: &[ContainerSummary]) -> String {
+    let mut dot = String::from("graph docker {\n    rankdir=LR;\n    node [shape=box];\n\n");
+
+    for network in networks {
+        let Some(name) = &network.name else {
+            continue;
+        };
+        let label = match &network.driver {
+            Some(driver) => format!("{}\n({})", name, driver),
+            None => name.clone(),
+        };
+        let _ = writeln!(
+            dot,
+            "    {} [label={}, shape=ellipse];",
+            escape_id(&format!("network:{}", name)),
+            escape_id(&label)
+        );
+    }
+    dot.push('\n');
+
+    for (project, members) in &by_project(containers) {
+        let indent = if project.is_some() {
+            "        "
+        } else {
+            "    "
+        };
+        if let Some(project) = project {
+            let _ = writeln!(
+                dot,
+                "    subgraph {} {{\n        label={};",
+                escape_id(&format!("cluster_{}", project)),
+                escape_id(project)
+            );
+        }
+        for container in members {
+            let _ = writeln!(
+                dot,
+                "{}{} [label={}];",
+                indent,
+                escape_id(&format!(
+                    "container:{}",
+                    container.id.as_deref().unwrap_or_default()
+                )),
+                escape_id(&container_label(container))
+            );
+        }
+        if project.is_some() {
+            dot.push_str("    }\n");
+        }
+    }
+    dot.push('\n');
+
+    for container in containers {
+        let Some(id) = &container.id else {
+            continue;
+        };
+        for network in attached_networks(container) {
+            let _ = writeln!(
+                dot,
+                "    {} -- {};",
+                escape_id(&format!("container:{}", id)),
+                escape_id(&format!("network:{}", network))
+            );
+        }
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn rect(svg: &mut String, (x, y, width, height): (f64, f64, f64, f64), style: &str) {
+    let _ = writeln!(
+        svg,
+        "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" {}/>",
+        x, y, width, height, style
+    );
+}
+
+const MARGIN: f64 = 20.0;
+const NODE_WIDTH: f64 = 240.0;
+const NODE_HEIGHT: f64 = 44.0;
+const NODE_GAP: f64 = 12.0;
+const CLUSTER_PADDING: f64 = 12.0;
+const CLUSTER_LABEL: f64 = 22.0;
+const NETWORK_X: f64 = 400.0;
+const NETWORK_WIDTH: f64 = 200.0;
+
+// The same graph as `network_topology_dot` in two layers, for when graphviz is not installed:
+// containers on the left in their project clusters and networks on the right. Each network is
+// placed at the average height of its containers, which keeps most edges from crossing.
+pub fn network_topology_svg(networks: &[Network], containers: &[ContainerSummary]) -> String {
+    let container_x = MARGIN + CLUSTER_PADDING;
+    let mut body = String::new();
+    let mut centers: HashMap<&str, f64> = HashMap::new();
+    let mut y = MARGIN;
+    for (project, members) in &by_project(containers) {
+        let top = y;
+        if project.is_some() {
+            y += CLUSTER_LABEL;
+        }
+        for container in members {
+            let id = container.id.as_deref().unwrap_or_default();
+            centers.insert(id, y + NODE_HEIGHT / 2.0);
+            let label = container_label(container);
+            let mut lines = label.lines();
+            rect(
+                &mut body,
+                (container_x, y, NODE_WIDTH, NODE_HEIGHT),
+                "fill=\"white\" stroke=\"black\"",
+            );
+            let _ = writeln!(
+                body,
+                "  <text x=\"{}\" y=\"{}\">{}</text>",
+                container_x + 8.0,
+                y + 18.0,
+                escape_xml(lines.next().unwrap_or_default())
+            );
+            if let Some(ports) = lines.next() {
+                let _ = writeln!(
+                    body,
+                    "  <text x=\"{}\" y=\"{}\" font-size=\"11\" fill=\"dimgray\">{}</text>",
+                    container_x + 8.0,
+                    y + 35.0,
+                    escape_xml(ports)
+                );
+            }
+            y += NODE_HEIGHT + NODE_GAP;
+        }
+        if let Some(project) = project {
+            y += CLUSTER_PADDING - NODE_GAP;
+            rect(
+                &mut body,
+                (MARGIN, top, NODE_WIDTH + 2.0 * CLUSTER_PADDING, y - top),
+                "fill=\"none\" stroke=\"gray\" stroke-dasharray=\"4\"",
+            );
+            let _ = writeln!(
+                body,
+                "  <text x=\"{}\" y=\"{}\" font-weight=\"bold\">{}</text>",
+                MARGIN + 6.0,
+                top + 16.0,
+                escape_xml(project)
+            );
+            y += NODE_GAP;
+        }
+    }
+
+    // Networks without containers go below the others in their listed order
+    let mut placed: Vec<(Option<f64>, &str, Option<&String>)> = networks
+        .iter()
+        .filter_map(|network| {
+            let name = network.name.as_deref()?;
+            let heights: Vec<f64> = containers
+                .iter()
+                .filter(|container| attached_networks(container).any(|network| network == name))
+                .filter_map(|container| centers.get(container.id.as_deref()?).copied())
+                .collect();
+            let average =
+                (!heights.is_empty()).then(|| heights.iter().sum::<f64>() / heights.len() as f64);
+            Some((average, name, network.driver.as_ref()))
+        })
+        .collect();
+    placed.sort_by(|a, b| match (a.0, b.0) {
+        (Some(a), Some(b)) => a.total_cmp(&b),
+        (a, b) => a.is_none().cmp(&b.is_none()),
+    });
+
+    let mut network_centers: HashMap<&str, f64> = HashMap::new();
+    let mut next_free = MARGIN;
+    for (average, name, driver) in &placed {
+        let center = average
+            .unwrap_or_default()
+            .max(next_free + NODE_HEIGHT / 2.0);
+        next_free = center + NODE_HEIGHT / 2.0 + NODE_GAP;
+        network_centers.insert(name, center);
+        let label = match driver {
+            Some(driver) => format!("{} ({})", name, driver),
+            None => name.to_string(),
+        };
+        let _ = writeln!(
+            body,
+            "  <ellipse cx=\"{}\" cy=\"{}\" rx=\"{}\" ry=\"{}\" fill=\"white\" stroke=\"black\"/>",
+            NETWORK_X + NETWORK_WIDTH / 2.0,
+            center,
+            NETWORK_WIDTH / 2.0,
+            NODE_HEIGHT / 2.0
+        );
+        let _ = writeln!(
+            body,
+            "  <text x=\"{}\" y=\"{}\" text-anchor=\"middle\">{}</text>",
+            NETWORK_X + NETWORK_WIDTH / 2.0,
+            center + 5.0,
+            escape_xml(&label)
+        );
+    }
+
+    let mut edges = String::new();
+    for container in containers {
+        let Some(from) = container.id.as_deref().and_then(|id| centers.get(id)) else {
+            continue;
+        };
+        for network in attached_networks(container) {
+            if let Some(to) = network_centers.get(network.as_str()) {
+                let _ = writeln!(
+                    edges,
+                    "  <line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"gray\"/>",
+                    container_x + NODE_WIDTH,
+                    from,
+                    NETWORK_X,
+                    to
+                );
+            }
+        }
+    }
+
+    let width = NETWORK_X + NETWORK_WIDTH + MARGIN;
+    let height = y.max(next_free) + MARGIN;
+    format!(
+        concat!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" ",
+            "font-family=\"sans-serif\" font-size=\"13\">\n{}{}</svg>\n"
+        ),
+        width, height, edges, body
+    )
+}
+
+// Writes the DOT file and with `svg` an SVG next to it, rendered by graphviz when it is
+// installed and laid out by `network_topology_svg` when it is not
+pub async fn export(
+    output: &Path,
+    networks: &[Network],
+    containers: &[ContainerSummary],
+    svg: bool,
+) -> Result<String, String> {
+    tokio::fs::write(output, network_topology_dot(networks, containers))
+        .await
+        .map_err(|e| format!("Failed to write {:?}: {}", output, e))?;
+    let mut message = format!("Network graph written to {:?}", output);
+    if !svg {
+        return Ok(message);
+    }
+    let svg_path = output.with_extension("svg");
+    if render_svg(output, &svg_path).await? {
+        message.push_str(&format!(" and rendered to {:?}", svg_path));
+    } else {
+        tokio::fs::write(&svg_path, network_topology_svg(networks, containers))
+            .await
+            .map_err(|e| format!("Failed to write {:?}: {}", svg_path, e))?;
+        message.push_str(&format!(" and drawn to {:?} without graphviz", svg_path));
+    }
+    Ok(message)
+}
+
+// False when graphviz is not installed
+async fn render_svg(dot_file: &Path, svg_file: &Path) -> Result<bool, String> {
+    let output = match Command::new("dot")
+        .arg("-Tsvg")
+        .arg("-o")
+        .arg(svg_file)
+        .arg(dot_file)
+        .output()
+        .await
+    {
+        Ok(output) => output,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(format!("Failed to run graphviz 'dot': {}", e)),
+    };
+    if output.status.success() {
+        Ok(true)
+    } else {
+        Err(format!(
+            "graphviz failed with exit code {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
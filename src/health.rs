@@ -0,0 +1,59 @@
+use bollard::container::InspectContainerOptions;
+use bollard::secret::HealthcheckResult;
+use eframe::egui::Color32;
+
+use crate::connection;
+use crate::theme::Theme;
+
+// Health of a container with a HEALTHCHECK, as reported at the end of its status
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Health {
+    Healthy,
+    Unhealthy,
+    Starting,
+}
+
+impl Health {
+    // The list endpoint embeds it in the status, e.g. "Up 3 minutes (healthy)"
+    pub fn from_status(status: &str) -> Option<Self> {
+        if status.ends_with("(healthy)") {
+            Some(Health::Healthy)
+        } else if status.ends_with("(unhealthy)") {
+            Some(Health::Unhealthy)
+        } else if status.ends_with("(health: starting)") {
+            Some(Health::Starting)
+        } else {
+            None
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Health::Healthy => "healthy",
+            Health::Unhealthy => "unhealthy",
+            Health::Starting => "starting",
+        }
+    }
+
+    pub fn color(self, theme: &Theme) -> Color32 {
+        match self {
+            Health::Healthy => theme.success,
+            Health::Unhealthy => theme.error,
+            Health::Starting => theme.warning,
+        }
+    }
+}
+
+// The most recent health probes of a container, oldest first as the daemon keeps them
+pub async fn health_log(container_id: String) -> Result<Vec<HealthcheckResult>, String> {
+    let docker = connection::connect()?;
+    let inspect = docker
+        .inspect_container(&container_id, None::<InspectContainerOptions>)
+        .await
+        .map_err(|e| format!("Failed to inspect container {}: {}", container_id, e))?;
+    Ok(inspect
+        .state
+        .and_then(|state| state.health)
+        .and_then(|health| health.log)
+        .unwrap_or_default())
+}
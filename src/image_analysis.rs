@@ -0,0 +1,316 @@
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Read};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use futures_util::stream::StreamExt;
+use tokio::sync::mpsc;
+
+use crate::connection;
+
+// Biggest files listed per layer in the report
+pub const BIGGEST_FILES: usize = 10;
+
+// Chunks of the save stream buffered ahead of the analysis, the rest waits in the daemon
+const BUFFERED_CHUNKS: usize = 16;
+
+// Whiteout files of the overlay format: `.wh.name` deletes `name` from the layers below,
+// `.wh..wh..opq` in a directory hides everything the layers below put in it
+const WHITEOUT_PREFIX: &str = ".wh.";
+const OPAQUE_WHITEOUT: &str = ".wh..wh..opq";
+
+// Regular files and whiteouts of one layer, in archive order
+#[derive(Clone, Debug, Default)]
+pub struct LayerFiles {
+    pub id: String,
+    pub files: Vec<(String, u64)>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FileEntry {
+    pub path: String,
+    pub size: u64,
+    // Overwritten or deleted by a later layer, so its bytes are shipped for nothing
+    pub wasted: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct LayerReport {
+    pub id: String,
+    pub size: u64,
+    pub wasted: u64,
+    pub biggest_files: Vec<FileEntry>,
+}
+
+#[derive(Clone, Debug)]
+pub struct AnalysisReport {
+    pub image: String,
+    pub layers: Vec<LayerReport>,
+}
+
+impl AnalysisReport {
+    pub fn total_size(&self) -> u64 {
+        self.layers.iter().map(|layer| layer.size).sum()
+    }
+
+    pub fn wasted(&self) -> u64 {
+        self.layers.iter().map(|layer| layer.wasted).sum()
+    }
+
+    // Share of the image's bytes that end up in the final filesystem, 1.0 for an empty image
+    pub fn efficiency(&self) -> f64 {
+        let total = self.total_size();
+
+        if total == 0 {
+            return 1.0;
+        }
+        1.0 - self.wasted() as f64 / total as f64
+    }
+}
+
+fn normalize(path: &str) -> &str {
+    path.trim_start_matches("./")
+        .trim_start_matches('/')
+        .trim_end_matches('/')
+}
+
+// Whether `path` or one of its parent directories is in `removed`
+fn is_removed(path: &str, removed: &HashSet<String>) -> bool {
+    removed.contains(path)
+        || path
+            .match_indices('/')
+            .any(|(index, _)| removed.contains(&path[..index]))
+}
+
+// Splits a whiteout into the path it removes, None for regular files
+fn whiteout_target(path: &str) -> Option<String> {
+    let (parent, name) = match path.rsplit_once('/') {
+        Some((parent, name)) => (Some(parent), name),
+        None => (None, path),
+    };
+    if name == OPAQUE_WHITEOUT {
+        // The directory itself stays, only what is below it is hidden
+        return Some(
+            parent
+                .map(|parent| format!("{}/", parent))
+                .unwrap_or_default(),
+        );
+    }
+    let target = name.strip_prefix(WHITEOUT_PREFIX)?;
+    Some(match parent {
+        Some(parent) => format!("{}/{}", parent, target),
+        None => target.to_string(),
+    })
+}
+
+// Wasted bytes and biggest files of every layer, given the layers bottom first. A file
+// counts as wasted when a later layer writes the same path again or deletes it.
+pub fn build_report(image: String, layers: &[LayerFiles]) -> AnalysisReport {
+    let mut written: HashSet<String> = HashSet::new();
+    // Deleted paths, and opaque directories with a trailing slash
+    let mut removed: HashSet<String> = HashSet::new();
+    let mut reports = Vec::with_capacity(layers.len());
+
+    for layer in layers.iter().rev() {
+        let mut entries = Vec::new();
+        let mut whiteouts = Vec::new();
+        for (path, size) in &layer.files {
+            let path = normalize(path);
+            if let Some(target) = whiteout_target(path) {
+                whiteouts.push(target);
+                continue;
+            }
+            let opaque_parent = path
+                .match_indices('/')
+                .any(|(index, _)| removed.contains(&path[..=index]));
+            let wasted = written.contains(path) || is_removed(path, &removed) || opaque_parent;
+            entries.push(FileEntry {
+                path: path.to_string(),
+                size: *size,
+                wasted,
+            });
+        }
+        written.extend(entries.iter().map(|entry| entry.path.clone()));
+        removed.extend(whiteouts);
+
+        let size = entries.iter().map(|entry| entry.size).sum();
+        let wasted = entries
+            .iter()
+            .filter(|entry| entry.wasted)
+            .map(|entry| entry.size)
+            .sum();
+        entries.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+        entries.truncate(BIGGEST_FILES);
+        reports.push(LayerReport {
+            id: layer.id.clone(),
+            size,
+            wasted,
+            biggest_files: entries,
+        });
+    }
+    reports.reverse();
+    AnalysisReport {
+        image,
+        layers: reports,
+    }
+}
+
+// "abc123.../layer.tar" and "blobs/sha256/abc123..." both become the short digest
+fn layer_id(path: &str) -> String {
+    let digest = path
+        .strip_suffix("/layer.tar")
+        .or_else(|| path.rsplit('/').next())
+        .unwrap_or(path);
+    digest.chars().take(12).collect()
+}
+
+// Regular files and whiteouts of a layer tarball, only the headers are read
+fn list_layer<R: Read>(reader: R, cancelled: &AtomicBool) -> io::Result<Vec<(String, u64)>> {
+    let mut archive = tar::Archive::new(reader);
+    let mut files = Vec::new();
+    for entry in archive.entries()? {
+        if cancelled.load(Ordering::Relaxed) {
+            return Err(io::Error::new(io::ErrorKind::Interrupted, "cancelled"));
+        }
+        let entry = entry?;
+        if entry.header().entry_type().is_file() {
+            let path = entry.path()?.to_string_lossy().into_owned();
+            files.push((path, entry.size()));
+        }
+    }
+    Ok(files)
+}
+
+// The layers of a `docker save` tarball bottom first, in the legacy layout with a
+// layer.tar per directory and the OCI one with blobs. Each entry is read once as it
+// streams past, so only the file lists are kept until manifest.json gives their order.
+pub fn read_layers<R: Read>(
+    reader: R,
+    cancelled: &AtomicBool,
+    progress: impl Fn(String),
+) -> Result<Vec<LayerFiles>, String> {
+    let failed = |e: io::Error| format!("Failed to read the image tarball: {}", e);
+    let mut archive = tar::Archive::new(reader);
+    let mut contents: HashMap<String, Vec<(String, u64)>> = HashMap::new();
+    let mut manifest = Vec::new();
+
+    for entry in archive.entries().map_err(failed)? {
+        if cancelled.load(Ordering::Relaxed) {
+            return Err("Analysis cancelled".to_string());
+        }
+        let mut entry = entry.map_err(failed)?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let path = entry.path().map_err(failed)?.to_string_lossy().into_owned();
+        if path == "manifest.json" {
+            entry.read_to_end(&mut manifest).map_err(failed)?;
+        } else if path.ends_with("/layer.tar") || path.starts_with("blobs/") {
+            // Image configs are blobs too, they are not tarballs and are skipped
+            match list_layer(&mut entry, cancelled) {
+                Ok(files) => {
+                    progress(format!(
+                        "Read {} layers, the last with {} files",
+                        contents.len() + 1,
+                        files.len()
+                    ));
+                    contents.insert(path, files);
+                }
+                Err(_) if cancelled.load(Ordering::Relaxed) => {
+                    return Err("Analysis cancelled".to_string());
+                }
+                Err(_) => {}
+            }
+        }
+    }
+
+    let manifest: serde_json::Value = serde_json::from_slice(&manifest)
+        .map_err(|e| format!("Failed to parse manifest.json: {}", e))?;
+    let layer_paths = manifest[0]["Layers"]
+        .as_array()
+        .ok_or("manifest.json lists no layers")?;
+    layer_paths
+        .iter()
+        .map(|path| {
+            let path = path.as_str().unwrap_or_default();
+            let files = contents
+                .get(path)
+                .cloned()
+                .ok_or_else(|| format!("Layer {} is missing from the tarball", path))?;
+            Ok(LayerFiles {
+                id: layer_id(path),
+                files,
+            })
+        })
+        .collect()
+}
+
+// Blocking reader over chunks sent from the async side
+struct ChannelReader {
+    receiver: mpsc::Receiver<Vec<u8>>,
+    chunk: Vec<u8>,
+    position: usize,
+}
+
+impl Read for ChannelReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.position == self.chunk.len() {
+            match self.receiver.blocking_recv() {
+                Some(chunk) => {
+                    self.chunk = chunk;
+                    self.position = 0;
+                }
+                None => return Ok(0),
+            }
+        }
+        let count = buf.len().min(self.chunk.len() - self.position);
+        buf[..count].copy_from_slice(&self.chunk[self.position..self.position + count]);
+        self.position += count;
+        Ok(count)
+    }
+}
+
+// Streams `docker save` of an image through the analysis without keeping it in memory,
+// setting `cancelled` stops it at the next tar entry
+pub async fn analyze_image(
+    image: String,
+    progress: mpsc::UnboundedSender<String>,
+    cancelled: Arc<AtomicBool>,
+) -> Result<AnalysisReport, String> {
+    let docker = connection::connect()?;
+    let (chunk_sender, receiver) = mpsc::channel(BUFFERED_CHUNKS);
+    let reader = ChannelReader {
+        receiver,
+        chunk: Vec::new(),
+        position: 0,
+    };
+    let analysis = tokio::task::spawn_blocking(move || {
+        read_layers(reader, &cancelled, |message| {
+            let _ = progress.send(message);
+        })
+    });
+
+    let mut stream = docker.export_image(&image);
+    let mut export_error = None;
+    while let Some(chunk) = stream.next().await {
+        match chunk {
+            Ok(chunk) => {
+                // The analysis stopped early, cancelled or on a broken tarball
+                if chunk_sender.send(chunk.to_vec()).await.is_err() {
+                    break;
+                }
+            }
+            Err(e) => {
+                export_error = Some(format!("Failed to export {}: {}", image, e));
+                break;
+            }
+        }
+    }
+    drop(chunk_sender);
+
+    let layers = analysis.await.map_err(|e| e.to_string())?;
+    if let Some(e) = export_error {
+        return Err(e);
+    }
+    Ok(build_report(image, &layers?))
+}
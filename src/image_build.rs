@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use bollard::image::BuildImageOptions;
+use futures_util::stream::StreamExt;
+use globset::{GlobBuilder, GlobMatcher};
+use tokio::sync::mpsc;
+use walkdir::WalkDir;
+
+use crate::connection;
+use crate::read_only;
+
+pub const DEFAULT_MAX_CONTEXT_FILE_MB: u64 = 100;
+
+// Name under which a Dockerfile from outside the build context is added to the tarball
+const EXTERNAL_DOCKERFILE_NAME: &str = ".dockerrs.Dockerfile";
+
+#[derive(Clone, Debug)]
+pub struct BuildSettings {
+    // Shell out to `docker build` instead of talking to the daemon socket
+    pub use_cli: bool,
+    // Files above this size are left out of the build context with a warning
+    pub max_context_file_size: u64,
+}
+
+impl Default for BuildSettings {
+    fn default() -> Self {
+        Self {
+            use_cli: false,
+            max_context_file_size: DEFAULT_MAX_CONTEXT_FILE_MB * 1024 * 1024,
+        }
+    }
+}
+
+// Patterns from `.dockerignore`, later patterns win and `!` re-includes
+pub struct DockerIgnore {
+    patterns: Vec<(GlobMatcher, bool)>,
+}
+
+impl DockerIgnore {
+    pub fn load(context: &Path) -> Result<Self, String> {
+        match std::fs::read_to_string(context.join(".dockerignore")) {
+            Ok(content) => Self::parse(&content),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self {
+                patterns: Vec::new(),
+            }),
+            Err(e) => Err(format!("Failed to read .dockerignore: {}", e)),
+        }
+    }
+
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let mut patterns = Vec::new();
+        for line in content.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (pattern, include) = match line.strip_prefix('!') {
+                Some(pattern) => (pattern.trim(), true),
+                None => (line, false),
+            };
+            let pattern = pattern.trim_start_matches("./").trim_start_matches('/');
+            let pattern = pattern.trim_end_matches('/');
+            if pattern.is_empty() {
+                continue;
+            }
+            let glob = GlobBuilder::new(pattern)
+                .literal_separator(true)
+                .build()
+                .map_err(|e| format!("Invalid .dockerignore pattern '{}': {}", line, e))?;
+            patterns.push((glob.compile_matcher(), include));
+        }
+        Ok(Self { patterns })
+    }
+
+    // A pattern that matches a directory also excludes everything below it
+    pub fn is_ignored(&self, relative: &Path) -> bool {
+        let mut ignored = false;
+        for (matcher, include) in &self.patterns {
+            if relative
+                .ancestors()
+                .any(|path| !path.as_os_str().is_empty() && matcher.is_match(path))
+            {
+                ignored = !include;
+            }
+        }
+        ignored
+    }
+}
+
+// Tars the build context in memory, returns the tarball and the Dockerfile's path inside it
+pub fn tar_context(
+    context: &Path,
+    dockerfile: &Path,
+    max_file_size: u64,
+    output: &mpsc::UnboundedSender<String>,
+) -> Result<(Vec<u8>, String), String> {
+    let ignore = DockerIgnore::load(context)?;
+    let mut builder = tar::Builder::new(Vec::new());
+    builder.follow_symlinks(false);
+
+    for entry in WalkDir::new(context).min_depth(1).sort_by_file_name() {
+        let entry = entry.map_err(|e| format!("Failed to read the build context: {}", e))?;
+        let Ok(relative) = entry.path().strip_prefix(context) else {
+            continue;
+        };
+        if ignore.is_ignored(relative) {
+            continue;
+        }
+        if entry.file_type().is_file() {
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            if size > max_file_size {
+                let _ = output.send(format!(
+                    "Warning: leaving {} out of the build context, {} bytes is above the {} \
+                     byte limit",
+                    relative.display(),
+                    size,
+                    max_file_size
+                ));
+                continue;
+            }
+        }
+        builder
+            .append_path_with_name(entry.path(), relative)
+            .map_err(|e| {
+                format!(
+                    "Failed to add {} to the build context: {}",
+                    relative.display(),
+                    e
+                )
+            })?;
+    }
+
+    // The Dockerfile is always sent, even when it is ignored or lives outside the context
+    let dockerfile_name = match dockerfile.strip_prefix(context) {
+        Ok(relative) if !ignore.is_ignored(relative) => relative.to_string_lossy().to_string(),
+        _ => {
+            builder
+                .append_path_with_name(dockerfile, EXTERNAL_DOCKERFILE_NAME)
+                .map_err(|e| {
+                    format!("Failed to add {:?} to the build context: {}", dockerfile, e)
+                })?;
+            EXTERNAL_DOCKERFILE_NAME.to_string()
+        }
+    };
+
+    let tarball = builder
+        .into_inner()
+        .map_err(|e| format!("Failed to write the build context: {}", e))?;
+    Ok((tarball, dockerfile_name))
+}
+
+// Builds through the daemon API, so only the socket is needed and not the docker CLI
+pub async fn build_image(
+    context: PathBuf,
+    dockerfile: PathBuf,
+    tag: String,
+    args: Vec<(String, String)>,
+    max_file_size: u64,
+    output: mpsc::UnboundedSender<String>,
+) -> Result<String, String> {
+    read_only::check()?;
+    let _ = output.send(format!(
+        "Building {} from {:?} with context {:?}",
+        tag, dockerfile, context
+    ));
+    let tar_output = output.clone();
+    let (tarball, dockerfile_name) = tokio::task::spawn_blocking(move || {
+        tar_context(&context, &dockerfile, max_file_size, &tar_output)
+    })
+    .await
+    .map_err(|e| format!("Failed to tar the build context: {}", e))??;
+    let _ = output.send(format!("Sending {} bytes of build context", tarball.len()));
+
+    let docker = connection::connect()?;
+    let options = BuildImageOptions {
+        dockerfile: dockerfile_name,
+        t: tag.clone(),
+        rm: true,
+        buildargs: args.into_iter().collect::<HashMap<_, _>>(),
+        ..Default::default()
+    };
+
+    // The last `Step n/m : <instruction>` line is the Dockerfile line a failure belongs to
+    let mut current_step = None;
+    let mut stream = docker.build_image(options, None, Some(tarball.into()));
+    while let Some(message) = stream.next().await {
+        let info = message.map_err(|e| format!("docker build of {} failed: {}", tag, e))?;
+        if let Some(text) = &info.stream {
+            for line in text.lines().filter(|line| !line.trim().is_empty()) {
+                if line.starts_with("Step ") {
+                    current_step = Some(line.to_string());
+                }
+                let _ = output.send(line.to_string());
+            }
+        }
+        if let Some(status) = &info.status {
+            let line = match (&info.id, &info.progress) {
+                (Some(id), Some(progress)) => format!("{}: {} {}", id, status, progress),
+                (Some(id), None) => format!("{}: {}", id, status),
+                _ => status.clone(),
+            };
+            let _ = output.send(line);
+        }
+        if let Some(error) = info
+            .error_detail
+            .and_then(|detail| detail.message)
+            .or(info.error)
+        {
+            let _ = output.send(error.clone());
+            return Err(match current_step {
+                Some(step) => format!("docker build of {} failed at {}: {}", tag, step, error),
+                None => format!("docker build of {} failed: {}", tag, error),
+            });
+        }
+    }
+    Ok(format!("Built image {}", tag))
+}
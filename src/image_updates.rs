@@ -0,0 +1,172 @@
+use std::collections::{BTreeSet, HashMap};
+use std::time::{Duration, Instant};
+
+use tokio::sync::mpsc;
+use tokio::time::sleep;
+
+use crate::connection;
+use crate::poller::ContainerSnapshot;
+use crate::registry::{registry_of, stored_credentials};
+
+// A checked image is not asked about again for this long
+pub const CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+// Between two registry requests, Docker Hub limits how often manifests are fetched
+pub const REQUEST_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum UpdateStatus {
+    UpToDate,
+    // The tag points at another manifest in the registry than the one pulled
+    Outdated,
+    // Not checked, with the reason, e.g. a private registry without credentials
+    Skipped(String),
+}
+
+// Image references of running containers, each once. Images referenced by digest or ID
+// cannot fall behind a tag, so they are left out.
+pub fn images_to_check(containers: &ContainerSnapshot) -> BTreeSet<String> {
+    containers
+        .values()
+        .filter(|(summary, _)| summary.state.as_deref() == Some("running"))
+        .filter_map(|(summary, _)| summary.image.clone())
+        .filter(|image| !image.contains('@') && !image.starts_with("sha256:"))
+        .collect()
+}
+
+// Whether the registry digest is none of the digests the local image was pulled with, which
+// look like `nginx@sha256:…`
+pub fn is_outdated(repo_digests: &[String], remote_digest: &str) -> bool {
+    !repo_digests
+        .iter()
+        .any(|digest| digest.rsplit('@').next() == Some(remote_digest))
+}
+
+pub fn outdated_label(containers: usize) -> String {
+    match containers {
+        1 => "1 container running an outdated image".to_string(),
+        count => format!("{} containers running outdated images", count),
+    }
+}
+
+// Compares the local image with the manifest the daemon finds in the registry
+pub async fn check_image(image: &str) -> UpdateStatus {
+    let docker = match connection::connect() {
+        Ok(docker) => docker,
+        Err(e) => return UpdateStatus::Skipped(e.to_string()),
+    };
+    let repo_digests = match docker.inspect_image(image).await {
+        Ok(inspect) => inspect.repo_digests.unwrap_or_default(),
+        Err(e) => return UpdateStatus::Skipped(format!("Failed to inspect: {}", e)),
+    };
+    if repo_digests.is_empty() {
+        return UpdateStatus::Skipped("Built locally, never pulled".to_string());
+    }
+    let credentials = match stored_credentials(registry_of(image)).await {
+        Ok(credentials) => credentials,
+        Err(e) => return UpdateStatus::Skipped(e),
+    };
+    let distribution = match docker.inspect_registry_image(image, credentials).await {
+        Ok(distribution) => distribution,
+        // Private registries without stored credentials end up here
+        Err(e) => return UpdateStatus::Skipped(format!("Registry not reachable: {}", e)),
+    };
+    match distribution.descriptor.digest {
+        Some(digest) if is_outdated(&repo_digests, &digest) => UpdateStatus::Outdated,
+        Some(_) => UpdateStatus::UpToDate,
+        None => UpdateStatus::Skipped("The registry sent no digest".to_string()),
+    }
+}
+
+// Results of the image update check by image reference, checked one image at a time in the
+// background. Only started by U or --check-updates, never on its own.
+#[derive(Default)]
+pub struct ImageUpdates {
+    pub results: HashMap<String, (Instant, UpdateStatus)>,
+    pub pending: Option<mpsc::UnboundedReceiver<(String, UpdateStatus)>>,
+}
+
+impl ImageUpdates {
+    // Images without a result younger than `CACHE_TTL`
+    pub fn stale(&self, images: BTreeSet<String>, now: Instant) -> Vec<String> {
+        images
+            .into_iter()
+            .filter(|image| {
+                self.results
+                    .get(image)
+                    .is_none_or(|(at, _)| now.saturating_duration_since(*at) >= CACHE_TTL)
+            })
+            .collect()
+    }
+
+    // Checks the stale images of the running containers, returns how many
+    pub fn start(&mut self, containers: &ContainerSnapshot, now: Instant) -> usize {
+        if self.pending.is_some() {
+            return 0;
+        }
+        let images = self.stale(images_to_check(containers), now);
+        if images.is_empty() {
+            return 0;
+        }
+        let count = images.len();
+        let (sender, receiver) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            for (index, image) in images.into_iter().enumerate() {
+                if index > 0 {
+                    sleep(REQUEST_INTERVAL).await;
+                }
+                let status = check_image(&image).await;
+                if sender.send((image, status)).is_err() {
+                    return;
+                }
+            }
+        });
+        self.pending = Some(receiver);
+        count
+    }
+
+    pub fn receive(&mut self, now: Instant) {
+        let Some(pending) = &mut self.pending else {
+            return;
+        };
+        loop {
+            match pending.try_recv() {
+                Ok((image, status)) => {
+                    self.results.insert(image, (now, status));
+                }
+                Err(mpsc::error::TryRecvError::Empty) => return,
+                Err(mpsc::error::TryRecvError::Disconnected) => {
+                    self.pending = None;
+                    return;
+                }
+            }
+        }
+    }
+
+    pub fn status(&self, image: &str) -> Option<&UpdateStatus> {
+        self.results.get(image).map(|(_, status)| status)
+    }
+
+    // Running containers whose image is known to be outdated
+    pub fn outdated_containers(&self, containers: &ContainerSnapshot) -> usize {
+        containers
+            .values()
+            .filter(|(summary, _)| summary.state.as_deref() == Some("running"))
+            .filter_map(|(summary, _)| summary.image.as_deref())
+            .filter(|image| self.status(image) == Some(&UpdateStatus::Outdated))
+            .count()
+    }
+
+    // Images that were not checked, with the reason, for the status bar tooltip
+    pub fn skipped(&self) -> Vec<String> {
+        let mut skipped: Vec<String> = self
+            .results
+            .iter()
+            .filter_map(|(image, (_, status))| match status {
+                UpdateStatus::Skipped(reason) => Some(format!("{}: {}", image, reason)),
+                _ => None,
+            })
+            .collect();
+        skipped.sort();
+        skipped
+    }
+}
@@ -0,0 +1,298 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+// A variable that had no value, or whose `:?` or `?` check failed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Missing {
+    pub name: String,
+    // The message of a failed `${VAR:?message}`, or why the reference is invalid
+    pub error: Option<String>,
+}
+
+impl Missing {
+    pub fn describe(&self) -> String {
+        match &self.error {
+            Some(error) => error.clone(),
+            None => format!("{} is not set", self.name),
+        }
+    }
+}
+
+// A stretch of an interpolated line
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Piece {
+    Text(String),
+    // A reference that could not be resolved, kept as written
+    Unresolved {
+        original: String,
+        missing: Vec<Missing>,
+    },
+}
+
+// Each line of `text` with its variables substituted the way compose does it:
+// `$VAR` and `${VAR}`, `${VAR:-default}` and `${VAR-default}` for defaults,
+// `${VAR:?error}` and `${VAR?error}` for required variables, `${VAR:+other}` and
+// `${VAR+other}` for alternatives, nested references in defaults and `$$` for a plain `$`.
+// The colon forms treat an empty variable as unset.
+pub fn interpolate(text: &str, lookup: &impl Fn(&str) -> Option<String>) -> Vec<Vec<Piece>> {
+    text.lines()
+        .map(|line| interpolate_line(line, lookup))
+        .collect()
+}
+
+pub fn interpolate_line(line: &str, lookup: &impl Fn(&str) -> Option<String>) -> Vec<Piece> {
+    let mut pieces = Vec::new();
+    let mut text = String::new();
+    let mut rest = line;
+    while let Some(start) = rest.find('$') {
+        text.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some((reference, length)) = next_reference(rest) else {
+            // A `$` that starts nothing stays as it is
+            text.push('$');
+            rest = &rest[1..];
+            continue;
+        };
+        let original = &rest[..length];
+        rest = &rest[length..];
+        let mut missing = Vec::new();
+        let value = resolve(reference, lookup, &mut missing);
+        if missing.is_empty() {
+            text.push_str(&value);
+        } else {
+            if !text.is_empty() {
+                pieces.push(Piece::Text(std::mem::take(&mut text)));
+            }
+            pieces.push(Piece::Unresolved {
+                original: original.to_string(),
+                missing,
+            });
+        }
+    }
+    text.push_str(rest);
+    if !text.is_empty() {
+        pieces.push(Piece::Text(text));
+    }
+    pieces
+}
+
+// Every missing variable of the interpolated text, in order
+pub fn missing(lines: &[Vec<Piece>]) -> Vec<&Missing> {
+    lines
+        .iter()
+        .flatten()
+        .flat_map(|piece| match piece {
+            Piece::Unresolved { missing, .. } => missing.iter().collect(),
+            Piece::Text(_) => Vec::new(),
+        })
+        .collect()
+}
+
+enum Reference<'a> {
+    // `$$`
+    Dollar,
+    Name(&'a str),
+    // `${...}` without its braces
+    Braced(&'a str),
+    // An unterminated `${` or one without a valid name
+    Invalid,
+}
+
+// The reference `text` starts with and its length, `text` starts with `$`
+fn next_reference(text: &str) -> Option<(Reference<'_>, usize)> {
+    let after = &text[1..];
+    if after.starts_with('$') {
+        return Some((Reference::Dollar, 2));
+    }
+    if let Some(inner) = after.strip_prefix('{') {
+        return Some(match closing_brace(inner) {
+            Some(end) => (Reference::Braced(&inner[..end]), end + 3),
+            None => (Reference::Invalid, text.len()),
+        });
+    }
+    let name = name_length(after);
+    (name > 0).then(|| (Reference::Name(&after[..name]), name + 1))
+}
+
+// Where the `}` matching an opened `${` is, counting the `${` nested in defaults
+fn closing_brace(inner: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut chars = inner.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '$' if chars.peek().is_some_and(|(_, next)| *next == '{') => {
+                chars.next();
+                depth += 1;
+            }
+            '}' if depth == 0 => return Some(index),
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+fn name_length(text: &str) -> usize {
+    let mut chars = text.char_indices();
+    match chars.next() {
+        Some((_, c)) if c.is_ascii_alphabetic() || c == '_' => {}
+        _ => return 0,
+    }
+    chars
+        .find(|(_, c)| !c.is_ascii_alphanumeric() && *c != '_')
+        .map_or(text.len(), |(index, _)| index)
+}
+
+fn resolve(
+    reference: Reference<'_>,
+    lookup: &impl Fn(&str) -> Option<String>,
+    missing: &mut Vec<Missing>,
+) -> String {
+    let braced = match reference {
+        Reference::Dollar => return "$".to_string(),
+        Reference::Name(name) => return value_or_missing(name, lookup, missing),
+        Reference::Invalid => {
+            missing.push(Missing {
+                name: String::new(),
+                error: Some("Unterminated ${".to_string()),
+            });
+            return String::new();
+        }
+        Reference::Braced(braced) => braced,
+    };
+    let length = name_length(braced);
+    let (name, operation) = braced.split_at(length);
+    if name.is_empty() {
+        missing.push(Missing {
+            name: braced.to_string(),
+            error: Some(format!("Invalid interpolation format ${{{}}}", braced)),
+        });
+        return String::new();
+    }
+    let (empty_is_unset, operation) = match operation.strip_prefix(':') {
+        Some(operation) => (true, operation),
+        None => (false, operation),
+    };
+    let value = lookup(name).filter(|value| !(empty_is_unset && value.is_empty()));
+    let invalid = |missing: &mut Vec<Missing>| {
+        missing.push(Missing {
+            name: name.to_string(),
+            error: Some(format!("Invalid interpolation format ${{{}}}", braced)),
+        });
+        String::new()
+    };
+    let Some(operator) = operation.chars().next() else {
+        // `${VAR:}` is not a reference compose knows
+        return if empty_is_unset {
+            invalid(missing)
+        } else {
+            value_or_missing(name, lookup, missing)
+        };
+    };
+    let argument = &operation[operator.len_utf8()..];
+    match (operator, value) {
+        ('-', Some(value)) => value,
+        ('-', None) => substitute(argument, lookup, missing),
+        ('?', Some(value)) => value,
+        ('?', None) => {
+            let message = substitute(argument, lookup, missing);
+            missing.push(Missing {
+                name: name.to_string(),
+                error: Some(if message.is_empty() {
+                    format!("{} is required", name)
+                } else {
+                    message
+                }),
+            });
+            String::new()
+        }
+        ('+', Some(_)) => substitute(argument, lookup, missing),
+        ('+', None) => String::new(),
+        _ => invalid(missing),
+    }
+}
+
+// Like compose, an unset variable without a default becomes an empty string, which is
+// rarely what was meant
+fn value_or_missing(
+    name: &str,
+    lookup: &impl Fn(&str) -> Option<String>,
+    missing: &mut Vec<Missing>,
+) -> String {
+    lookup(name).unwrap_or_else(|| {
+        missing.push(Missing {
+            name: name.to_string(),
+            error: None,
+        });
+        String::new()
+    })
+}
+
+// Substitutes every reference in a default or alternative
+fn substitute(
+    text: &str,
+    lookup: &impl Fn(&str) -> Option<String>,
+    missing: &mut Vec<Missing>,
+) -> String {
+    let mut result = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('$') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        match next_reference(rest) {
+            Some((reference, length)) => {
+                result.push_str(&resolve(reference, lookup, missing));
+                rest = &rest[length..];
+            }
+            None => {
+                result.push('$');
+                rest = &rest[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+// Variables of a `.env` file: `KEY=value` lines, optionally with `export` in front and the
+// value in single or double quotes. Comments and lines without `=` are skipped.
+pub fn parse_env_file(content: &str) -> HashMap<String, String> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            if line.starts_with('#') {
+                return None;
+            }
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (key, value) = line.split_once('=')?;
+            let key = key.trim();
+            if key.is_empty() {
+                return None;
+            }
+            let value = value.trim();
+            let value = match value.chars().next() {
+                Some(quote @ ('"' | '\'')) if value.len() > 1 && value.ends_with(quote) => {
+                    value[1..value.len() - 1].to_string()
+                }
+                // An unquoted value ends at a comment
+                _ => value
+                    .split_once(" #")
+                    .map_or(value, |(value, _)| value.trim_end())
+                    .to_string(),
+            };
+            Some((key.to_string(), value))
+        })
+        .collect()
+}
+
+// What compose substitutes for the file: the `.env` next to it, overridden by the
+// environment dockerrs runs in. A missing `.env` is no error.
+pub fn compose_variables(compose_path: &Path) -> HashMap<String, String> {
+    let env_file = compose_path.parent().unwrap_or(Path::new(".")).join(".env");
+    let mut variables = std::fs::read_to_string(env_file)
+        .map(|content| parse_env_file(&content))
+        .unwrap_or_default();
+    variables.extend(std::env::vars());
+    variables
+}
@@ -0,0 +1,156 @@
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use crate::alerts::{exit_code, watch_name};
+use crate::poller::ContainerSnapshot;
+
+// Wait before each automatic restart, it gives up once they are used up
+pub const RESTART_DELAYS: [Duration; 5] = [
+    Duration::from_secs(1),
+    Duration::from_secs(5),
+    Duration::from_secs(30),
+    Duration::from_secs(30),
+    Duration::from_secs(30),
+];
+
+// Running this long after a restart counts as recovered, the next crash starts over
+pub const STABLE_AFTER: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum KeepAliveEvent {
+    Restart {
+        id: String,
+        name: String,
+        attempt: usize,
+    },
+    GaveUp {
+        name: String,
+    },
+}
+
+#[derive(Default)]
+struct Restarts {
+    attempts: usize,
+    // Container ID and when to start it
+    scheduled: Option<(String, Instant)>,
+    last: Option<Instant>,
+    gave_up: bool,
+}
+
+// Restarts kept-alive containers that exit with a non-zero code. Containers are kept by
+// name so the setting survives them being recreated.
+#[derive(Default)]
+pub struct KeepAlive {
+    pub names: HashSet<String>,
+    // Stopped or killed by hand, left alone until they run again
+    pub suspended: HashSet<String>,
+    restarts: HashMap<String, Restarts>,
+}
+
+impl KeepAlive {
+    pub fn new(names: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            names: names.into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
+    // Returns whether the container is kept alive now
+    pub fn toggle(&mut self, name: &str) -> bool {
+        self.restarts.remove(name);
+        self.suspended.remove(name);
+        if self.names.remove(name) {
+            false
+        } else {
+            self.names.insert(name.to_string());
+            true
+        }
+    }
+
+    pub fn is_kept(&self, name: &str) -> bool {
+        self.names.contains(name)
+    }
+
+    pub fn suspend(&mut self, name: &str) {
+        if self.names.contains(name) {
+            self.suspended.insert(name.to_string());
+            self.restarts.remove(name);
+        }
+    }
+
+    // Schedules restarts for kept containers that crashed, and forgets those that recovered
+    pub fn observe(&mut self, snapshot: &ContainerSnapshot, now: Instant) -> Vec<KeepAliveEvent> {
+        let mut events = Vec::new();
+        if self.names.is_empty() {
+            return events;
+        }
+        for (id, (summary, _)) in snapshot {
+            let Some(name) = watch_name(snapshot, id).filter(|name| self.names.contains(name))
+            else {
+                continue;
+            };
+            match summary.state.as_deref() {
+                Some("running") => {
+                    self.suspended.remove(&name);
+                    if let Some(restarts) = self.restarts.get_mut(&name) {
+                        restarts.scheduled = None;
+                        if restarts
+                            .last
+                            .is_none_or(|last| now.saturating_duration_since(last) >= STABLE_AFTER)
+                        {
+                            self.restarts.remove(&name);
+                        }
+                    }
+                }
+                Some("exited") if !self.suspended.contains(&name) => {
+                    let status = summary.status.as_deref().unwrap_or_default();
+                    if exit_code(status).is_none_or(|code| code == 0) {
+                        continue;
+                    }
+                    let restarts = self.restarts.entry(name.clone()).or_default();
+                    if restarts.scheduled.is_some() || restarts.gave_up {
+                        continue;
+                    }
+                    match RESTART_DELAYS.get(restarts.attempts) {
+                        Some(delay) => restarts.scheduled = Some((id.clone(), now + *delay)),
+                        None => {
+                            restarts.gave_up = true;
+                            events.push(KeepAliveEvent::GaveUp { name });
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        events
+    }
+
+    // Restarts whose delay is over
+    pub fn due(&mut self, now: Instant) -> Vec<KeepAliveEvent> {
+        let mut events = Vec::new();
+        for (name, restarts) in &mut self.restarts {
+            let Some((id, at)) = restarts.scheduled.take() else {
+                continue;
+            };
+            if at > now {
+                restarts.scheduled = Some((id, at));
+                continue;
+            }
+            restarts.attempts += 1;
+            restarts.last = Some(now);
+            events.push(KeepAliveEvent::Restart {
+                id,
+                name: name.clone(),
+                attempt: restarts.attempts,
+            });
+        }
+        events
+    }
+
+    // Whether a restart is waiting for its delay, the UI redraws until it is due
+    pub fn is_waiting(&self) -> bool {
+        self.restarts
+            .values()
+            .any(|restarts| restarts.scheduled.is_some())
+    }
+}
@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LabelCondition {
+    Exists(String),
+    Equals(String, String),
+    NotEquals(String, String),
+}
+
+impl LabelCondition {
+    fn holds(&self, labels: &HashMap<String, String>) -> bool {
+        match self {
+            LabelCondition::Exists(key) => labels.contains_key(key),
+            LabelCondition::Equals(key, value) => labels.get(key) == Some(value),
+            LabelCondition::NotEquals(key, value) => labels.get(key) != Some(value),
+        }
+    }
+}
+
+impl fmt::Display for LabelCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LabelCondition::Exists(key) => write!(f, "{}", key),
+            LabelCondition::Equals(key, value) => write!(f, "{}={}", key, value),
+            LabelCondition::NotEquals(key, value) => write!(f, "{}!={}", key, value),
+        }
+    }
+}
+
+// Label conditions that must all hold, written like `env=staging,tier!=db,traefik.enable`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LabelFilter {
+    pub conditions: Vec<LabelCondition>,
+}
+
+impl LabelFilter {
+    // An empty expression matches every container
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        if expression.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        let conditions = expression
+            .split(',')
+            .map(|part| {
+                let part = part.trim();
+                let condition = if let Some((key, value)) = part.split_once("!=") {
+                    LabelCondition::NotEquals(key.trim().to_string(), value.trim().to_string())
+                } else if let Some((key, value)) = part.split_once('=') {
+                    LabelCondition::Equals(key.trim().to_string(), value.trim().to_string())
+                } else {
+                    LabelCondition::Exists(part.to_string())
+                };
+                let key = match &condition {
+                    LabelCondition::Exists(key)
+                    | LabelCondition::Equals(key, _)
+                    | LabelCondition::NotEquals(key, _) => key,
+                };
+                if key.is_empty() {
+                    Err(format!("Missing label key in {:?}", part))
+                } else if key.contains(|c: char| c.is_whitespace() || c == '!' || c == '=') {
+                    Err(format!("Invalid label key {:?}", key))
+                } else {
+                    Ok(condition)
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { conditions })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.conditions.is_empty()
+    }
+
+    // The conditions the daemon can check, as the `filters` of a container list. It has
+    // no negation for labels, so `!=` is left to `matches`.
+    pub fn daemon_filters(&self) -> HashMap<String, Vec<String>> {
+        let labels: Vec<String> = self
+            .conditions
+            .iter()
+            .filter(|condition| !matches!(condition, LabelCondition::NotEquals(..)))
+            .map(ToString::to_string)
+            .collect();
+        if labels.is_empty() {
+            HashMap::new()
+        } else {
+            HashMap::from([("label".to_string(), labels)])
+        }
+    }
+
+    pub fn matches(&self, labels: Option<&HashMap<String, String>>) -> bool {
+        let empty = HashMap::new();
+        let labels = labels.unwrap_or(&empty);
+        self.conditions
+            .iter()
+            .all(|condition| condition.holds(labels))
+    }
+}
+
+impl fmt::Display for LabelFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let conditions: Vec<String> = self.conditions.iter().map(ToString::to_string).collect();
+        write!(f, "{}", conditions.join(","))
+    }
+}
@@ -0,0 +1,126 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+// Windows narrower than this, in points, get the compact layout
+pub const NARROW_WIDTH: f32 = 900.0;
+// Room the container name and the Logs button always get in a row
+pub const NAME_WIDTH: f32 = 180.0;
+pub const LOGS_BUTTON_WIDTH: f32 = 50.0;
+// Between two columns of a row
+pub const COLUMN_SPACING: f32 = 8.0;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LayoutMode {
+    #[default]
+    Wide,
+    // Details take over the containers view and the status bar drops its hints
+    Narrow,
+}
+
+impl LayoutMode {
+    pub fn for_width(width: f32) -> Self {
+        if width < NARROW_WIDTH {
+            LayoutMode::Narrow
+        } else {
+            LayoutMode::Wide
+        }
+    }
+}
+
+// Optional columns of a container row, shown in this order when there is room
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RowColumn {
+    Service,
+    Health,
+    Image,
+    Created,
+    Ports,
+    Id,
+}
+
+pub const ALL_COLUMNS: [RowColumn; 6] = [
+    RowColumn::Service,
+    RowColumn::Health,
+    RowColumn::Image,
+    RowColumn::Created,
+    RowColumn::Ports,
+    RowColumn::Id,
+];
+
+// Which columns stay when the window gets narrow, the last ones go first
+pub const DEFAULT_COLUMN_PRIORITY: [RowColumn; 6] = [
+    RowColumn::Health,
+    RowColumn::Service,
+    RowColumn::Image,
+    RowColumn::Created,
+    RowColumn::Ports,
+    RowColumn::Id,
+];
+
+impl RowColumn {
+    // Room the column needs, enough for typical values
+    pub fn width(self) -> f32 {
+        match self {
+            RowColumn::Service => 140.0,
+            RowColumn::Health => 80.0,
+            RowColumn::Image => 160.0,
+            RowColumn::Created => 110.0,
+            RowColumn::Ports => 180.0,
+            RowColumn::Id => 100.0,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            RowColumn::Service => "Service",
+            RowColumn::Health => "Health",
+            RowColumn::Image => "Image",
+            RowColumn::Created => "Created",
+            RowColumn::Ports => "Ports",
+            RowColumn::Id => "ID",
+        }
+    }
+
+    // The created time is hidden until turned on in the column chooser
+    pub fn shown_by_default(self) -> bool {
+        self != RowColumn::Created
+    }
+}
+
+// Columns of `shown` that fit next to the name within `available` points, taken in
+// `priority` order and returned in display order. A column that does not fit does not stop
+// a narrower one after it.
+pub fn visible_columns(
+    available: f32,
+    priority: &[RowColumn],
+    shown: &BTreeSet<RowColumn>,
+) -> Vec<RowColumn> {
+    let mut remaining = available - NAME_WIDTH - LOGS_BUTTON_WIDTH;
+    let mut columns = Vec::new();
+    for &column in priority {
+        let needed = column.width() + COLUMN_SPACING;
+        if shown.contains(&column) && !columns.contains(&column) && needed <= remaining {
+            remaining -= needed;
+            columns.push(column);
+        }
+    }
+    columns.sort();
+    columns
+}
+
+// The status bar text for a queued action that can still be undone
+pub fn undo_hint(label: &str, seconds: u64, mode: LayoutMode) -> String {
+    match mode {
+        LayoutMode::Wide => format!("{} in {}s (Ctrl+Z to undo)", label, seconds),
+        LayoutMode::Narrow => format!("{} {}s", label, seconds),
+    }
+}
+
+pub fn alerts_label(count: usize, mode: LayoutMode) -> String {
+    match mode {
+        LayoutMode::Wide => format!("Alerts ({})", count),
+        LayoutMode::Narrow => format!("⚠ {}", count),
+    }
+}
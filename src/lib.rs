@@ -0,0 +1,65 @@
+// The app, the Docker poller and the helpers behind them, so they can be embedded and tested
+// without going through the binary
+pub mod accessibility;
+pub mod action_queue;
+pub mod alerts;
+pub mod attach;
+pub mod audit;
+pub mod builds;
+pub mod capabilities;
+pub mod cli;
+pub mod commands;
+pub mod compose_file;
+pub mod compose_logs;
+pub mod config;
+pub mod connection;
+pub mod container_counts;
+pub mod container_diff;
+pub mod container_names;
+pub mod container_order;
+pub mod details;
+pub mod diagnostics;
+pub mod disk_usage;
+pub mod docker_api;
+pub mod docker_viewer_app;
+pub mod dockerfile;
+pub mod doctor;
+pub mod error;
+pub mod files;
+pub mod fingerprint;
+pub mod graph;
+pub mod health;
+pub mod image_analysis;
+pub mod image_build;
+pub mod image_updates;
+pub mod interpolation;
+pub mod keep_alive;
+pub mod label_filter;
+pub mod layout;
+pub mod log_layout;
+pub mod log_level;
+pub mod log_range;
+pub mod log_selection;
+pub mod log_stream;
+pub mod networks;
+pub mod notifications;
+pub mod poller;
+pub mod port_conflicts;
+pub mod ps;
+pub mod read_only;
+pub mod registry;
+pub mod resources;
+pub mod restarts;
+pub mod scan;
+pub mod signals;
+pub mod split_view;
+pub mod stats;
+pub mod system_info;
+pub mod tarballs;
+pub mod theme;
+pub mod time_format;
+pub mod top;
+pub mod ui_state;
+pub mod unused_images;
+pub mod utils;
+pub mod volumes;
@@ -0,0 +1,112 @@
+use eframe::egui::{Color32, Vec2};
+
+use crate::theme::Theme;
+
+pub const MAX_PREFIX_WIDTH: usize = 24;
+pub const PREFIX_SEPARATOR: &str = " │ ";
+// Longer lines, usually JSON logs, are cut off in the logs panel and shown whole on hover
+pub const MAX_LINE_CHARS: usize = 500;
+
+// Width of the prefix column for the selected containers, capped at `max`
+pub fn prefix_width<'a>(names: impl IntoIterator<Item = &'a str>, max: usize) -> usize {
+    names
+        .into_iter()
+        .map(|name| name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .min(max)
+}
+
+// Right pads the name to `width`, truncating with an ellipsis when it does not fit
+pub fn format_prefix(name: &str, width: usize) -> String {
+    let length = name.chars().count();
+    if length <= width {
+        format!("{}{}", name, " ".repeat(width - length))
+    } else if width == 0 {
+        String::new()
+    } else {
+        let truncated: String = name.chars().take(width - 1).collect();
+        format!("{}…", truncated)
+    }
+}
+
+// FNV-1a, so a container keeps its color across sessions and Rust versions
+pub fn prefix_color(name: &str, theme: &Theme) -> Color32 {
+    let hash = name.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    });
+    theme.prefixes[(hash % theme.prefixes.len() as u64) as usize]
+}
+
+// Splits a message into rows of at most `columns - indent` characters, where `indent` is the
+// prefix plus separator width, so continuation rows line up under the message
+pub fn wrap_message(message: &str, prefix_width: usize, columns: usize) -> Vec<String> {
+    let indent = prefix_width + PREFIX_SEPARATOR.chars().count();
+    let available = columns.saturating_sub(indent).max(1);
+
+    let chars: Vec<char> = message.chars().collect();
+    if chars.is_empty() {
+        return vec![String::new()];
+    }
+    chars
+        .chunks(available)
+        .enumerate()
+        .map(|(index, chunk)| {
+            let row: String = chunk.iter().collect();
+            if index == 0 {
+                row
+            } else {
+                format!("{}{}", " ".repeat(indent), row)
+            }
+        })
+        .collect()
+}
+
+// Cuts a line to `max` characters with an ellipsis, None when it already fits
+pub fn truncate_line(line: &str, max: usize) -> Option<String> {
+    let mut chars = line.char_indices();
+    let (end, _) = chars.nth(max)?;
+    Some(format!("{}…", &line[..end]))
+}
+
+// Characters Left and Right move unwrapped logs by
+pub const H_SCROLL_STEP: usize = 8;
+
+// Scrolling the logs panel with the keyboard
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogScroll {
+    Left,
+    Right,
+    PageUp,
+    PageDown,
+}
+
+// The offset of the logs after a key press, in points like egui's scroll offsets. Wrapped
+// lines take as many rows as they wrap onto and the offset counts those rows, so a page is
+// always the visible height whether or not the logs wrap. One row of the previous page stays
+// in view to keep the place. `max` is the content size minus the viewport, which leaves no
+// horizontal room while the logs wrap.
+pub fn scroll_offset(
+    scroll: LogScroll,
+    offset: Vec2,
+    viewport: Vec2,
+    max: Vec2,
+    row_height: f32,
+    char_width: f32,
+) -> Vec2 {
+    let page = (viewport.y - row_height).max(row_height);
+    let step = H_SCROLL_STEP as f32 * char_width;
+    let moved = match scroll {
+        LogScroll::Left => Vec2::new(offset.x - step, offset.y),
+        LogScroll::Right => Vec2::new(offset.x + step, offset.y),
+        LogScroll::PageUp => Vec2::new(offset.x, offset.y - page),
+        LogScroll::PageDown => Vec2::new(offset.x, offset.y + page),
+    };
+    moved.clamp(Vec2::ZERO, max.max(Vec2::ZERO))
+}
+
+// Shown next to the log controls while unwrapped logs are scrolled to the right
+pub fn h_offset_label(offset: f32, char_width: f32) -> Option<String> {
+    let columns = (offset / char_width.max(1.0)).round() as usize;
+    (columns > 0).then(|| format!("→ {} chars", columns))
+}
@@ -0,0 +1,137 @@
+use eframe::egui::Color32;
+
+use crate::theme::Theme;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    // Info keeps the default text color
+    pub fn color(self, theme: &Theme) -> Option<Color32> {
+        match self {
+            LogLevel::Debug => Some(theme.muted),
+            LogLevel::Info => None,
+            LogLevel::Warn => Some(theme.warning),
+            LogLevel::Error => Some(theme.error),
+        }
+    }
+
+    fn parse(word: &str) -> Option<Self> {
+        match word.to_ascii_lowercase().as_str() {
+            "trace" | "debug" | "dbg" => Some(LogLevel::Debug),
+            "info" | "information" | "notice" => Some(LogLevel::Info),
+            "warn" | "warning" => Some(LogLevel::Warn),
+            "error" | "err" | "fatal" | "critical" | "crit" | "panic" | "emerg" | "alert" => {
+                Some(LogLevel::Error)
+            }
+            _ => None,
+        }
+    }
+}
+
+// Minimum level shown in the logs panel, cycled with a button or the L key
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LevelFilter {
+    #[default]
+    All,
+    Warn,
+    Error,
+}
+
+impl LevelFilter {
+    pub fn next(self) -> Self {
+        match self {
+            LevelFilter::All => LevelFilter::Warn,
+            LevelFilter::Warn => LevelFilter::Error,
+            LevelFilter::Error => LevelFilter::All,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            LevelFilter::All => "all levels",
+            LevelFilter::Warn => "warn+",
+            LevelFilter::Error => "errors only",
+        }
+    }
+
+    pub fn allows(self, level: Option<LogLevel>) -> bool {
+        match self {
+            LevelFilter::All => true,
+            LevelFilter::Warn => level >= Some(LogLevel::Warn),
+            LevelFilter::Error => level == Some(LogLevel::Error),
+        }
+    }
+}
+
+// Keys JSON loggers put the level under
+const JSON_LEVEL_KEYS: [&str; 5] = [
+    "\"level\"",
+    "\"lvl\"",
+    "\"severity\"",
+    "\"log.level\"",
+    "\"loglevel\"",
+];
+
+// Only the start of a line is looked at, where timestamps and levels go
+const SCAN_CHARS: usize = 120;
+
+// The string value of the first level key in a JSON object, without parsing the whole line
+fn json_level(line: &str) -> Option<LogLevel> {
+    JSON_LEVEL_KEYS.iter().find_map(|key| {
+        let start = line.find(key)? + key.len();
+        let rest = line[start..].trim_start().strip_prefix(':')?.trim_start();
+        let value = rest.strip_prefix('"')?;
+        LogLevel::parse(&value[..value.find('"')?])
+    })
+}
+
+// The level of a log line: a JSON "level" field, logfmt `level=warn`, or an upper case word
+// like ERROR or [WARN] near the start. Lower case words are not trusted on their own, they
+// show up in plain messages too often.
+pub fn classify(line: &str) -> Option<LogLevel> {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with('{') {
+        return json_level(trimmed);
+    }
+    let end = trimmed
+        .char_indices()
+        .nth(SCAN_CHARS)
+        .map_or(trimmed.len(), |(index, _)| index);
+    let head = &trimmed[..end];
+    for word in head.split(|c: char| c.is_whitespace() || matches!(c, '[' | ']' | '|' | ',')) {
+        if let Some(value) = word
+            .strip_prefix("level=")
+            .or_else(|| word.strip_prefix("lvl="))
+        {
+            return LogLevel::parse(value.trim_matches('"'));
+        }
+        let word = word.trim_end_matches(':');
+        if word.len() >= 3 && word.chars().all(|c| c.is_ascii_uppercase()) {
+            if let Some(level) = LogLevel::parse(word) {
+                return Some(level);
+            }
+        }
+    }
+    None
+}
+
+// Levels of consecutive lines, where an indented line without one, like a stack trace
+// frame, belongs to the line before it
+pub fn classify_lines<'a>(lines: impl IntoIterator<Item = &'a str>) -> Vec<Option<LogLevel>> {
+    let mut previous = None;
+    lines
+        .into_iter()
+        .map(|line| {
+            let continuation = line.starts_with(char::is_whitespace);
+            let level = classify(line).or(previous.filter(|_| continuation));
+            previous = level;
+            level
+        })
+        .collect()
+}
@@ -0,0 +1,98 @@
+use bollard::container::LogsOptions;
+use futures_util::stream::StreamExt;
+
+use crate::connection;
+use crate::time_format::parse_timestamp;
+
+// A point in time typed in the range prompt: "now", a duration before now like "90s", "15m",
+// "2h" or "1d", or an RFC 3339 timestamp
+pub fn parse_time(expression: &str, now: i64) -> Result<i64, String> {
+    let expression = expression.trim();
+    if expression.eq_ignore_ascii_case("now") {
+        return Ok(now);
+    }
+    let invalid = || {
+        format!(
+            "Invalid time {:?}, use a duration like 15m or 2h, or a timestamp like \
+             2024-05-01T12:00:00Z",
+            expression
+        )
+    };
+    if let Some(epoch) = parse_timestamp(expression) {
+        return Ok(epoch);
+    }
+    let unit = expression.chars().last().ok_or_else(invalid)?;
+    let seconds = match unit {
+        's' => 1,
+        'm' => 60,
+        'h' => 3600,
+        'd' => 86400,
+        _ => return Err(invalid()),
+    };
+    let amount: i64 = expression[..expression.len() - 1]
+        .parse()
+        .map_err(|_| invalid())?;
+    if amount < 0 {
+        return Err(invalid());
+    }
+    Ok(now - amount * seconds)
+}
+
+// Logs between two points in time instead of the newest lines
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogRange {
+    pub since: i64,
+    // None is up to now
+    pub until: Option<i64>,
+    // The expressions as typed, e.g. "since 15m until now"
+    pub label: String,
+}
+
+impl LogRange {
+    // An empty `until` means up to now
+    pub fn parse(since: &str, until: &str, now: i64) -> Result<Self, String> {
+        if since.trim().is_empty() {
+            return Err("Enter where the range starts, like 15m".to_string());
+        }
+        let start = parse_time(since, now)?;
+        let end = if until.trim().is_empty() {
+            None
+        } else {
+            Some(parse_time(until, now)?)
+        };
+        if end.is_some_and(|end| end <= start) {
+            return Err(format!("{} is not before {}", since.trim(), until.trim()));
+        }
+        let until_label = match until.trim() {
+            "" => "now",
+            until => until,
+        };
+        Ok(Self {
+            since: start,
+            until: end,
+            label: format!("since {} until {}", since.trim(), until_label),
+        })
+    }
+}
+
+// All logs of the container within the range, fetched once rather than followed
+pub async fn fetch_logs(container_id: String, range: LogRange) -> Result<String, String> {
+    let docker = connection::connect()?;
+    let options = LogsOptions::<String> {
+        follow: false,
+        stdout: true,
+        stderr: true,
+        since: range.since,
+        // Zero means no end
+        until: range.until.unwrap_or_default(),
+        tail: "all".to_string(),
+        ..Default::default()
+    };
+    let mut logs = String::new();
+    let mut stream = docker.logs(&container_id, Some(options));
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| format!("Failed to fetch logs {}: {}", range.label, e))?;
+        logs.push_str(&String::from_utf8_lossy(&chunk.into_bytes()));
+    }
+    Ok(logs)
+}
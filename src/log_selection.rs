@@ -0,0 +1,86 @@
+use std::ops::RangeInclusive;
+
+// What the keys ask of the selection, carried out by the logs panel which knows the lines
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SelectionRequest {
+    Start,
+    Up,
+    Down,
+    Copy,
+    Clear,
+}
+
+// Log lines picked to copy, from `anchor` to `cursor` in either order. Lines are numbered
+// from the start of the stream rather than by their place in the buffer, so dropping old
+// lines does not move the selection.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogSelection {
+    pub container_id: String,
+    pub anchor: usize,
+    pub cursor: usize,
+}
+
+impl LogSelection {
+    pub fn new(container_id: String, line: usize) -> Self {
+        Self {
+            container_id,
+            anchor: line,
+            cursor: line,
+        }
+    }
+
+    pub fn range(&self) -> RangeInclusive<usize> {
+        self.anchor.min(self.cursor)..=self.anchor.max(self.cursor)
+    }
+
+    pub fn contains(&self, line: usize) -> bool {
+        self.range().contains(&line)
+    }
+
+    // Moves the cursor to the line above or below among `shown`, the numbers of the lines
+    // the filters leave in view, in order
+    pub fn step(&mut self, shown: &[usize], down: bool) {
+        let next = if down {
+            shown.iter().find(|line| **line > self.cursor)
+        } else {
+            shown.iter().rev().find(|line| **line < self.cursor)
+        };
+        if let Some(line) = next {
+            self.cursor = *line;
+        }
+    }
+
+    // Keeps what is still in the buffer, whose oldest line is now `first`. None once every
+    // selected line is gone.
+    pub fn clamp(mut self, first: usize) -> Option<Self> {
+        if *self.range().end() < first {
+            return None;
+        }
+        self.anchor = self.anchor.max(first);
+        self.cursor = self.cursor.max(first);
+        Some(self)
+    }
+}
+
+// The selected lines of `lines`, numbered as for the selection, joined with newlines, and
+// how many there are
+pub fn selected_text<'a>(
+    lines: impl IntoIterator<Item = (usize, &'a str)>,
+    selection: &LogSelection,
+) -> (String, usize) {
+    let selected: Vec<&str> = lines
+        .into_iter()
+        .filter(|(number, _)| selection.contains(*number))
+        .map(|(_, line)| line)
+        .collect();
+    (selected.join("\n"), selected.len())
+}
+
+// "1 line", "3 lines"
+pub fn line_count(count: usize) -> String {
+    if count == 1 {
+        "1 line".to_string()
+    } else {
+        format!("{} lines", count)
+    }
+}
@@ -0,0 +1,399 @@
+use std::collections::{HashMap, VecDeque};
+
+use bollard::container::LogsOptions;
+use bollard::Docker;
+use futures_util::stream::StreamExt;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::compose_logs::{parse_line, ComposeLogs, COMPOSE_NAME};
+use crate::connection;
+use crate::time_format::parse_timestamp_nanos;
+
+// Lines kept in memory by default, older ones are dropped. Set with --max-log-lines.
+pub const DEFAULT_MAX_LOG_LINES: usize = 10_000;
+
+// The newest `cap` lines of a stream. Pushing past the cap drops one line from the front,
+// so appending stays O(1) however long the stream runs.
+pub struct LogBuffer<T> {
+    lines: VecDeque<T>,
+    cap: usize,
+    dropped: usize,
+}
+
+impl<T> LogBuffer<T> {
+    pub fn new(cap: usize) -> Self {
+        Self {
+            lines: VecDeque::new(),
+            cap: cap.max(1),
+            dropped: 0,
+        }
+    }
+
+    pub fn push(&mut self, line: T) {
+        if self.lines.len() == self.cap {
+            self.lines.pop_front();
+            self.dropped += 1;
+        }
+        self.lines.push_back(line);
+    }
+
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    pub fn cap(&self) -> usize {
+        self.cap
+    }
+
+    // Lines dropped from the front since the buffer was created
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    pub fn range(&self, rows: std::ops::Range<usize>) -> impl Iterator<Item = &T> {
+        self.lines.range(rows)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.lines.iter()
+    }
+}
+
+// Scroll offset that keeps the same lines in view after `dropped` rows left the top
+pub fn adjust_scroll(offset: f32, dropped: usize, row_height: f32) -> f32 {
+    (offset - dropped as f32 * row_height).max(0.0)
+}
+
+// The timestamp the daemon puts in front of a line when asked to, and the line without it.
+// Lines without one, such as the messages of a failed stream, come back whole.
+pub fn split_timestamp(line: &str) -> (Option<(i64, u32)>, &str) {
+    match line.split_once(' ') {
+        Some((timestamp, rest)) => match parse_timestamp_nanos(timestamp) {
+            Some(timestamp) => (Some(timestamp), rest),
+            None => (None, line),
+        },
+        None => match parse_timestamp_nanos(line) {
+            Some(timestamp) => (Some(timestamp), ""),
+            None => (None, line),
+        },
+    }
+}
+
+// How far a followed stream got, so following the same container again only fetches newer
+// lines. The daemon takes `since` in whole seconds and includes that second, so lines up to
+// the newest one seen come again and are skipped, counting the ones that share its timestamp.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LogCursor {
+    // Seconds and nanoseconds of the newest line
+    last: Option<(i64, u32)>,
+    // Lines seen with exactly that timestamp
+    at_last: usize,
+    // Of those, how many the resumed stream has yet to repeat
+    to_skip: usize,
+}
+
+impl LogCursor {
+    // The `since` to resume from, None to start with the tail
+    pub fn resume(&mut self) -> Option<i64> {
+        self.to_skip = self.at_last;
+        self.last.map(|(seconds, _)| seconds)
+    }
+
+    // Whether a line with `timestamp` is new, moving the cursor past it
+    pub fn accept(&mut self, timestamp: (i64, u32)) -> bool {
+        match self.last {
+            Some(last) if timestamp < last => false,
+            Some(last) if timestamp == last && self.to_skip > 0 => {
+                self.to_skip -= 1;
+                false
+            }
+            Some(last) if timestamp == last => {
+                self.at_last += 1;
+                true
+            }
+            _ => {
+                self.last = Some(timestamp);
+                self.at_last = 1;
+                self.to_skip = 0;
+                true
+            }
+        }
+    }
+}
+
+// What a followed stream had when it stopped, to pick up from when it is followed again
+pub struct StoppedLogs {
+    lines: LogBuffer<String>,
+    cursor: LogCursor,
+}
+
+// Lines of several containers interleaved in the order they arrive, like
+// `docker compose logs -f`. Dropping it cancels every stream.
+pub struct MergedLogs {
+    pub title: String,
+    // Display names of the followed containers, for the prefix width
+    pub names: Vec<String>,
+    // Container name and line
+    pub lines: LogBuffer<(String, String)>,
+    // Dropped count the view has already adjusted its scroll offset for
+    pub dropped_seen: usize,
+    pub scroll_offset: f32,
+    // Wrapped lines continue indented under the message, not under the prefix
+    pub wrap: bool,
+    // Prefix colors `docker compose logs` gave its services, as indexes into the theme's
+    pub colors: HashMap<String, usize>,
+    receiver: mpsc::UnboundedReceiver<(String, String)>,
+    streams: Vec<JoinHandle<()>>,
+    compose: Option<ComposeLogs>,
+}
+
+impl MergedLogs {
+    // Follows every (id, name) container, starting with the last `tail` lines of each, and
+    // keeps the newest `cap` lines
+    pub fn follow(
+        title: String,
+        containers: Vec<(String, String)>,
+        tail: usize,
+        cap: usize,
+    ) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let names = containers.iter().map(|(_, name)| name.clone()).collect();
+        let streams = match connection::connect() {
+            Ok(docker) => containers
+                .into_iter()
+                .map(|(id, name)| {
+                    let options = follow_options(tail, None);
+                    tokio::spawn(follow_one(
+                        docker.clone(),
+                        id,
+                        name,
+                        options,
+                        sender.clone(),
+                    ))
+                })
+                .collect(),
+            Err(e) => {
+                let _ = sender.send((
+                    "dockerrs".to_string(),
+                    format!("Failed to connect to Docker: {}", e),
+                ));
+                Vec::new()
+            }
+        };
+        Self {
+            title,
+            names,
+            lines: LogBuffer::new(cap),
+            dropped_seen: 0,
+            scroll_offset: 0.0,
+            wrap: false,
+            colors: HashMap::new(),
+            receiver,
+            streams,
+            compose: None,
+        }
+    }
+
+    // The output of `docker compose logs -f`, split into services by its prefixes. The
+    // services are learned from the lines, not from container labels.
+    pub fn compose(title: String, logs: ComposeLogs, cap: usize) -> Self {
+        let (_, receiver) = mpsc::unbounded_channel();
+        Self {
+            title,
+            names: Vec::new(),
+            lines: LogBuffer::new(cap),
+            dropped_seen: 0,
+            scroll_offset: 0.0,
+            wrap: false,
+            colors: HashMap::new(),
+            receiver,
+            streams: Vec::new(),
+            compose: Some(logs),
+        }
+    }
+
+    // Moves lines that arrived since the last frame into `lines`
+    pub fn receive(&mut self) {
+        while let Ok(line) = self.receiver.try_recv() {
+            self.lines.push(line);
+        }
+        let Some(compose) = &mut self.compose else {
+            return;
+        };
+        while let Ok(line) = compose.output.try_recv() {
+            let line = parse_line(&line);
+            let name = line.service.unwrap_or_else(|| COMPOSE_NAME.to_string());
+            if let Some(color) = line.color {
+                self.colors.insert(name.clone(), color);
+            }
+            if !self.names.contains(&name) {
+                self.names.push(name.clone());
+            }
+            self.lines.push((name, line.message));
+        }
+        if let Some(failure) = compose.failure() {
+            self.lines.push((COMPOSE_NAME.to_string(), failure));
+        }
+    }
+
+    pub fn is_streaming(&self) -> bool {
+        self.streams.iter().any(|stream| !stream.is_finished())
+            || self.compose.as_ref().is_some_and(ComposeLogs::is_streaming)
+    }
+}
+
+impl Drop for MergedLogs {
+    fn drop(&mut self) {
+        for stream in &self.streams {
+            stream.abort();
+        }
+    }
+}
+
+// Logs of the selected container as they come in, for Follow in the logs panel. Dropping it
+// cancels the stream, so replacing it when the selection changes leaves no task behind.
+pub struct FollowedLogs {
+    pub container_id: String,
+    pub lines: LogBuffer<String>,
+    cursor: LogCursor,
+    receiver: mpsc::UnboundedReceiver<(String, String)>,
+    stream: Option<JoinHandle<()>>,
+}
+
+impl FollowedLogs {
+    // Starts with the last `tail` lines, like the polled logs it replaces
+    pub fn follow(container_id: String, tail: usize, cap: usize) -> Self {
+        Self::resume(container_id, tail, cap, None)
+    }
+
+    // Continues where `stopped` left off, fetching only the lines that came since. A
+    // recreated container has a new ID and so nothing to continue from.
+    pub fn resume(
+        container_id: String,
+        tail: usize,
+        cap: usize,
+        stopped: Option<StoppedLogs>,
+    ) -> Self {
+        let (lines, mut cursor) = match stopped {
+            Some(stopped) => (stopped.lines, stopped.cursor),
+            None => (LogBuffer::new(cap), LogCursor::default()),
+        };
+        // Whatever came in the meantime, up to what the buffer keeps anyway. The timestamps
+        // only move the cursor and are stripped in `receive`.
+        let options = match cursor.resume() {
+            Some(since) => follow_options(lines.cap(), Some(since)),
+            None => follow_options(tail, None),
+        };
+        let options = LogsOptions {
+            timestamps: true,
+            ..options
+        };
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let stream = match connection::connect() {
+            Ok(docker) => Some(tokio::spawn(follow_one(
+                docker,
+                container_id.clone(),
+                String::new(),
+                options,
+                sender,
+            ))),
+            Err(e) => {
+                let _ = sender.send((String::new(), format!("Failed to connect to Docker: {}", e)));
+                None
+            }
+        };
+        Self {
+            container_id,
+            lines,
+            cursor,
+            receiver,
+            stream,
+        }
+    }
+
+    pub fn receive(&mut self) {
+        while let Ok((_, line)) = self.receiver.try_recv() {
+            match split_timestamp(&line) {
+                (Some(timestamp), text) => {
+                    if self.cursor.accept(timestamp) {
+                        self.lines.push(text.to_string());
+                    }
+                }
+                (None, _) => self.lines.push(line),
+            }
+        }
+    }
+
+    // Cancels the stream and keeps what it got
+    pub fn stop(mut self) -> StoppedLogs {
+        self.receive();
+        StoppedLogs {
+            lines: std::mem::replace(&mut self.lines, LogBuffer::new(1)),
+            cursor: std::mem::take(&mut self.cursor),
+        }
+    }
+
+    pub fn is_streaming(&self) -> bool {
+        self.stream
+            .as_ref()
+            .is_some_and(|stream| !stream.is_finished())
+    }
+}
+
+impl Drop for FollowedLogs {
+    fn drop(&mut self) {
+        if let Some(stream) = &self.stream {
+            stream.abort();
+        }
+    }
+}
+
+// The last `tail` lines, from `since` on when given, and the ones to come
+fn follow_options(tail: usize, since: Option<i64>) -> LogsOptions<String> {
+    LogsOptions {
+        follow: true,
+        stdout: true,
+        stderr: true,
+        tail: tail.to_string(),
+        since: since.unwrap_or_default(),
+        ..Default::default()
+    }
+}
+
+// Sends complete lines only, a chunk can end in the middle of one when the container has a TTY
+async fn follow_one(
+    docker: Docker,
+    id: String,
+    name: String,
+    options: LogsOptions<String>,
+    sender: mpsc::UnboundedSender<(String, String)>,
+) {
+    let mut stream = docker.logs(&id, Some(options));
+    let mut partial = String::new();
+    while let Some(chunk) = stream.next().await {
+        match chunk {
+            Ok(output) => {
+                partial.push_str(&String::from_utf8_lossy(&output.into_bytes()));
+                while let Some(end) = partial.find('\n') {
+                    let line = partial[..end].trim_end_matches('\r').to_string();
+                    partial.drain(..=end);
+                    if sender.send((name.clone(), line)).is_err() {
+                        return;
+                    }
+                }
+            }
+            Err(e) => {
+                let _ = sender.send((name.clone(), format!("log stream ended: {}", e)));
+                return;
+            }
+        }
+    }
+    if !partial.is_empty() {
+        let _ = sender.send((name, partial));
+    }
+}
@@ -1,81 +1,192 @@
-pub mod docker_viewer_app;
-pub mod utils;
-
-use bollard::container::{ListContainersOptions, LogsOptions};
-use bollard::Docker;
-
-use docker_viewer_app::{AppView, DockerViewerApp};
-use futures_util::stream::StreamExt;
-use std::collections::HashMap;
-use std::path::Path;
+use std::sync::{Arc, OnceLock};
 use std::time::Duration;
-use tokio::sync::mpsc;
-use tokio::time::sleep;
 
-#[tokio::main]
-async fn main() {
-    let log_options: LogsOptions<String> = LogsOptions::<String> {
-        follow: false,
-        stdout: true,
-        stderr: true,
-        tail: "100".to_string(),
-        ..Default::default()
-    };
-    let (sender, receiver) = mpsc::channel(100);
-    tokio::spawn(async move {
-        let docker = Docker::connect_with_unix_defaults().expect("Failed to connect to Docker");
+use clap::Parser;
+use eframe::egui;
+use tokio::sync::{mpsc, oneshot};
 
-        loop {
-            let containers = docker
-                .list_containers(Some(ListContainersOptions::<String> {
-                    all: true, // You may want to see all containers, not just running ones
-                    ..Default::default()
-                }))
-                .await
-                .expect("Failed to list containers");
+use dockerrs::audit::ActionHistory;
+use dockerrs::cli::{self, Cli};
+use dockerrs::config::{self, Config};
+use dockerrs::docker_viewer_app::DockerViewerApp;
+use dockerrs::error::{report, DockerrsError};
+use dockerrs::keep_alive::KeepAlive;
+use dockerrs::poller::{self, PollSettings};
+use dockerrs::read_only;
+use dockerrs::theme::{self, Theme};
 
-            let mut summaries = HashMap::new();
+// How long the window waits for the first containers before it opens without them
+const STARTUP_WAIT: Duration = Duration::from_millis(1500);
+// How long the poller gets to stop after the window closed
+const SHUTDOWN_WAIT: Duration = Duration::from_secs(1);
 
-            // let mut containers = vec![];
-            for container in &containers {
-                if let Some(id) = &container.id {
-                    let mut logs = String::new();
-                    let mut log_stream = docker.logs(id, Some(log_options.clone()));
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+    let config_path = cli.config.clone().or_else(config::default_path);
+    let loaded = config_path.as_deref().map(Config::load);
+    // Either the flag or the config file turns read-only mode on, neither can turn it off
+    let config_read_only = matches!(&loaded, Some(Ok(config)) if config.read_only);
+    read_only::set(cli.read_only || config_read_only);
+    if let Some(command) = cli.command {
+        if let Err(e) = cli::run(command, config_path).await {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
 
-                    while let Some(chunk) = log_stream.next().await {
-                        if let Ok(log) = chunk {
-                            logs.push_str(&String::from_utf8_lossy(&log.into_bytes()));
-                        }
-                    }
+    let (sender, receiver) = mpsc::channel(100);
+    let (images_sender, images_receiver) = mpsc::channel(100);
+    let (networks_sender, networks_receiver) = mpsc::channel(100);
+    let (capabilities_sender, capabilities_receiver) = oneshot::channel();
 
-                    let name = container
-                        .names
-                        .as_ref()
-                        .map_or_else(|| "Unnamed Container".to_string(), |names| names.join(", "));
-                    summaries.insert(name, (container.clone(), logs));
-                }
+    let options = eframe::NativeOptions::default();
+    let mut app = DockerViewerApp::new(
+        receiver,
+        images_receiver,
+        networks_receiver,
+        capabilities_receiver,
+        cli.scan_settings(),
+        cli.build_settings(),
+    );
+    app.max_log_lines = cli.max_log_lines;
+    app.detach_keys = cli.detach_keys.clone();
+    app.stats_samples = cli.stats_samples;
+    app.follow_on_start = cli.follow.clone();
+    app.check_updates_on_start = cli.check_updates;
+    app.screen_reader = cli.screen_reader;
+    if let Some(path) = cli.audit_log.clone() {
+        app.action_history = ActionHistory::with_log(path, app.error_sender.clone());
+    }
+    app.config_path = config_path;
+    if let Some(loaded) = loaded {
+        match loaded {
+            Ok(config) => {
+                app.watched = config.alerts.watch.iter().cloned().collect();
+                app.keep_alive = KeepAlive::new(config.keep_alive.clone());
+                app.config = config;
             }
-
-            if sender.send(summaries).await.is_err() {
-                eprintln!("Failed to send container logs");
-                break;
+            Err(message) => {
+                let error = DockerrsError::Parse {
+                    context: "Ignoring the config file".to_string(),
+                    message,
+                };
+                report(&app.error_sender, error);
             }
-            sleep(Duration::from_millis(50)).await;
         }
+    }
+    app.theme = Theme::new(theme::select(
+        cli.theme,
+        app.config.theme,
+        theme::no_color_requested(),
+    ));
+    app.rescan_compose_files();
+    app.rescan_dockerfiles();
+    let poll_settings = PollSettings {
+        log_tail: app.log_tail.clone(),
+        label_filter: app.label_filter.clone(),
+        connection: app.connection.clone(),
+        errors: app.error_sender.clone(),
+        shutdown: app.shutdown.clone(),
+    };
+    // The poller wakes the UI when something changed, it does not redraw otherwise. It starts
+    // before the window exists, so it repaints once there is one.
+    let window: Arc<OnceLock<egui::Context>> = Arc::default();
+    let repaint_window = window.clone();
+    let poller = tokio::spawn(async move {
+        let repaint = move || {
+            if let Some(ctx) = repaint_window.get() {
+                ctx.request_repaint();
+            }
+        };
+        let Some(docker) = poller::wait_for_daemon(&poll_settings, &repaint).await else {
+            return;
+        };
+        poller::poll(
+            docker,
+            sender,
+            images_sender,
+            networks_sender,
+            capabilities_sender,
+            poll_settings,
+            repaint,
+        )
+        .await;
     });
+    // The first frame shows the containers rather than an empty list, unless the daemon is
+    // slow or unreachable, which the containers view then says
+    if let Ok(Some(snapshot)) = tokio::time::timeout(STARTUP_WAIT, app.receiver.recv()).await {
+        app.update_containers(snapshot);
+    }
+    let dropped_on_exit = app.dropped_on_exit.clone();
+    eframe::run_native(
+        "dockerrs",
+        options,
+        Box::new(move |cc| {
+            let ctx = cc.egui_ctx.clone();
+            let _ = window.set(ctx.clone());
+            let mut app = app;
+            app.theme.apply(&ctx);
+            app.background_tasks
+                .push(tokio::spawn(close_on_signal(ctx)));
+            Box::new(app)
+        }),
+    )
+    .unwrap();
+    // Closing the window cancelled the poller, it only has to notice
+    let _ = tokio::time::timeout(SHUTDOWN_WAIT, poller).await;
+    for label in dropped_on_exit.lock().unwrap().iter() {
+        eprintln!(
+            "Warning: dropping queued action '{}' on shutdown, it was not executed",
+            label
+        );
+    }
+}
 
-    let options = eframe::NativeOptions::default();
-    let mut app = DockerViewerApp {
-        receiver,
-        containers: HashMap::new(),
-        selected_container: None,
-        current_view: AppView::Containers,
-        selected_compose_for_preview: None,
-        compose_files: Vec::new(),
-        dockerfiles: Vec::new(),
-        selected_dockerfile_for_preview: None,
+// Ctrl-C or SIGTERM in the terminal, or closing the console on Windows, closes the window like
+// its close button, so the background tasks are stopped the same way
+async fn close_on_signal(ctx: egui::Context) {
+    shutdown_signal().await;
+    ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+    ctx.request_repaint();
+}
+
+#[cfg(unix)]
+async fn shutdown_signal() {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut terminate = match signal(SignalKind::terminate()) {
+        Ok(terminate) => terminate,
+        Err(e) => {
+            eprintln!("Failed to listen for SIGTERM: {}", e);
+            let _ = tokio::signal::ctrl_c().await;
+            return;
+        }
     };
-    app.load_compose_files(Path::new("../"));
-    app.load_dockerfiles(Path::new("../"));
-    eframe::run_native("dockerrs", options, Box::new(|_cc| Box::new(app))).unwrap();
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = terminate.recv() => {}
+    }
+}
+
+#[cfg(windows)]
+async fn shutdown_signal() {
+    let mut close = match tokio::signal::windows::ctrl_close() {
+        Ok(close) => close,
+        Err(e) => {
+            eprintln!("Failed to listen for the console closing: {}", e);
+            let _ = tokio::signal::ctrl_c().await;
+            return;
+        }
+    };
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = close.recv() => {}
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+async fn shutdown_signal() {
+    let _ = tokio::signal::ctrl_c().await;
 }
@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+
+use bollard::network::CreateNetworkOptions;
+use bollard::secret::Network;
+
+use crate::connection;
+use crate::poller::{display_name, ContainerSnapshot};
+use crate::read_only;
+
+// Drivers a network can be created with from the app
+pub const NETWORK_DRIVERS: [&str; 4] = ["bridge", "overlay", "macvlan", "ipvlan"];
+
+// Networks every daemon has, they cannot be removed
+pub const BUILTIN_NETWORKS: [&str; 3] = ["bridge", "host", "none"];
+
+// A container attached to a network, addresses are empty when it has none
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct NetworkEndpoint {
+    pub name: String,
+    pub container_id: String,
+    // With the prefix length, e.g. "172.18.0.2/16"
+    pub ipv4: String,
+    pub ipv6: String,
+    pub mac: String,
+}
+
+fn with_prefix(address: Option<&String>, prefix: Option<i64>) -> String {
+    match (address.filter(|address| !address.is_empty()), prefix) {
+        (Some(address), Some(prefix)) if prefix > 0 => format!("{}/{}", address, prefix),
+        (Some(address), _) => address.clone(),
+        (None, _) => String::new(),
+    }
+}
+
+// Containers attached to a network with their addresses on it, sorted by name. The list
+// endpoint leaves the containers of a network empty, so they come from the snapshot unless
+// the network was inspected.
+pub fn connected_containers(
+    network: &Network,
+    containers: &ContainerSnapshot,
+) -> Vec<NetworkEndpoint> {
+    let mut connected: Vec<NetworkEndpoint> = match network
+        .containers
+        .as_ref()
+        .filter(|inspected| !inspected.is_empty())
+    {
+        Some(inspected) => inspected
+            .iter()
+            .map(|(id, container)| NetworkEndpoint {
+                name: container.name.clone().unwrap_or_else(|| id.clone()),
+                container_id: id.clone(),
+                ipv4: container.ipv4_address.clone().unwrap_or_default(),
+                ipv6: container.ipv6_address.clone().unwrap_or_default(),
+                mac: container.mac_address.clone().unwrap_or_default(),
+            })
+            .collect(),
+        None => containers
+            .iter()
+            .filter_map(|(id, (summary, _))| {
+                let networks = summary.network_settings.as_ref()?.networks.as_ref()?;
+                let endpoint = networks.iter().find_map(|(name, endpoint)| {
+                    let same_id =
+                        endpoint.network_id.is_some() && endpoint.network_id == network.id;
+                    (same_id || Some(name) == network.name.as_ref()).then_some(endpoint)
+                })?;
+                Some(NetworkEndpoint {
+                    // Named like the containers of an inspected network
+                    name: display_name(summary).trim_start_matches('/').to_string(),
+                    container_id: id.clone(),
+                    ipv4: with_prefix(endpoint.ip_address.as_ref(), endpoint.ip_prefix_len),
+                    ipv6: with_prefix(
+                        endpoint.global_ipv6_address.as_ref(),
+                        endpoint.global_ipv6_prefix_len,
+                    ),
+                    mac: endpoint.mac_address.clone().unwrap_or_default(),
+                })
+            })
+            .collect(),
+    };
+    connected.sort();
+    connected
+}
+
+// One entry of a network's IPAM config, fields the daemon left out are empty
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IpamRow {
+    pub subnet: String,
+    pub gateway: String,
+    pub ip_range: String,
+}
+
+// IPAM config of the network, empty for host and none
+pub fn ipam_rows(network: &Network) -> Vec<IpamRow> {
+    network
+        .ipam
+        .as_ref()
+        .and_then(|ipam| ipam.config.as_ref())
+        .map(|configs| {
+            configs
+                .iter()
+                .map(|config| IpamRow {
+                    subnet: config.subnet.clone().unwrap_or_default(),
+                    gateway: config.gateway.clone().unwrap_or_default(),
+                    ip_range: config.ip_range.clone().unwrap_or_default(),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// Driver options as "key=value", sorted
+pub fn driver_options(network: &Network) -> Vec<String> {
+    let mut options: Vec<String> = network
+        .options
+        .iter()
+        .flatten()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect();
+    options.sort();
+    options
+}
+
+pub async fn create_network(name: String, driver: String) -> Result<String, String> {
+    read_only::check()?;
+    let docker = connection::connect()?;
+    let options = CreateNetworkOptions {
+        name: name.clone(),
+        check_duplicate: true,
+        driver: driver.clone(),
+        options: HashMap::new(),
+        labels: HashMap::new(),
+        ..Default::default()
+    };
+    docker
+        .create_network(options)
+        .await
+        .map_err(|e| format!("Failed to create network {}: {}", name, e))?;
+    Ok(format!("Created {} network {}", driver, name))
+}
+
+pub async fn remove_network(id: String, name: String) -> Result<String, String> {
+    read_only::check()?;
+    let docker = connection::connect()?;
+    docker
+        .remove_network(&id)
+        .await
+        .map_err(|e| format!("Failed to remove network {}: {}", name, e))?;
+    Ok(format!("Removed network {}", name))
+}
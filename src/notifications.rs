@@ -0,0 +1,105 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+// How long a notification stays up unless it is dismissed
+pub const NOTIFICATION_DURATION: Duration = Duration::from_secs(10);
+// Older notifications are taken down when more than this many are up at once
+pub const MAX_SHOWN: usize = 5;
+// Notifications kept for the history popup, including the ones no longer shown
+pub const HISTORY_LEN: usize = 50;
+
+// The outcome of a background action or a failure, as the app shows it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Notification {
+    pub message: String,
+    pub success: bool,
+    // The container the message is about, clicking the notification selects it
+    pub container: Option<String>,
+    // How often the same message came in a row
+    pub count: usize,
+    pub at: Instant,
+}
+
+impl Notification {
+    fn repeats(&self, message: &str, success: bool, container: &Option<String>) -> bool {
+        self.message == message && self.success == success && &self.container == container
+    }
+
+    pub fn label(&self) -> String {
+        match self.count {
+            1 => self.message.clone(),
+            count => format!("{} (×{})", self.message, count),
+        }
+    }
+}
+
+// The notifications up in the corner of the window and the recent ones before them. The clock
+// is passed in by the caller.
+#[derive(Default)]
+pub struct Notifications {
+    pub shown: Vec<Notification>,
+    // Oldest first
+    pub history: VecDeque<Notification>,
+}
+
+impl Notifications {
+    // A message identical to one that is up counts as a repeat of it and moves it to the end
+    pub fn push(
+        &mut self,
+        result: Result<String, String>,
+        container: Option<String>,
+        now: Instant,
+    ) {
+        let (message, success) = match result {
+            Ok(message) => (message, true),
+            Err(message) => (message, false),
+        };
+        let repeated = self
+            .shown
+            .iter()
+            .position(|shown| shown.repeats(&message, success, &container));
+        let count = match repeated {
+            Some(index) => self.shown.remove(index).count + 1,
+            None => 1,
+        };
+        let notification = Notification {
+            message,
+            success,
+            container,
+            count,
+            at: now,
+        };
+        match self.history.back_mut() {
+            Some(last) if last.repeats(&notification.message, success, &notification.container) => {
+                last.count += 1;
+                last.at = now;
+            }
+            _ => self.history.push_back(Notification {
+                count: 1,
+                ..notification.clone()
+            }),
+        }
+        if self.history.len() > HISTORY_LEN {
+            self.history.pop_front();
+        }
+        self.shown.push(notification);
+        let excess = self.shown.len().saturating_sub(MAX_SHOWN);
+        self.shown.drain(..excess);
+    }
+
+    // Takes down the expired notifications, returns when the next one expires
+    pub fn expire(&mut self, now: Instant) -> Option<Duration> {
+        self.shown
+            .retain(|shown| now.saturating_duration_since(shown.at) < NOTIFICATION_DURATION);
+        self.shown
+            .iter()
+            .map(|shown| NOTIFICATION_DURATION - now.saturating_duration_since(shown.at))
+            .min()
+    }
+
+    pub fn dismiss(&mut self, index: usize) {
+        if index < self.shown.len() {
+            self.shown.remove(index);
+        }
+    }
+}
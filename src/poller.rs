@@ -0,0 +1,273 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use bollard::secret::{ContainerSummary, ImageSummary, Network};
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::sleep;
+use tokio_util::sync::CancellationToken;
+
+use crate::capabilities::Capabilities;
+use crate::docker_api::{BollardDocker, DockerApi};
+use crate::error::{report, DockerrsError, ErrorSender};
+use crate::fingerprint::{images_fingerprint, networks_fingerprint, snapshot_fingerprint};
+use crate::label_filter::LabelFilter;
+
+// Log lines kept per container in each snapshot, until the logs panel picks another tail
+pub const LOG_TAIL: usize = 100;
+// A tail of this many lines fetches the whole log
+pub const ALL_LOGS: usize = usize::MAX;
+pub const LOG_TAIL_CHOICES: [(usize, &str); 4] = [
+    (100, "100"),
+    (1000, "1000"),
+    (10000, "10000"),
+    (ALL_LOGS, "all"),
+];
+
+// Every container by ID, with its summary and the tail of its logs. Names are not unique
+// while a container is being replaced or renamed, so they are only used for display.
+pub type ContainerSnapshot = HashMap<String, (ContainerSummary, String)>;
+
+// All names of a container joined, as shown in the container list
+pub fn display_name(summary: &ContainerSummary) -> String {
+    summary
+        .names
+        .as_ref()
+        .filter(|names| !names.is_empty())
+        .map_or_else(|| "Unnamed Container".to_string(), |names| names.join(", "))
+}
+
+// How long the poller waits before asking an unreachable daemon again
+pub const RETRY_INTERVAL: Duration = Duration::from_secs(2);
+
+// Whether the daemon answered the last listing, so an unreachable daemon is not mistaken
+// for one without containers
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum Connection {
+    #[default]
+    Connecting,
+    Connected,
+    Failed(String),
+}
+
+// What the app can change while polling, the poller reads it every tick. The poller writes
+// the connection and reports failures other than the daemon being unreachable to `errors`.
+// Cancelling `shutdown` stops it wherever it is waiting.
+#[derive(Clone)]
+pub struct PollSettings {
+    pub log_tail: Arc<AtomicUsize>,
+    pub label_filter: Arc<Mutex<LabelFilter>>,
+    pub connection: Arc<Mutex<Connection>>,
+    pub errors: ErrorSender,
+    pub shutdown: CancellationToken,
+}
+
+impl Default for PollSettings {
+    fn default() -> Self {
+        Self {
+            log_tail: Arc::new(AtomicUsize::new(LOG_TAIL)),
+            label_filter: Arc::new(Mutex::new(LabelFilter::default())),
+            connection: Arc::new(Mutex::new(Connection::default())),
+            // Reports go nowhere until the app hands over its sender
+            errors: mpsc::unbounded_channel().0,
+            shutdown: CancellationToken::new(),
+        }
+    }
+}
+
+// Connects once a daemon listens at one of the endpoints `connection::target` tries, keeping
+// why it cannot in `settings.connection` meanwhile. None when shut down before that.
+pub async fn wait_for_daemon(
+    settings: &PollSettings,
+    repaint: &impl Fn(),
+) -> Option<BollardDocker> {
+    loop {
+        match BollardDocker::connect() {
+            Ok(docker) => return Some(docker),
+            Err(e) => {
+                let failed = Connection::Failed(e);
+                let previous =
+                    std::mem::replace(&mut *settings.connection.lock().unwrap(), failed.clone());
+                if previous != failed {
+                    repaint();
+                }
+            }
+        }
+        tokio::select! {
+            _ = settings.shutdown.cancelled() => return None,
+            _ = sleep(RETRY_INTERVAL) => {}
+        }
+    }
+}
+
+// Sends the daemon capabilities once, then checks containers, images and networks every 50ms
+// until the app drops its receivers or cancels `settings.shutdown`. A listing is only sent when
+// its fingerprint differs from the previous one, and `repaint` is called after each send so an
+// idle UI does not have to redraw to find out. While the daemon cannot be reached the error is
+// kept in `settings.connection` and the listing is retried every `RETRY_INTERVAL`.
+pub async fn poll<D, R>(
+    docker: D,
+    sender: mpsc::Sender<ContainerSnapshot>,
+    images_sender: mpsc::Sender<Vec<ImageSummary>>,
+    networks_sender: mpsc::Sender<Vec<Network>>,
+    capabilities_sender: oneshot::Sender<Capabilities>,
+    settings: PollSettings,
+    repaint: R,
+) where
+    D: DockerApi,
+    R: Fn() + Send,
+{
+    let shutdown = settings.shutdown.clone();
+    let polling = poll_until_closed(
+        docker,
+        sender,
+        images_sender,
+        networks_sender,
+        capabilities_sender,
+        settings,
+        repaint,
+    );
+    tokio::select! {
+        _ = shutdown.cancelled() => {}
+        _ = polling => {}
+    }
+}
+
+async fn poll_until_closed<D, R>(
+    docker: D,
+    sender: mpsc::Sender<ContainerSnapshot>,
+    images_sender: mpsc::Sender<Vec<ImageSummary>>,
+    networks_sender: mpsc::Sender<Vec<Network>>,
+    capabilities_sender: oneshot::Sender<Capabilities>,
+    settings: PollSettings,
+    repaint: R,
+) where
+    D: DockerApi,
+    R: Fn() + Send,
+{
+    // Asked again after the daemon comes back, when it could not be reached at first
+    let mut capabilities_sender = Some(capabilities_sender);
+    let mut reconnected = true;
+    let mut last_summaries = None;
+    let mut last_images = None;
+    let mut last_networks = None;
+    // The failing listings are retried every tick, each failure is only reported once
+    let mut images_failed = false;
+    let mut networks_failed = false;
+    // Nothing is sent while the daemon is idle, so the receivers going away is checked here
+    while !sender.is_closed() && !images_sender.is_closed() && !networks_sender.is_closed() {
+        if let Some(capabilities) = capabilities_sender.take().filter(|_| reconnected) {
+            match docker.version().await {
+                Ok(version) => {
+                    let _ = capabilities.send(Capabilities::from_version(&version));
+                    repaint();
+                }
+                Err(e) => {
+                    let error = DockerrsError::docker("Failed to query the Docker version", e);
+                    report(&settings.errors, error);
+                    capabilities_sender = Some(capabilities);
+                    repaint();
+                }
+            }
+        }
+        // You may want to see all containers, not just running ones. The daemon filters by
+        // label, except for the negations it does not support.
+        let filter = settings.label_filter.lock().unwrap().clone();
+        let containers = match docker.list_containers(true, filter.daemon_filters()).await {
+            Ok(containers) => containers,
+            Err(e) => {
+                let failed = Connection::Failed(format!("Cannot reach the Docker daemon: {}", e));
+                let previous = std::mem::replace(&mut *settings.connection.lock().unwrap(), failed);
+                // The app shows the connection, so it only needs a repaint when it changes
+                if !matches!(previous, Connection::Failed(_)) {
+                    repaint();
+                }
+                reconnected = true;
+                sleep(RETRY_INTERVAL).await;
+                continue;
+            }
+        };
+        reconnected = false;
+        let previous = std::mem::replace(
+            &mut *settings.connection.lock().unwrap(),
+            Connection::Connected,
+        );
+        // The first snapshot repaints anyway, an unchanged one after an outage does not
+        if matches!(previous, Connection::Failed(_)) {
+            repaint();
+        }
+        let containers = containers
+            .into_iter()
+            .filter(|container| filter.matches(container.labels.as_ref()));
+
+        let tail = settings.log_tail.load(Ordering::Relaxed);
+        let mut summaries = HashMap::new();
+
+        for container in containers {
+            if let Some(id) = &container.id {
+                let logs = docker.logs(id, tail).await.unwrap_or_default();
+                summaries.insert(id.clone(), (container.clone(), logs));
+            }
+        }
+
+        let fingerprint = snapshot_fingerprint(&summaries);
+        if last_summaries != Some(fingerprint) {
+            // The app is gone
+            if sender.send(summaries).await.is_err() {
+                break;
+            }
+            last_summaries = Some(fingerprint);
+            repaint();
+        }
+
+        let images = docker.list_images().await;
+        images_failed = match images.map(|images| (images_fingerprint(&images), images)) {
+            Ok((fingerprint, images)) if last_images != Some(fingerprint) => {
+                if images_sender.send(images).await.is_err() {
+                    break;
+                }
+                last_images = Some(fingerprint);
+                repaint();
+                false
+            }
+            Ok(_) => false,
+            Err(e) => {
+                if !images_failed {
+                    report(
+                        &settings.errors,
+                        DockerrsError::docker("Failed to list images", e),
+                    );
+                    repaint();
+                }
+                true
+            }
+        };
+
+        // A daemon without network support should not stop the rest of the polling
+        let networks = docker.list_networks().await;
+        networks_failed = match networks.map(|networks| (networks_fingerprint(&networks), networks))
+        {
+            Ok((fingerprint, networks)) if last_networks != Some(fingerprint) => {
+                if networks_sender.send(networks).await.is_err() {
+                    break;
+                }
+                last_networks = Some(fingerprint);
+                repaint();
+                false
+            }
+            Ok(_) => false,
+            Err(e) => {
+                if !networks_failed {
+                    report(
+                        &settings.errors,
+                        DockerrsError::docker("Failed to list networks", e),
+                    );
+                    repaint();
+                }
+                true
+            }
+        };
+        sleep(Duration::from_millis(50)).await;
+    }
+}
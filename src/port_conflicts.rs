@@ -0,0 +1,149 @@
+use std::io::ErrorKind;
+use std::net::{TcpListener, UdpSocket};
+use std::path::{Path, PathBuf};
+
+use bollard::secret::ContainerSummary;
+
+use crate::compose_file::{ComposeFile, HostPort};
+use crate::poller::{display_name, ContainerSnapshot};
+use crate::utils::compose_project_location;
+
+// What already holds a port a compose file wants
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Holder {
+    Container { id: String, name: String },
+    // Something outside Docker listening on this machine
+    Host,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PortConflict {
+    pub service: String,
+    pub port: HostPort,
+    pub holder: Holder,
+}
+
+impl PortConflict {
+    pub fn describe(&self) -> String {
+        let holder = match &self.holder {
+            Holder::Container { name, .. } => format!("container {}", name),
+            Holder::Host => "another program on this machine".to_string(),
+        };
+        format!("{}: {} is used by {}", self.service, self.port, holder)
+    }
+}
+
+// A compose up found ports in use and waits for the user to decide
+pub struct PendingUp {
+    pub path: PathBuf,
+    pub services: Vec<String>,
+    pub conflicts: Vec<PortConflict>,
+}
+
+impl PendingUp {
+    // Containers to stop to free the ports, each once
+    pub fn containers(&self) -> Vec<(String, String)> {
+        let mut containers: Vec<(String, String)> = Vec::new();
+        for conflict in &self.conflicts {
+            if let Holder::Container { id, name } = &conflict.holder {
+                if !containers.iter().any(|(known, _)| known == id) {
+                    containers.push((id.clone(), name.clone()));
+                }
+            }
+        }
+        containers
+    }
+}
+
+// Host ports of `services`, or of every service when there are none
+pub fn requested_ports(compose: &ComposeFile, services: &[String]) -> Vec<(String, HostPort)> {
+    compose
+        .services
+        .iter()
+        .filter(|(name, _)| services.is_empty() || services.contains(name))
+        .flat_map(|(name, service)| {
+            service
+                .host_ports()
+                .into_iter()
+                .map(move |port| (name.clone(), port))
+        })
+        .collect()
+}
+
+// Requested ports that running containers or, through `in_use_on_host`, other programs
+// already bind. Containers of the project in `working_dir` are left out, compose up
+// replaces them itself.
+pub fn find_conflicts(
+    requested: &[(String, HostPort)],
+    containers: &ContainerSnapshot,
+    working_dir: &Path,
+    in_use_on_host: impl Fn(&HostPort) -> bool,
+) -> Vec<PortConflict> {
+    let mut running: Vec<(&String, &ContainerSummary, bool)> = containers
+        .iter()
+        .filter(|(_, (summary, _))| summary.state.as_deref() == Some("running"))
+        .map(|(id, (summary, _))| {
+            let own = compose_project_location(summary)
+                .is_some_and(|project| project.working_dir == working_dir);
+            (id, summary, own)
+        })
+        .collect();
+    running.sort_by_key(|(id, _, _)| *id);
+
+    let mut conflicts = Vec::new();
+    for (service, port) in requested {
+        let holders: Vec<_> = running
+            .iter()
+            .filter(|(_, summary, _)| binds(summary, port))
+            .collect();
+        for (id, summary, _) in holders.iter().filter(|(_, _, own)| !own) {
+            conflicts.push(PortConflict {
+                service: service.clone(),
+                port: port.clone(),
+                holder: Holder::Container {
+                    id: id.to_string(),
+                    name: display_name(summary),
+                },
+            });
+        }
+        // A container binding the port makes the probe fail too
+        if holders.is_empty() && in_use_on_host(port) {
+            conflicts.push(PortConflict {
+                service: service.clone(),
+                port: port.clone(),
+                holder: Holder::Host,
+            });
+        }
+    }
+    conflicts
+}
+
+fn binds(summary: &ContainerSummary, wanted: &HostPort) -> bool {
+    summary.ports.iter().flatten().any(|port| {
+        let protocol = port
+            .typ
+            .map(|typ| typ.to_string())
+            .filter(|typ| !typ.is_empty())
+            .unwrap_or_else(|| "tcp".to_string());
+        port.public_port == Some(wanted.port)
+            && protocol == wanted.protocol
+            && addresses_overlap(port.ip.as_deref(), wanted.host_ip.as_deref())
+    })
+}
+
+// An unspecified address covers every other one
+fn addresses_overlap(bound: Option<&str>, wanted: Option<&str>) -> bool {
+    let any = |ip: Option<&str>| matches!(ip, None | Some("" | "0.0.0.0" | "::"));
+    any(bound) || any(wanted) || bound == wanted
+}
+
+// Whether binding the port here fails because it is taken. Other failures, like missing
+// permission for ports below 1024, say nothing about conflicts.
+pub fn in_use_on_host(port: &HostPort) -> bool {
+    let ip = port.host_ip.as_deref().unwrap_or("0.0.0.0");
+    let result = match port.protocol.as_str() {
+        "udp" => UdpSocket::bind((ip, port.port)).map(drop),
+        _ => TcpListener::bind((ip, port.port)).map(drop),
+    };
+    matches!(result, Err(e) if e.kind() == ErrorKind::AddrInUse)
+}
@@ -0,0 +1,177 @@
+use bollard::secret::ContainerSummary;
+use serde::Serialize;
+
+use crate::details::port_rows;
+use crate::docker_api::DockerApi;
+use crate::health::Health;
+use crate::label_filter::LabelFilter;
+use crate::poller::display_name;
+use crate::utils::{compose_project, compose_service};
+
+// What `dockerrs ps` prints per container, the same fields the containers view shows
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ContainerRow {
+    pub id: String,
+    pub name: String,
+    pub image: String,
+    pub state: String,
+    pub status: String,
+    pub health: Option<&'static str>,
+    pub project: Option<String>,
+    pub service: Option<String>,
+    // Like `docker ps`, e.g. "0.0.0.0:8080->80/tcp" or "53/udp" when not published
+    pub ports: Vec<String>,
+}
+
+pub fn container_row(summary: &ContainerSummary) -> ContainerRow {
+    let status = summary.status.clone().unwrap_or_default();
+    ContainerRow {
+        id: summary
+            .id
+            .as_deref()
+            .unwrap_or_default()
+            .chars()
+            .take(12)
+            .collect(),
+        name: display_name(summary).trim_start_matches('/').to_string(),
+        image: summary.image.clone().unwrap_or_default(),
+        state: summary.state.clone().unwrap_or_default(),
+        health: Health::from_status(&status).map(Health::label),
+        status,
+        project: compose_project(summary),
+        service: compose_service(summary),
+        ports: port_rows(summary)
+            .into_iter()
+            .map(|(host, container, protocol)| match host.as_str() {
+                "-" => format!("{}/{}", container, protocol),
+                _ => format!("{}->{}/{}", host, container, protocol),
+            })
+            .collect(),
+    }
+}
+
+// Every container matching the label filter, sorted by name
+pub async fn list_containers<D: DockerApi>(
+    docker: &D,
+    filter: &LabelFilter,
+) -> Result<Vec<ContainerRow>, String> {
+    let containers = docker
+        .list_containers(true, filter.daemon_filters())
+        .await
+        .map_err(|e| format!("Failed to list containers: {}", e))?;
+    let mut rows: Vec<ContainerRow> = containers
+        .iter()
+        .filter(|container| filter.matches(container.labels.as_ref()))
+        .map(container_row)
+        .collect();
+    rows.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(rows)
+}
+
+// Columns padded to their widest value, like `docker ps`
+pub fn format_table(rows: &[ContainerRow]) -> String {
+    let mut lines = vec![[
+        "CONTAINER ID".to_string(),
+        "NAME".to_string(),
+        "STATE".to_string(),
+        "HEALTH".to_string(),
+        "PROJECT".to_string(),
+        "PORTS".to_string(),
+    ]];
+    lines.extend(rows.iter().map(|row| {
+        [
+            row.id.clone(),
+            row.name.clone(),
+            row.state.clone(),
+            row.health.unwrap_or("-").to_string(),
+            row.project.clone().unwrap_or_else(|| "-".to_string()),
+            row.ports.join(", "),
+        ]
+    }));
+    let mut widths = [0; 6];
+    for line in &lines {
+        for (width, cell) in widths.iter_mut().zip(line) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    lines
+        .iter()
+        .map(|line| {
+            let cells: Vec<String> = line
+                .iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{:width$}", cell, width = width))
+                .collect();
+            cells.join("  ").trim_end().to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// The container with this name or ID, or else the only one whose name or ID starts with it.
+// Several prefix matches are an error listing them, so the wrong container is never picked.
+pub fn resolve_container<'a>(
+    containers: impl IntoIterator<Item = &'a ContainerSummary>,
+    query: &str,
+) -> Result<&'a ContainerSummary, String> {
+    let names = |summary: &'a ContainerSummary| {
+        summary
+            .names
+            .iter()
+            .flatten()
+            .map(|name| name.trim_start_matches('/'))
+    };
+    let containers: Vec<&ContainerSummary> = containers.into_iter().collect();
+    let exact = containers.iter().find(|summary| {
+        summary.id.as_deref() == Some(query) || names(summary).any(|name| name == query)
+    });
+    if let Some(summary) = exact {
+        return Ok(summary);
+    }
+    let mut matches: Vec<&ContainerSummary> = containers
+        .into_iter()
+        .filter(|summary| {
+            summary
+                .id
+                .as_deref()
+                .is_some_and(|id| id.starts_with(query))
+                || names(summary).any(|name| name.starts_with(query))
+        })
+        .collect();
+    match matches.len() {
+        0 => Err(format!("No such container: {}", query)),
+        1 => Ok(matches.remove(0)),
+        _ => {
+            let mut names: Vec<String> = matches
+                .into_iter()
+                .map(|summary| display_name(summary).trim_start_matches('/').to_string())
+                .collect();
+            names.sort();
+            Err(format!(
+                "{} matches several containers: {}",
+                query,
+                names.join(", ")
+            ))
+        }
+    }
+}
+
+// The last `tail` log lines of the container `resolve_container` finds
+pub async fn container_logs<D: DockerApi>(
+    docker: &D,
+    container: &str,
+    tail: usize,
+) -> Result<String, String> {
+    let containers = docker
+        .list_containers(true, Default::default())
+        .await
+        .map_err(|e| format!("Failed to list containers: {}", e))?;
+    let id = resolve_container(&containers, container)?
+        .id
+        .clone()
+        .ok_or_else(|| format!("No such container: {}", container))?;
+    docker
+        .logs(&id, tail)
+        .await
+        .map_err(|e| format!("Failed to get logs of {}: {}", container, e))
+}
@@ -0,0 +1,23 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// Set once at startup from --read-only. Every function that changes something on the
+// daemon checks it, so a button that was missed still cannot do harm.
+static READ_ONLY: AtomicBool = AtomicBool::new(false);
+
+pub const READ_ONLY_MESSAGE: &str = "Disabled in read-only mode";
+
+pub fn set(enabled: bool) {
+    READ_ONLY.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    READ_ONLY.load(Ordering::Relaxed)
+}
+
+pub fn check() -> Result<(), String> {
+    if is_enabled() {
+        Err(READ_ONLY_MESSAGE.to_string())
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,240 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::Stdio;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use bollard::auth::DockerCredentials;
+use bollard::image::PushImageOptions;
+use futures_util::stream::StreamExt;
+use serde::Deserialize;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::sync::mpsc;
+
+use crate::connection;
+use crate::read_only;
+
+pub const DOCKER_HUB: &str = "docker.io";
+// The key Docker Hub credentials are stored under in config.json
+const DOCKER_HUB_AUTH_KEY: &str = "https://index.docker.io/v1/";
+
+// The subset of ~/.docker/config.json needed to find credentials
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DockerConfig {
+    #[serde(default)]
+    pub auths: HashMap<String, AuthEntry>,
+    pub creds_store: Option<String>,
+    #[serde(default)]
+    pub cred_helpers: HashMap<String, String>,
+}
+
+#[derive(Deserialize, Default)]
+pub struct AuthEntry {
+    pub auth: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct HelperCredentials {
+    username: String,
+    secret: String,
+}
+
+// Registry host of an image reference, None for Docker Hub images like `nginx`, `me/app` or
+// `docker.io/me/app`, whose credentials are stored under `DOCKER_HUB_AUTH_KEY`
+pub fn registry_of(image: &str) -> Option<&str> {
+    let (first, _) = image.split_once('/')?;
+    if matches!(
+        first,
+        "docker.io" | "index.docker.io" | "registry-1.docker.io"
+    ) {
+        return None;
+    }
+    (first.contains('.') || first.contains(':') || first == "localhost").then_some(first)
+}
+
+// Splits `repo[:tag]` at the last colon after the last slash, the tag defaults to latest. A
+// digest in `repo[:tag]@sha256:...` is dropped, or returned in place of the tag when there is
+// no tag, so it is never mistaken for one.
+pub fn split_tag(image: &str) -> (&str, &str) {
+    let (name, digest) = match image.split_once('@') {
+        Some((name, digest)) => (name, Some(digest)),
+        None => (image, None),
+    };
+    match (name.rsplit_once(':'), digest) {
+        (Some((repo, tag)), _) if !tag.contains('/') => (repo, tag),
+        (_, Some(digest)) => (name, digest),
+        _ => (name, "latest"),
+    }
+}
+
+pub fn docker_config_path() -> Option<PathBuf> {
+    match std::env::var_os("DOCKER_CONFIG") {
+        Some(dir) => Some(PathBuf::from(dir).join("config.json")),
+        None => std::env::var_os("HOME")
+            .map(|home| PathBuf::from(home).join(".docker").join("config.json")),
+    }
+}
+
+pub fn load_docker_config() -> Result<DockerConfig, String> {
+    let Some(path) = docker_config_path() else {
+        return Ok(DockerConfig::default());
+    };
+    match std::fs::read_to_string(&path) {
+        Ok(content) => {
+            serde_json::from_str(&content).map_err(|e| format!("Failed to parse {:?}: {}", path, e))
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(DockerConfig::default()),
+        Err(e) => Err(format!("Failed to read {:?}: {}", path, e)),
+    }
+}
+
+// Asks `docker-credential-<helper>` for the credentials of a registry
+async fn helper_credentials(
+    helper: &str,
+    server: &str,
+) -> Result<Option<HelperCredentials>, String> {
+    let program = format!("docker-credential-{}", helper);
+    let mut child = Command::new(&program)
+        .arg("get")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(server.as_bytes())
+            .await
+            .map_err(|e| format!("Failed to query {}: {}", program, e))?;
+    }
+    let output = child
+        .wait_with_output()
+        .await
+        .map_err(|e| format!("Failed to query {}: {}", program, e))?;
+    // Helpers exit non-zero with "credentials not found" when there is nothing stored
+    if !output.status.success() {
+        return Ok(None);
+    }
+    serde_json::from_slice(&output.stdout)
+        .map(Some)
+        .map_err(|e| format!("Unexpected output from {}: {}", program, e))
+}
+
+// Credentials stored by `docker login`: a per-registry helper, the global store, then plain auths
+pub async fn stored_credentials(
+    registry: Option<&str>,
+) -> Result<Option<DockerCredentials>, String> {
+    let config = load_docker_config()?;
+    let server = registry.unwrap_or(DOCKER_HUB_AUTH_KEY);
+
+    let helper = config
+        .cred_helpers
+        .get(server)
+        .or(config.creds_store.as_ref());
+    if let Some(helper) = helper {
+        if let Some(credentials) = helper_credentials(helper, server).await? {
+            return Ok(Some(DockerCredentials {
+                username: Some(credentials.username),
+                password: Some(credentials.secret),
+                serveraddress: Some(server.to_string()),
+                ..Default::default()
+            }));
+        }
+    }
+
+    let entry = config.auths.iter().find(|(key, _)| {
+        let host = key
+            .trim_start_matches("https://")
+            .trim_start_matches("http://")
+            .split('/')
+            .next()
+            .unwrap_or_default();
+        key.as_str() == server || registry.is_some_and(|registry| host == registry)
+    });
+    let Some(auth) = entry.and_then(|(_, entry)| entry.auth.as_ref()) else {
+        return Ok(None);
+    };
+    let decoded = STANDARD
+        .decode(auth.trim())
+        .map_err(|e| format!("Invalid auth entry for {}: {}", server, e))?;
+    let decoded = String::from_utf8_lossy(&decoded);
+    let (username, password) = decoded
+        .split_once(':')
+        .ok_or_else(|| format!("Invalid auth entry for {}", server))?;
+    Ok(Some(DockerCredentials {
+        username: Some(username.to_string()),
+        password: Some(password.to_string()),
+        serveraddress: Some(server.to_string()),
+        ..Default::default()
+    }))
+}
+
+// Rejections from the registry read like generic errors, point at the usual cause
+fn describe_push_error(registry: Option<&str>, error: &str) -> String {
+    let lower = error.to_lowercase();
+    if lower.contains("401")
+        || lower.contains("denied")
+        || lower.contains("unauthorized")
+        || lower.contains("authentication required")
+    {
+        match registry {
+            Some(registry) => format!(
+                "{} (check that you are logged in: docker login {})",
+                error, registry
+            ),
+            None => format!("{} (check that you are logged in: docker login)", error),
+        }
+    } else {
+        error.to_string()
+    }
+}
+
+// Pushes `repo[:tag]`, streaming status changes and returning the pushed digest
+pub async fn push_image(
+    image: String,
+    credentials: Option<DockerCredentials>,
+    output: mpsc::UnboundedSender<String>,
+) -> Result<String, String> {
+    read_only::check()?;
+    let registry = registry_of(&image);
+    let (repo, tag) = split_tag(&image);
+    let _ = output.send(format!(
+        "Pushing {}:{} to {}",
+        repo,
+        tag,
+        registry.unwrap_or(DOCKER_HUB)
+    ));
+
+    let docker = connection::connect()?;
+    let mut stream = docker.push_image(repo, Some(PushImageOptions { tag }), credentials);
+
+    // bollard drops the layer ID from push messages, so repeats of the same status are
+    // collapsed instead of forwarding every progress update
+    let mut last_status = None;
+    let mut digest = None;
+    while let Some(message) = stream.next().await {
+        let info = message.map_err(|e| describe_push_error(registry, &e.to_string()))?;
+        if let Some(error) = info.error {
+            let _ = output.send(error.clone());
+            return Err(describe_push_error(registry, &error));
+        }
+        let Some(status) = info.status else {
+            continue;
+        };
+        if let Some((_, rest)) = status.split_once("digest: ") {
+            digest = rest.split_whitespace().next().map(str::to_string);
+        }
+        if last_status.as_ref() != Some(&status) {
+            let _ = output.send(status.clone());
+            last_status = Some(status);
+        }
+    }
+
+    match digest {
+        Some(digest) => Ok(format!("Pushed {}:{} ({})", repo, tag, digest)),
+        None => Ok(format!("Pushed {}:{}", repo, tag)),
+    }
+}
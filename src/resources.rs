@@ -0,0 +1,205 @@
+use bollard::container::{InspectContainerOptions, UpdateContainerOptions};
+use bollard::secret::{RestartPolicy, RestartPolicyNameEnum};
+
+use crate::connection;
+use crate::read_only;
+use crate::utils::{parse_size_in, SizeUnits};
+
+// The daemon refuses memory limits below this
+pub const MIN_MEMORY: i64 = 6 * 1024 * 1024;
+
+pub const RESTART_POLICIES: [&str; 4] = ["no", "always", "unless-stopped", "on-failure"];
+
+// Limits as set on the container, zero means unlimited
+#[derive(Clone, Debug, Default)]
+pub struct ResourceLimits {
+    pub memory: i64,
+    // Memory plus swap, -1 is unlimited swap
+    pub memory_swap: i64,
+    pub nano_cpus: i64,
+    pub restart_policy: String,
+    // Only meaningful with on-failure, zero is unlimited
+    pub restart_max_retries: i64,
+}
+
+// Parses sizes like the docker CLI does: "512m", "1.5g", "1048576" (bytes), with k, m and g
+// counting in 1024s
+pub fn parse_memory(input: &str) -> Result<i64, String> {
+    let bytes = parse_size_in(input, SizeUnits::Binary)?;
+    let bytes = i64::try_from(bytes)
+        .map_err(|_| format!("Memory limit '{}' is too large", input.trim()))?;
+    if bytes != 0 && bytes < MIN_MEMORY {
+        return Err("Memory limit must be at least 6m".to_string());
+    }
+    Ok(bytes)
+}
+
+// Fractional CPUs like "1.5", converted to the nano CPUs the API expects
+pub fn parse_cpus(input: &str) -> Result<i64, String> {
+    let cpus: f64 = input
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid number of CPUs '{}'", input.trim()))?;
+    if !(0.0..=1024.0).contains(&cpus) {
+        return Err(format!("Number of CPUs out of range: {}", cpus));
+    }
+    Ok((cpus * 1e9).round() as i64)
+}
+
+pub fn format_memory(bytes: i64) -> String {
+    if bytes == 0 {
+        String::new()
+    } else if bytes % (1024 * 1024 * 1024) == 0 {
+        format!("{}g", bytes / (1024 * 1024 * 1024))
+    } else if bytes % (1024 * 1024) == 0 {
+        format!("{}m", bytes / (1024 * 1024))
+    } else if bytes % 1024 == 0 {
+        format!("{}k", bytes / 1024)
+    } else {
+        bytes.to_string()
+    }
+}
+
+pub fn format_cpus(nano_cpus: i64) -> String {
+    if nano_cpus == 0 {
+        String::new()
+    } else {
+        (nano_cpus as f64 / 1e9).to_string()
+    }
+}
+
+pub async fn current_limits(container_id: String) -> Result<ResourceLimits, String> {
+    let docker = connection::connect()?;
+    let inspect = docker
+        .inspect_container(&container_id, None::<InspectContainerOptions>)
+        .await
+        .map_err(|e| format!("Failed to inspect container {}: {}", container_id, e))?;
+    let host_config = inspect.host_config.unwrap_or_default();
+    let restart_policy = host_config.restart_policy.unwrap_or_default();
+    Ok(ResourceLimits {
+        memory: host_config.memory.unwrap_or_default(),
+        memory_swap: host_config.memory_swap.unwrap_or_default(),
+        nano_cpus: host_config.nano_cpus.unwrap_or_default(),
+        restart_policy: restart_policy
+            .name
+            .map(|name| name.to_string())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| "no".to_string()),
+        restart_max_retries: restart_policy.maximum_retry_count.unwrap_or_default(),
+    })
+}
+
+// E.g. "on-failure (max 3 retries)", no count is unlimited
+pub fn describe_restart_policy(name: &str, max_retries: i64) -> String {
+    match (name, max_retries) {
+        ("", _) => "no".to_string(),
+        ("on-failure", 1) => "on-failure (max 1 retry)".to_string(),
+        ("on-failure", retries) if retries > 0 => {
+            format!("on-failure (max {} retries)", retries)
+        }
+        (name, _) => name.to_string(),
+    }
+}
+
+// The policy `docker update --restart` would set, the daemon only takes a retry count with
+// on-failure
+pub fn restart_policy(name: &str, max_retries: i64) -> Result<RestartPolicy, String> {
+    let policy = restart_policy_name(name)?;
+    if max_retries < 0 {
+        return Err("Maximum retry count cannot be negative".to_string());
+    }
+    if max_retries > 0 && policy != RestartPolicyNameEnum::ON_FAILURE {
+        return Err(format!("Restart policy '{}' takes no retry count", name));
+    }
+    Ok(RestartPolicy {
+        name: Some(policy),
+        maximum_retry_count: Some(max_retries),
+    })
+}
+
+fn restart_policy_name(name: &str) -> Result<RestartPolicyNameEnum, String> {
+    match name {
+        "no" => Ok(RestartPolicyNameEnum::NO),
+        "always" => Ok(RestartPolicyNameEnum::ALWAYS),
+        "unless-stopped" => Ok(RestartPolicyNameEnum::UNLESS_STOPPED),
+        "on-failure" => Ok(RestartPolicyNameEnum::ON_FAILURE),
+        _ => Err(format!("Unknown restart policy '{}'", name)),
+    }
+}
+
+// The policy to send for the one picked in the limits form, none when it is unchanged so the
+// daemon keeps the container's retry count
+pub fn restart_policy_change(
+    selected: &str,
+    current: &ResourceLimits,
+) -> Result<Option<RestartPolicy>, String> {
+    if selected.is_empty() || selected == current.restart_policy {
+        return Ok(None);
+    }
+    restart_policy(selected, 0).map(Some)
+}
+
+// The swap limit to send with a new memory limit. The daemon refuses memory above the swap
+// limit, so a container's swap on top of its memory is kept rather than the total, and a
+// container without a swap limit keeps unlimited swap.
+pub fn memory_swap(memory: Option<i64>, current: &ResourceLimits) -> Option<i64> {
+    let memory = memory?;
+    if current.memory_swap <= 0 || memory == 0 {
+        return Some(-1);
+    }
+    let swap = (current.memory_swap - current.memory).max(0);
+    Some(memory.saturating_add(swap))
+}
+
+// Changes limits in place, like `docker update`, without recreating the container
+pub async fn update_resources(
+    container_id: String,
+    memory: Option<i64>,
+    nano_cpus: Option<i64>,
+    restart_policy: Option<RestartPolicy>,
+) -> Result<String, String> {
+    read_only::check()?;
+    let memory_swap = match memory {
+        Some(_) => memory_swap(memory, &current_limits(container_id.clone()).await?),
+        None => None,
+    };
+    let docker = connection::connect()?;
+    let options = UpdateContainerOptions::<String> {
+        memory,
+        memory_swap,
+        nano_cp_us: nano_cpus,
+        restart_policy,
+        ..Default::default()
+    };
+    docker
+        .update_container(&container_id, options)
+        .await
+        .map_err(|e| format!("Failed to update container {}: {}", container_id, e))?;
+    let short_id: String = container_id.chars().take(12).collect();
+    Ok(format!("Updated the limits of {}", short_id))
+}
+
+// Takes effect right away, also on a running container
+pub async fn update_restart_policy(
+    container_id: String,
+    name: String,
+    max_retries: i64,
+) -> Result<String, String> {
+    read_only::check()?;
+    let policy = restart_policy(&name, max_retries)?;
+    let docker = connection::connect()?;
+    let options = UpdateContainerOptions::<String> {
+        restart_policy: Some(policy),
+        ..Default::default()
+    };
+    docker
+        .update_container(&container_id, options)
+        .await
+        .map_err(|e| format!("Failed to update container {}: {}", container_id, e))?;
+    let short_id: String = container_id.chars().take(12).collect();
+    Ok(format!(
+        "Restart policy of {} is now {}",
+        short_id,
+        describe_restart_policy(&name, max_retries)
+    ))
+}
@@ -0,0 +1,101 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use bollard::container::InspectContainerOptions;
+
+use crate::connection;
+use crate::poller::ContainerSnapshot;
+
+// From this many restarts on the badge turns red, the container is likely crash looping
+pub const RESTART_WARN_THRESHOLD: i64 = 5;
+
+// What only an inspect tells about a container's restarts
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RestartInfo {
+    // Restarts by the daemon's restart policy
+    pub restart_count: i64,
+    // Whether its last exit was an OOM kill
+    pub oom_killed: bool,
+}
+
+impl RestartInfo {
+    pub fn badge(&self) -> Option<String> {
+        (self.restart_count > 0).then(|| format!("↻ {}", self.restart_count))
+    }
+
+    pub fn is_looping(&self) -> bool {
+        self.restart_count >= RESTART_WARN_THRESHOLD
+    }
+}
+
+// Restart counts of the listed containers. A container is inspected again only when its
+// status text changes, so an idle list costs no API calls.
+#[derive(Default)]
+pub struct RestartTracker {
+    // Status each container was last inspected at
+    inspected: HashMap<String, String>,
+    info: HashMap<String, RestartInfo>,
+    // Inspects sent and not answered yet
+    pending: usize,
+}
+
+impl RestartTracker {
+    // Containers to inspect for this snapshot, they count as inspected from now on. Gone
+    // containers are forgotten.
+    pub fn due(&mut self, snapshot: &ContainerSnapshot) -> Vec<String> {
+        self.inspected.retain(|id, _| snapshot.contains_key(id));
+        self.info.retain(|id, _| snapshot.contains_key(id));
+        let mut due = Vec::new();
+        for (id, (summary, _)) in snapshot {
+            let status = summary.status.as_deref().unwrap_or_default();
+            if self.inspected.get(id).map(String::as_str) != Some(status) {
+                self.inspected.insert(id.clone(), status.to_string());
+                due.push(id.clone());
+            }
+        }
+        self.pending += due.len();
+        due.sort();
+        due
+    }
+
+    // Returns whether the container's info changed. A failed inspect is dropped, the
+    // container was most likely removed in the meantime.
+    pub fn record(&mut self, id: String, info: Result<RestartInfo, String>) -> bool {
+        self.pending = self.pending.saturating_sub(1);
+        match info {
+            Ok(info) if self.inspected.contains_key(&id) => {
+                self.info.insert(id, info) != Some(info)
+            }
+            _ => false,
+        }
+    }
+
+    // Not inspected yet counts as never restarted
+    pub fn get(&self, id: &str) -> RestartInfo {
+        self.info.get(id).copied().unwrap_or_default()
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.pending > 0
+    }
+
+    // Most restarts first, keeping the order of containers restarted equally often
+    pub fn sort(&self, ids: &mut [String]) {
+        ids.sort_by_key(|id| Reverse(self.get(id).restart_count));
+    }
+}
+
+pub async fn inspect_restarts(container_id: String) -> Result<RestartInfo, String> {
+    let docker = connection::connect()?;
+    let inspect = docker
+        .inspect_container(&container_id, None::<InspectContainerOptions>)
+        .await
+        .map_err(|e| format!("Failed to inspect container {}: {}", container_id, e))?;
+    Ok(RestartInfo {
+        restart_count: inspect.restart_count.unwrap_or_default(),
+        oom_killed: inspect
+            .state
+            .and_then(|state| state.oom_killed)
+            .unwrap_or_default(),
+    })
+}
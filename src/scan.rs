@@ -0,0 +1,193 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use tokio::sync::mpsc;
+use walkdir::{DirEntry, WalkDir};
+
+use crate::error::{report, DockerrsError, ErrorSender};
+
+pub const DEFAULT_EXCLUDES: &[&str] = &["node_modules", ".git", "target", "vendor"];
+
+const COMPOSE_STEMS: &[&str] = &["compose", "docker-compose", "docker_compose"];
+
+// The file name without `.yml`/`.yaml`, lowercased, if it is a YAML file
+fn yaml_stem(file_name: &str) -> Option<String> {
+    let lower = file_name.to_lowercase();
+    lower
+        .strip_suffix(".yaml")
+        .or_else(|| lower.strip_suffix(".yml"))
+        .map(str::to_string)
+}
+
+// Matches every file name docker compose picks up by default, including override files
+pub fn is_compose_file(file_name: &str) -> bool {
+    yaml_stem(file_name).is_some_and(|stem| {
+        let base = stem.strip_suffix(".override").unwrap_or(&stem);
+        COMPOSE_STEMS.contains(&base)
+    })
+}
+
+pub fn is_compose_override(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .and_then(yaml_stem)
+        .is_some_and(|stem| stem.ends_with(".override"))
+}
+
+// Sort key that keeps override files directly below the base file in the same directory
+pub fn compose_sort_key(path: &Path) -> (PathBuf, String, bool) {
+    let stem = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .and_then(yaml_stem)
+        .unwrap_or_default();
+    let is_override = stem.ends_with(".override");
+    let base = stem.trim_end_matches(".override").to_string();
+    (
+        path.parent().map(Path::to_owned).unwrap_or_default(),
+        base,
+        is_override,
+    )
+}
+
+// The files compose would load for `path`: a base file with its overrides, or an
+// override together with the base file it extends
+pub fn compose_file_set(path: &Path, known: &[PathBuf]) -> Vec<PathBuf> {
+    let (directory, base, _) = compose_sort_key(path);
+    let mut files: Vec<PathBuf> = known
+        .iter()
+        .filter(|other| {
+            let (other_directory, other_base, _) = compose_sort_key(other);
+            other_directory == directory && other_base == base
+        })
+        .cloned()
+        .collect();
+    files.sort_by_key(|file| compose_sort_key(file));
+    if files.is_empty() {
+        files.push(path.to_owned());
+    }
+    files
+}
+
+fn is_excluded(entry: &DirEntry, excludes: &[String]) -> bool {
+    entry.depth() > 0
+        && entry.file_type().is_dir()
+        && entry
+            .file_name()
+            .to_str()
+            .is_some_and(|name| excludes.iter().any(|exclude| exclude == name))
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ScanSettings {
+    pub roots: Vec<PathBuf>,
+    // Directory names that are never descended into
+    pub excludes: Vec<String>,
+    pub max_depth: Option<usize>,
+}
+
+// A directory walk running on a blocking thread, streaming matching files as they are found.
+// Dropping the scan cancels the walk.
+pub struct Scan {
+    pub receiver: mpsc::UnboundedReceiver<PathBuf>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Scan {
+    pub fn start(settings: ScanSettings, matches: fn(&str) -> bool, errors: ErrorSender) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let walk_cancelled = cancelled.clone();
+        tokio::task::spawn_blocking(move || {
+            walk(&settings, matches, &sender, &walk_cancelled, &errors);
+        });
+        Self {
+            receiver,
+            cancelled,
+        }
+    }
+}
+
+impl Drop for Scan {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+// Walks every root, skipping excluded directories, and sends the absolute path of each
+// matching file once, even when it is reachable from more than one root. Unreadable paths are
+// skipped and reported once at the end, a home directory has plenty of them.
+fn walk(
+    settings: &ScanSettings,
+    matches: fn(&str) -> bool,
+    sender: &mpsc::UnboundedSender<PathBuf>,
+    cancelled: &AtomicBool,
+    errors: &ErrorSender,
+) {
+    let mut seen = HashSet::new();
+    let mut skipped = 0;
+    let mut first_error = None;
+    for root in &settings.roots {
+        let mut walker = WalkDir::new(root);
+        if let Some(max_depth) = settings.max_depth {
+            walker = walker.max_depth(max_depth);
+        }
+        let entries = walker
+            .into_iter()
+            .filter_entry(|entry| !is_excluded(entry, &settings.excludes));
+        for entry in entries {
+            if cancelled.load(Ordering::Relaxed) {
+                return;
+            }
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    skipped += 1;
+                    first_error.get_or_insert_with(|| std::io::Error::from(e));
+                    continue;
+                }
+            };
+            // The walk reports a symlink's own type, a linked file counts when its target is one
+            if !entry.file_name().to_str().is_some_and(matches) || !entry.path().is_file() {
+                continue;
+            }
+            // Resolve the path to an absolute path
+            match entry.path().canonicalize() {
+                Ok(path) => {
+                    if seen.insert(path.clone()) && sender.send(path).is_err() {
+                        return;
+                    }
+                }
+                Err(e) => {
+                    skipped += 1;
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+    }
+    if let Some(source) = first_error {
+        let context = match skipped {
+            1 => "Skipped an unreadable path while scanning".to_string(),
+            count => format!(
+                "Skipped {} unreadable paths while scanning, the first",
+                count
+            ),
+        };
+        report(errors, DockerrsError::Io { context, source });
+    }
+}
+
+// Dockerfile, Dockerfile.dev, Dockerfile-prod, api.dockerfile, ... but not files with an
+// extension of their own after the variant, like dockerfile-notes.md
+pub fn is_dockerfile(file_name: &str) -> bool {
+    let lower = file_name.to_lowercase();
+    let variant = lower.strip_prefix("dockerfile").is_some_and(|rest| {
+        rest.is_empty()
+            || rest
+                .strip_prefix(['.', '-', '_'])
+                .is_some_and(|variant| !variant.is_empty() && !variant.contains('.'))
+    });
+    variant || lower.ends_with(".dockerfile")
+}
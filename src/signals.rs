@@ -0,0 +1,57 @@
+// The signal Kill sends unless another one is picked
+pub const SIGKILL: &str = "SIGKILL";
+
+// Offered by the signal picker next to Kill, any other known signal can be typed
+pub const COMMON_SIGNALS: [&str; 6] = [
+    "SIGTERM", "SIGHUP", "SIGINT", "SIGUSR1", "SIGUSR2", "SIGQUIT",
+];
+
+// Linux signals by name and number, the daemon takes the names
+const SIGNALS: [(&str, u8); 31] = [
+    ("SIGHUP", 1),
+    ("SIGINT", 2),
+    ("SIGQUIT", 3),
+    ("SIGILL", 4),
+    ("SIGTRAP", 5),
+    ("SIGABRT", 6),
+    ("SIGBUS", 7),
+    ("SIGFPE", 8),
+    ("SIGKILL", 9),
+    ("SIGUSR1", 10),
+    ("SIGSEGV", 11),
+    ("SIGUSR2", 12),
+    ("SIGPIPE", 13),
+    ("SIGALRM", 14),
+    ("SIGTERM", 15),
+    ("SIGSTKFLT", 16),
+    ("SIGCHLD", 17),
+    ("SIGCONT", 18),
+    ("SIGSTOP", 19),
+    ("SIGTSTP", 20),
+    ("SIGTTIN", 21),
+    ("SIGTTOU", 22),
+    ("SIGURG", 23),
+    ("SIGXCPU", 24),
+    ("SIGXFSZ", 25),
+    ("SIGVTALRM", 26),
+    ("SIGPROF", 27),
+    ("SIGWINCH", 28),
+    ("SIGIO", 29),
+    ("SIGPWR", 30),
+    ("SIGSYS", 31),
+];
+
+// A signal as typed, e.g. "SIGTERM", "term" or "15", by its name. Unknown signals are
+// rejected here rather than by the daemon, after the container was picked.
+pub fn parse_signal(input: &str) -> Result<&'static str, String> {
+    let input = input.trim();
+    let upper = input.to_uppercase();
+    let name = upper.strip_prefix("SIG").unwrap_or(&upper);
+    SIGNALS
+        .iter()
+        .find(|(signal, number)| {
+            signal[3..] == *name || input.parse::<u8>().is_ok_and(|n| n == *number)
+        })
+        .map(|(signal, _)| *signal)
+        .ok_or_else(|| format!("Unknown signal: {}", input))
+}
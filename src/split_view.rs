@@ -0,0 +1,72 @@
+use std::time::{Duration, Instant};
+
+// How long the selection has to stay on a container before its logs are streamed, so
+// moving through the list does not open a stream per row
+pub const SWITCH_DELAY: Duration = Duration::from_millis(300);
+
+// The half of the split view the scroll keys act on, Tab switches
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Pane {
+    #[default]
+    List,
+    Logs,
+}
+
+impl Pane {
+    pub fn other(self) -> Self {
+        match self {
+            Pane::List => Pane::Logs,
+            Pane::Logs => Pane::List,
+        }
+    }
+}
+
+// The container list next to the live logs of the selected container
+#[derive(Default)]
+pub struct SplitView {
+    pub enabled: bool,
+    pub focus: Pane,
+    // Whose logs are streamed
+    streamed: Option<String>,
+    // The selection waiting for `SWITCH_DELAY` to pass, and since when
+    candidate: Option<(String, Instant)>,
+}
+
+impl SplitView {
+    pub fn toggle(&mut self) {
+        self.enabled = !self.enabled;
+        self.focus = Pane::List;
+        self.streamed = None;
+        self.candidate = None;
+    }
+
+    // The container to stream for the current selection. The first one is streamed right
+    // away, a different one once it has been selected for `SWITCH_DELAY`.
+    pub fn streamed(&mut self, selected: Option<&String>, now: Instant) -> Option<&String> {
+        let Some(selected) = selected else {
+            self.streamed = None;
+            self.candidate = None;
+            return None;
+        };
+        if self.streamed.is_none() || self.streamed.as_ref() == Some(selected) {
+            self.streamed = Some(selected.clone());
+            self.candidate = None;
+            return self.streamed.as_ref();
+        }
+        match &self.candidate {
+            Some((id, since)) if id == selected => {
+                if now.duration_since(*since) >= SWITCH_DELAY {
+                    self.streamed = Some(selected.clone());
+                    self.candidate = None;
+                }
+            }
+            _ => self.candidate = Some((selected.clone(), now)),
+        }
+        self.streamed.as_ref()
+    }
+
+    // Whether a switch is waiting, the frames have to keep coming until it happens
+    pub fn is_switching(&self) -> bool {
+        self.candidate.is_some()
+    }
+}
@@ -0,0 +1,167 @@
+use std::collections::VecDeque;
+
+use bollard::container::{MemoryStatsStats, Stats, StatsOptions};
+use futures_util::stream::StreamExt;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::connection;
+use crate::error::{report, DockerrsError, ErrorSender};
+
+// Samples kept per container by default, about two minutes at one per second. Set with
+// --stats-samples.
+pub const DEFAULT_STATS_SAMPLES: usize = 120;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StatsSample {
+    pub cpu_percent: f64,
+    pub memory: u64,
+}
+
+// CPU use since the previous sample like `docker stats` shows it, 100% per busy core.
+// None for the first sample of a stream, which has nothing to compare with.
+pub fn cpu_percent(stats: &Stats) -> Option<f64> {
+    let cpu_delta = stats
+        .cpu_stats
+        .cpu_usage
+        .total_usage
+        .checked_sub(stats.precpu_stats.cpu_usage.total_usage)?;
+    let system_delta = stats
+        .cpu_stats
+        .system_cpu_usage?
+        .checked_sub(stats.precpu_stats.system_cpu_usage?)?;
+    if system_delta == 0 {
+        return None;
+    }
+    let cpus = stats.cpu_stats.online_cpus.unwrap_or_else(|| {
+        stats
+            .cpu_stats
+            .cpu_usage
+            .percpu_usage
+            .as_ref()
+            .map_or(1, |usage| usage.len() as u64)
+    });
+    Some(cpu_delta as f64 / system_delta as f64 * cpus as f64 * 100.0)
+}
+
+// Memory in use without the page cache the kernel can reclaim, like `docker stats`
+pub fn memory_usage(stats: &Stats) -> Option<u64> {
+    let usage = stats.memory_stats.usage?;
+    let inactive = match stats.memory_stats.stats {
+        Some(MemoryStatsStats::V1(v1)) => v1.total_inactive_file,
+        Some(MemoryStatsStats::V2(v2)) => v2.inactive_file,
+        None => 0,
+    };
+    Some(usage.saturating_sub(inactive))
+}
+
+// The newest samples of one container, oldest first
+#[derive(Clone, Debug)]
+pub struct StatsHistory {
+    pub samples: VecDeque<StatsSample>,
+    capacity: usize,
+}
+
+impl StatsHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::new(),
+            capacity: capacity.max(2),
+        }
+    }
+
+    pub fn push(&mut self, sample: StatsSample) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    pub fn cpu(&self) -> Vec<f64> {
+        self.samples
+            .iter()
+            .map(|sample| sample.cpu_percent)
+            .collect()
+    }
+
+    pub fn memory(&self) -> Vec<f64> {
+        self.samples
+            .iter()
+            .map(|sample| sample.memory as f64)
+            .collect()
+    }
+}
+
+// Current, min and max of a series, None when it is empty
+pub fn summary(values: &[f64]) -> Option<(f64, f64, f64)> {
+    let current = *values.last()?;
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    Some((current, min, max))
+}
+
+// Streams the stats of one container until the receiver is dropped or the container stops
+pub async fn stream_stats(
+    container_id: String,
+    sender: mpsc::UnboundedSender<StatsSample>,
+    errors: ErrorSender,
+) {
+    let docker = match connection::connect() {
+        Ok(docker) => docker,
+        Err(e) => {
+            return report(
+                &errors,
+                DockerrsError::docker("Failed to connect to Docker", e),
+            )
+        }
+    };
+    let options = StatsOptions {
+        stream: true,
+        one_shot: false,
+    };
+    let mut stream = docker.stats(&container_id, Some(options));
+    while let Some(stats) = stream.next().await {
+        let stats = match stats {
+            Ok(stats) => stats,
+            Err(e) => {
+                let context = format!("Stats of {} ended", container_id);
+                return report(&errors, DockerrsError::docker(context, e));
+            }
+        };
+        let (Some(cpu_percent), Some(memory)) = (cpu_percent(&stats), memory_usage(&stats)) else {
+            continue;
+        };
+        let sample = StatsSample {
+            cpu_percent,
+            memory,
+        };
+        if sender.send(sample).is_err() {
+            return;
+        }
+    }
+}
+
+// The stats stream of the selected container, stopped when dropped
+pub struct StatsStream {
+    pub container_id: String,
+    pub receiver: mpsc::UnboundedReceiver<StatsSample>,
+    task: JoinHandle<()>,
+}
+
+impl StatsStream {
+    pub fn start(container_id: String, errors: ErrorSender) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let task = tokio::spawn(stream_stats(container_id.clone(), sender, errors));
+        Self {
+            container_id,
+            receiver,
+            task,
+        }
+    }
+}
+
+impl Drop for StatsStream {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
@@ -0,0 +1,76 @@
+use bollard::secret::SystemInfo;
+use bollard::system::Version;
+
+use crate::connection;
+use crate::utils::format_size;
+
+// What `docker info` and `docker version` say about the daemon, as label and value rows
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DaemonInfo {
+    pub rows: Vec<(&'static str, String)>,
+    pub warnings: Vec<String>,
+}
+
+fn or_unknown(value: Option<&str>) -> String {
+    match value {
+        Some(value) if !value.is_empty() => value.to_string(),
+        _ => "unknown".to_string(),
+    }
+}
+
+// Fields the daemon leaves out show as "unknown". OS and architecture come from the version
+// since the info reports the host's distribution name instead.
+pub fn summarize(info: &SystemInfo, version: &Version) -> DaemonInfo {
+    let os = version.os.as_deref().or(info.os_type.as_deref());
+    let arch = version.arch.as_deref().or(info.architecture.as_deref());
+    // Both enums have an empty variant for daemons that leave them blank
+    let cgroup_version = info.cgroup_version.map(|version| version.to_string());
+    let cgroup_driver = info.cgroup_driver.map(|driver| driver.to_string());
+    let cgroup = match (cgroup_version.as_deref(), cgroup_driver.as_deref()) {
+        (None | Some(""), _) => "unknown".to_string(),
+        (Some(version), None | Some("")) => format!("v{}", version),
+        (Some(version), Some(driver)) => format!("v{} ({})", version, driver),
+    };
+    let containers = format!(
+        "{} ({} running, {} paused, {} stopped)",
+        info.containers.unwrap_or_default(),
+        info.containers_running.unwrap_or_default(),
+        info.containers_paused.unwrap_or_default(),
+        info.containers_stopped.unwrap_or_default()
+    );
+    let resources = format!(
+        "{} CPUs, {}",
+        info.ncpu.unwrap_or_default(),
+        format_size(info.mem_total.unwrap_or_default())
+    );
+    DaemonInfo {
+        rows: vec![
+            ("Daemon version", or_unknown(version.version.as_deref())),
+            ("API version", or_unknown(version.api_version.as_deref())),
+            (
+                "OS/arch",
+                format!("{}/{}", or_unknown(os), or_unknown(arch)),
+            ),
+            ("Host", or_unknown(info.operating_system.as_deref())),
+            ("Kernel", or_unknown(info.kernel_version.as_deref())),
+            ("Resources", resources),
+            ("Containers", containers),
+            ("Images", info.images.unwrap_or_default().to_string()),
+            ("Storage driver", or_unknown(info.driver.as_deref())),
+            ("Cgroup", cgroup),
+        ],
+        warnings: info.warnings.clone().unwrap_or_default(),
+    }
+}
+
+// Also a check that the daemon answers at all, so a failure names the socket it tried
+pub async fn daemon_info() -> Result<DaemonInfo, String> {
+    let candidate = connection::target()?;
+    let unreachable = |e: bollard::errors::Error| {
+        format!("Cannot reach the Docker daemon at {}: {}", candidate, e)
+    };
+    let docker = connection::connect_to(&candidate).map_err(unreachable)?;
+    let info = docker.info().await.map_err(unreachable)?;
+    let version = docker.version().await.map_err(unreachable)?;
+    Ok(summarize(&info, &version))
+}
@@ -0,0 +1,136 @@
+use std::path::{Path, PathBuf};
+
+use bollard::errors::Error;
+use bollard::image::ImportImageOptions;
+use futures_util::stream::{Stream, StreamExt};
+use tokio::fs::File;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::mpsc;
+
+use crate::connection;
+use crate::read_only;
+use crate::utils::format_size;
+
+// Bytes between two progress updates, multi-GB exports should visibly move
+const PROGRESS_INTERVAL: u64 = 16 * 1024 * 1024;
+
+// Writes a tar stream to `path`, removing the partial file when anything goes wrong
+pub async fn write_stream<S, B>(
+    mut stream: S,
+    path: &Path,
+    what: &str,
+    progress: &mpsc::UnboundedSender<Result<String, String>>,
+) -> Result<u64, String>
+where
+    S: Stream<Item = Result<B, Error>> + Unpin,
+    B: AsRef<[u8]>,
+{
+    let mut file = File::create(path)
+        .await
+        .map_err(|e| format!("Failed to create {:?}: {}", path, e))?;
+    let mut written = 0u64;
+    let mut reported = 0u64;
+    let result: Result<(), String> = async {
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.map_err(|e| format!("Failed to export {}: {}", what, e))?;
+            // Disk full and permission errors surface here
+            file.write_all(chunk.as_ref())
+                .await
+                .map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+            written += chunk.as_ref().len() as u64;
+            if written - reported >= PROGRESS_INTERVAL {
+                reported = written;
+                let _ = progress.send(Ok(format!(
+                    "Exporting {}: {} written",
+                    what,
+                    format_size(written as i64)
+                )));
+            }
+        }
+        file.flush()
+            .await
+            .map_err(|e| format!("Failed to write {:?}: {}", path, e))
+    }
+    .await;
+
+    if let Err(e) = result {
+        drop(file);
+        let _ = tokio::fs::remove_file(path).await;
+        return Err(e);
+    }
+    Ok(written)
+}
+
+// The filesystem of a container as a tarball, like `docker export`
+pub async fn export_container(
+    container_id: String,
+    path: PathBuf,
+    progress: mpsc::UnboundedSender<Result<String, String>>,
+) -> Result<String, String> {
+    let docker = connection::connect()?;
+    let stream = Box::pin(docker.export_container(&container_id));
+    let written = write_stream(stream, &path, &container_id, &progress).await?;
+    Ok(format!(
+        "Exported {} to {:?} ({})",
+        container_id,
+        path,
+        format_size(written as i64)
+    ))
+}
+
+// An image with all its layers and tags, like `docker save`
+pub async fn save_image(
+    image: String,
+    path: PathBuf,
+    progress: mpsc::UnboundedSender<Result<String, String>>,
+) -> Result<String, String> {
+    let docker = connection::connect()?;
+    let stream = Box::pin(docker.export_image(&image));
+    let written = write_stream(stream, &path, &image, &progress).await?;
+    Ok(format!(
+        "Saved {} to {:?} ({})",
+        image,
+        path,
+        format_size(written as i64)
+    ))
+}
+
+// Loads a tarball written by `docker save`, like `docker load`, and reports the loaded tags
+pub async fn load_image(
+    path: PathBuf,
+    progress: mpsc::UnboundedSender<Result<String, String>>,
+) -> Result<String, String> {
+    read_only::check()?;
+    // bollard only takes the tarball as one buffer
+    let tarball = tokio::fs::read(&path)
+        .await
+        .map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+    let _ = progress.send(Ok(format!(
+        "Loading {:?}: sending {}",
+        path,
+        format_size(tarball.len() as i64)
+    )));
+
+    let docker = connection::connect()?;
+    let mut stream = docker.import_image(ImportImageOptions { quiet: true }, tarball.into(), None);
+    let mut loaded = Vec::new();
+    while let Some(message) = stream.next().await {
+        let info = message.map_err(|e| format!("Failed to load {:?}: {}", path, e))?;
+        if let Some(error) = info.error {
+            return Err(format!("Failed to load {:?}: {}", path, error));
+        }
+        for line in info.stream.iter().flat_map(|stream| stream.lines()) {
+            if let Some(image) = line
+                .strip_prefix("Loaded image: ")
+                .or_else(|| line.strip_prefix("Loaded image ID: "))
+            {
+                loaded.push(image.trim().to_string());
+            }
+        }
+    }
+    if loaded.is_empty() {
+        Ok(format!("Loaded {:?}", path))
+    } else {
+        Ok(format!("Loaded {}", loaded.join(", ")))
+    }
+}
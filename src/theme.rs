@@ -0,0 +1,154 @@
+use clap::ValueEnum;
+use eframe::egui::{self, Color32};
+use serde::{Deserialize, Serialize};
+
+// Picked with --theme or `theme:` in the config file
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThemeName {
+    #[default]
+    Default,
+    Monochrome,
+    HighContrast,
+}
+
+// The flag wins over the config file, which wins over NO_COLOR (https://no-color.org),
+// which turns the default into monochrome
+pub fn select(flag: Option<ThemeName>, config: Option<ThemeName>, no_color: bool) -> ThemeName {
+    match flag.or(config) {
+        Some(name) => name,
+        None if no_color => ThemeName::Monochrome,
+        None => ThemeName::Default,
+    }
+}
+
+// NO_COLOR counts when it is set to anything but the empty string
+pub fn no_color_requested() -> bool {
+    std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+}
+
+// Every color the app draws with, so no view hardcodes one
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Theme {
+    pub name: ThemeName,
+    pub error: Color32,
+    pub warning: Color32,
+    pub success: Color32,
+    // Debug log lines and comments
+    pub muted: Color32,
+    // The layer an image history row is selected at
+    pub emphasis: Color32,
+    // Text on a `warning` background, like the read-only badge
+    pub on_warning: Color32,
+    pub cpu: Color32,
+    pub memory: Color32,
+    // Container name prefixes in interleaved logs
+    pub prefixes: [Color32; 8],
+    pub keyword: Color32,
+    pub continuation: Color32,
+    pub heredoc: Color32,
+    pub variable: Color32,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::new(ThemeName::Default)
+    }
+}
+
+impl Theme {
+    pub fn new(name: ThemeName) -> Self {
+        match name {
+            ThemeName::Default => Self {
+                name,
+                error: Color32::RED,
+                warning: Color32::YELLOW,
+                success: Color32::GREEN,
+                muted: Color32::GRAY,
+                emphasis: Color32::WHITE,
+                on_warning: Color32::BLACK,
+                cpu: Color32::LIGHT_BLUE,
+                memory: Color32::LIGHT_GREEN,
+                prefixes: [
+                    Color32::from_rgb(0x4e, 0xc9, 0xb0),
+                    Color32::from_rgb(0x56, 0x9c, 0xd6),
+                    Color32::from_rgb(0xc5, 0x86, 0xc0),
+                    Color32::from_rgb(0xdc, 0xdc, 0xaa),
+                    Color32::from_rgb(0xce, 0x91, 0x78),
+                    Color32::from_rgb(0x9c, 0xdc, 0xfe),
+                    Color32::from_rgb(0xd1, 0x69, 0x69),
+                    Color32::from_rgb(0xb5, 0xce, 0xa8),
+                ],
+                keyword: Color32::from_rgb(86, 156, 214),
+                continuation: Color32::from_rgb(197, 134, 192),
+                heredoc: Color32::from_rgb(206, 145, 120),
+                variable: Color32::from_rgb(78, 201, 176),
+            },
+            // Brightness only, the text says what a color would
+            ThemeName::Monochrome => Self {
+                name,
+                error: Color32::WHITE,
+                warning: Color32::from_gray(220),
+                success: Color32::from_gray(200),
+                muted: Color32::GRAY,
+                emphasis: Color32::WHITE,
+                on_warning: Color32::BLACK,
+                cpu: Color32::LIGHT_GRAY,
+                memory: Color32::LIGHT_GRAY,
+                prefixes: [Color32::LIGHT_GRAY; 8],
+                keyword: Color32::WHITE,
+                continuation: Color32::LIGHT_GRAY,
+                heredoc: Color32::LIGHT_GRAY,
+                variable: Color32::LIGHT_GRAY,
+            },
+            // Saturated colors on black
+            ThemeName::HighContrast => Self {
+                name,
+                error: Color32::from_rgb(255, 90, 90),
+                warning: Color32::from_rgb(255, 255, 0),
+                success: Color32::from_rgb(0, 255, 0),
+                muted: Color32::from_gray(200),
+                emphasis: Color32::WHITE,
+                on_warning: Color32::BLACK,
+                cpu: Color32::from_rgb(0, 200, 255),
+                memory: Color32::from_rgb(0, 255, 0),
+                prefixes: [
+                    Color32::from_rgb(0, 255, 255),
+                    Color32::from_rgb(255, 255, 0),
+                    Color32::from_rgb(255, 0, 255),
+                    Color32::from_rgb(0, 255, 0),
+                    Color32::from_rgb(255, 160, 0),
+                    Color32::from_rgb(120, 170, 255),
+                    Color32::from_rgb(255, 90, 90),
+                    Color32::WHITE,
+                ],
+                keyword: Color32::from_rgb(0, 255, 255),
+                continuation: Color32::from_rgb(255, 0, 255),
+                heredoc: Color32::from_rgb(255, 160, 0),
+                variable: Color32::from_rgb(0, 255, 0),
+            },
+        }
+    }
+
+    // Widget colors that go with the theme, the default keeps egui's own
+    pub fn apply(&self, ctx: &egui::Context) {
+        let mut visuals = egui::Visuals::dark();
+        match self.name {
+            ThemeName::Default => return,
+            ThemeName::Monochrome => {
+                visuals.selection.bg_fill = Color32::from_gray(90);
+                visuals.hyperlink_color = Color32::WHITE;
+            }
+            ThemeName::HighContrast => {
+                visuals.override_text_color = Some(Color32::WHITE);
+                visuals.panel_fill = Color32::BLACK;
+                visuals.window_fill = Color32::BLACK;
+                visuals.extreme_bg_color = Color32::BLACK;
+                visuals.faint_bg_color = Color32::from_gray(30);
+                visuals.selection.bg_fill = Color32::from_rgb(0, 90, 200);
+                visuals.hyperlink_color = Color32::from_rgb(0, 255, 255);
+            }
+        }
+        ctx.set_visuals(visuals);
+    }
+}
@@ -0,0 +1,150 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+// Coarse age of a unix timestamp relative to `now`, e.g. "3 days ago". Timestamps in the
+// future, from a skewed clock, count as zero seconds ago.
+pub fn format_age(created: i64, now: i64) -> String {
+    let seconds = (now - created).max(0);
+    let (amount, unit) = match seconds {
+        0..=59 => (seconds, "second"),
+        60..=3599 => (seconds / 60, "minute"),
+        3600..=86399 => (seconds / 3600, "hour"),
+        86400..=2591999 => (seconds / 86400, "day"),
+        2592000..=31535999 => (seconds / 2592000, "month"),
+        _ => (seconds / 31536000, "year"),
+    };
+    let plural = if amount == 1 { "" } else { "s" };
+    format!("{} {}{} ago", amount, unit, plural)
+}
+
+// The two largest units of a duration, e.g. "3d 4h" or "5m 12s", negative ones are clamped
+pub fn format_duration(seconds: i64) -> String {
+    let seconds = seconds.max(0);
+    let units = [(86400, "d"), (3600, "h"), (60, "m"), (1, "s")];
+    let Some(first) = units.iter().position(|(size, _)| seconds >= *size) else {
+        return "0s".to_string();
+    };
+    units[first..]
+        .iter()
+        .take(2)
+        .scan(seconds, |rest, (size, unit)| {
+            let amount = *rest / size;
+            *rest %= size;
+            Some((amount, unit))
+        })
+        .enumerate()
+        .filter(|(index, (amount, _))| *index == 0 || *amount > 0)
+        .map(|(_, (amount, unit))| format!("{}{}", amount, unit))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Days since 1970-01-01 of a proleptic Gregorian date, Howard Hinnant's days_from_civil
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+// The inverse, year, month and day of a number of days since 1970-01-01
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+// A unix timestamp as "2024-05-01 12:34:56 UTC"
+pub fn format_timestamp(epoch: i64) -> String {
+    let (year, month, day) = civil_from_days(epoch.div_euclid(86400));
+    let seconds = epoch.rem_euclid(86400);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+// Parses the RFC 3339 timestamps of the inspect endpoint, like
+// "2024-05-01T12:34:56.123456789Z" or "2024-05-01T14:34:56+02:00", fractions are dropped
+pub fn parse_timestamp(timestamp: &str) -> Option<i64> {
+    let (date, time) = timestamp.split_once('T')?;
+    let mut date_parts = date.splitn(3, '-').map(|part| part.parse::<i64>().ok());
+    let (year, month, day) = (
+        date_parts.next()??,
+        date_parts.next()??,
+        date_parts.next()??,
+    );
+
+    let (time, offset) = if let Some(time) = time.strip_suffix('Z') {
+        (time, 0)
+    } else {
+        let split = time.rfind(['+', '-'])?;
+        let (time, offset) = time.split_at(split);
+        let sign = if offset.starts_with('-') { -1 } else { 1 };
+        let (hours, minutes) = offset[1..].split_once(':')?;
+        let offset = hours.parse::<i64>().ok()? * 3600 + minutes.parse::<i64>().ok()? * 60;
+        (time, sign * offset)
+    };
+    let time = time.split('.').next()?;
+    let mut time_parts = time.splitn(3, ':').map(|part| part.parse::<i64>().ok());
+    let (hour, minute, second) = (
+        time_parts.next()??,
+        time_parts.next()??,
+        time_parts.next()??,
+    );
+
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    Some(days_from_civil(year, month, day) * 86400 + hour * 3600 + minute * 60 + second - offset)
+}
+
+// Like `parse_timestamp` with the fraction kept as nanoseconds, for ordering log lines that
+// fall in the same second. The daemon drops trailing zeros of the fraction.
+pub fn parse_timestamp_nanos(timestamp: &str) -> Option<(i64, u32)> {
+    let seconds = parse_timestamp(timestamp)?;
+    let nanos = match timestamp.split_once('.') {
+        Some((_, fraction)) => {
+            let digits: String = fraction
+                .chars()
+                .take_while(char::is_ascii_digit)
+                .take(9)
+                .collect();
+            format!("{:0<9}", digits).parse().ok()?
+        }
+        None => 0,
+    };
+    Some((seconds, nanos))
+}
+
+// How long a running container has been up, from the StartedAt of its inspect. The daemon
+// reports the zero time for containers that never started.
+pub fn uptime(started_at: &str, now: i64) -> Option<String> {
+    let started = parse_timestamp(started_at).filter(|started| *started > 0)?;
+    Some(format_duration(now - started))
+}
@@ -0,0 +1,87 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::stats::{StatsHistory, StatsSample};
+
+// The list sorted by CPU or memory moves at most this often, so rows can still be clicked
+pub const RESORT_INTERVAL: Duration = Duration::from_secs(1);
+
+// How many containers the leaderboard lists per resource
+pub const LEADERS: usize = 5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Resource {
+    Cpu,
+    Memory,
+}
+
+impl Resource {
+    pub fn value(self, sample: &StatsSample) -> f64 {
+        match self {
+            Resource::Cpu => sample.cpu_percent,
+            Resource::Memory => sample.memory as f64,
+        }
+    }
+}
+
+// The newest sample of a container, None before its first one
+pub fn latest(history: &HashMap<String, StatsHistory>, id: &str) -> Option<StatsSample> {
+    history.get(id)?.samples.back().copied()
+}
+
+// Heaviest users of `resource` first. Containers without samples go last and keep their
+// order among themselves, as do containers using exactly as much.
+pub fn sort_by_usage(
+    ids: &mut [String],
+    history: &HashMap<String, StatsHistory>,
+    resource: Resource,
+) {
+    ids.sort_by(|a, b| {
+        let usage = |id: &str| latest(history, id).map(|sample| resource.value(&sample));
+        match (usage(a), usage(b)) {
+            (Some(a), Some(b)) => b.partial_cmp(&a).unwrap_or(Ordering::Equal),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+    });
+}
+
+// The `LEADERS` containers among `ids` using the most of `resource`, with their newest sample
+pub fn leaders<'a>(
+    ids: impl IntoIterator<Item = &'a String>,
+    history: &HashMap<String, StatsHistory>,
+    resource: Resource,
+) -> Vec<(String, StatsSample)> {
+    let mut ids: Vec<String> = ids
+        .into_iter()
+        .filter(|id| latest(history, id).is_some())
+        .cloned()
+        .collect();
+    sort_by_usage(&mut ids, history, resource);
+    ids.into_iter()
+        .take(LEADERS)
+        .filter_map(|id| Some((id.clone(), latest(history, &id)?)))
+        .collect()
+}
+
+// When the usage sorted list was last reordered. The clock is passed in by the caller.
+#[derive(Default)]
+pub struct Resort {
+    last: Option<Instant>,
+}
+
+impl Resort {
+    // Whether the list may move now, remembering that it does
+    pub fn due(&mut self, now: Instant) -> bool {
+        if self
+            .last
+            .is_some_and(|last| now.duration_since(last) < RESORT_INTERVAL)
+        {
+            return false;
+        }
+        self.last = Some(now);
+        true
+    }
+}
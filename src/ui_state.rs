@@ -0,0 +1,70 @@
+use std::collections::VecDeque;
+
+use crate::log_level::LevelFilter;
+
+pub const DEFAULT_CAPACITY: usize = 32;
+
+// View state of a container that is restored when it is selected again during the session
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ContainerUiState {
+    pub log_scroll: f32,
+    // How far unwrapped logs are scrolled to the right, in points
+    pub log_h_scroll: f32,
+    pub follow: bool,
+    pub wrap: bool,
+    pub level_filter: LevelFilter,
+    pub search: String,
+}
+
+// Per-container UI state keyed by container ID, evicting the least recently used entry
+pub struct UiStateCache {
+    capacity: usize,
+    // Most recently used first
+    entries: VecDeque<(String, ContainerUiState)>,
+}
+
+impl Default for UiStateCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl UiStateCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: VecDeque::new(),
+        }
+    }
+
+    // Returns the state for `id`, marking it as most recently used
+    pub fn get_mut(&mut self, id: &str) -> &mut ContainerUiState {
+        match self.entries.iter().position(|(key, _)| key == id) {
+            Some(index) => {
+                let entry = self.entries.remove(index).expect("index is in bounds");
+                self.entries.push_front(entry);
+            }
+            None => {
+                self.entries
+                    .push_front((id.to_string(), ContainerUiState::default()));
+                self.entries.truncate(self.capacity);
+            }
+        }
+        &mut self.entries[0].1
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScrollRestore {
+    Restore(f32),
+    // The saved offset is past the end of the lines still available
+    Clamp(f32),
+}
+
+pub fn restore_scroll(saved: f32, max_offset: f32) -> ScrollRestore {
+    if saved <= max_offset {
+        ScrollRestore::Restore(saved)
+    } else {
+        ScrollRestore::Clamp(max_offset.max(0.0))
+    }
+}
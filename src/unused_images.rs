@@ -0,0 +1,135 @@
+use std::collections::{BTreeMap, HashSet};
+
+use bollard::errors::Error;
+use bollard::image::RemoveImageOptions;
+use bollard::secret::ImageSummary;
+
+use crate::connection;
+use crate::poller::ContainerSnapshot;
+use crate::read_only;
+use crate::registry::split_tag;
+use crate::utils::is_dangling;
+
+// Unused tags younger than this are left alone unless the config says otherwise
+pub const DEFAULT_KEEP_DAYS: u64 = 7;
+
+// A tag of an image no container uses, running or stopped
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnusedTag {
+    // `repo:tag`, what gets removed
+    pub tag: String,
+    pub image_id: String,
+    pub size: i64,
+    // Unix seconds the image was built
+    pub created: i64,
+}
+
+// Unused tags by repository, newest first within each, so old builds of one app show
+// together. Dangling images have no tag and are left to Remove dangling.
+pub fn unused_tags(
+    images: &[ImageSummary],
+    containers: &ContainerSnapshot,
+) -> BTreeMap<String, Vec<UnusedTag>> {
+    let used: HashSet<&str> = containers
+        .values()
+        .filter_map(|(summary, _)| summary.image_id.as_deref())
+        .collect();
+    let mut groups: BTreeMap<String, Vec<UnusedTag>> = BTreeMap::new();
+    for image in images {
+        if used.contains(image.id.as_str()) || is_dangling(image) {
+            continue;
+        }
+        for tag in image.repo_tags.iter().filter(|tag| *tag != "<none>:<none>") {
+            let (repository, _) = split_tag(tag);
+            groups
+                .entry(repository.to_string())
+                .or_default()
+                .push(UnusedTag {
+                    tag: tag.clone(),
+                    image_id: image.id.clone(),
+                    size: image.size,
+                    created: image.created,
+                });
+        }
+    }
+    for tags in groups.values_mut() {
+        tags.sort_by(|a, b| b.created.cmp(&a.created).then_with(|| a.tag.cmp(&b.tag)));
+    }
+    groups
+}
+
+// Whether the cleanup removes `tag`, younger ones are kept
+pub fn is_removable(tag: &UnusedTag, keep_days: u64, now: i64) -> bool {
+    now - tag.created >= keep_days as i64 * 24 * 60 * 60
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Removal {
+    Removed,
+    // Other images are built on top of it, the daemon refuses to remove it
+    HasChildren,
+    Failed(String),
+}
+
+// One line for the whole batch. Images with children are expected and only listed, the
+// batch fails when anything else went wrong.
+pub fn summarize_removals(outcomes: &[(String, Removal)]) -> Result<String, String> {
+    let removed = outcomes
+        .iter()
+        .filter(|(_, removal)| *removal == Removal::Removed)
+        .count();
+    let with_children: Vec<&str> = outcomes
+        .iter()
+        .filter(|(_, removal)| *removal == Removal::HasChildren)
+        .map(|(tag, _)| tag.as_str())
+        .collect();
+    let failed: Vec<String> = outcomes
+        .iter()
+        .filter_map(|(tag, removal)| match removal {
+            Removal::Failed(e) => Some(format!("{}: {}", tag, e)),
+            _ => None,
+        })
+        .collect();
+    let mut message = format!("Removed {} unused tags", removed);
+    if !with_children.is_empty() {
+        message.push_str(&format!(
+            ", kept {} with dependent child images ({})",
+            with_children.len(),
+            with_children.join(", ")
+        ));
+    }
+    if failed.is_empty() {
+        Ok(message)
+    } else {
+        Err(format!(
+            "{}, {} failed: {}",
+            message,
+            failed.len(),
+            failed.join("; ")
+        ))
+    }
+}
+
+// Removes every tag in turn, an image goes once its last tag is removed. A failure does not
+// stop the rest of the batch.
+pub async fn remove_unused_tags(tags: Vec<String>) -> Result<String, String> {
+    read_only::check()?;
+    let docker = connection::connect()?;
+    let mut outcomes = Vec::new();
+    for tag in tags {
+        let options = RemoveImageOptions {
+            force: false,
+            noprune: false,
+        };
+        let removal = match docker.remove_image(&tag, Some(options), None).await {
+            Ok(_) => Removal::Removed,
+            Err(Error::DockerResponseServerError {
+                status_code: 409,
+                message,
+            }) if message.contains("dependent child images") => Removal::HasChildren,
+            Err(e) => Removal::Failed(e.to_string()),
+        };
+        outcomes.push((tag, removal));
+    }
+    summarize_removals(&outcomes)
+}
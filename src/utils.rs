@@ -1,110 +1,796 @@
-use bollard::container::{KillContainerOptions, RemoveContainerOptions};
-use bollard::secret::ContainerSummary;
+use crate::connection;
+use crate::docker_api::RemoveOptions;
+use crate::error::{report, DockerrsError, ErrorSender};
+use crate::poller::display_name;
+use crate::read_only;
+use crate::registry::split_tag;
+use crate::top::Resource;
+use bollard::container::{
+    Config, CreateContainerOptions, KillContainerOptions, RemoveContainerOptions,
+};
+use bollard::image::{CommitContainerOptions, RemoveImageOptions, TagImageOptions};
+use bollard::secret::{
+    ContainerSummary, HistoryResponseItem, HostConfig, ImageSummary, PortBinding,
+};
 use bollard::Docker;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
 use tokio::process::Command;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
 
-use std::path::Path;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::{ExitStatus, Stdio};
 
-pub async fn run_docker_compose_up(directory: &Path) {
-    println!("Running 'docker compose up' in {:?}", directory);
+pub const COMPOSE_PROJECT_LABEL: &str = "com.docker.compose.project";
+pub const COMPOSE_SERVICE_LABEL: &str = "com.docker.compose.service";
+pub const COMPOSE_WORKING_DIR_LABEL: &str = "com.docker.compose.project.working_dir";
+pub const COMPOSE_CONFIG_FILES_LABEL: &str = "com.docker.compose.project.config_files";
 
-    match Command::new("docker")
-        .arg("compose")
-        .arg("up")
-        .arg("-d") // Run in detached mode
-        .current_dir(directory)
-        .status()
-        .await
-    {
-        Ok(status) if status.success() => {
-            println!("docker compose up executed successfully in {:?}", directory);
+pub fn compose_project(container: &ContainerSummary) -> Option<String> {
+    container
+        .labels
+        .as_ref()
+        .and_then(|labels| labels.get(COMPOSE_PROJECT_LABEL))
+        .cloned()
+}
+
+pub fn compose_service(container: &ContainerSummary) -> Option<String> {
+    container
+        .labels
+        .as_ref()
+        .and_then(|labels| labels.get(COMPOSE_SERVICE_LABEL))
+        .cloned()
+}
+
+// Running and total replicas per compose service, for the containers of one project
+pub fn service_replicas<'a>(
+    containers: impl IntoIterator<Item = &'a ContainerSummary>,
+) -> HashMap<String, (usize, usize)> {
+    let mut replicas = HashMap::new();
+    for container in containers {
+        let Some(service) = compose_service(container) else {
+            continue;
+        };
+        let (running, total) = replicas.entry(service).or_insert((0, 0));
+        if container.state.as_deref() == Some("running") {
+            *running += 1;
+        }
+        *total += 1;
+    }
+    replicas
+}
+
+// Case-insensitive substring match on the name, image, status and compose project
+pub fn container_matches(container: &ContainerSummary, filter: &str) -> bool {
+    let filter = filter.trim().to_lowercase();
+    if filter.is_empty() {
+        return true;
+    }
+    [
+        Some(display_name(container)),
+        container.image.clone(),
+        container.status.clone(),
+        compose_project(container),
+    ]
+    .into_iter()
+    .flatten()
+    .any(|field| field.to_lowercase().contains(&filter))
+}
+
+// Order of the containers list, O cycles it
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ContainerSort {
+    #[default]
+    Name,
+    // Newest first
+    Created,
+    // Most restarts first, see `RestartTracker::sort`
+    Restarts,
+    // Busiest first by the newest stats sample, see `top::sort_by_usage`
+    Cpu,
+    Memory,
+}
+
+impl ContainerSort {
+    pub fn label(self) -> &'static str {
+        match self {
+            ContainerSort::Name => "Name",
+            ContainerSort::Created => "Created",
+            ContainerSort::Restarts => "Restarts",
+            ContainerSort::Cpu => "CPU",
+            ContainerSort::Memory => "Memory",
+        }
+    }
+
+    pub fn resource(self) -> Option<Resource> {
+        match self {
+            ContainerSort::Cpu => Some(Resource::Cpu),
+            ContainerSort::Memory => Some(Resource::Memory),
+            _ => None,
         }
-        Ok(status) => {
-            eprintln!(
-                "docker compose up failed in {:?} with exit code {}",
-                directory, status
-            );
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            ContainerSort::Name => ContainerSort::Created,
+            ContainerSort::Created => ContainerSort::Restarts,
+            ContainerSort::Restarts => ContainerSort::Cpu,
+            ContainerSort::Cpu => ContainerSort::Memory,
+            ContainerSort::Memory => ContainerSort::Name,
         }
-        Err(e) => {
-            eprintln!(
-                "Failed to execute docker compose up in {:?}: {}",
-                directory, e
-            );
+    }
+
+    // Sorts by the creation timestamp itself, not its "3 days ago" text, and by name within
+    // the same second. Restart counts and stats are not in the summary, by them the list is
+    // sorted by name first.
+    pub fn key(self, container: &ContainerSummary) -> (Reverse<i64>, String) {
+        let created = match self {
+            ContainerSort::Created => container.created.unwrap_or_default(),
+            _ => 0,
+        };
+        (Reverse(created), display_name(container))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ComposeCommand {
+    Up,
+    Down,
+    Stop,
+    Restart,
+    Logs,
+}
+
+impl ComposeCommand {
+    pub fn args(self) -> &'static [&'static str] {
+        match self {
+            ComposeCommand::Up => &["up", "-d"], // Run in detached mode
+            ComposeCommand::Down => &["down"],
+            ComposeCommand::Stop => &["stop"],
+            ComposeCommand::Restart => &["restart"],
+            ComposeCommand::Logs => &["logs", "--tail", "200"],
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ComposeCommand::Up => "Run",
+            ComposeCommand::Down => "Down",
+            ComposeCommand::Stop => "Stop",
+            ComposeCommand::Restart => "Restart",
+            ComposeCommand::Logs => "Logs",
         }
     }
+
+    // Every command but logs changes containers, so they are refused in read-only mode
+    pub fn is_mutating(self) -> bool {
+        self != ComposeCommand::Logs
+    }
+
+    pub fn name(self) -> &'static str {
+        self.args()[0]
+    }
 }
 
-pub async fn build_docker_image(dockerfile: &Path) {
-    println!(
-        "Building Docker image from {:?}, named {:?}",
-        dockerfile,
-        dockerfile.file_stem().unwrap()
+// Where compose was run from, as recorded in the labels of the containers it created
+pub struct ComposeProject {
+    pub name: String,
+    pub working_dir: PathBuf,
+    pub config_files: Vec<PathBuf>,
+}
+
+pub fn compose_project_location(container: &ContainerSummary) -> Option<ComposeProject> {
+    let labels = container.labels.as_ref()?;
+    Some(ComposeProject {
+        name: labels.get(COMPOSE_PROJECT_LABEL)?.clone(),
+        working_dir: PathBuf::from(labels.get(COMPOSE_WORKING_DIR_LABEL)?),
+        config_files: labels
+            .get(COMPOSE_CONFIG_FILES_LABEL)
+            .map(|files| files.split(',').map(PathBuf::from).collect())
+            .unwrap_or_default(),
+    })
+}
+
+pub async fn run_docker_compose_project(
+    project: &ComposeProject,
+    command: ComposeCommand,
+    output: mpsc::UnboundedSender<String>,
+) -> Result<String, String> {
+    if command.is_mutating() {
+        read_only::check()?;
+    }
+    check_working_dir(project)?;
+    run_docker_compose(
+        &project.working_dir,
+        Some(&project.name),
+        &project.config_files,
+        command,
+        &[],
+        output,
+    )
+    .await
+}
+
+fn check_working_dir(project: &ComposeProject) -> Result<(), String> {
+    if project.working_dir.is_dir() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Working directory {:?} of project {} no longer exists",
+            project.working_dir, project.name
+        ))
+    }
+}
+
+// Runs `docker compose up -d --scale service=replicas service` for the project. Compose picks
+// the replicas it removes when scaling down, the next poll shows which ones are left.
+pub async fn scale_compose_service(
+    project: &ComposeProject,
+    service: &str,
+    replicas: u32,
+    output: mpsc::UnboundedSender<String>,
+) -> Result<String, String> {
+    read_only::check()?;
+    check_working_dir(project)?;
+    let scale = format!("{}={}", service, replicas);
+    let _ = output.send(format!(
+        "$ docker compose up -d --scale {} {} (in {:?})",
+        scale, service, project.working_dir
+    ));
+    let mut cmd = compose_command(
+        &project.working_dir,
+        Some(&project.name),
+        &project.config_files,
     );
+    cmd.args(["up", "-d", "--scale", &scale, service]);
+    match stream_command(&mut cmd, &output).await {
+        Ok(status) if status.success() => Ok(format!(
+            "Scaled {} of project {} to {} replicas",
+            service, project.name, replicas
+        )),
+        Ok(status) => Err(format!(
+            "Scaling {} of project {} failed with exit code {}",
+            service, project.name, status
+        )),
+        Err(e) => Err(format!(
+            "Failed to execute docker compose up --scale: {}",
+            e
+        )),
+    }
+}
+
+// `docker compose` with the project name and files, run from `directory`
+pub fn compose_command(
+    directory: &Path,
+    project_name: Option<&str>,
+    compose_files: &[PathBuf],
+) -> Command {
+    let mut cmd = Command::new("docker");
+    cmd.arg("compose");
+    if let Some(project_name) = project_name {
+        cmd.arg("-p").arg(project_name);
+    }
+    for compose_file in compose_files {
+        cmd.arg("-f").arg(compose_file);
+    }
+    cmd.current_dir(directory);
+    cmd
+}
+
+// Spawns the command with piped output and forwards every stdout/stderr line as it arrives,
+// followed by a final line with the exit status
+pub async fn stream_command(
+    command: &mut Command,
+    output: &mpsc::UnboundedSender<String>,
+) -> std::io::Result<ExitStatus> {
+    let mut child = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
 
-    let output = Command::new("docker")
-        .arg("build")
+    let stdout = child
+        .stdout
+        .take()
+        .map(|stdout| forward_lines(stdout, output.clone()));
+    let stderr = child
+        .stderr
+        .take()
+        .map(|stderr| forward_lines(stderr, output.clone()));
+
+    let status = child.wait().await?;
+    for forwarder in stdout.into_iter().chain(stderr) {
+        let _ = forwarder.await;
+    }
+    let _ = output.send(format!("exit status: {}", status));
+    Ok(status)
+}
+
+pub fn forward_lines<R>(reader: R, output: mpsc::UnboundedSender<String>) -> JoinHandle<()>
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(reader).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if output.send(line).is_err() {
+                break;
+            }
+        }
+    })
+}
+
+// Runs the command for the whole project, or only for `services` when there are any
+pub async fn run_docker_compose(
+    directory: &Path,
+    project_name: Option<&str>,
+    compose_files: &[PathBuf],
+    command: ComposeCommand,
+    services: &[String],
+    output: mpsc::UnboundedSender<String>,
+) -> Result<String, String> {
+    if command.is_mutating() {
+        read_only::check()?;
+    }
+    let args: Vec<&str> = command
+        .args()
+        .iter()
+        .copied()
+        .chain(services.iter().map(String::as_str))
+        .collect();
+    let _ = output.send(format!(
+        "$ docker compose {} (in {:?})",
+        args.join(" "),
+        directory
+    ));
+
+    let mut cmd = compose_command(directory, project_name, compose_files);
+    cmd.args(&args);
+
+    let name = match services {
+        [] => command.name().to_string(),
+        services => format!("{} {}", command.name(), services.join(" ")),
+    };
+    match stream_command(&mut cmd, &output).await {
+        Ok(status) if status.success() => Ok(format!(
+            "docker compose {} executed successfully in {:?}",
+            name, directory
+        )),
+        Ok(status) => Err(format!(
+            "docker compose {} failed in {:?} with exit code {}",
+            name, directory, status
+        )),
+        Err(e) => Err(format!(
+            "Failed to execute docker compose {} in {:?}: {}",
+            name, directory, e
+        )),
+    }
+}
+
+// Image name derived from the build context directory plus the Dockerfile variant, e.g.
+// `api/Dockerfile.dev` becomes `api-dev` and `api/worker.dockerfile` becomes `api-worker`
+pub fn default_image_name(dockerfile: &Path) -> Option<String> {
+    let directory = dockerfile
+        .parent()?
+        .file_name()?
+        .to_string_lossy()
+        .to_lowercase();
+    let file_name = dockerfile.file_name()?.to_string_lossy().to_lowercase();
+    let variant = file_name
+        .strip_prefix("dockerfile")
+        .map(|rest| rest.trim_start_matches(['.', '-', '_']))
+        .or_else(|| file_name.strip_suffix(".dockerfile"))
+        .unwrap_or_default();
+    Some(if variant.is_empty() {
+        directory
+    } else {
+        format!("{}-{}", directory, variant)
+    })
+}
+
+// The tag the build form suggests, e.g. `api-dev:latest`
+pub fn suggested_tag(dockerfile: &Path) -> Option<String> {
+    default_image_name(dockerfile).map(|name| format!("{}:latest", name))
+}
+
+// Trims the tag typed in the build form, the "add tag" placeholder counts as empty
+pub fn check_image_tag(tag: &str) -> Result<String, String> {
+    let tag = tag.trim();
+    if tag.is_empty() || tag == "add tag" {
+        return Err("Enter an image tag, like myapp:latest".to_string());
+    }
+    if tag.contains(char::is_whitespace) {
+        return Err(format!("Image tag {:?} cannot contain whitespace", tag));
+    }
+    Ok(tag.to_string())
+}
+
+// Names of the stages declared with `FROM <image> AS <name>`, in file order
+pub fn dockerfile_stages(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            if !words.first()?.eq_ignore_ascii_case("FROM") {
+                return None;
+            }
+            let alias = words
+                .iter()
+                .position(|word| word.eq_ignore_ascii_case("AS"))?;
+            words.get(alias + 1).map(|name| name.to_string())
+        })
+        .collect()
+}
+
+pub async fn build_docker_image(
+    context: &Path,
+    dockerfile: &Path,
+    tag: &str,
+    args: &[(String, String)],
+    target: Option<&str>,
+    output: mpsc::UnboundedSender<String>,
+) -> Result<String, String> {
+    read_only::check()?;
+    let mut cmd = Command::new("docker");
+    cmd.arg("build")
         .arg("-t")
-        // Use the file name as the image name
-        .arg(dockerfile.file_stem().unwrap())
-        .arg(dockerfile)
-        .output()
-        .await
-        .expect("Failed to execute process");
+        .arg(tag)
+        .arg("-f")
+        .arg(dockerfile);
+    for (key, value) in args {
+        cmd.arg("--build-arg").arg(format!("{}={}", key, value));
+    }
+    if let Some(target) = target {
+        cmd.arg("--target").arg(target);
+    }
+    cmd.arg(context);
+
+    let mut echo = format!("$ docker build -t {} -f {:?}", tag, dockerfile);
+    for (key, value) in args {
+        echo.push_str(&format!(" --build-arg {}={}", key, value));
+    }
+    if let Some(target) = target {
+        echo.push_str(&format!(" --target {}", target));
+    }
+    let _ = output.send(format!("{} {:?}", echo, context));
 
-    println!("status: {}", output.status);
-    println!("stdout: {}", String::from_utf8_lossy(&output.stdout));
-    println!("stderr: {}", String::from_utf8_lossy(&output.stderr));
+    match stream_command(&mut cmd, &output).await {
+        Ok(status) if status.success() => Ok(format!("Built image {}", tag)),
+        Ok(status) => Err(format!(
+            "docker build of {} failed with exit code {}",
+            tag, status
+        )),
+        Err(e) => Err(format!("Failed to execute docker build: {}", e)),
+    }
 }
 
-pub async fn kill_containers(containers: Vec<ContainerSummary>) {
-    let docker = Docker::connect_with_unix_defaults().expect("Failed to connect to Docker");
+// Goes on with the other containers when one fails, every failure is reported
+pub async fn kill_containers(containers: Vec<ContainerSummary>, errors: &ErrorSender) {
+    let docker = match connect() {
+        Ok(docker) => docker,
+        Err(e) => return report(errors, e),
+    };
     for container in containers {
-        _kill_container(&docker, &container).await;
+        if let Err(e) = _kill_container(&docker, &container).await {
+            report(errors, e);
+        }
     }
 }
-pub async fn remove_containers(containers: Vec<ContainerSummary>) {
-    let docker = Docker::connect_with_unix_defaults().expect("Failed to connect to Docker");
+pub async fn remove_containers(
+    containers: Vec<ContainerSummary>,
+    options: RemoveOptions,
+    errors: &ErrorSender,
+) {
+    let docker = match connect() {
+        Ok(docker) => docker,
+        Err(e) => return report(errors, e),
+    };
     for container in containers {
-        _remove_container(&docker, &container).await;
+        if let Err(e) = _remove_container(&docker, &container, options).await {
+            report(errors, e);
+        }
     }
 }
 
-pub async fn kill_container(container: &ContainerSummary) {
-    let docker = Docker::connect_with_unix_defaults().expect("Failed to connect to Docker");
-    _kill_container(&docker, container).await;
+fn connect() -> Result<Docker, DockerrsError> {
+    connection::connect().map_err(|e| DockerrsError::docker("Failed to connect to Docker", e))
+}
+
+pub async fn kill_container(container: &ContainerSummary) -> Result<(), DockerrsError> {
+    _kill_container(&connect()?, container).await
 }
 
-pub async fn _kill_container(docker: &Docker, container: &ContainerSummary) {
+pub async fn _kill_container(
+    docker: &Docker,
+    container: &ContainerSummary,
+) -> Result<(), DockerrsError> {
     let Some(container_id) = container.id.clone() else {
-        return;
+        return Ok(());
     };
     let kill_options = KillContainerOptions { signal: "SIGKILL" };
-    if let Err(e) = docker
+    docker
         .kill_container(&container_id, Some(kill_options))
         .await
-    {
-        eprintln!("Failed to kill container {}: {}", container_id, e);
-    }
+        .map_err(|e| DockerrsError::docker(format!("Failed to kill container {}", container_id), e))
 }
 
-pub async fn remove_container(container: &ContainerSummary) {
-    let docker = Docker::connect_with_unix_defaults().expect("Failed to connect to Docker");
-    _remove_container(&docker, container).await;
+pub async fn remove_container(
+    container: &ContainerSummary,
+    options: RemoveOptions,
+) -> Result<(), DockerrsError> {
+    _remove_container(&connect()?, container, options).await
 }
 
-pub async fn _remove_container(docker: &Docker, container: &ContainerSummary) {
+pub async fn _remove_container(
+    docker: &Docker,
+    container: &ContainerSummary,
+    options: RemoveOptions,
+) -> Result<(), DockerrsError> {
     let Some(container_id) = container.id.clone() else {
-        return;
+        return Ok(());
     };
 
-    let remove_options = RemoveContainerOptions {
-        force: true,
+    docker
+        .remove_container(&container_id, Some(options.to_bollard()))
+        .await
+        .map_err(|e| {
+            DockerrsError::docker(format!("Failed to remove container {}", container_id), e)
+        })
+}
+
+// Parses comma separated "host:container" port mappings, e.g. "8080:80, 8443:443"
+pub fn parse_port_mappings(input: &str) -> Result<Vec<(u16, u16)>, String> {
+    input
+        .split(',')
+        .map(str::trim)
+        .filter(|mapping| !mapping.is_empty())
+        .map(|mapping| {
+            let (host, container) = mapping.split_once(':').ok_or_else(|| {
+                format!(
+                    "Invalid port mapping '{}', expected host:container",
+                    mapping
+                )
+            })?;
+            let host = host
+                .trim()
+                .parse::<u16>()
+                .map_err(|_| format!("Invalid host port in '{}'", mapping))?;
+            let container = container
+                .trim()
+                .parse::<u16>()
+                .map_err(|_| format!("Invalid container port in '{}'", mapping))?;
+            Ok((host, container))
+        })
+        .collect()
+}
+
+pub async fn create_and_start_container(
+    image: String,
+    name: Option<String>,
+    ports: Vec<(u16, u16)>,
+    env: Vec<String>,
+) -> Result<String, String> {
+    read_only::check()?;
+    let docker = connection::connect()?;
+
+    let mut exposed_ports = HashMap::new();
+    let mut port_bindings = HashMap::new();
+    for (host, container) in ports {
+        let key = format!("{}/tcp", container);
+        exposed_ports.insert(key.clone(), HashMap::new());
+        port_bindings.insert(
+            key,
+            Some(vec![PortBinding {
+                host_ip: None,
+                host_port: Some(host.to_string()),
+            }]),
+        );
+    }
+
+    let config = Config {
+        image: Some(image.clone()),
+        env: Some(env),
+        exposed_ports: Some(exposed_ports),
+        host_config: Some(HostConfig {
+            port_bindings: Some(port_bindings),
+            ..Default::default()
+        }),
         ..Default::default()
     };
-    if let Err(e) = docker
-        .remove_container(&container_id, Some(remove_options))
+    let options = name.map(|name| CreateContainerOptions {
+        name,
+        platform: None,
+    });
+
+    let created = docker
+        .create_container(options, config)
         .await
-    {
-        eprintln!("Failed to remove container {}: {}", container_id, e);
+        .map_err(|e| format!("Failed to create container from {}: {}", image, e))?;
+    // A container that never started is removed again rather than left behind, e.g. when its
+    // host port is taken
+    if let Err(e) = docker.start_container::<String>(&created.id, None).await {
+        let short_id: String = created.id.chars().take(12).collect();
+        let options = RemoveContainerOptions {
+            force: true,
+            ..Default::default()
+        };
+        return Err(
+            match docker.remove_container(&created.id, Some(options)).await {
+                Ok(()) => format!(
+                    "Failed to start container {}, it was removed again: {}",
+                    short_id, e
+                ),
+                Err(remove) => format!(
+                    "Failed to start container {}: {}, and failed to remove it: {}",
+                    short_id, e, remove
+                ),
+            },
+        );
     }
+    Ok(created.id)
+}
+
+// Images without a tag, left behind when a tag moves to a newer build
+pub fn is_dangling(image: &ImageSummary) -> bool {
+    image.repo_tags.iter().all(|tag| tag == "<none>:<none>")
+}
+
+pub fn format_size(bytes: i64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut size = bytes.max(0) as f64;
+    let mut unit = 0;
+    while size >= 1000.0 && unit < UNITS.len() - 1 {
+        size /= 1000.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+// What k, m, g and t count in. Sizes dockerrs shows count in thousands like `format_size`,
+// the docker CLI's memory flags take "512m" as 512 MiB.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SizeUnits {
+    Decimal,
+    Binary,
+}
+
+// The inverse of `format_size`: "10GB", "1.5 gb" or "512MiB" in bytes, a plain number is
+// bytes already. KB to TB count in thousands like `format_size` shows them, KiB to TiB in
+// 1024s.
+pub fn parse_size(input: &str) -> Result<u64, String> {
+    parse_size_in(input, SizeUnits::Decimal)
+}
+
+// `parse_size` with KB to TB counting in `units`, KiB to TiB are always 1024s
+pub fn parse_size_in(input: &str, units: SizeUnits) -> Result<u64, String> {
+    let input = input.trim();
+    let split = input
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(input.len());
+    let (number, unit) = input.split_at(split);
+    let number: f64 = number
+        .parse()
+        .map_err(|_| format!("Invalid size '{}', expected e.g. 512m or 10GB", input))?;
+    let base: u64 = match units {
+        SizeUnits::Decimal => 1_000,
+        SizeUnits::Binary => 1 << 10,
+    };
+    let multiplier: u64 = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kb" => base,
+        "m" | "mb" => base.pow(2),
+        "g" | "gb" => base.pow(3),
+        "t" | "tb" => base.pow(4),
+        "kib" => 1 << 10,
+        "mib" => 1 << 20,
+        "gib" => 1 << 30,
+        "tib" => 1 << 40,
+        other => return Err(format!("Unknown size unit '{}' in '{}'", other, input)),
+    };
+    Ok((number * multiplier as f64).round() as u64)
+}
+
+pub async fn remove_image(image: String) -> Result<String, String> {
+    read_only::check()?;
+    let docker = connection::connect()?;
+    let options = RemoveImageOptions {
+        force: false,
+        noprune: false,
+    };
+    docker
+        .remove_image(&image, Some(options), None)
+        .await
+        .map_err(|e| format!("Failed to remove image {}: {}", image, e))?;
+    Ok(format!("Removed image {}", image))
+}
+
+// Keeps going after a failure so one image in use does not block the rest
+pub async fn remove_images(images: Vec<String>) -> Result<String, String> {
+    read_only::check()?;
+    let mut removed = 0;
+    let mut errors = Vec::new();
+    for image in images {
+        match remove_image(image).await {
+            Ok(_) => removed += 1,
+            Err(e) => errors.push(e),
+        }
+    }
+    if errors.is_empty() {
+        Ok(format!("Removed {} images", removed))
+    } else {
+        Err(format!(
+            "Removed {} images, {} failed: {}",
+            removed,
+            errors.len(),
+            errors.join("; ")
+        ))
+    }
+}
+
+// `target` is `repo[:tag]`, the tag defaults to latest like the docker CLI
+pub async fn tag_image(image: String, target: String) -> Result<String, String> {
+    read_only::check()?;
+    let target = target.trim();
+    if target.is_empty() {
+        return Err("Enter a repository to tag the image with".to_string());
+    }
+    let (repo, tag) = split_tag(target);
+    let docker = connection::connect()?;
+    docker
+        .tag_image(&image, Some(TagImageOptions { repo, tag }))
+        .await
+        .map_err(|e| format!("Failed to tag image {}: {}", image, e))?;
+    Ok(format!("Tagged {} as {}:{}", image, repo, tag))
+}
+
+// Layers of an image, newest first as the daemon returns them
+pub async fn image_history(image: String) -> Result<Vec<HistoryResponseItem>, String> {
+    let docker = connection::connect()?;
+    docker
+        .image_history(&image)
+        .await
+        .map_err(|e| format!("Failed to read the history of {}: {}", image, e))
+}
+
+// Snapshots a container into a new image, like `docker commit`. The daemon pauses a running
+// container for the duration, a paused one is committed as is
+pub async fn commit_container(
+    container_id: String,
+    repo: String,
+    tag: String,
+    comment: String,
+) -> Result<String, String> {
+    read_only::check()?;
+    let repo = repo.trim().to_string();
+    if repo.is_empty() {
+        return Err("Enter a repository name for the new image".to_string());
+    }
+    let tag = match tag.trim() {
+        "" => "latest".to_string(),
+        tag => tag.to_string(),
+    };
+    let docker = connection::connect()?;
+    let options = CommitContainerOptions {
+        container: container_id.clone(),
+        repo: repo.clone(),
+        tag: tag.clone(),
+        comment,
+        pause: true,
+        ..Default::default()
+    };
+    let commit = docker
+        .commit_container(options, Config::<String>::default())
+        .await
+        .map_err(|e| format!("Failed to commit container {}: {}", container_id, e))?;
+    Ok(format!(
+        "Committed {}:{} ({})",
+        repo,
+        tag,
+        commit.id.unwrap_or_default()
+    ))
 }
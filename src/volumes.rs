@@ -0,0 +1,200 @@
+use std::collections::HashMap;
+
+use bollard::container::{
+    Config, CreateContainerOptions, LogOutput, RemoveContainerOptions, StartContainerOptions,
+};
+use bollard::exec::{CreateExecOptions, StartExecResults};
+use bollard::image::CreateImageOptions;
+use bollard::secret::{HostConfig, MountPointTypeEnum, Volume};
+use bollard::volume::ListVolumesOptions;
+use bollard::Docker;
+use futures_util::stream::StreamExt;
+use tokio::sync::mpsc;
+
+use crate::connection;
+use crate::error::{report, DockerrsError, ErrorSender};
+use crate::files::{self, FileEntry};
+use crate::poller::{display_name, ContainerSnapshot};
+use crate::read_only;
+
+// Image of the short-lived container a volume is browsed from
+pub const VOLUME_HELPER_IMAGE: &str = "alpine:latest";
+
+// Where the helper container sees the volume, read-only
+const VOLUME_PATH: &str = "/volume";
+
+// A container that mounts a volume
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct VolumeMount {
+    pub container: String,
+    pub destination: String,
+    pub read_write: bool,
+}
+
+// Containers from the snapshot that mount the volume, sorted by name. Stopped containers
+// count too, they keep the volume in use.
+pub fn mounting_containers(volume: &str, containers: &ContainerSnapshot) -> Vec<VolumeMount> {
+    let mut mounts: Vec<VolumeMount> = containers
+        .values()
+        .flat_map(|(summary, _)| {
+            summary
+                .mounts
+                .iter()
+                .flatten()
+                .filter(|mount| {
+                    mount.typ == Some(MountPointTypeEnum::VOLUME)
+                        && mount.name.as_deref() == Some(volume)
+                })
+                .map(|mount| VolumeMount {
+                    container: display_name(summary),
+                    destination: mount.destination.clone().unwrap_or_default(),
+                    read_write: mount.rw.unwrap_or(true),
+                })
+        })
+        .collect();
+    mounts.sort();
+    mounts
+}
+
+// Volumes sorted by name
+pub async fn list_volumes() -> Result<Vec<Volume>, String> {
+    let docker = connection::connect()?;
+    let mut volumes = docker
+        .list_volumes(None::<ListVolumesOptions<String>>)
+        .await
+        .map_err(|e| format!("Failed to list volumes: {}", e))?
+        .volumes
+        .unwrap_or_default();
+    volumes.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(volumes)
+}
+
+// Size per volume name from the daemon's verbose df, which walks every volume and can take
+// a while on big ones. Volumes whose size the driver does not report are left out.
+pub async fn volume_sizes() -> Result<HashMap<String, i64>, String> {
+    let docker = connection::connect()?;
+    let usage = docker
+        .df()
+        .await
+        .map_err(|e| format!("Failed to get volume sizes: {}", e))?;
+    Ok(usage
+        .volumes
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|volume| {
+            let size = volume.usage_data?.size;
+            (size >= 0).then_some((volume.name, size))
+        })
+        .collect())
+}
+
+// Top-level entries of a volume and its total size in bytes
+#[derive(Clone, Debug)]
+pub struct VolumeListing {
+    pub entries: Vec<FileEntry>,
+    pub size: Option<u64>,
+}
+
+// Total of `du -sk` output in bytes
+pub fn parse_du(output: &str) -> Option<u64> {
+    let kib: u64 = output.split_whitespace().next()?.parse().ok()?;
+    Some(kib * 1024)
+}
+
+// Lists the volume from a temporary helper container that mounts it read-only. The helper
+// is removed afterwards, also when listing fails, and a failed removal goes to `errors`.
+pub async fn browse_volume(
+    volume: String,
+    progress: mpsc::UnboundedSender<String>,
+    errors: ErrorSender,
+) -> Result<VolumeListing, String> {
+    read_only::check()?;
+    let docker = connection::connect()?;
+    if docker.inspect_image(VOLUME_HELPER_IMAGE).await.is_err() {
+        let _ = progress.send(format!("Pulling {}", VOLUME_HELPER_IMAGE));
+        let options = CreateImageOptions {
+            from_image: VOLUME_HELPER_IMAGE,
+            ..Default::default()
+        };
+        let mut pull = docker.create_image(Some(options), None, None);
+        while let Some(step) = pull.next().await {
+            step.map_err(|e| format!("Failed to pull {}: {}", VOLUME_HELPER_IMAGE, e))?;
+        }
+    }
+
+    let _ = progress.send("Starting helper container".to_string());
+    let config = Config {
+        image: Some(VOLUME_HELPER_IMAGE.to_string()),
+        // Ends on its own should removing it fail
+        cmd: Some(vec!["sleep".to_string(), "300".to_string()]),
+        labels: Some(HashMap::from([(
+            "dockerrs.helper".to_string(),
+            "volume-browse".to_string(),
+        )])),
+        host_config: Some(HostConfig {
+            binds: Some(vec![format!("{}:{}:ro", volume, VOLUME_PATH)]),
+            auto_remove: Some(true),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let helper = docker
+        .create_container(None::<CreateContainerOptions<String>>, config)
+        .await
+        .map_err(|e| format!("Failed to create a helper container: {}", e))?
+        .id;
+
+    let listing = list_helper(&docker, &helper, &progress).await;
+    let removed = docker
+        .remove_container(
+            &helper,
+            Some(RemoveContainerOptions {
+                force: true,
+                ..Default::default()
+            }),
+        )
+        .await;
+    if let Err(e) = removed {
+        let context = format!("Failed to remove helper container {}", helper);
+        report(&errors, DockerrsError::docker(context, e));
+    }
+    listing
+}
+
+async fn list_helper(
+    docker: &Docker,
+    helper: &str,
+    progress: &mpsc::UnboundedSender<String>,
+) -> Result<VolumeListing, String> {
+    docker
+        .start_container(helper, None::<StartContainerOptions<String>>)
+        .await
+        .map_err(|e| format!("Failed to start the helper container: {}", e))?;
+    let _ = progress.send("Listing".to_string());
+    let entries = files::list_path(helper.to_string(), VOLUME_PATH.to_string()).await?;
+    let _ = progress.send("Measuring size".to_string());
+    let du = docker
+        .create_exec(
+            helper,
+            CreateExecOptions {
+                cmd: Some(vec!["du", "-sk", VOLUME_PATH]),
+                attach_stdout: Some(true),
+                ..Default::default()
+            },
+        )
+        .await
+        .map_err(|e| format!("Failed to measure the volume: {}", e))?;
+    let mut stdout = String::new();
+    if let Ok(StartExecResults::Attached { mut output, .. }) = docker.start_exec(&du.id, None).await
+    {
+        while let Some(Ok(message)) = output.next().await {
+            if let LogOutput::StdOut { message } = message {
+                stdout.push_str(&String::from_utf8_lossy(&message));
+            }
+        }
+    }
+    Ok(VolumeListing {
+        entries,
+        size: parse_du(&stdout),
+    })
+}
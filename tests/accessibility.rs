@@ -0,0 +1,70 @@
+use std::time::Instant;
+
+use dockerrs::accessibility::{container_name, notification_name, state_changes};
+use dockerrs::notifications::Notification;
+use dockerrs::poller::ContainerSnapshot;
+
+mod common;
+
+use common::{container, snapshot};
+
+#[test]
+fn container_names_carry_state_and_status() {
+    assert_eq!(
+        container_name("web", Some("running"), Some("Up 2 hours (healthy)")),
+        "web, running, Up 2 hours (healthy)"
+    );
+    assert_eq!(container_name("web", None, Some("")), "web");
+}
+
+#[test]
+fn notifications_say_whether_they_worked() {
+    let mut notification = Notification {
+        message: "Container abc: stop done".to_string(),
+        success: true,
+        container: None,
+        count: 1,
+        at: Instant::now(),
+    };
+    assert_eq!(
+        notification_name(&notification),
+        "Done: Container abc: stop done"
+    );
+    notification.success = false;
+    notification.count = 2;
+    assert_eq!(
+        notification_name(&notification),
+        "Failed: Container abc: stop done (×2)"
+    );
+}
+
+#[test]
+fn state_changes_cover_started_created_and_removed_containers() {
+    let before = snapshot([
+        container("web").name("web").state("running"),
+        container("db").name("db").state("running"),
+        container("old").name("old").state("exited"),
+    ]);
+    let after = snapshot([
+        container("web").name("web").state("exited"),
+        container("db").name("db").state("running"),
+        container("api").name("api").state("created"),
+    ]);
+    assert_eq!(
+        state_changes(&before, &after),
+        vec![
+            ("api".to_string(), "/api was created, created".to_string()),
+            ("old".to_string(), "/old was removed".to_string()),
+            (
+                "web".to_string(),
+                "/web is now exited, was running".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn the_first_snapshot_announces_nothing() {
+    let after = snapshot([container("web").name("web").state("running")]);
+    assert!(state_changes(&ContainerSnapshot::new(), &after).is_empty());
+}
@@ -0,0 +1,128 @@
+use std::time::{Duration, Instant};
+
+use bollard::secret::ContainerSummary;
+use dockerrs::action_queue::{ActionQueue, QueuedAction};
+use dockerrs::docker_api::RemoveOptions;
+
+const GRACE: Duration = Duration::from_secs(5);
+
+fn remove(name: &str) -> QueuedAction {
+    let summary = ContainerSummary {
+        id: Some(name.to_string()),
+        names: Some(vec![format!("/{name}")]),
+        ..Default::default()
+    };
+    QueuedAction::RemoveContainer(Box::new(summary), RemoveOptions::FORCE)
+}
+
+fn described(actions: Vec<QueuedAction>) -> Vec<String> {
+    actions.iter().map(QueuedAction::describe).collect()
+}
+
+#[test]
+fn actions_are_due_once_the_grace_period_elapses() {
+    let mut queue = ActionQueue::new(GRACE);
+    let start = Instant::now();
+    queue.push("Remove web".to_string(), remove("web"), start);
+    queue.push(
+        "Remove db".to_string(),
+        remove("db"),
+        start + Duration::from_secs(2),
+    );
+    assert_eq!(queue.pending()[0].remaining(start), GRACE);
+
+    assert!(queue.take_due(start).is_empty());
+    assert!(queue
+        .take_due(start + GRACE - Duration::from_millis(1))
+        .is_empty());
+    assert_eq!(queue.pending().len(), 2);
+
+    let first = described(queue.take_due(start + GRACE));
+    assert_eq!(first, [remove("web").describe()]);
+    assert_eq!(queue.pending().len(), 1);
+
+    let second = described(queue.take_due(start + Duration::from_secs(7)));
+    assert_eq!(second, [remove("db").describe()]);
+    assert!(queue.is_empty());
+}
+
+#[test]
+fn cancelled_actions_are_never_due() {
+    let mut queue = ActionQueue::new(GRACE);
+    let start = Instant::now();
+    let web = queue.push("Remove web".to_string(), remove("web"), start);
+    queue.push("Remove db".to_string(), remove("db"), start);
+
+    assert!(queue.cancel(web));
+    assert!(!queue.cancel(web));
+    let due = described(queue.take_due(start + GRACE));
+    assert_eq!(due, [remove("db").describe()]);
+    assert!(!queue.cancel(web + 1));
+}
+
+#[test]
+fn undo_cancels_the_most_recent_action() {
+    let mut queue = ActionQueue::new(GRACE);
+    let start = Instant::now();
+    queue.push("Remove web".to_string(), remove("web"), start);
+    queue.push("Remove db".to_string(), remove("db"), start);
+
+    let undone = queue.undo_last().unwrap();
+    assert_eq!(undone.label, "Remove db");
+    let due = described(queue.take_due(start + GRACE));
+    assert_eq!(due, [remove("web").describe()]);
+    assert!(queue.undo_last().is_none());
+}
+
+#[test]
+fn pending_actions_are_dropped_not_dispatched() {
+    let mut queue = ActionQueue::new(GRACE);
+    let start = Instant::now();
+    queue.push("Remove web".to_string(), remove("web"), start);
+    queue.push("Remove db".to_string(), remove("db"), start);
+
+    assert_eq!(queue.drop_pending(), ["Remove web", "Remove db"]);
+    assert!(queue.is_empty());
+    assert!(queue.take_due(start + GRACE).is_empty());
+}
+
+#[test]
+fn image_and_network_removals_wait_in_the_queue() {
+    let mut queue = ActionQueue::new(GRACE);
+    let start = Instant::now();
+    queue.push(
+        "Remove nginx:1.25".to_string(),
+        QueuedAction::RemoveImage("nginx:1.25".to_string()),
+        start,
+    );
+    queue.push(
+        "Remove dangling images".to_string(),
+        QueuedAction::RemoveImages(vec!["sha256:aa".to_string(), "sha256:bb".to_string()]),
+        start,
+    );
+    queue.push(
+        "Remove 1 unused image tags".to_string(),
+        QueuedAction::RemoveUnusedTags(vec!["redis:6".to_string()]),
+        start,
+    );
+    queue.push(
+        "Remove network backend".to_string(),
+        QueuedAction::RemoveNetwork {
+            id: "f00".to_string(),
+            name: "backend".to_string(),
+        },
+        start,
+    );
+
+    assert!(queue.take_due(start).is_empty());
+    let undone = queue.undo_last().unwrap();
+    assert_eq!(undone.label, "Remove network backend");
+    assert_eq!(
+        described(queue.take_due(start + GRACE)),
+        [
+            "Remove image nginx:1.25",
+            "Remove 2 images",
+            "Remove 1 unused image tags"
+        ]
+    );
+}
@@ -0,0 +1,80 @@
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+use dockerrs::alerts::{detect_transitions, exit_code, AlertLimiter, Transition};
+
+mod common;
+
+use common::{container, snapshot};
+
+#[test]
+fn crashes_of_watched_containers_are_transitions() {
+    let before = snapshot([
+        container("1")
+            .name("api")
+            .state("running")
+            .status("Up 1 hour (healthy)"),
+        container("2")
+            .name("db")
+            .state("running")
+            .status("Up 1 hour"),
+        container("3")
+            .name("worker")
+            .state("running")
+            .status("Up 1 hour"),
+    ]);
+    let after = snapshot([
+        container("1")
+            .name("api")
+            .state("running")
+            .status("Up 1 hour (unhealthy)"),
+        container("2")
+            .name("db")
+            .state("exited")
+            .status("Exited (137) 2 seconds ago"),
+        container("3")
+            .name("worker")
+            .state("exited")
+            .status("Exited (1) 2 seconds ago"),
+        container("4")
+            .name("new")
+            .state("exited")
+            .status("Exited (1) 2 seconds ago"),
+    ]);
+    let watched: HashSet<String> = ["api", "db", "new"].map(String::from).into();
+    assert_eq!(
+        detect_transitions(&before, &after, &watched),
+        [
+            ("1".to_string(), "api".to_string(), Transition::Unhealthy),
+            (
+                "2".to_string(),
+                "db".to_string(),
+                Transition::Exited(Some(137))
+            ),
+        ]
+    );
+    // Staying unhealthy or exited is not a new transition
+    assert!(detect_transitions(&after, &after, &watched).is_empty());
+    assert!(detect_transitions(&before, &after, &HashSet::new()).is_empty());
+    assert_eq!(exit_code("Exited (0) 3 days ago"), Some(0));
+    assert_eq!(exit_code("Up 2 minutes"), None);
+}
+
+#[test]
+fn flapping_containers_notify_once_a_minute() {
+    let mut limiter = AlertLimiter::default();
+    let start = Instant::now();
+    assert!(limiter.allow("api", start));
+    assert!(!limiter.allow("api", start + Duration::from_secs(30)));
+    assert!(limiter.allow("db", start + Duration::from_secs(30)));
+    assert!(limiter.allow("api", start + Duration::from_secs(61)));
+}
+
+#[test]
+fn only_failed_exits_and_oom_kills_are_crashes() {
+    assert!(Transition::Exited(Some(137)).is_crash());
+    assert!(Transition::OomKilled.is_crash());
+    assert!(!Transition::Exited(Some(0)).is_crash());
+    assert!(!Transition::Exited(None).is_crash());
+    assert!(!Transition::Unhealthy.is_crash());
+}
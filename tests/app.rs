@@ -0,0 +1,799 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use bollard::secret::{ContainerSummary, ImageSummary, Network};
+use dockerrs::alerts::{Alert, AlertLimiter, Transition, FLASH_FRAMES};
+use dockerrs::commands::Command;
+use dockerrs::docker_api::ContainerAction;
+use dockerrs::docker_viewer_app::{AppView, BulkScope, DockerViewerApp, PendingAction};
+use dockerrs::error::{report, DockerrsError};
+use dockerrs::image_build::BuildSettings;
+use dockerrs::layout::LayoutMode;
+use dockerrs::poller::{display_name, ContainerSnapshot};
+use dockerrs::scan::ScanSettings;
+use dockerrs::signals::SIGKILL;
+use dockerrs::stats::{StatsHistory, StatsSample};
+use dockerrs::utils::{
+    service_replicas, ContainerSort, COMPOSE_PROJECT_LABEL, COMPOSE_SERVICE_LABEL,
+    COMPOSE_WORKING_DIR_LABEL,
+};
+use tokio::sync::{mpsc, oneshot};
+
+fn summary(name: &str, id: &str, project: Option<&str>) -> ContainerSummary {
+    ContainerSummary {
+        id: Some(id.to_string()),
+        names: Some(vec![name.to_string()]),
+        labels: project.map(|project| {
+            HashMap::from([(COMPOSE_PROJECT_LABEL.to_string(), project.to_string())])
+        }),
+        ..Default::default()
+    }
+}
+
+fn snapshot(containers: &[(&str, &str, Option<&str>)]) -> ContainerSnapshot {
+    containers
+        .iter()
+        .map(|(name, id, project)| (id.to_string(), (summary(name, id, *project), String::new())))
+        .collect()
+}
+
+// Names of the listed containers, in list order
+fn names(app: &DockerViewerApp, ids: &[String]) -> Vec<String> {
+    ids.iter()
+        .map(|id| display_name(&app.containers[id].0))
+        .collect()
+}
+
+fn app() -> (DockerViewerApp, mpsc::Sender<ContainerSnapshot>) {
+    let (sender, receiver) = mpsc::channel(8);
+    let (_, images_receiver) = mpsc::channel(8);
+    let (_, networks_receiver) = mpsc::channel(8);
+    let (_, capabilities_receiver) = oneshot::channel();
+    let settings = ScanSettings {
+        roots: Vec::new(),
+        excludes: Vec::new(),
+        max_depth: None,
+    };
+    let app = DockerViewerApp::new(
+        receiver,
+        images_receiver,
+        networks_receiver,
+        capabilities_receiver,
+        settings,
+        BuildSettings::default(),
+    );
+    (app, sender)
+}
+
+#[test]
+fn container_names_are_sorted() {
+    let (mut app, sender) = app();
+    sender
+        .try_send(snapshot(&[
+            ("/web", "1", None),
+            ("/api", "2", None),
+            ("/db", "3", None),
+        ]))
+        .unwrap();
+    app.receive_updates();
+    assert_eq!(names(&app, &app.container_ids), ["/api", "/db", "/web"]);
+}
+
+#[test]
+fn containers_sort_by_created_time_newest_first() {
+    let (mut app, sender) = app();
+    let mut containers = snapshot(&[
+        ("/a", "1", None),
+        ("/b", "2", None),
+        ("/c", "3", None),
+        ("/d", "4", None),
+    ]);
+    // "9" sorts after "10" as text, not as a timestamp
+    for (id, created) in [("1", 9), ("2", 10), ("3", 100), ("4", 10)] {
+        containers.get_mut(id).unwrap().0.created = Some(created);
+    }
+    sender.try_send(containers).unwrap();
+    app.receive_updates();
+    app.set_container_sort(ContainerSort::Created);
+    assert_eq!(names(&app, &app.container_ids), ["/c", "/b", "/d", "/a"]);
+    app.set_container_sort(ContainerSort::Name);
+    assert_eq!(names(&app, &app.container_ids), ["/a", "/b", "/c", "/d"]);
+}
+
+// The selection is an ID, so it stays on its container while the rows move
+#[test]
+fn containers_sort_by_cpu_use() {
+    let (mut app, sender) = app();
+    sender
+        .try_send(snapshot(&[
+            ("/a", "1", None),
+            ("/b", "2", None),
+            ("/c", "3", None),
+        ]))
+        .unwrap();
+    app.receive_updates();
+    app.selected_container = Some("1".to_string());
+    for (id, cpu_percent) in [("1", 2.0), ("2", 50.0)] {
+        let mut history = StatsHistory::new(10);
+        history.push(StatsSample {
+            cpu_percent,
+            memory: 0,
+        });
+        app.stats_history.insert(id.to_string(), history);
+    }
+    app.set_container_sort(ContainerSort::Cpu);
+    assert_eq!(names(&app, &app.container_ids), ["/b", "/a", "/c"]);
+    assert_eq!(app.selected_container.as_deref(), Some("1"));
+}
+
+// Polls that add, remove or rename a few containers patch the list in place, which has to
+// end up where a full sort would
+#[test]
+fn incremental_updates_keep_the_order() {
+    let (mut app, sender) = app();
+    let mut containers: ContainerSnapshot = (0..40)
+        .map(|i| {
+            let id = format!("{:02}", i);
+            let project = format!("p{}", i % 3);
+            let name = format!("/c{}", (i * 7) % 40);
+            (
+                id.clone(),
+                (summary(&name, &id, Some(&project)), String::new()),
+            )
+        })
+        .collect();
+    sender.try_send(containers.clone()).unwrap();
+    app.receive_updates();
+
+    containers.remove("05");
+    containers.insert(
+        "new".to_string(),
+        (summary("/c13b", "new", Some("p1")), String::new()),
+    );
+    containers.get_mut("10").unwrap().0.names = Some(vec!["/a-renamed".to_string()]);
+    containers.get_mut("11").unwrap().0.labels = None;
+    containers.get_mut("12").unwrap().0.status = Some("Exited (0)".to_string());
+    sender.try_send(containers).unwrap();
+    app.receive_updates();
+
+    let (ids, groups) = (app.container_ids.clone(), app.container_groups.clone());
+    app.order_containers();
+    assert_eq!(ids, app.container_ids);
+    assert_eq!(names(&app, &ids)[0], "/a-renamed");
+    let projects = |groups: &[dockerrs::docker_viewer_app::ContainerGroup]| {
+        groups
+            .iter()
+            .map(|group| (group.project.clone(), group.ids.clone()))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(projects(&groups), projects(&app.container_groups));
+    assert_eq!(groups.last().unwrap().ids, ["11"]);
+}
+
+// Polls of a large host mostly change statuses, which must not resort and regroup the list.
+// The lists are scrambled after the first poll, so any rebuild would show.
+#[test]
+fn status_only_polls_of_ten_thousand_containers_keep_the_lists() {
+    let (mut app, _sender) = app();
+    let poll = |count: usize, seconds: usize| -> ContainerSnapshot {
+        (0..count)
+            .map(|i| {
+                let id = format!("{:064x}", i * 7919);
+                let mut container =
+                    summary(&format!("/c{}", i), &id, Some(&format!("p{}", i % 50)));
+                container.status = Some(format!("Up {} seconds", seconds));
+                (id, (container, String::new()))
+            })
+            .collect()
+    };
+    app.update_containers(poll(10_000, 0));
+    assert_eq!(app.container_groups.len(), 50);
+    app.container_ids.reverse();
+    app.container_groups.reverse();
+    let ids = app.container_ids.clone();
+    let groups: Vec<Option<String>> = app
+        .container_groups
+        .iter()
+        .map(|group| group.project.clone())
+        .collect();
+    let projects = |app: &DockerViewerApp| -> Vec<Option<String>> {
+        app.container_groups
+            .iter()
+            .map(|group| group.project.clone())
+            .collect()
+    };
+
+    for seconds in [2, 4, 6] {
+        app.update_containers(poll(10_000, seconds));
+        assert!(
+            app.container_ids == ids,
+            "resorted after {} seconds",
+            seconds
+        );
+        assert_eq!(
+            projects(&app),
+            groups,
+            "regrouped after {} seconds",
+            seconds
+        );
+    }
+
+    // A new container is put in, so the groups are built again
+    app.update_containers(poll(10_001, 8));
+    assert_eq!(app.container_ids.len(), 10_001);
+    assert_ne!(projects(&app), groups);
+}
+
+#[test]
+fn follow_flag_opens_the_logs_with_the_first_containers() {
+    let (mut app, sender) = app();
+    app.follow_on_start = Some("we".to_string());
+    app.current_view = AppView::Images;
+    sender
+        .try_send(snapshot(&[("/web", "1", None), ("/api", "2", None)]))
+        .unwrap();
+    app.receive_updates();
+    assert_eq!(app.selected_container.as_deref(), Some("1"));
+    assert!(matches!(app.current_view, AppView::Containers));
+    assert!(app.container_ui_state.get_mut("1").follow);
+    assert_eq!(app.follow_on_start, None);
+}
+
+#[test]
+fn follow_flag_falls_back_to_the_list_when_not_found() {
+    let (mut app, sender) = app();
+    app.follow_on_start = Some("db".to_string());
+    sender
+        .try_send(snapshot(&[("/web", "1", None), ("/api", "2", None)]))
+        .unwrap();
+    app.receive_updates();
+    assert_eq!(app.selected_container, None);
+    assert_eq!(
+        app.status_message,
+        Some(Err("No such container: db".to_string()))
+    );
+}
+
+#[test]
+fn containers_sharing_a_name_are_both_listed() {
+    let (mut app, sender) = app();
+    sender
+        .try_send(snapshot(&[("/web", "2", None), ("/web", "1", None)]))
+        .unwrap();
+    app.receive_updates();
+    assert_eq!(app.container_ids, ["1", "2"]);
+    assert_eq!(names(&app, &app.container_ids), ["/web", "/web"]);
+}
+
+#[test]
+fn selection_survives_a_rename() {
+    let (mut app, sender) = app();
+    sender
+        .try_send(snapshot(&[("/before", "1", None)]))
+        .unwrap();
+    app.receive_updates();
+    app.selected_container = Some("1".to_string());
+    sender.try_send(snapshot(&[("/after", "1", None)])).unwrap();
+    app.receive_updates();
+    assert_eq!(app.selected_container.as_deref(), Some("1"));
+    assert_eq!(names(&app, &app.container_ids), ["/after"]);
+}
+
+#[test]
+fn only_the_latest_snapshot_is_kept() {
+    let (mut app, sender) = app();
+    sender.try_send(snapshot(&[("/old", "1", None)])).unwrap();
+    sender.try_send(snapshot(&[("/new", "2", None)])).unwrap();
+    app.receive_updates();
+    assert_eq!(names(&app, &app.container_ids), ["/new"]);
+}
+
+#[test]
+fn groups_follow_compose_projects_with_standalone_last() {
+    let (mut app, sender) = app();
+    sender
+        .try_send(snapshot(&[
+            ("/solo", "1", None),
+            ("/shop-web", "2", Some("shop")),
+            ("/blog-web", "3", Some("blog")),
+            ("/shop-db", "4", Some("shop")),
+        ]))
+        .unwrap();
+    app.receive_updates();
+    let groups: Vec<(Option<&str>, Vec<String>)> = app
+        .container_groups
+        .iter()
+        .map(|group| (group.project.as_deref(), names(&app, &group.ids)))
+        .collect();
+    assert_eq!(
+        groups,
+        [
+            (Some("blog"), vec!["/blog-web".to_string()]),
+            (
+                Some("shop"),
+                vec!["/shop-db".to_string(), "/shop-web".to_string()]
+            ),
+            (None, vec!["/solo".to_string()]),
+        ]
+    );
+}
+
+#[test]
+fn selected_project_is_cleared_when_it_disappears() {
+    let (mut app, sender) = app();
+    sender
+        .try_send(snapshot(&[("/shop-web", "1", Some("shop"))]))
+        .unwrap();
+    app.receive_updates();
+    app.selected_project = Some("shop".to_string());
+    sender.try_send(snapshot(&[("/solo", "2", None)])).unwrap();
+    app.receive_updates();
+    assert_eq!(app.selected_project, None);
+}
+
+#[test]
+fn pending_selection_waits_for_the_container_to_show_up() {
+    let (mut app, sender) = app();
+    app.pending_selection = Some("new-id".to_string());
+    sender
+        .try_send(snapshot(&[("/old", "old-id", None)]))
+        .unwrap();
+    app.receive_updates();
+    assert_eq!(app.selected_container, None);
+
+    sender
+        .try_send(snapshot(&[
+            ("/old", "old-id", None),
+            ("/new", "new-id", None),
+        ]))
+        .unwrap();
+    app.receive_updates();
+    assert_eq!(app.selected_container.as_deref(), Some("new-id"));
+    assert_eq!(app.pending_selection, None);
+}
+
+#[test]
+fn filter_matches_projects_case_insensitively_and_keeps_the_selection() {
+    let (mut app, sender) = app();
+    sender
+        .try_send(snapshot(&[
+            ("/shop-web", "1", Some("shop")),
+            ("/blog-web", "2", Some("blog")),
+            ("/solo", "3", None),
+        ]))
+        .unwrap();
+    app.receive_updates();
+    app.selected_container = Some("2".to_string());
+
+    app.container_filter = "SHOP".to_string();
+    assert_eq!(app.visible_container_ids(), ["1"]);
+    assert_eq!(app.selected_container.as_deref(), Some("2"));
+
+    app.container_filter = "web".to_string();
+    assert_eq!(app.visible_container_ids(), ["2", "1"]);
+}
+
+#[test]
+fn bulk_actions_only_target_their_scope() {
+    let (mut app, sender) = app();
+    sender
+        .try_send(snapshot(&[
+            ("/shop-web", "1", Some("shop")),
+            ("/shop-db", "2", Some("shop")),
+            ("/database", "3", None),
+        ]))
+        .unwrap();
+    app.receive_updates();
+    let targets = |app: &DockerViewerApp| -> Vec<String> {
+        app.bulk_targets()
+            .iter()
+            .map(|summary| summary.id.clone().unwrap())
+            .collect()
+    };
+
+    app.bulk_scope = BulkScope::Project("shop".to_string());
+    assert_eq!(targets(&app), ["2", "1"]);
+
+    app.bulk_scope = BulkScope::Filter;
+    app.container_filter = "data".to_string();
+    assert_eq!(targets(&app), ["3"]);
+
+    app.bulk_scope = BulkScope::All;
+    assert_eq!(targets(&app).len(), 3);
+}
+
+fn pending(action: ContainerAction, state: &str) -> (PendingAction, oneshot::Sender<()>) {
+    let (done_sender, done) = oneshot::channel();
+    let pending = PendingAction {
+        action,
+        since: Instant::now(),
+        state: Some(state.to_string()),
+        done,
+    };
+    (pending, done_sender)
+}
+
+#[tokio::test]
+async fn a_second_action_waits_for_the_pending_one() {
+    let (mut app, sender) = app();
+    sender.try_send(snapshot(&[("/web", "1", None)])).unwrap();
+    app.receive_updates();
+    app.start_action("1".to_string(), ContainerAction::Stop);
+    app.start_action("1".to_string(), ContainerAction::Kill(SIGKILL));
+    assert_eq!(app.pending_actions.len(), 1);
+    assert_eq!(app.pending_actions["1"].action, ContainerAction::Stop);
+}
+
+#[test]
+fn pending_actions_clear_on_a_state_change() {
+    let (mut app, sender) = app();
+    let mut running = snapshot(&[("/web", "1", None)]);
+    running.get_mut("1").unwrap().0.state = Some("running".to_string());
+    let (action, _done) = pending(ContainerAction::Stop, "running");
+    app.pending_actions.insert("1".to_string(), action);
+    sender.try_send(running.clone()).unwrap();
+    app.receive_updates();
+    assert!(app.pending_actions.contains_key("1"));
+
+    running.get_mut("1").unwrap().0.state = Some("exited".to_string());
+    sender.try_send(running).unwrap();
+    app.receive_updates();
+    assert!(app.pending_actions.is_empty());
+}
+
+#[test]
+fn pending_actions_time_out_with_a_warning() {
+    let (mut app, sender) = app();
+    let mut running = snapshot(&[("/web", "1", None)]);
+    running.get_mut("1").unwrap().0.state = Some("running".to_string());
+    sender.try_send(running).unwrap();
+    app.receive_updates();
+    let (action, _done) = pending(ContainerAction::Stop, "running");
+    app.pending_actions.insert("1".to_string(), action);
+
+    app.clear_finished_actions(Instant::now() + Duration::from_secs(29));
+    assert!(app.pending_actions.contains_key("1"));
+    app.clear_finished_actions(Instant::now() + Duration::from_secs(31));
+    assert!(app.pending_actions.is_empty());
+    app.receive_updates();
+    assert_eq!(
+        app.status_message,
+        Some(Err("No response to stop of 1 after 30s".to_string()))
+    );
+}
+
+#[test]
+fn invalid_label_filter_keeps_the_active_one() {
+    let (mut app, _sender) = app();
+    app.apply_label_filter("env = staging");
+    assert_eq!(app.label_filter.lock().unwrap().to_string(), "env=staging");
+    assert_eq!(app.label_filter_input, "env=staging");
+
+    app.apply_label_filter("env=staging,");
+    assert_eq!(app.label_filter.lock().unwrap().to_string(), "env=staging");
+    assert!(matches!(
+        &app.status_message,
+        Some(Err(e)) if e.starts_with("Label filter: ")
+    ));
+}
+
+#[test]
+fn only_compose_services_can_be_scaled() {
+    let (mut app, sender) = app();
+    let mut containers = snapshot(&[
+        ("/api-web-1", "1", Some("api")),
+        ("/api-web-2", "2", Some("api")),
+        ("/api-db-1", "3", Some("api")),
+        ("/standalone", "4", None),
+    ]);
+    for (id, service) in [("1", "web"), ("2", "web"), ("3", "db")] {
+        let summary = &mut containers.get_mut(id).unwrap().0;
+        let labels = summary.labels.as_mut().unwrap();
+        labels.insert(COMPOSE_SERVICE_LABEL.to_string(), service.to_string());
+        labels.insert(
+            COMPOSE_WORKING_DIR_LABEL.to_string(),
+            "/srv/api".to_string(),
+        );
+    }
+    containers.get_mut("1").unwrap().0.state = Some("running".to_string());
+    sender.try_send(containers).unwrap();
+    app.receive_updates();
+
+    let replicas = service_replicas(app.containers.values().map(|(summary, _)| summary));
+    assert_eq!(replicas["web"], (1, 2));
+    assert_eq!(replicas["db"], (0, 1));
+
+    app.open_scale_form("4");
+    assert!(app.scale_form.is_none());
+    assert_eq!(
+        app.status_message,
+        Some(Err(
+            "/standalone is not part of a compose project, only compose services can be scaled"
+                .to_string()
+        ))
+    );
+
+    app.open_scale_form("2");
+    let form = app.scale_form.as_ref().unwrap();
+    assert_eq!(form.project.name, "api");
+    assert_eq!(form.service, "web");
+    assert_eq!(form.replicas, 2);
+}
+
+#[test]
+fn watched_containers_turning_unhealthy_are_alerted() {
+    let (mut app, sender) = app();
+    app.config.alerts.desktop = false;
+    let mut containers = snapshot(&[("/api", "1", None)]);
+    containers.get_mut("1").unwrap().0.status = Some("Up 1 hour (healthy)".to_string());
+    sender.try_send(containers.clone()).unwrap();
+    app.receive_updates();
+    app.toggle_watch("1");
+    assert!(app.watched.contains("api"));
+
+    containers.get_mut("1").unwrap().0.status = Some("Up 1 hour (unhealthy)".to_string());
+    sender.try_send(containers).unwrap();
+    app.receive_updates();
+    assert_eq!(app.alerts.len(), 1);
+    assert_eq!(app.alerts[0].container, "api");
+
+    app.toggle_watch("1");
+    assert!(app.watched.is_empty());
+}
+
+#[test]
+fn related_containers_networks_and_images_can_be_selected() {
+    let (mut app, _sender) = app();
+    app.containers = snapshot(&[("/api", "a", None)]);
+    app.networks = vec![Network {
+        id: Some("n1".to_string()),
+        name: Some("backend".to_string()),
+        ..Default::default()
+    }];
+    app.images = vec![ImageSummary {
+        id: "sha256:abc".to_string(),
+        ..Default::default()
+    }];
+
+    // Containers list their networks by name
+    assert!(app.select_network_by_id("backend"));
+    assert_eq!(app.selected_network.as_deref(), Some("n1"));
+    assert!(matches!(app.current_view, AppView::Networks));
+
+    assert!(app.select_container_by_id("a"));
+    assert_eq!(app.selected_container.as_deref(), Some("a"));
+    assert!(matches!(app.current_view, AppView::Containers));
+
+    assert!(app.select_image_by_id("sha256:abc"));
+    assert_eq!(app.selected_image.as_deref(), Some("sha256:abc"));
+    assert!(matches!(app.current_view, AppView::Images));
+
+    // What is gone is reported and the view stays
+    assert!(!app.select_container_by_id("b"));
+    assert!(!app.select_network_by_id("frontend"));
+    assert!(matches!(app.current_view, AppView::Images));
+    assert!(app
+        .status_message
+        .as_ref()
+        .is_some_and(|status| status.is_err()));
+}
+
+#[test]
+fn reported_errors_become_notifications() {
+    let (mut app, _sender) = app();
+    report(
+        &app.error_sender,
+        DockerrsError::docker("Failed to remove container abc", "conflict"),
+    );
+    app.receive_updates();
+    assert_eq!(app.notifications.shown.len(), 1);
+    assert_eq!(
+        app.notifications.shown[0].message,
+        "Failed to remove container abc: conflict"
+    );
+    assert!(!app.notifications.shown[0].success);
+}
+
+#[test]
+fn action_results_notify_and_select_their_container() {
+    let (mut app, sender) = app();
+    sender
+        .try_send(snapshot(&[("/web", "abc", None), ("/db", "def", None)]))
+        .unwrap();
+    app.receive_updates();
+    app.result_sender
+        .send(Ok("Removed 2 containers".to_string()))
+        .unwrap();
+    for _ in 0..2 {
+        app.action_result_sender
+            .send((
+                "def".to_string(),
+                Ok("Container def: stop done".to_string()),
+            ))
+            .unwrap();
+    }
+    app.receive_updates();
+    let labels: Vec<String> = app
+        .notifications
+        .shown
+        .iter()
+        .map(|notification| notification.label())
+        .collect();
+    assert_eq!(
+        labels,
+        ["Removed 2 containers", "Container def: stop done (×2)"]
+    );
+    assert_eq!(
+        app.status_message,
+        Some(Ok("Container def: stop done".to_string()))
+    );
+
+    let container = app.notifications.shown[1].container.clone().unwrap();
+    assert!(app.select_container_by_id(&container));
+    assert_eq!(app.selected_container.as_deref(), Some("def"));
+}
+
+#[test]
+fn rows_tell_containers_with_the_same_name_apart() {
+    let (mut app, sender) = app();
+    sender
+        .try_send(snapshot(&[
+            ("/app-1", "1", Some("proj1")),
+            ("/app-1", "2", Some("proj2")),
+            ("/db", "3", None),
+        ]))
+        .unwrap();
+    app.receive_updates();
+    let row_names: Vec<String> = ["1", "2", "3"]
+        .iter()
+        .map(|id| app.row_name(id, &app.containers[*id].0))
+        .collect();
+    assert_eq!(row_names, ["proj1/app-1", "proj2/app-1", "/db"]);
+
+    sender
+        .try_send(snapshot(&[("/app-1", "1", Some("proj1"))]))
+        .unwrap();
+    app.receive_updates();
+    assert_eq!(app.row_name("1", &app.containers["1"].0), "/app-1");
+}
+
+#[tokio::test]
+async fn followed_logs_stream_the_selected_container_only() {
+    let (mut app, sender) = app();
+    sender
+        .try_send(snapshot(&[("/web", "1", None), ("/db", "2", None)]))
+        .unwrap();
+    app.receive_updates();
+    app.selected_container = Some("1".to_string());
+    app.update_followed_logs();
+    assert!(app.followed_logs.is_none());
+
+    app.container_ui_state.get_mut("1").follow = true;
+    app.update_followed_logs();
+    assert_eq!(app.followed_logs.as_ref().unwrap().container_id, "1");
+
+    // Another container without Follow stops the stream
+    app.selected_container = Some("2".to_string());
+    app.update_followed_logs();
+    assert!(app.followed_logs.is_none());
+
+    app.container_ui_state.get_mut("2").follow = true;
+    app.update_followed_logs();
+    assert_eq!(app.followed_logs.as_ref().unwrap().container_id, "2");
+    app.container_ui_state.get_mut("2").follow = false;
+    app.update_followed_logs();
+    assert!(app.followed_logs.is_none());
+}
+
+#[tokio::test]
+async fn stopped_streams_are_kept_until_the_container_is_gone() {
+    let (mut app, sender) = app();
+    sender
+        .try_send(snapshot(&[("/web", "1", None), ("/db", "2", None)]))
+        .unwrap();
+    app.receive_updates();
+    app.selected_container = Some("1".to_string());
+    app.container_ui_state.get_mut("1").follow = true;
+    app.update_followed_logs();
+
+    app.selected_container = Some("2".to_string());
+    app.update_followed_logs();
+    assert!(app.stopped_logs.contains_key("1"));
+
+    // Following it again picks the stream back up
+    app.selected_container = Some("1".to_string());
+    app.update_followed_logs();
+    assert!(!app.stopped_logs.contains_key("1"));
+    assert_eq!(app.followed_logs.as_ref().unwrap().container_id, "1");
+
+    // Recreated under a new ID, it starts over
+    app.selected_container = None;
+    app.update_followed_logs();
+    sender
+        .try_send(snapshot(&[("/web", "3", None), ("/db", "2", None)]))
+        .unwrap();
+    app.receive_updates();
+    app.update_followed_logs();
+    assert!(app.stopped_logs.is_empty());
+}
+
+#[tokio::test]
+async fn split_view_streams_the_selection_without_follow() {
+    let (mut app, sender) = app();
+    sender
+        .try_send(snapshot(&[("/web", "1", None), ("/db", "2", None)]))
+        .unwrap();
+    app.receive_updates();
+    app.selected_container = Some("1".to_string());
+    app.run_command(Command::SplitView, None);
+    app.update_followed_logs();
+    assert_eq!(app.followed_logs.as_ref().unwrap().container_id, "1");
+
+    // A new selection keeps the old stream until it settles
+    app.selected_container = Some("2".to_string());
+    app.update_followed_logs();
+    assert_eq!(app.followed_logs.as_ref().unwrap().container_id, "1");
+    assert!(app.is_busy());
+
+    // Too narrow for two halves, back to streaming only with Follow
+    app.layout = LayoutMode::Narrow;
+    app.update_followed_logs();
+    assert!(app.followed_logs.is_none());
+}
+
+#[test]
+fn crashes_in_the_background_flash_the_tab_bar() {
+    let (mut app, _sender) = app();
+    app.config.alerts.desktop = false;
+    app.config.alerts.flash = true;
+    let alert = |transition| Alert {
+        at: 0,
+        container: "api".to_string(),
+        transition,
+    };
+    app.record_alert(alert(Transition::Exited(Some(1))), Instant::now());
+    assert_eq!(app.crash_flash_frames, 0, "the window has focus");
+
+    app.window_focused = false;
+    app.alert_limiter = AlertLimiter::default();
+    app.record_alert(alert(Transition::Exited(Some(0))), Instant::now());
+    assert_eq!(app.crash_flash_frames, 0, "a clean exit is no crash");
+
+    app.alert_limiter = AlertLimiter::default();
+    app.record_alert(alert(Transition::OomKilled), Instant::now());
+    assert_eq!(app.crash_flash_frames, FLASH_FRAMES);
+}
+
+#[test]
+fn commands_with_an_argument_get_it_from_the_palette() {
+    let (mut app, sender) = app();
+    sender
+        .try_send(snapshot(&[("/web", "1", None), ("/api", "2", None)]))
+        .unwrap();
+    app.receive_updates();
+    app.run_command(Command::ShowView(AppView::Volumes), None);
+    assert_eq!(app.current_view, AppView::Volumes);
+
+    app.run_command(Command::FollowLogs, Some(" api ".to_string()));
+    assert_eq!(app.current_view, AppView::Containers);
+    assert_eq!(app.selected_container.as_deref(), Some("2"));
+    assert!(app.container_ui_state.get_mut("2").follow);
+
+    app.run_command(Command::FilterByLabels, Some("env=prod".to_string()));
+    assert_eq!(app.label_filter.lock().unwrap().to_string(), "env=prod");
+}
+
+#[test]
+fn container_commands_need_a_selection() {
+    let (mut app, sender) = app();
+    sender.try_send(snapshot(&[("/web", "1", None)])).unwrap();
+    app.receive_updates();
+    app.run_command(Command::WrapLogs, None);
+    assert!(!app.container_ui_state.get_mut("1").wrap);
+
+    app.selected_container = Some("1".to_string());
+    app.run_command(Command::WrapLogs, None);
+    assert!(app.container_ui_state.get_mut("1").wrap);
+    app.run_command(Command::Remove, None);
+    assert_eq!(app.confirm_remove.as_deref(), Some("1"));
+}
@@ -0,0 +1,20 @@
+use dockerrs::attach::{detach_key_bytes, strip_ansi, DEFAULT_DETACH_KEYS};
+
+#[test]
+fn detach_keys_follow_the_docker_format() {
+    assert_eq!(detach_key_bytes(DEFAULT_DETACH_KEYS), Ok(vec![16, 17]));
+    assert_eq!(detach_key_bytes("ctrl-a,x"), Ok(vec![1, b'x']));
+    assert_eq!(detach_key_bytes("ctrl-@,ctrl-_"), Ok(vec![0, 31]));
+    assert!(detach_key_bytes("ctrl-1").is_err());
+    assert!(detach_key_bytes("ctrl-p,,ctrl-q").is_err());
+}
+
+#[test]
+fn escape_sequences_are_stripped() {
+    assert_eq!(
+        strip_ansi("\u{1b}[1;32m>>> \u{1b}[0mprint(1)\r\n"),
+        ">>> print(1)\n"
+    );
+    assert_eq!(strip_ansi("\u{1b}[2K\u{1b}[Gready"), "ready");
+    assert_eq!(strip_ansi("plain"), "plain");
+}
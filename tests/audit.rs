@@ -0,0 +1,91 @@
+use std::time::Duration;
+
+use dockerrs::audit::{ActionHistory, AuditKind};
+use dockerrs::docker_api::{ContainerAction, RemoveOptions};
+use dockerrs::signals::SIGKILL;
+use tokio::sync::mpsc;
+
+#[test]
+fn actions_are_described_for_the_history() {
+    assert_eq!(ContainerAction::Stop.describe("/web"), "Stop /web");
+    assert_eq!(
+        ContainerAction::Kill(SIGKILL).describe("/web"),
+        "Kill /web with SIGKILL"
+    );
+    let options = RemoveOptions {
+        force: true,
+        volumes: true,
+    };
+    assert_eq!(
+        ContainerAction::Remove(options).describe("/web"),
+        "Remove /web (force, volumes)"
+    );
+    assert_eq!(
+        ContainerAction::Remove(RemoveOptions::default()).describe("/web"),
+        "Remove /web"
+    );
+}
+
+#[test]
+fn sent_actions_and_results_are_kept_in_order() {
+    let mut history = ActionHistory::default();
+    history.sent("Stop /web".to_string(), 60);
+    history.result(&Err("Failed to stop container web: gone".to_string()), 61);
+    history.result(&Ok("Container web: stop done".to_string()), 62);
+    let kinds: Vec<AuditKind> = history.entries.iter().map(|entry| entry.kind).collect();
+    assert_eq!(
+        kinds,
+        [AuditKind::Sent, AuditKind::Failed, AuditKind::Succeeded]
+    );
+    assert_eq!(
+        history.entries[1].line(),
+        "1970-01-01 00:01:01 UTC failed Failed to stop container web: gone"
+    );
+}
+
+#[tokio::test]
+async fn the_audit_log_is_appended_to() {
+    let path = std::env::temp_dir().join(format!("dockerrs-audit-{}.log", std::process::id()));
+    std::fs::write(&path, "earlier session\n").unwrap();
+    let (errors, mut error_receiver) = mpsc::unbounded_channel();
+    let mut history = ActionHistory::with_log(path.clone(), errors);
+    history.sent("Stop /web".to_string(), 0);
+    history.result(&Ok("Container web: stop done".to_string()), 1);
+
+    let expected = "earlier session\n\
+        1970-01-01 00:00:00 UTC sent   Stop /web\n\
+        1970-01-01 00:00:01 UTC ok     Container web: stop done\n";
+    let mut written = String::new();
+    for _ in 0..100 {
+        written = std::fs::read_to_string(&path).unwrap();
+        if written == expected {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(written, expected);
+    assert!(error_receiver.try_recv().is_err());
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn an_unwritable_audit_log_is_reported_and_the_history_kept() {
+    let path = std::env::temp_dir()
+        .join(format!("dockerrs-missing-{}", std::process::id()))
+        .join("audit.log");
+    let (errors, mut error_receiver) = mpsc::unbounded_channel();
+    let mut history = ActionHistory::with_log(path, errors);
+    history.sent("Stop /web".to_string(), 0);
+    let error = tokio::time::timeout(Duration::from_secs(5), error_receiver.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(
+        error
+            .to_string()
+            .starts_with("Failed to write the audit log"),
+        "{}",
+        error
+    );
+    assert_eq!(history.entries.len(), 1);
+}
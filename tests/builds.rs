@@ -0,0 +1,78 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use dockerrs::builds::{BuildHistory, BuildJob, BuildStatus, MAX_BUILDS_PER_FILE};
+
+async fn finish(history: &mut BuildHistory, now: Instant) -> Vec<Result<String, String>> {
+    for _ in 0..100 {
+        let (_, results) = history.receive(now);
+        if !results.is_empty() {
+            return results;
+        }
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+    panic!("build did not finish");
+}
+
+#[tokio::test]
+async fn builds_report_output_status_and_duration() {
+    let dockerfile = PathBuf::from("/work/api/Dockerfile");
+    let started = Instant::now();
+    let mut history = BuildHistory::default();
+    history.push(BuildJob::start(
+        dockerfile.clone(),
+        "api:1".to_string(),
+        started,
+        |output| async move {
+            let _ = output.send("Step 1/2 : FROM alpine:3".to_string());
+            let _ = output.send("Step 2/2 : RUN false".to_string());
+            Err("The command returned a non-zero code: 1".to_string())
+        },
+    ));
+    assert!(history.is_running());
+    assert_eq!(
+        history.latest(&dockerfile).unwrap().summary(started),
+        "api:1 0s"
+    );
+
+    let results = finish(&mut history, started + Duration::from_secs(12)).await;
+    assert_eq!(
+        results,
+        vec![Err("The command returned a non-zero code: 1".to_string())]
+    );
+    let job = history.find(&dockerfile, started).unwrap();
+    assert_eq!(
+        job.status,
+        BuildStatus::Failed("The command returned a non-zero code: 1".to_string())
+    );
+    assert_eq!(job.output.len(), 2);
+    assert_eq!(job.duration, Some(Duration::from_secs(12)));
+    assert_eq!(
+        job.summary(started + Duration::from_secs(60)),
+        "✗ api:1 12s"
+    );
+    assert!(!history.is_running());
+}
+
+#[tokio::test]
+async fn only_the_newest_builds_are_kept_per_file() {
+    let dockerfile = Path::new("/work/web/Dockerfile");
+    let start = Instant::now();
+    let mut history = BuildHistory::default();
+    for i in 0..MAX_BUILDS_PER_FILE + 2 {
+        history.push(BuildJob::start(
+            dockerfile.to_path_buf(),
+            format!("web:{}", i),
+            start + Duration::from_secs(i as u64),
+            |_| async { Ok("Built".to_string()) },
+        ));
+    }
+    let tags: Vec<&str> = history
+        .jobs(dockerfile)
+        .map(|job| job.tag.as_str())
+        .collect();
+    assert_eq!(tags, vec!["web:6", "web:5", "web:4", "web:3", "web:2"]);
+    assert!(history
+        .latest(Path::new("/work/other/Dockerfile"))
+        .is_none());
+}
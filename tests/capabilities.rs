@@ -0,0 +1,72 @@
+use bollard::errors::Error;
+use bollard::system::Version;
+use dockerrs::capabilities::{describe_error, parse_api_version, Capabilities, Feature};
+
+fn daemon(api_version: &str) -> Capabilities {
+    Capabilities::from_version(&Version {
+        version: Some("20.10.24".to_string()),
+        api_version: Some(api_version.to_string()),
+        ..Default::default()
+    })
+}
+
+#[test]
+fn api_versions_are_parsed_up_to_the_minor_number() {
+    assert_eq!(parse_api_version("1.41"), Some((1, 41)));
+    assert_eq!(parse_api_version("1.43"), Some((1, 43)));
+    assert_eq!(parse_api_version(" v1.44 "), Some((1, 44)));
+    assert_eq!(parse_api_version("1.45-beta"), Some((1, 45)));
+    assert_eq!(parse_api_version("1.41.2"), Some((1, 41)));
+    assert_eq!(parse_api_version(""), None);
+    assert_eq!(parse_api_version("1"), None);
+    assert_eq!(parse_api_version("garbage"), None);
+    assert_eq!(parse_api_version("1.x"), None);
+}
+
+#[test]
+fn features_are_gated_on_the_negotiated_api_version() {
+    let old = daemon("1.41");
+    assert!(old.supports(Feature::BuildCachePrune));
+    assert!(old.supports(Feature::ContainerStatsOneShot));
+    assert!(!old.supports(Feature::DiskUsageByType));
+    assert!(!old.supports(Feature::VolumePruneAll));
+
+    let new = daemon("1.43");
+    assert!(new.supports(Feature::DiskUsageByType));
+    assert!(new.supports(Feature::VolumePruneAll));
+
+    let ancient = daemon("1.38");
+    assert!(!ancient.supports(Feature::BuildCachePrune));
+    assert_eq!(ancient.describe(), "Docker 20.10.24 (API 1.38)");
+}
+
+#[test]
+fn unknown_versions_support_everything() {
+    let unknown = Capabilities::default();
+    assert!(unknown.supports(Feature::VolumePruneAll));
+    assert_eq!(unknown.describe(), "Docker API unknown");
+
+    let garbage = daemon("garbage");
+    assert_eq!(garbage.api_version, None);
+    assert!(garbage.supports(Feature::DiskUsageByType));
+}
+
+#[test]
+fn not_found_errors_name_the_required_version() {
+    let not_found = Error::DockerResponseServerError {
+        status_code: 404,
+        message: "page not found".to_string(),
+    };
+    assert_eq!(
+        describe_error(Feature::VolumePruneAll, &not_found),
+        "Not supported by this daemon (requires API ≥ 1.42)"
+    );
+    let conflict = Error::DockerResponseServerError {
+        status_code: 409,
+        message: "a prune operation is already running".to_string(),
+    };
+    assert_eq!(
+        describe_error(Feature::VolumePruneAll, &conflict),
+        conflict.to_string()
+    );
+}
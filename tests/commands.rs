@@ -0,0 +1,115 @@
+use std::collections::HashSet;
+
+use dockerrs::commands::{fuzzy_score, info, matching_commands, Binding, Command, COMMANDS};
+use dockerrs::docker_viewer_app::AppView;
+
+#[test]
+fn characters_have_to_appear_in_order() {
+    assert!(fuzzy_score("net", "Switch to networks").is_some());
+    assert!(fuzzy_score("NET", "switch to networks").is_some());
+    assert_eq!(fuzzy_score("ten", "Switch to networks"), None);
+    assert_eq!(fuzzy_score("networkss", "Switch to networks"), None);
+}
+
+#[test]
+fn empty_query_and_spaces_match_everything() {
+    assert_eq!(fuzzy_score("", "Disk usage"), Some(0));
+    assert_eq!(
+        fuzzy_score("disk usage", "Disk usage"),
+        fuzzy_score("diskusage", "Disk usage")
+    );
+}
+
+#[test]
+fn runs_and_word_starts_score_higher() {
+    let run = fuzzy_score("usa", "Disk usage").unwrap();
+    let scattered = fuzzy_score("usa", "Remove unused images").unwrap();
+    assert!(run > scattered);
+
+    let word_start = fuzzy_score("i", "Open image").unwrap();
+    let inside = fuzzy_score("i", "Diagnostics").unwrap();
+    assert!(word_start > inside);
+}
+
+#[test]
+fn best_match_comes_first() {
+    let names: Vec<&str> = matching_commands("sw net")
+        .iter()
+        .map(|info| info.name)
+        .collect();
+    assert_eq!(names[0], "Switch to networks");
+
+    let names: Vec<&str> = matching_commands("open logs")
+        .iter()
+        .map(|info| info.name)
+        .collect();
+    assert_eq!(names[0], "Open logs for…");
+}
+
+#[test]
+fn empty_query_lists_every_command_but_the_palette_in_registry_order() {
+    let commands: Vec<Command> = matching_commands("")
+        .iter()
+        .map(|info| info.command)
+        .collect();
+    let expected: Vec<Command> = COMMANDS
+        .iter()
+        .map(|info| info.command)
+        .filter(|command| *command != Command::Palette)
+        .collect();
+    assert_eq!(commands, expected);
+}
+
+#[test]
+fn no_binding_is_used_twice() {
+    let mut seen = HashSet::new();
+    for info in COMMANDS {
+        for binding in info.bindings {
+            assert!(
+                seen.insert(binding.label()),
+                "{} is bound twice",
+                binding.label()
+            );
+        }
+    }
+}
+
+#[test]
+fn shift_bindings_come_before_the_plain_key() {
+    // Extra Shift is ignored when matching, so Shift+A would also run A otherwise
+    let position = |wanted: Binding| {
+        COMMANDS
+            .iter()
+            .position(|info| info.bindings.contains(&wanted))
+            .unwrap()
+    };
+    for info in COMMANDS {
+        for binding in info.bindings {
+            let Binding::Key(modifiers, key) = *binding else {
+                continue;
+            };
+            let plain = Binding::Key(eframe::egui::Modifiers::NONE, key);
+            let bound = COMMANDS.iter().any(|info| info.bindings.contains(&plain));
+            if modifiers.shift && bound {
+                assert!(position(*binding) < position(plain), "{}", binding.label());
+            }
+        }
+    }
+}
+
+#[test]
+fn labels_and_prompts() {
+    let palette = info(Command::Palette).unwrap();
+    let labels: Vec<String> = palette
+        .bindings
+        .iter()
+        .map(|binding| binding.label())
+        .collect();
+    assert_eq!(labels, ["Ctrl+P", ":"]);
+    assert_eq!(
+        info(Command::ShowView(AppView::Images)).unwrap().bindings[0].label(),
+        "Ctrl+4"
+    );
+    assert!(info(Command::FollowLogs).unwrap().prompt.is_some());
+    assert!(info(Command::DiskUsage).unwrap().prompt.is_none());
+}
@@ -0,0 +1,66 @@
+// Builders shared by the integration tests, each test uses only some of them
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+
+use bollard::secret::ContainerSummary;
+use dockerrs::poller::ContainerSnapshot;
+use dockerrs::utils::COMPOSE_PROJECT_LABEL;
+
+// A container as the poller lists it, only the ID is set until more is given
+pub struct TestContainer(ContainerSummary);
+
+pub fn container(id: &str) -> TestContainer {
+    TestContainer(ContainerSummary {
+        id: Some(id.to_string()),
+        ..Default::default()
+    })
+}
+
+impl TestContainer {
+    // Without the leading slash the API puts in front of names
+    pub fn name(mut self, name: &str) -> Self {
+        self.0.names = Some(vec![format!("/{}", name)]);
+        self
+    }
+
+    pub fn state(mut self, state: &str) -> Self {
+        self.0.state = Some(state.to_string());
+        self
+    }
+
+    pub fn status(mut self, status: &str) -> Self {
+        self.0.status = Some(status.to_string());
+        self
+    }
+
+    pub fn project(self, project: &str) -> Self {
+        self.label(COMPOSE_PROJECT_LABEL, project)
+    }
+
+    pub fn label(mut self, key: &str, value: &str) -> Self {
+        self.0
+            .labels
+            .get_or_insert_with(HashMap::new)
+            .insert(key.to_string(), value.to_string());
+        self
+    }
+
+    pub fn build(self) -> ContainerSummary {
+        self.0
+    }
+}
+
+// Keyed by ID like the poller's snapshots, without fingerprints
+pub fn snapshot(containers: impl IntoIterator<Item = TestContainer>) -> ContainerSnapshot {
+    containers
+        .into_iter()
+        .map(|container| {
+            let summary = container.build();
+            (
+                summary.id.clone().unwrap_or_default(),
+                (summary, String::new()),
+            )
+        })
+        .collect()
+}
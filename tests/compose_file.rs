@@ -0,0 +1,141 @@
+use dockerrs::compose_file::{error_lines, ComposeFile, HostPort};
+
+const COMPOSE: &str = r#"
+services:
+  web:
+    build:
+      context: ./web
+    ports:
+      - "8080:80"
+      - "9000"
+      - target: 443
+        published: 8443
+        protocol: tcp
+    depends_on:
+      api:
+        condition: service_healthy
+  api:
+    image: example/api:1.0
+    depends_on: [db]
+  db:
+    image: postgres:16
+volumes:
+  data:
+  cache: {}
+"#;
+
+#[test]
+fn services_are_summarized() {
+    let compose = ComposeFile::parse(COMPOSE).unwrap();
+    let web = &compose.services["web"];
+    assert_eq!(web.image, None);
+    assert_eq!(web.build_context().as_deref(), Some("./web"));
+    assert_eq!(web.published_ports(), vec!["8080:80", "8443:443/tcp"]);
+    assert_eq!(web.depends_on(), vec!["api"]);
+    assert_eq!(compose.services["api"].depends_on(), vec!["db"]);
+    assert_eq!(
+        compose.volumes.keys().collect::<Vec<_>>(),
+        vec!["cache", "data"]
+    );
+}
+
+#[test]
+fn start_order_puts_dependencies_first() {
+    let compose = ComposeFile::parse(COMPOSE).unwrap();
+    assert_eq!(compose.start_order().unwrap(), vec!["db", "api", "web"]);
+}
+
+#[test]
+fn start_order_reports_cycles_and_undefined_services() {
+    let cycle = ComposeFile::parse(
+        "services:\n  a:\n    depends_on: [b]\n  b:\n    depends_on: [a]\n  c: {}\n",
+    )
+    .unwrap();
+    assert_eq!(
+        cycle.start_order(),
+        Err("Dependency cycle between a, b".to_string())
+    );
+
+    let missing = ComposeFile::parse("services:\n  a:\n    depends_on: [b]\n").unwrap();
+    assert_eq!(
+        missing.start_order(),
+        Err("a depends on undefined service b".to_string())
+    );
+}
+
+#[test]
+fn invalid_yaml_reports_the_line() {
+    let e = ComposeFile::parse("services:\n  web:\n    image: [\n").unwrap_err();
+    assert!(!error_lines(&e).is_empty(), "{}", e);
+}
+
+#[test]
+fn error_lines_are_found_in_messages() {
+    assert_eq!(
+        error_lines("yaml: line 12: did not find expected key, at line 3 column 5"),
+        vec![3, 12]
+    );
+    assert!(error_lines("service \"web\" has neither an image nor a build context").is_empty());
+}
+
+#[test]
+fn host_ports_read_short_and_long_syntax() {
+    let compose = ComposeFile::parse(
+        r#"
+services:
+  web:
+    ports:
+      - "8080:80"
+      - "127.0.0.1:5432:5432/udp"
+      - "[::1]:6379:6379"
+      - "9000"
+      - "127.0.0.1::80"
+      - 3000
+      - target: 443
+        published: 8443
+      - target: 53
+        published: "5353"
+        host_ip: 10.0.0.1
+        protocol: udp
+      - target: 80
+"#,
+    )
+    .unwrap();
+    let ports: Vec<String> = compose.services["web"]
+        .host_ports()
+        .iter()
+        .map(HostPort::to_string)
+        .collect();
+    assert_eq!(
+        ports,
+        vec![
+            "8080/tcp",
+            "127.0.0.1:5432/udp",
+            "[::1]:6379/tcp",
+            "8443/tcp",
+            "10.0.0.1:5353/udp",
+        ]
+    );
+}
+
+#[test]
+fn host_port_ranges_are_expanded() {
+    let compose = ComposeFile::parse(
+        r#"
+services:
+  web:
+    ports:
+      - "8000-8002:80-82"
+      - target: 9000
+        published: "9100-9101"
+      - "70-x:80"
+"#,
+    )
+    .unwrap();
+    let ports: Vec<u16> = compose.services["web"]
+        .host_ports()
+        .iter()
+        .map(|port| port.port)
+        .collect();
+    assert_eq!(ports, vec![8000, 8001, 8002, 9100, 9101]);
+}
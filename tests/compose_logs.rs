@@ -0,0 +1,46 @@
+use dockerrs::compose_logs::{explain, parse_line, ComposeLine};
+
+#[test]
+fn colored_prefixes_give_the_service_and_its_color() {
+    assert_eq!(
+        parse_line("\u{1b}[36mweb-1  |\u{1b}[0m GET / 200"),
+        ComposeLine {
+            service: Some("web-1".to_string()),
+            color: Some(6),
+            message: "GET / 200".to_string(),
+        }
+    );
+    // Bright colors and bold
+    let line = parse_line("\u{1b}[1;93mdb-1   |\u{1b}[0m ready | accepting");
+    assert_eq!(line.service.as_deref(), Some("db-1"));
+    assert_eq!(line.color, Some(3));
+    assert_eq!(line.message, "ready | accepting");
+}
+
+#[test]
+fn plain_prefixes_and_empty_messages() {
+    assert_eq!(
+        parse_line("worker-2  |"),
+        ComposeLine {
+            service: Some("worker-2".to_string()),
+            color: None,
+            message: String::new(),
+        }
+    );
+}
+
+#[test]
+fn lines_without_a_prefix_are_compose_itself() {
+    let line = parse_line("no configuration file provided: not found");
+    assert_eq!(line.service, None);
+    assert_eq!(line.message, "no configuration file provided: not found");
+    // Spaces before the bar are not a service
+    assert_eq!(parse_line("a b | c").service, None);
+}
+
+#[test]
+fn a_missing_compose_plugin_says_what_to_install() {
+    let message = explain("docker: 'compose' is not a docker command.");
+    assert!(message.contains("install the Docker Compose plugin"));
+    assert_eq!(explain("other"), "other");
+}
@@ -0,0 +1,71 @@
+use std::path::Path;
+
+use dockerrs::config::{Config, MAX_RECENT_TAGS};
+use dockerrs::utils::{check_image_tag, suggested_tag};
+
+#[test]
+fn saved_filters_survive_a_round_trip() {
+    let dir = std::env::temp_dir().join(format!("dockerrs-config-{}", std::process::id()));
+    let path = dir.join("nested").join("config.yaml");
+    assert_eq!(Config::load(&path).unwrap(), Config::default());
+
+    let mut config = Config::default();
+    config.save_filter("staging", "env=staging");
+    config.save_filter("api", "com.docker.compose.project=api");
+    config.save_filter("staging", "env=staging,tier!=db");
+    config.save(&path).unwrap();
+
+    let loaded = Config::load(&path).unwrap();
+    assert_eq!(loaded, config);
+    assert_eq!(loaded.saved_filters.len(), 2);
+    assert_eq!(loaded.saved_filters[0].expression, "env=staging,tier!=db");
+
+    std::fs::write(&path, "saved_filters: 3").unwrap();
+    assert!(Config::load(&path).is_err());
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn build_tags_are_remembered_per_dockerfile() {
+    let api = Path::new("/work/api/Dockerfile");
+    let mut config = Config::default();
+    assert!(config.recent_tags(api).is_empty());
+    for i in 0..12 {
+        config.remember_tag(api, &format!("api:{}", i));
+    }
+    config.remember_tag(api, "api:5");
+    config.remember_tag(Path::new("/work/web/Dockerfile"), "web:latest");
+
+    let recent = config.recent_tags(api);
+    assert_eq!(recent.len(), MAX_RECENT_TAGS);
+    assert_eq!(recent[..3], ["api:5", "api:11", "api:10"]);
+    assert_eq!(
+        config.recent_tags(Path::new("/work/web/Dockerfile")),
+        ["web:latest"]
+    );
+}
+
+#[test]
+fn build_tags_are_checked_and_suggested() {
+    assert_eq!(check_image_tag(" api:1 "), Ok("api:1".to_string()));
+    assert!(check_image_tag("add tag").is_err());
+    assert!(check_image_tag("  ").is_err());
+    assert!(check_image_tag("my app").is_err());
+    assert_eq!(
+        suggested_tag(Path::new("/work/myapp/Dockerfile")).as_deref(),
+        Some("myapp:latest")
+    );
+}
+
+#[test]
+fn read_only_mode_can_be_set_in_the_config_file() {
+    let dir = std::env::temp_dir().join(format!("dockerrs-config-ro-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("config.yaml");
+    std::fs::write(&path, "read_only: true\n").unwrap();
+    assert!(Config::load(&path).unwrap().read_only);
+
+    std::fs::write(&path, "keep_alive: [web]\n").unwrap();
+    assert!(!Config::load(&path).unwrap().read_only);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
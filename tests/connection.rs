@@ -0,0 +1,133 @@
+use std::path::{Path, PathBuf};
+
+use dockerrs::connection::{candidates, select, Endpoint, Platform, DEFAULT_PIPE};
+
+fn socket(path: &str) -> Endpoint {
+    Endpoint::Socket(PathBuf::from(path))
+}
+
+fn endpoints(platform: Platform, docker_host: Option<&str>) -> Vec<Endpoint> {
+    candidates(platform, docker_host, Some(Path::new("/Users/me")))
+        .into_iter()
+        .map(|candidate| candidate.endpoint)
+        .collect()
+}
+
+#[test]
+fn macos_tries_docker_desktop_first() {
+    assert_eq!(
+        endpoints(Platform::MacOs, None),
+        [
+            socket("/Users/me/.docker/run/docker.sock"),
+            socket("/var/run/docker.sock")
+        ]
+    );
+    // Without a home directory only the usual socket is left
+    let without_home: Vec<Endpoint> = candidates(Platform::MacOs, None, None)
+        .into_iter()
+        .map(|candidate| candidate.endpoint)
+        .collect();
+    assert_eq!(without_home, [socket("/var/run/docker.sock")]);
+}
+
+#[test]
+fn linux_uses_the_usual_socket_and_windows_the_pipe() {
+    assert_eq!(
+        endpoints(Platform::Unix, None),
+        [socket("/var/run/docker.sock")]
+    );
+    assert_eq!(
+        endpoints(Platform::Windows, None),
+        [Endpoint::NamedPipe(DEFAULT_PIPE.to_string())]
+    );
+}
+
+#[test]
+fn docker_host_is_the_only_candidate() {
+    assert_eq!(
+        endpoints(Platform::MacOs, Some("unix:///run/user/1000/docker.sock")),
+        [socket("/run/user/1000/docker.sock")]
+    );
+    assert_eq!(
+        endpoints(
+            Platform::Windows,
+            Some("npipe:////./pipe/dockerDesktopEngine")
+        ),
+        [Endpoint::NamedPipe(
+            r"\\.\pipe\dockerDesktopEngine".to_string()
+        )]
+    );
+    // An empty DOCKER_HOST is unset
+    assert_eq!(
+        endpoints(Platform::Unix, Some("")),
+        [socket("/var/run/docker.sock")]
+    );
+}
+
+#[test]
+fn unsupported_docker_hosts_do_not_fall_back_to_the_local_daemon() {
+    assert_eq!(
+        endpoints(Platform::Unix, Some("tcp://10.0.0.2:2375")),
+        [Endpoint::Unsupported("tcp://10.0.0.2:2375".to_string())]
+    );
+    let error = select(
+        candidates(Platform::Unix, Some("tcp://10.0.0.2:2375"), None),
+        |_| true,
+    )
+    .unwrap_err();
+    assert_eq!(
+        error,
+        "DOCKER_HOST scheme tcp:// is not supported, tried tcp://10.0.0.2:2375 (DOCKER_HOST \
+         environment variable)"
+    );
+    let error = select(
+        candidates(Platform::MacOs, Some("ssh://me@build-host"), None),
+        |_| true,
+    )
+    .unwrap_err();
+    assert!(error.starts_with("DOCKER_HOST scheme ssh:// is not supported"));
+    let error = select(
+        candidates(Platform::Unix, Some("10.0.0.2:2375"), None),
+        |_| true,
+    )
+    .unwrap_err();
+    assert!(error.starts_with("DOCKER_HOST value 10.0.0.2:2375 is not supported"));
+}
+
+#[test]
+fn the_first_existing_endpoint_is_used() {
+    let found = select(
+        candidates(Platform::MacOs, None, Some(Path::new("/Users/me"))),
+        |endpoint| *endpoint == socket("/var/run/docker.sock"),
+    )
+    .unwrap();
+    assert_eq!(found.endpoint, socket("/var/run/docker.sock"));
+    assert_eq!(found.source, "default");
+
+    let found = select(
+        candidates(Platform::MacOs, None, Some(Path::new("/Users/me"))),
+        |_| true,
+    )
+    .unwrap();
+    assert_eq!(found.source, "Docker Desktop");
+}
+
+#[test]
+fn failure_lists_every_endpoint_tried() {
+    let error = select(
+        candidates(Platform::MacOs, None, Some(Path::new("/Users/me"))),
+        |_| false,
+    )
+    .unwrap_err();
+    assert_eq!(
+        error,
+        "No Docker daemon found, tried unix:///Users/me/.docker/run/docker.sock (Docker Desktop), \
+         unix:///var/run/docker.sock (default)"
+    );
+
+    let error = select(candidates(Platform::Windows, None, None), |_| false).unwrap_err();
+    assert_eq!(
+        error,
+        "No Docker daemon found, tried npipe:////./pipe/docker_engine (default)"
+    );
+}
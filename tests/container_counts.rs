@@ -0,0 +1,50 @@
+use dockerrs::container_counts::ContainerCounts;
+
+mod common;
+
+use common::{container, snapshot};
+
+#[test]
+fn containers_are_counted_by_state_and_health() {
+    let counts = ContainerCounts::of(&snapshot([
+        container("1").state("running").status("Up 3 minutes"),
+        container("2")
+            .state("running")
+            .status("Up 3 minutes (healthy)"),
+        container("3")
+            .state("running")
+            .status("Up 1 minute (unhealthy)"),
+        container("4")
+            .state("exited")
+            .status("Exited (0) 2 hours ago"),
+        container("5")
+            .state("exited")
+            .status("Exited (137) 5 minutes ago"),
+        container("6").state("created").status("Created"),
+    ]));
+    assert_eq!(
+        counts,
+        ContainerCounts {
+            running: 3,
+            unhealthy: 1,
+            exited: 2,
+            total: 6,
+        }
+    );
+    assert_eq!(
+        counts.window_title(),
+        "dockerrs — 3 running, 1 unhealthy, 2 exited"
+    );
+    assert_eq!(counts.tab_label(), "Containers (3/6)");
+}
+
+#[test]
+fn empty_counts_are_left_out() {
+    let counts = ContainerCounts::of(&snapshot([container("7")
+        .state("running")
+        .status("Up 3 minutes")]));
+    assert_eq!(counts.window_title(), "dockerrs — 1 running");
+    let none = ContainerCounts::of(&snapshot([]));
+    assert_eq!(none.window_title(), "dockerrs");
+    assert_eq!(none.tab_label(), "Containers");
+}
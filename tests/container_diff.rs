@@ -0,0 +1,42 @@
+use dockerrs::container_diff::{diff_rows, ChangeKind, DiffRow};
+
+fn changes() -> Vec<(ChangeKind, String)> {
+    vec![
+        (ChangeKind::Changed, "/etc".to_string()),
+        (ChangeKind::Added, "/var/log/app.log".to_string()),
+        (ChangeKind::Changed, "/etc/nginx/nginx.conf".to_string()),
+        (ChangeKind::Added, "/tmp/cache".to_string()),
+        (ChangeKind::Deleted, "/etc/motd".to_string()),
+    ]
+}
+
+#[test]
+fn changes_are_grouped_by_kind_and_sorted() {
+    let rows = diff_rows(&changes(), "");
+    assert_eq!(
+        rows,
+        [
+            DiffRow::Header(ChangeKind::Added, 2),
+            DiffRow::Path(ChangeKind::Added, "/tmp/cache".to_string()),
+            DiffRow::Path(ChangeKind::Added, "/var/log/app.log".to_string()),
+            DiffRow::Header(ChangeKind::Changed, 2),
+            DiffRow::Path(ChangeKind::Changed, "/etc".to_string()),
+            DiffRow::Path(ChangeKind::Changed, "/etc/nginx/nginx.conf".to_string()),
+            DiffRow::Header(ChangeKind::Deleted, 1),
+            DiffRow::Path(ChangeKind::Deleted, "/etc/motd".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn search_keeps_only_matching_groups() {
+    let rows = diff_rows(&changes(), "NGINX");
+    assert_eq!(
+        rows,
+        [
+            DiffRow::Header(ChangeKind::Changed, 1),
+            DiffRow::Path(ChangeKind::Changed, "/etc/nginx/nginx.conf".to_string()),
+        ]
+    );
+    assert!(diff_rows(&changes(), "missing").is_empty());
+}
@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+
+use dockerrs::container_names::disambiguated_names;
+
+mod common;
+
+use common::{container, snapshot};
+
+fn names(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs
+        .iter()
+        .map(|(id, name)| (id.to_string(), name.to_string()))
+        .collect()
+}
+
+#[test]
+fn unique_names_are_left_alone() {
+    let containers = snapshot([
+        container("1").name("proj1-app-1").project("proj1"),
+        container("2").name("proj2-app-1").project("proj2"),
+        container("3").name("db"),
+    ]);
+    assert!(disambiguated_names(&containers).is_empty());
+}
+
+#[test]
+fn duplicates_get_their_project_or_else_their_short_id() {
+    let containers = snapshot([
+        container("1").name("app-1").project("proj1"),
+        container("2").name("app-1").project("proj2"),
+        container("3").name("app-1"),
+        container("aaaaaaaaaaaaaaaa")
+            .name("worker")
+            .project("proj1"),
+        container("bbbbbbbbbbbbbbbb")
+            .name("worker")
+            .project("proj1"),
+        container("4").name("db"),
+    ]);
+    assert_eq!(
+        disambiguated_names(&containers),
+        names(&[
+            ("1", "proj1/app-1"),
+            ("2", "proj2/app-1"),
+            ("3", "app-1"),
+            ("aaaaaaaaaaaaaaaa", "proj1/worker (aaaaaaaaaaaa)"),
+            ("bbbbbbbbbbbbbbbb", "proj1/worker (bbbbbbbbbbbb)"),
+        ])
+    );
+}
+
+#[test]
+fn containers_without_compose_labels_fall_back_to_the_short_id() {
+    let containers = snapshot([
+        container("0123456789abcdef").name("web"),
+        container("fedcba9876543210").name("web"),
+    ]);
+    assert_eq!(
+        disambiguated_names(&containers),
+        names(&[
+            ("0123456789abcdef", "web (0123456789ab)"),
+            ("fedcba9876543210", "web (fedcba987654)"),
+        ])
+    );
+}
+
+#[test]
+fn a_rename_ends_the_collision() {
+    let mut containers = snapshot([
+        container("1").name("app-1").project("proj1"),
+        container("2").name("app-1").project("proj2"),
+    ]);
+    assert_eq!(disambiguated_names(&containers).len(), 2);
+    containers.get_mut("2").unwrap().0.names = Some(vec!["/app-2".to_string()]);
+    assert!(disambiguated_names(&containers).is_empty());
+}
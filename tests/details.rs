@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+
+use bollard::secret::{
+    ContainerSummary, ContainerSummaryNetworkSettings, EndpointSettings, MountPoint,
+    MountPointTypeEnum, Port, PortTypeEnum,
+};
+use dockerrs::details::{command_line, label_rows, mount_rows, network_rows, port_rows};
+
+fn strings(rows: Vec<(String, String, String)>) -> Vec<[String; 3]> {
+    rows.into_iter().map(|(a, b, c)| [a, b, c]).collect()
+}
+
+#[test]
+fn ports_show_host_and_container_side() {
+    let port = |ip: Option<&str>, private_port, public_port, typ| Port {
+        ip: ip.map(String::from),
+        private_port,
+        public_port,
+        typ: Some(typ),
+    };
+    let summary = ContainerSummary {
+        ports: Some(vec![
+            port(Some("0.0.0.0"), 80, Some(8080), PortTypeEnum::TCP),
+            port(Some("::"), 80, Some(8080), PortTypeEnum::TCP),
+            port(None, 53, None, PortTypeEnum::UDP),
+            port(Some("0.0.0.0"), 80, Some(8080), PortTypeEnum::TCP),
+        ]),
+        ..Default::default()
+    };
+    assert_eq!(
+        strings(port_rows(&summary)),
+        [
+            ["-", "53", "udp"],
+            ["0.0.0.0:8080", "80", "tcp"],
+            ["[::]:8080", "80", "tcp"],
+        ]
+    );
+}
+
+#[test]
+fn mounts_networks_and_labels_are_rows() {
+    let summary = ContainerSummary {
+        mounts: Some(vec![
+            MountPoint {
+                typ: Some(MountPointTypeEnum::VOLUME),
+                name: Some("data".to_string()),
+                source: Some("/var/lib/docker/volumes/data/_data".to_string()),
+                destination: Some("/data".to_string()),
+                rw: Some(true),
+                ..Default::default()
+            },
+            MountPoint {
+                typ: Some(MountPointTypeEnum::BIND),
+                source: Some("/etc/app.conf".to_string()),
+                destination: Some("/app.conf".to_string()),
+                rw: Some(false),
+                ..Default::default()
+            },
+        ]),
+        network_settings: Some(ContainerSummaryNetworkSettings {
+            networks: Some(HashMap::from([(
+                "bridge".to_string(),
+                EndpointSettings {
+                    ip_address: Some("172.17.0.2".to_string()),
+                    gateway: Some(String::new()),
+                    ..Default::default()
+                },
+            )])),
+        }),
+        labels: Some(HashMap::from([
+            ("b".to_string(), "2".to_string()),
+            ("a".to_string(), "1".to_string()),
+        ])),
+        ..Default::default()
+    };
+    assert_eq!(
+        strings(mount_rows(&summary)),
+        [
+            ["data", "/data", "rw"],
+            ["/etc/app.conf", "/app.conf", "ro"]
+        ]
+    );
+    assert_eq!(
+        strings(network_rows(&summary)),
+        [["bridge", "172.17.0.2", "-"]]
+    );
+    let keys: Vec<String> = label_rows(&summary).into_iter().map(|(k, _)| k).collect();
+    assert_eq!(keys, ["a", "b"]);
+}
+
+#[test]
+fn command_lines_quote_arguments_with_spaces() {
+    let args = ["sh", "-c", "echo 'hi there'", ""].map(String::from);
+    assert_eq!(command_line(&args), r#"sh -c 'echo '\''hi there'\''' ''"#);
+}
@@ -0,0 +1,136 @@
+use dockerrs::diagnostics::{
+    check_config, check_connection_target, check_notifications, check_state_writable, format_text,
+    CheckResult, CheckStatus,
+};
+
+fn scratch_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "dockerrs-diagnostics-{}-{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+// The only test in this file that touches DOCKER_HOST, so the cases cannot race
+#[test]
+fn connection_target_follows_docker_host() {
+    let dir = scratch_dir("target");
+    let socket = dir.join("docker.sock");
+    std::fs::write(&socket, b"").unwrap();
+
+    std::env::set_var("DOCKER_HOST", format!("unix://{}", socket.display()));
+    let found = check_connection_target();
+    assert_eq!(found.status, CheckStatus::Pass);
+    assert!(found.detail.contains("docker.sock"), "{}", found.detail);
+    assert_eq!(found.hint, None);
+
+    std::env::set_var(
+        "DOCKER_HOST",
+        format!("unix://{}", dir.join("gone.sock").display()),
+    );
+    let missing = check_connection_target();
+    assert_eq!(missing.status, CheckStatus::Fail);
+    assert!(missing.hint.is_some());
+
+    std::env::set_var("DOCKER_HOST", "tcp://10.0.0.2:2375");
+    let remote = check_connection_target();
+    assert_eq!(remote.status, CheckStatus::Fail);
+    assert!(remote.detail.contains("tcp://"), "{}", remote.detail);
+
+    std::env::remove_var("DOCKER_HOST");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+fn results() -> Vec<CheckResult> {
+    vec![
+        CheckResult {
+            name: "daemon reachable",
+            status: CheckStatus::Pass,
+            detail: "ping answered in 3 ms".to_string(),
+            hint: None,
+        },
+        CheckResult {
+            name: "docker group",
+            status: CheckStatus::Warn,
+            detail: "current user groups: me wheel".to_string(),
+            hint: Some("Add yourself to the docker group".to_string()),
+        },
+    ]
+}
+
+#[test]
+fn text_output_puts_hints_below_their_check() {
+    assert_eq!(
+        format_text(&results()),
+        "[PASS] daemon reachable: ping answered in 3 ms\n\
+         [WARN] docker group: current user groups: me wheel\n       \
+         hint: Add yourself to the docker group"
+    );
+    assert_eq!(format_text(&[]), "");
+}
+
+#[test]
+fn json_output_has_lowercase_statuses_and_null_hints() {
+    let json = serde_json::to_value(results()).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!([
+            {
+                "name": "daemon reachable",
+                "status": "pass",
+                "detail": "ping answered in 3 ms",
+                "hint": null
+            },
+            {
+                "name": "docker group",
+                "status": "warn",
+                "detail": "current user groups: me wheel",
+                "hint": "Add yourself to the docker group"
+            }
+        ])
+    );
+}
+
+#[test]
+fn config_files_are_checked_for_parsing_and_writing() {
+    let dir = scratch_dir("config");
+    let config = dir.join("dockerrs").join("config.yaml");
+
+    // Not created yet, saving creates the directory
+    assert_eq!(check_config(Some(&config)).status, CheckStatus::Pass);
+    assert_eq!(
+        check_state_writable(Some(&config)).status,
+        CheckStatus::Pass
+    );
+
+    std::fs::create_dir_all(config.parent().unwrap()).unwrap();
+    std::fs::write(&config, "keep_alive: [web]\n").unwrap();
+    assert_eq!(check_config(Some(&config)).status, CheckStatus::Pass);
+    assert_eq!(
+        check_state_writable(Some(&config)).status,
+        CheckStatus::Pass
+    );
+
+    std::fs::write(&config, "keep_alive: {not: [a list\n").unwrap();
+    let broken = check_config(Some(&config));
+    assert_eq!(broken.status, CheckStatus::Fail);
+    assert!(
+        broken.detail.contains("Failed to parse"),
+        "{}",
+        broken.detail
+    );
+
+    assert_eq!(check_config(None).status, CheckStatus::Warn);
+    assert_eq!(check_state_writable(None).status, CheckStatus::Warn);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn notifications_are_only_checked_with_desktop_alerts_on() {
+    let off = check_notifications(false);
+    assert_eq!(off.status, CheckStatus::Pass);
+    assert_eq!(off.detail, "desktop alerts are off");
+}
@@ -0,0 +1,153 @@
+use bollard::secret::{
+    BuildCache, ContainerSummary, ImageSummary, SystemDataUsageResponse, Volume, VolumeUsageData,
+};
+use dockerrs::disk_usage::{
+    reclaimed_build_cache, summarize, BuildCachePrune, Category, CategoryUsage,
+};
+use dockerrs::utils::{format_size, parse_size, parse_size_in, SizeUnits};
+
+fn image(size: i64, shared_size: i64, containers: i64) -> ImageSummary {
+    ImageSummary {
+        size,
+        shared_size,
+        containers,
+        ..Default::default()
+    }
+}
+
+fn container(state: &str, size_rw: i64) -> ContainerSummary {
+    ContainerSummary {
+        state: Some(state.to_string()),
+        size_rw: Some(size_rw),
+        ..Default::default()
+    }
+}
+
+fn volume(size: i64, ref_count: i64) -> Volume {
+    Volume {
+        usage_data: Some(VolumeUsageData { size, ref_count }),
+        ..Default::default()
+    }
+}
+
+fn cache(size: i64, in_use: bool) -> BuildCache {
+    BuildCache {
+        size: Some(size),
+        in_use: Some(in_use),
+        shared: Some(false),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn reclaimable_space_is_what_nothing_uses() {
+    let usage = SystemDataUsageResponse {
+        layers_size: Some(1000),
+        images: Some(vec![image(600, 100, 1), image(300, 100, 0)]),
+        containers: Some(vec![container("running", 10), container("exited", 20)]),
+        volumes: Some(vec![volume(50, 1), volume(70, 0), volume(-1, -1)]),
+        build_cache: Some(vec![cache(5, true), cache(8, false)]),
+    };
+    let rows = summarize(&usage);
+    let row = |category| rows.iter().find(|row| row.category == category).unwrap();
+
+    // The layers the used image shares with others are not counted as used twice
+    assert_eq!(
+        row(Category::Images),
+        &CategoryUsage {
+            category: Category::Images,
+            total: 2,
+            active: 1,
+            size: 1000,
+            reclaimable: 500,
+        }
+    );
+    let containers = row(Category::Containers);
+    assert_eq!((containers.active, containers.size), (1, 30));
+    assert_eq!(containers.reclaimable, 20);
+    // Sizes the daemon did not compute are reported as -1
+    let volumes = row(Category::Volumes);
+    assert_eq!(
+        (volumes.total, volumes.size, volumes.reclaimable),
+        (3, 120, 70)
+    );
+    assert_eq!(row(Category::BuildCache).reclaimable, 8);
+}
+
+#[test]
+fn empty_daemon_has_nothing_to_reclaim() {
+    let rows = summarize(&SystemDataUsageResponse::default());
+    assert_eq!(rows.len(), 4);
+    assert!(rows
+        .iter()
+        .all(|row| row.total == 0 && row.size == 0 && row.reclaimable == 0));
+}
+
+#[test]
+fn sizes_parse_in_the_units_they_are_shown_in() {
+    assert_eq!(parse_size("10GB"), Ok(10_000_000_000));
+    assert_eq!(parse_size(" 1.5 gb "), Ok(1_500_000_000));
+    assert_eq!(parse_size("512MiB"), Ok(512 * 1024 * 1024));
+    assert_eq!(parse_size("2k"), Ok(2_000));
+    assert_eq!(parse_size("4096"), Ok(4096));
+    assert_eq!(parse_size("0B"), Ok(0));
+    assert!(parse_size("GB").is_err());
+    assert!(parse_size("10 parsecs").is_err());
+    assert!(parse_size("").is_err());
+
+    for bytes in [0, 999, 1_500_000, 12_300_000_000] {
+        let shown = format_size(bytes);
+        assert_eq!(parse_size(&shown), Ok(bytes as u64), "{}", shown);
+    }
+}
+
+#[test]
+fn only_the_short_units_depend_on_the_unit_system() {
+    assert_eq!(
+        parse_size("512m"),
+        parse_size_in("512m", SizeUnits::Decimal)
+    );
+    assert_eq!(parse_size_in("512m", SizeUnits::Decimal), Ok(512_000_000));
+    assert_eq!(
+        parse_size_in("512m", SizeUnits::Binary),
+        Ok(512 * 1024 * 1024)
+    );
+    assert_eq!(parse_size_in("2TB", SizeUnits::Binary), Ok(2 << 40));
+    for units in [SizeUnits::Decimal, SizeUnits::Binary] {
+        assert_eq!(parse_size_in("512MiB", units), Ok(512 * 1024 * 1024));
+        assert_eq!(parse_size_in("4096", units), Ok(4096));
+        assert!(parse_size_in("10 parsecs", units).is_err());
+    }
+}
+
+#[test]
+fn build_cache_prune_passes_its_settings_to_the_cli() {
+    assert_eq!(
+        BuildCachePrune::default().args(),
+        ["builder", "prune", "--force"]
+    );
+    let everything_above = BuildCachePrune {
+        keep_storage: Some(10_000_000_000),
+        all: true,
+    };
+    assert_eq!(
+        everything_above.args(),
+        [
+            "builder",
+            "prune",
+            "--force",
+            "--keep-storage",
+            "10000000000",
+            "--all"
+        ]
+    );
+}
+
+#[test]
+fn reclaimed_build_cache_comes_from_the_total() {
+    let output =
+        "ID\tRECLAIMABLE\tSIZE\tLAST ACCESSED\nx1\ttrue\t1.2GB\t2 days ago\nTotal:\t1.234GB\n";
+    assert_eq!(reclaimed_build_cache(output), Some(1_234_000_000));
+    assert_eq!(reclaimed_build_cache("Total:\t0B\n"), Some(0));
+    assert_eq!(reclaimed_build_cache(""), None);
+}
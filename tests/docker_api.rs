@@ -0,0 +1,675 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use bollard::container::Config;
+use bollard::secret::{
+    ContainerConfig, ContainerInspectResponse, ContainerState, ContainerSummary, EndpointSettings,
+    HostConfig, ImageSummary, Network, NetworkSettings,
+};
+use bollard::system::Version;
+use dockerrs::docker_api::{
+    recreate, recreate_config, run_action, run_bulk, ContainerAction, DockerApi, RemoveOptions,
+};
+use dockerrs::docker_viewer_app::DockerViewerApp;
+use dockerrs::image_build::BuildSettings;
+use dockerrs::label_filter::LabelFilter;
+use dockerrs::poller::{self, Connection, PollSettings, RETRY_INTERVAL};
+use dockerrs::ps;
+use dockerrs::scan::ScanSettings;
+use dockerrs::signals::{parse_signal, SIGKILL};
+use tokio::sync::{mpsc, oneshot};
+
+// In-memory daemon, containers are only known by ID and calls are recorded
+#[derive(Default)]
+struct MockDocker {
+    containers: Vec<ContainerSummary>,
+    calls: Mutex<Vec<String>>,
+    // Listings that fail as if the daemon was down, before it answers
+    unreachable: AtomicUsize,
+    // IDs of containers made with `create`
+    created: Mutex<Vec<String>>,
+    create_fails: bool,
+}
+
+impl MockDocker {
+    fn with_containers(ids: &[&str]) -> Self {
+        let containers = ids
+            .iter()
+            .map(|id| ContainerSummary {
+                id: Some(id.to_string()),
+                names: Some(vec![format!("/{}", id)]),
+                ..Default::default()
+            })
+            .collect();
+        Self {
+            containers,
+            ..Default::default()
+        }
+    }
+
+    fn record(&self, call: String) {
+        self.calls.lock().unwrap().push(call);
+    }
+
+    fn check_exists(&self, id: &str) -> Result<(), String> {
+        if self
+            .containers
+            .iter()
+            .any(|container| container.id.as_deref() == Some(id))
+            || self
+                .created
+                .lock()
+                .unwrap()
+                .iter()
+                .any(|created| created == id)
+        {
+            Ok(())
+        } else {
+            Err(format!("No such container: {}", id))
+        }
+    }
+}
+
+impl DockerApi for MockDocker {
+    async fn version(&self) -> Result<Version, String> {
+        Ok(Version {
+            version: Some("25.0.0".to_string()),
+            api_version: Some("1.44".to_string()),
+            ..Default::default()
+        })
+    }
+
+    // Only the label filters the daemon supports, `label=key` and `label=key=value`
+    async fn list_containers(
+        &self,
+        _all: bool,
+        filters: HashMap<String, Vec<String>>,
+    ) -> Result<Vec<ContainerSummary>, String> {
+        self.record("list_containers".to_string());
+        if self
+            .unreachable
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |left| {
+                left.checked_sub(1)
+            })
+            .is_ok()
+        {
+            return Err("connection refused".to_string());
+        }
+        let conditions = filters.get("label").cloned().unwrap_or_default();
+        Ok(self
+            .containers
+            .iter()
+            .filter(|container| {
+                let labels = container.labels.clone().unwrap_or_default();
+                conditions
+                    .iter()
+                    .all(|condition| match condition.split_once('=') {
+                        Some((key, value)) => labels.get(key).map(String::as_str) == Some(value),
+                        None => labels.contains_key(condition),
+                    })
+            })
+            .cloned()
+            .collect())
+    }
+
+    async fn list_images(&self) -> Result<Vec<ImageSummary>, String> {
+        Ok(Vec::new())
+    }
+
+    async fn list_networks(&self) -> Result<Vec<Network>, String> {
+        Ok(Vec::new())
+    }
+
+    async fn logs(&self, id: &str, _tail: usize) -> Result<String, String> {
+        Ok(format!("logs of {}\n", id))
+    }
+
+    async fn start(&self, id: &str) -> Result<(), String> {
+        self.record(format!("start {}", id));
+        self.check_exists(id)
+    }
+
+    async fn stop(&self, id: &str) -> Result<(), String> {
+        self.record(format!("stop {}", id));
+        self.check_exists(id)
+    }
+
+    async fn kill(&self, id: &str, signal: &str) -> Result<(), String> {
+        self.record(format!("kill {} {}", id, signal));
+        self.check_exists(id)
+    }
+
+    async fn remove(&self, id: &str, options: RemoveOptions) -> Result<(), String> {
+        let force = if options.force { " --force" } else { "" };
+        let volumes = if options.volumes { " --volumes" } else { "" };
+        self.record(format!("remove {}{}{}", id, force, volumes));
+        self.check_exists(id)
+    }
+
+    // Every container runs the image named after it
+    async fn inspect(&self, id: &str) -> Result<ContainerInspectResponse, String> {
+        self.record(format!("inspect {}", id));
+        self.check_exists(id)?;
+        Ok(ContainerInspectResponse {
+            id: Some(id.to_string()),
+            name: Some(format!("/{}", id)),
+            state: Some(ContainerState {
+                running: Some(true),
+                ..Default::default()
+            }),
+            config: Some(ContainerConfig {
+                image: Some(format!("{}:latest", id)),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+    }
+
+    async fn create(&self, name: &str, config: Config<String>) -> Result<String, String> {
+        self.record(format!(
+            "create {} {}",
+            name,
+            config.image.unwrap_or_default()
+        ));
+        if self.create_fails {
+            return Err("image not found".to_string());
+        }
+        let id = format!("{}-new", name);
+        self.created.lock().unwrap().push(id.clone());
+        Ok(id)
+    }
+
+    async fn rename(&self, id: &str, name: &str) -> Result<(), String> {
+        self.record(format!("rename {} {}", id, name));
+        self.check_exists(id)
+    }
+}
+
+#[tokio::test]
+async fn stop_on_a_missing_container_fails() {
+    let docker = MockDocker::with_containers(&["abc"]);
+    let result = run_action(&docker, ContainerAction::Stop, "missing").await;
+    let error = result.unwrap_err();
+    assert!(
+        error.contains("Failed to stop container missing"),
+        "{}",
+        error
+    );
+    assert!(error.contains("No such container"), "{}", error);
+}
+
+#[tokio::test]
+async fn actions_reach_the_daemon() {
+    let docker = MockDocker::with_containers(&["abc"]);
+    for action in [
+        ContainerAction::Start,
+        ContainerAction::Stop,
+        ContainerAction::Kill(SIGKILL),
+        ContainerAction::Remove(RemoveOptions::default()),
+    ] {
+        assert!(run_action(&docker, action, "abc").await.is_ok());
+    }
+    assert_eq!(
+        *docker.calls.lock().unwrap(),
+        ["start abc", "stop abc", "kill abc SIGKILL", "remove abc"]
+    );
+}
+
+#[tokio::test]
+async fn recreate_replaces_the_container_under_the_same_name() {
+    let docker = MockDocker::with_containers(&["web"]);
+    let done = run_action(&docker, ContainerAction::Recreate, "web")
+        .await
+        .unwrap();
+    assert_eq!(done, "Container web: recreated as web-new");
+    assert_eq!(
+        *docker.calls.lock().unwrap(),
+        [
+            "inspect web",
+            "stop web",
+            "rename web web_dockerrs_old",
+            "create web web:latest",
+            "remove web",
+            "start web-new"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn recreate_restores_the_old_container_when_create_fails() {
+    let docker = MockDocker {
+        create_fails: true,
+        ..MockDocker::with_containers(&["web"])
+    };
+    let error = recreate(&docker, "web").await.unwrap_err();
+    assert_eq!(
+        error,
+        "Failed to create the new container: image not found, the old container was kept"
+    );
+    assert_eq!(
+        *docker.calls.lock().unwrap(),
+        [
+            "inspect web",
+            "stop web",
+            "rename web web_dockerrs_old",
+            "create web web:latest",
+            "rename web web",
+            "start web"
+        ]
+    );
+}
+
+#[test]
+fn recreate_config_leaves_out_what_the_daemon_assigned() {
+    let inspected = ContainerInspectResponse {
+        id: Some("0123456789abcdef".to_string()),
+        config: Some(ContainerConfig {
+            hostname: Some("0123456789ab".to_string()),
+            image: Some("api:latest".to_string()),
+            env: Some(vec!["MODE=prod".to_string()]),
+            ..Default::default()
+        }),
+        host_config: Some(HostConfig {
+            memory: Some(512 * 1024 * 1024),
+            ..Default::default()
+        }),
+        network_settings: Some(NetworkSettings {
+            networks: Some(HashMap::from([(
+                "backend".to_string(),
+                EndpointSettings {
+                    aliases: Some(vec!["0123456789ab".to_string(), "api".to_string()]),
+                    ip_address: Some("172.18.0.5".to_string()),
+                    network_id: Some("net".to_string()),
+                    ..Default::default()
+                },
+            )])),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let config = recreate_config(inspected);
+    assert_eq!(config.hostname, None);
+    assert_eq!(config.image.as_deref(), Some("api:latest"));
+    assert_eq!(config.env, Some(vec!["MODE=prod".to_string()]));
+    assert_eq!(
+        config.host_config.and_then(|host| host.memory),
+        Some(512 * 1024 * 1024)
+    );
+    let endpoints = config.networking_config.unwrap().endpoints_config;
+    assert_eq!(
+        endpoints["backend"],
+        EndpointSettings {
+            aliases: Some(vec!["api".to_string()]),
+            ..Default::default()
+        }
+    );
+}
+
+#[tokio::test]
+async fn bulk_actions_continue_past_failures_and_count_them() {
+    let docker = MockDocker::with_containers(&["abc", "def"]);
+    let ids = ["abc", "missing", "def"].map(String::from);
+    let error = run_bulk(&docker, ContainerAction::Kill(SIGKILL), &ids)
+        .await
+        .unwrap_err();
+    assert!(
+        error.starts_with("Kill: 2 of 3 containers done, 1 failed"),
+        "{}",
+        error
+    );
+    assert!(error.contains("missing"), "{}", error);
+    assert_eq!(
+        *docker.calls.lock().unwrap(),
+        [
+            "kill abc SIGKILL",
+            "kill missing SIGKILL",
+            "kill def SIGKILL"
+        ]
+    );
+
+    let done = run_bulk(&docker, ContainerAction::Stop, &ids[..1])
+        .await
+        .unwrap();
+    assert_eq!(done, "Stop: 1 of 1 containers done");
+}
+
+#[tokio::test]
+async fn poller_only_sends_and_repaints_on_changes() {
+    let (sender, mut receiver) = mpsc::channel(1);
+    let (images_sender, mut images_receiver) = mpsc::channel(1);
+    let (networks_sender, mut networks_receiver) = mpsc::channel(1);
+    let (capabilities_sender, capabilities_receiver) = oneshot::channel();
+    let repaints = Arc::new(AtomicUsize::new(0));
+    let docker = MockDocker::with_containers(&["abc", "def"]);
+    let counter = repaints.clone();
+    let poller = tokio::spawn(poller::poll(
+        docker,
+        sender,
+        images_sender,
+        networks_sender,
+        capabilities_sender,
+        PollSettings::default(),
+        move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        },
+    ));
+
+    let capabilities = capabilities_receiver.await.unwrap();
+    assert_eq!(capabilities.api_version, Some((1, 44)));
+
+    let snapshot = tokio::time::timeout(Duration::from_secs(5), receiver.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(snapshot.len(), 2);
+    assert_eq!(snapshot["abc"].1, "logs of abc\n");
+    images_receiver.recv().await.unwrap();
+    networks_receiver.recv().await.unwrap();
+
+    // The mock never changes, so later ticks send nothing and leave the UI idle
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert!(receiver.try_recv().is_err());
+    assert!(images_receiver.try_recv().is_err());
+    assert!(networks_receiver.try_recv().is_err());
+    assert_eq!(repaints.load(Ordering::SeqCst), 4);
+
+    // The poller stops once the app is gone
+    drop(receiver);
+    drop(images_receiver);
+    drop(networks_receiver);
+    tokio::time::timeout(Duration::from_secs(5), poller)
+        .await
+        .unwrap()
+        .unwrap();
+}
+
+#[tokio::test]
+async fn poller_reports_an_unreachable_daemon_and_retries() {
+    let docker = MockDocker::with_containers(&["abc"]);
+    docker.unreachable.store(1, Ordering::SeqCst);
+    let (sender, mut receiver) = mpsc::channel(1);
+    let (images_sender, _images_receiver) = mpsc::channel(1);
+    let (networks_sender, _networks_receiver) = mpsc::channel(1);
+    let (capabilities_sender, _) = oneshot::channel();
+    let settings = PollSettings::default();
+    let connection = settings.connection.clone();
+    let poller = tokio::spawn(poller::poll(
+        docker,
+        sender,
+        images_sender,
+        networks_sender,
+        capabilities_sender,
+        settings,
+        || {},
+    ));
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(
+        *connection.lock().unwrap(),
+        Connection::Failed("Cannot reach the Docker daemon: connection refused".to_string())
+    );
+    assert!(receiver.try_recv().is_err());
+
+    let snapshot = tokio::time::timeout(RETRY_INTERVAL * 3, receiver.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(snapshot.len(), 1);
+    assert_eq!(*connection.lock().unwrap(), Connection::Connected);
+    poller.abort();
+}
+
+#[tokio::test]
+async fn poller_stops_promptly_on_shutdown() {
+    // Down for good, so the poller is waiting out a retry when it is cancelled
+    let docker = MockDocker::with_containers(&["abc"]);
+    docker.unreachable.store(usize::MAX, Ordering::SeqCst);
+    let (sender, _receiver) = mpsc::channel(1);
+    let (images_sender, _images_receiver) = mpsc::channel(1);
+    let (networks_sender, _networks_receiver) = mpsc::channel(1);
+    let (capabilities_sender, _) = oneshot::channel();
+    let settings = PollSettings::default();
+    let shutdown = settings.shutdown.clone();
+    let poller = tokio::spawn(poller::poll(
+        docker,
+        sender,
+        images_sender,
+        networks_sender,
+        capabilities_sender,
+        settings,
+        || {},
+    ));
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(!poller.is_finished());
+    shutdown.cancel();
+    tokio::time::timeout(Duration::from_millis(200), poller)
+        .await
+        .expect("the poller outlived its shutdown")
+        .unwrap();
+}
+
+#[tokio::test]
+async fn closing_the_app_cancels_the_poller() {
+    let (_sender, receiver) = mpsc::channel(1);
+    let (_images_sender, images_receiver) = mpsc::channel(1);
+    let (_networks_sender, networks_receiver) = mpsc::channel(1);
+    let (_capabilities_sender, capabilities_receiver) = oneshot::channel();
+    let mut app = DockerViewerApp::new(
+        receiver,
+        images_receiver,
+        networks_receiver,
+        capabilities_receiver,
+        ScanSettings::default(),
+        BuildSettings::default(),
+    );
+    let shutdown = app.shutdown.clone();
+    eframe::App::on_exit(&mut app, None);
+    assert!(shutdown.is_cancelled());
+}
+
+#[tokio::test]
+async fn poller_lists_containers_by_label() {
+    let mut docker = MockDocker::with_containers(&["api", "db", "web"]);
+    for (container, (env, tier)) in docker.containers.iter_mut().zip([
+        ("staging", "app"),
+        ("staging", "db"),
+        ("production", "app"),
+    ]) {
+        container.labels = Some(HashMap::from([
+            ("env".to_string(), env.to_string()),
+            ("tier".to_string(), tier.to_string()),
+        ]));
+    }
+    let (sender, mut receiver) = mpsc::channel(1);
+    let (images_sender, _images_receiver) = mpsc::channel(1);
+    let (networks_sender, _networks_receiver) = mpsc::channel(1);
+    let (capabilities_sender, _) = oneshot::channel();
+    let filter = LabelFilter::parse("env=staging,tier!=db").unwrap();
+    let poller = tokio::spawn(poller::poll(
+        docker,
+        sender,
+        images_sender,
+        networks_sender,
+        capabilities_sender,
+        PollSettings {
+            label_filter: Arc::new(Mutex::new(filter)),
+            ..PollSettings::default()
+        },
+        || {},
+    ));
+
+    let snapshot = tokio::time::timeout(Duration::from_secs(5), receiver.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    let ids: Vec<&String> = snapshot.keys().collect();
+    assert_eq!(ids, ["api"]);
+    poller.abort();
+}
+
+#[tokio::test]
+async fn ps_lists_sorted_rows_and_finds_logs_by_name_or_id() {
+    let mut docker = MockDocker::with_containers(&["def456", "abc123"]);
+    docker.containers[0].status = Some("Up 2 minutes (healthy)".to_string());
+    docker.containers[0].labels = Some(HashMap::from([(
+        "com.docker.compose.project".to_string(),
+        "api".to_string(),
+    )]));
+
+    let rows = ps::list_containers(&docker, &LabelFilter::default())
+        .await
+        .unwrap();
+    let names: Vec<&str> = rows.iter().map(|row| row.name.as_str()).collect();
+    assert_eq!(names, ["abc123", "def456"]);
+    assert_eq!(rows[1].health, Some("healthy"));
+    assert_eq!(rows[1].project.as_deref(), Some("api"));
+
+    let table = ps::format_table(&rows);
+    let header = table.lines().next().unwrap();
+    assert!(
+        header.starts_with("CONTAINER ID  NAME    STATE"),
+        "{}",
+        header
+    );
+    assert_eq!(table.lines().count(), 3);
+
+    let filter = LabelFilter::parse("com.docker.compose.project=api").unwrap();
+    let rows = ps::list_containers(&docker, &filter).await.unwrap();
+    assert_eq!(rows.len(), 1);
+
+    assert_eq!(
+        ps::container_logs(&docker, "abc123", 10).await.unwrap(),
+        "logs of abc123\n"
+    );
+    assert_eq!(
+        ps::container_logs(&docker, "def", 10).await.unwrap(),
+        "logs of def456\n"
+    );
+    assert_eq!(
+        ps::container_logs(&docker, "missing", 10)
+            .await
+            .unwrap_err(),
+        "No such container: missing"
+    );
+}
+
+#[test]
+fn containers_resolve_by_name_id_or_unique_prefix() {
+    let docker = MockDocker::with_containers(&["api", "api-worker", "abc123", "web"]);
+    let resolve = |query| {
+        ps::resolve_container(&docker.containers, query).map(|summary| summary.id.clone().unwrap())
+    };
+    // A full name wins over the longer names it is a prefix of
+    assert_eq!(resolve("api").unwrap(), "api");
+    assert_eq!(resolve("api-w").unwrap(), "api-worker");
+    assert_eq!(resolve("abc").unwrap(), "abc123");
+    assert_eq!(resolve("w").unwrap(), "web");
+    assert_eq!(
+        resolve("a").unwrap_err(),
+        "a matches several containers: abc123, api, api-worker"
+    );
+    assert_eq!(resolve("db").unwrap_err(), "No such container: db");
+}
+
+#[tokio::test]
+async fn a_failed_stop_is_shown_in_the_status_bar() {
+    let (_, receiver) = mpsc::channel(1);
+    let (_, images_receiver) = mpsc::channel(1);
+    let (_, networks_receiver) = mpsc::channel(1);
+    let (_, capabilities_receiver) = oneshot::channel();
+    let mut app = DockerViewerApp::new(
+        receiver,
+        images_receiver,
+        networks_receiver,
+        capabilities_receiver,
+        ScanSettings::default(),
+        BuildSettings::default(),
+    );
+    let docker = MockDocker::with_containers(&["abc"]);
+    app.start_action_with(Ok(docker), "missing".to_string(), ContainerAction::Stop);
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+    while app.status_message.is_none() && tokio::time::Instant::now() < deadline {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        app.receive_updates();
+    }
+    assert_eq!(
+        app.status_message,
+        Some(Err(
+            "Failed to stop container missing: No such container: missing".to_string()
+        ))
+    );
+    let notification = &app.notifications.shown[0];
+    assert!(!notification.success);
+    assert_eq!(notification.container.as_deref(), Some("missing"));
+    let history: Vec<&str> = app
+        .action_history
+        .entries
+        .iter()
+        .map(|entry| entry.message.as_str())
+        .collect();
+    assert_eq!(
+        history,
+        [
+            "Stop missing",
+            "Failed to stop container missing: No such container: missing"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn kill_sends_the_picked_signal_and_names_it() {
+    let docker = MockDocker::with_containers(&["abc"]);
+    let signal = parse_signal("term").unwrap();
+    assert_eq!(
+        run_action(&docker, ContainerAction::Kill(signal), "abc").await,
+        Ok("Container abc: sent SIGTERM".to_string())
+    );
+    assert_eq!(
+        run_action(&docker, ContainerAction::Kill(SIGKILL), "missing").await,
+        Err("Failed to send SIGKILL to container missing: No such container: missing".to_string())
+    );
+    assert_eq!(
+        *docker.calls.lock().unwrap(),
+        ["kill abc SIGTERM", "kill missing SIGKILL"]
+    );
+}
+
+#[test]
+fn signals_are_known_by_name_or_number() {
+    for input in ["SIGHUP", "sighup", "HUP", " hup ", "1"] {
+        assert_eq!(parse_signal(input), Ok("SIGHUP"), "{}", input);
+    }
+    assert_eq!(parse_signal("SIGUSR2"), Ok("SIGUSR2"));
+    assert_eq!(parse_signal("28"), Ok("SIGWINCH"));
+    assert_eq!(
+        parse_signal("SIGFOO"),
+        Err("Unknown signal: SIGFOO".to_string())
+    );
+    assert_eq!(parse_signal("64"), Err("Unknown signal: 64".to_string()));
+    assert!(parse_signal("").is_err());
+}
+
+#[tokio::test]
+async fn removal_options_reach_the_daemon() {
+    let docker = MockDocker::with_containers(&["abc"]);
+    let options = RemoveOptions {
+        force: true,
+        volumes: true,
+    };
+    assert!(run_action(&docker, ContainerAction::Remove(options), "abc")
+        .await
+        .is_ok());
+    assert_eq!(
+        *docker.calls.lock().unwrap(),
+        ["remove abc --force --volumes"]
+    );
+
+    let bollard = options.to_bollard();
+    assert!(bollard.force && bollard.v);
+    // Nothing running is removed, nor a volume, unless asked for
+    let bollard = RemoveOptions::default().to_bollard();
+    assert!(!bollard.force && !bollard.v);
+}
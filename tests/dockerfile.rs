@@ -0,0 +1,141 @@
+use dockerrs::dockerfile::{instructions, lint, tokenize, Lint, LintWarning, TokenKind};
+
+fn joined(content: &str) -> String {
+    tokenize(content).iter().map(|(_, text)| *text).collect()
+}
+
+#[test]
+fn tokens_join_back_into_the_file() {
+    let content =
+        "FROM debian:12\n\n# tools\nRUN apt-get update \\\n    && apt-get install -y curl\n";
+    assert_eq!(joined(content), content);
+}
+
+#[test]
+fn continued_run_lines_are_one_instruction() {
+    let content =
+        "RUN apt-get update \\\n    # refresh first\n    && echo $HOME \\\n    && make\nRUN true\n";
+    let tokens = tokenize(content);
+    assert_eq!(
+        tokens,
+        vec![
+            (TokenKind::Instruction, "RUN"),
+            (TokenKind::Text, " apt-get update "),
+            (TokenKind::Continuation, "\\"),
+            (TokenKind::Text, "\n"),
+            (TokenKind::Text, "    "),
+            (TokenKind::Comment, "# refresh first\n"),
+            (TokenKind::Text, "    "),
+            (TokenKind::Text, "&& echo "),
+            (TokenKind::Variable, "$HOME"),
+            (TokenKind::Text, " "),
+            (TokenKind::Continuation, "\\"),
+            (TokenKind::Text, "\n"),
+            (TokenKind::Text, "    "),
+            (TokenKind::Text, "&& make"),
+            (TokenKind::Text, "\n"),
+            (TokenKind::Instruction, "RUN"),
+            (TokenKind::Text, " true"),
+            (TokenKind::Text, "\n"),
+        ]
+    );
+    // A continued line starting with an instruction word is an argument, not an instruction
+    assert_eq!(
+        instructions("RUN echo \\\n  copy\nCOPY a b\n"),
+        vec![
+            dockerrs::dockerfile::Instruction {
+                line: 1,
+                keyword: "RUN".to_string(),
+                arguments: "echo copy".to_string(),
+            },
+            dockerrs::dockerfile::Instruction {
+                line: 3,
+                keyword: "COPY".to_string(),
+                arguments: "a b".to_string(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn heredoc_bodies_run_until_their_terminator() {
+    let content = "RUN <<EOF\nFROM is not an instruction here\necho ${NAME}\nEOF\nCMD [\"sh\"]\n";
+    let tokens = tokenize(content);
+    assert_eq!(
+        tokens,
+        vec![
+            (TokenKind::Instruction, "RUN"),
+            (TokenKind::Text, " "),
+            (TokenKind::Heredoc, "<<EOF"),
+            (TokenKind::Text, "\n"),
+            (TokenKind::Heredoc, "FROM is not an instruction here\n"),
+            (TokenKind::Heredoc, "echo ${NAME}\n"),
+            (TokenKind::Heredoc, "EOF\n"),
+            (TokenKind::Instruction, "CMD"),
+            (TokenKind::Text, " [\"sh\"]"),
+            (TokenKind::Text, "\n"),
+        ]
+    );
+    assert_eq!(joined(content), content);
+}
+
+#[test]
+fn heredoc_variants_and_shifts() {
+    let content = "COPY <<-\"A\" <<'B' /dst/\n\tone\n\tA\ntwo\nB\nRUN echo $((1<<2))\n";
+    let heredocs: Vec<&str> = tokenize(content)
+        .into_iter()
+        .filter(|(kind, _)| *kind == TokenKind::Heredoc)
+        .map(|(_, text)| text)
+        .collect();
+    assert_eq!(
+        heredocs,
+        vec!["<<-\"A\"", "<<'B'", "\tone\n", "\tA\n", "two\n", "B\n"]
+    );
+    assert_eq!(instructions(content)[1].keyword, "RUN");
+}
+
+#[test]
+fn lint_warnings() {
+    let content = "\
+FROM node AS build
+FROM build
+FROM scratch
+FROM ghcr.io/org/app@sha256:abc
+FROM localhost:5000/app
+ADD app.tar.gz /app/
+ADD https://example.com/file /file
+ADD src /src
+RUN apt-get update && apt-get install -y \\
+    curl
+RUN apt-get install --no-install-recommends -y git
+CMD [\"a\"]
+CMD [\"b\"]
+FROM debian:12
+CMD [\"c\"]
+";
+    assert_eq!(
+        lint(content),
+        vec![
+            LintWarning {
+                line: 1,
+                lint: Lint::UntaggedBaseImage
+            },
+            LintWarning {
+                line: 5,
+                lint: Lint::UntaggedBaseImage
+            },
+            LintWarning {
+                line: 8,
+                lint: Lint::AddInsteadOfCopy
+            },
+            LintWarning {
+                line: 9,
+                lint: Lint::AptInstallRecommends
+            },
+            LintWarning {
+                line: 13,
+                lint: Lint::MultipleCmd
+            },
+        ]
+    );
+}
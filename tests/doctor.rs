@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+
+use bollard::secret::{ContainerSummary, Network};
+use dockerrs::compose_file::ComposeFile;
+use dockerrs::doctor::{diagnose, FixStep, COMPOSE_NETWORK_LABEL};
+use dockerrs::utils::{COMPOSE_PROJECT_LABEL, COMPOSE_SERVICE_LABEL};
+
+const COMPOSE: &str = r#"
+services:
+  web:
+    image: nginx
+    networks: [front]
+  db:
+    image: postgres:16
+networks:
+  front:
+  shared:
+    external: true
+"#;
+
+fn container(id: &str, project: &str, service: &str, state: &str) -> ContainerSummary {
+    ContainerSummary {
+        id: Some(id.to_string()),
+        names: Some(vec![format!("/{}-{}-1", project, service)]),
+        state: Some(state.to_string()),
+        labels: Some(HashMap::from([
+            (COMPOSE_PROJECT_LABEL.to_string(), project.to_string()),
+            (COMPOSE_SERVICE_LABEL.to_string(), service.to_string()),
+        ])),
+        ..Default::default()
+    }
+}
+
+fn network(name: &str, labels: &[(&str, &str)]) -> Network {
+    Network {
+        id: Some(format!("{}-id", name)),
+        name: Some(name.to_string()),
+        labels: Some(
+            labels
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+        ),
+        ..Default::default()
+    }
+}
+
+fn compose_network(name: &str, key: &str) -> Network {
+    network(
+        name,
+        &[
+            (COMPOSE_PROJECT_LABEL, "shop"),
+            (COMPOSE_NETWORK_LABEL, key),
+        ],
+    )
+}
+
+fn healthy_networks() -> Vec<Network> {
+    vec![
+        compose_network("shop_front", "front"),
+        compose_network("shop_default", "default"),
+    ]
+}
+
+// Name, live containers, live networks and the planned steps
+type Case = (
+    &'static str,
+    Vec<ContainerSummary>,
+    Vec<Network>,
+    Vec<FixStep>,
+);
+
+#[test]
+fn problems_are_planned_as_fix_steps() {
+    let cases: Vec<Case> = vec![
+        (
+            "nothing to fix",
+            vec![
+                container("1", "shop", "web", "running"),
+                container("2", "shop", "db", "exited"),
+            ],
+            healthy_networks(),
+            vec![],
+        ),
+        (
+            "created but never started",
+            vec![container("1", "shop", "web", "created")],
+            healthy_networks(),
+            vec![FixStep::StartContainer {
+                id: "1".to_string(),
+                name: "shop-web-1".to_string(),
+            }],
+        ),
+        (
+            "orphan of a removed service, even when only created",
+            vec![
+                container("1", "shop", "worker", "running"),
+                container("2", "shop", "cron", "created"),
+            ],
+            healthy_networks(),
+            vec![
+                FixStep::RemoveOrphan {
+                    id: "1".to_string(),
+                    name: "shop-worker-1".to_string(),
+                },
+                FixStep::RemoveOrphan {
+                    id: "2".to_string(),
+                    name: "shop-cron-1".to_string(),
+                },
+            ],
+        ),
+        (
+            "containers of other projects are left alone",
+            vec![
+                container("1", "blog", "worker", "created"),
+                ContainerSummary {
+                    id: Some("2".to_string()),
+                    state: Some("created".to_string()),
+                    ..Default::default()
+                },
+            ],
+            healthy_networks(),
+            vec![],
+        ),
+        (
+            "missing networks, the external one is not ours to create",
+            vec![],
+            vec![compose_network("shop_front", "front")],
+            vec![FixStep::CreateNetwork {
+                key: "default".to_string(),
+                name: "shop_default".to_string(),
+            }],
+        ),
+        (
+            "network without compose labels",
+            vec![],
+            vec![
+                network("shop_front", &[]),
+                compose_network("shop_default", "default"),
+            ],
+            vec![FixStep::RecreateNetwork {
+                id: "shop_front-id".to_string(),
+                key: "front".to_string(),
+                name: "shop_front".to_string(),
+            }],
+        ),
+        (
+            "network labelled for another project or key",
+            vec![],
+            vec![
+                network(
+                    "shop_front",
+                    &[
+                        (COMPOSE_PROJECT_LABEL, "blog"),
+                        (COMPOSE_NETWORK_LABEL, "front"),
+                    ],
+                ),
+                compose_network("shop_default", "front"),
+            ],
+            vec![
+                FixStep::RecreateNetwork {
+                    id: "shop_front-id".to_string(),
+                    key: "front".to_string(),
+                    name: "shop_front".to_string(),
+                },
+                FixStep::RecreateNetwork {
+                    id: "shop_default-id".to_string(),
+                    key: "default".to_string(),
+                    name: "shop_default".to_string(),
+                },
+            ],
+        ),
+    ];
+
+    let compose = ComposeFile::parse(COMPOSE).unwrap();
+    for (case, containers, networks, expected) in cases {
+        assert_eq!(
+            diagnose("shop", &compose, &containers, &networks),
+            expected,
+            "{}",
+            case
+        );
+    }
+}
+
+#[test]
+fn steps_describe_themselves() {
+    let step = FixStep::StartContainer {
+        id: "1".to_string(),
+        name: "shop-web-1".to_string(),
+    };
+    assert_eq!(
+        step.describe(),
+        "Start shop-web-1, it was created but never started"
+    );
+}
@@ -0,0 +1,19 @@
+use dockerrs::error::DockerrsError;
+
+#[test]
+fn errors_read_as_context_and_cause() {
+    let docker = DockerrsError::docker("Failed to kill container abc", "No such container: abc");
+    assert_eq!(
+        docker.to_string(),
+        "Failed to kill container abc: No such container: abc"
+    );
+    let spawn = DockerrsError::Spawn {
+        program: "notify-send".to_string(),
+        source: std::io::Error::from(std::io::ErrorKind::NotFound),
+    };
+    assert_eq!(
+        spawn.to_string(),
+        "Failed to run notify-send: entity not found"
+    );
+    assert!(std::error::Error::source(&spawn).is_some());
+}
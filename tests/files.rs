@@ -0,0 +1,158 @@
+use std::path::{Path, PathBuf};
+
+use dockerrs::files::{extract_tar, join_path, parse_ls, EntryKind, FileEntry};
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("dockerrs-files-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+// Appends a file with a raw name, the tar builder itself refuses to write unsafe paths
+fn append_raw(builder: &mut tar::Builder<Vec<u8>>, name: &str, contents: &[u8]) {
+    let mut header = tar::Header::new_old();
+    header.as_old_mut().name[..name.len()].copy_from_slice(name.as_bytes());
+    header.set_size(contents.len() as u64);
+    header.set_mode(0o644);
+    header.set_entry_type(tar::EntryType::Regular);
+    header.set_cksum();
+    builder.append(&header, contents).unwrap();
+}
+
+fn tarball(entries: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut builder = tar::Builder::new(Vec::new());
+    for (name, contents) in entries {
+        append_raw(&mut builder, name, contents);
+    }
+    builder.into_inner().unwrap()
+}
+
+fn listing(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = walkdir::WalkDir::new(dir)
+        .min_depth(1)
+        .into_iter()
+        .map(|entry| {
+            entry
+                .unwrap()
+                .path()
+                .strip_prefix(dir)
+                .unwrap()
+                .display()
+                .to_string()
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn escaping_entries_are_refused_without_writing_anything() {
+    let dir = scratch_dir("escape");
+    let destination = dir.join("destination");
+    std::fs::create_dir_all(&destination).unwrap();
+
+    for unsafe_name in ["../evil", "/etc/x"] {
+        let archive = tarball(&[("ok.txt", b"fine"), (unsafe_name, b"owned")]);
+        let error = extract_tar(archive.as_slice(), &destination).unwrap_err();
+        assert!(error.contains("outside of the destination"), "{}", error);
+    }
+    assert!(listing(&destination).is_empty());
+    assert!(!dir.join("evil").exists());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn extracted_directories_merge_into_existing_ones() {
+    let dir = scratch_dir("merge");
+    std::fs::create_dir_all(dir.join("app")).unwrap();
+    std::fs::write(dir.join("app/kept.txt"), "kept").unwrap();
+    std::fs::write(dir.join("app/replaced.txt"), "old").unwrap();
+
+    let archive = tarball(&[
+        ("app/replaced.txt", b"new"),
+        ("app/sub/added.txt", b"added"),
+    ]);
+    assert_eq!(extract_tar(archive.as_slice(), &dir), Ok(2));
+    assert_eq!(
+        listing(&dir),
+        vec![
+            "app",
+            "app/kept.txt",
+            "app/replaced.txt",
+            "app/sub",
+            "app/sub/added.txt"
+        ]
+    );
+    assert_eq!(
+        std::fs::read_to_string(dir.join("app/replaced.txt")).unwrap(),
+        "new"
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+fn entry<'a>(entries: &'a [FileEntry], name: &str) -> &'a FileEntry {
+    entries
+        .iter()
+        .find(|entry| entry.name == name)
+        .unwrap_or_else(|| panic!("{} is missing from {:?}", name, entries))
+}
+
+#[test]
+fn parse_ls_reads_coreutils_output() {
+    let output = "\
+total 16
+drwxr-xr-x 1 root root 4096 Mar  4 10:12 .
+drwxr-xr-x 1 root root 4096 Mar  4 10:12 ..
+crw-rw-rw- 1 root root 1, 3 Mar  4 10:12 null
+lrwxrwxrwx 1 root root    1 Mar  4 10:12 a -> b
+-rw-r--r-- 1 root root   42 Jan 12  2023 my notes.txt
+drwxr-xr-x 2 root root 4096 Mar  4 10:12 etc
+";
+    let entries = parse_ls(output);
+    let names: Vec<&str> = entries.iter().map(|entry| entry.name.as_str()).collect();
+    assert_eq!(names, vec!["etc", "a", "my notes.txt", "null"]);
+
+    let device = entry(&entries, "null");
+    assert_eq!(device.kind, EntryKind::Other);
+    assert_eq!(device.size, "1, 3");
+    assert_eq!(device.modified, "Mar 4 10:12");
+    assert_eq!(
+        entry(&entries, "a").kind,
+        EntryKind::Symlink("b".to_string())
+    );
+    let spaced = entry(&entries, "my notes.txt");
+    assert_eq!(spaced.kind, EntryKind::File);
+    assert_eq!(spaced.size, "42");
+    assert_eq!(spaced.modified, "Jan 12 2023");
+}
+
+#[test]
+fn parse_ls_reads_busybox_output() {
+    let output = "\
+drwxr-xr-x    1 root     root          4096 Mar  4 10:12 .
+drwxr-xr-x    1 root     root          4096 Mar  4 10:12 ..
+crw-rw-rw-    1 root     root        1,   3 Mar  4 10:12 null
+lrwxrwxrwx    1 root     root             1 Mar  4 10:12 a -> b
+-rw-r--r--    1 root     root            42 Mar  4 10:12 my notes.txt
+";
+    let entries = parse_ls(output);
+    assert_eq!(entries.len(), 3);
+    assert_eq!(entry(&entries, "null").size, "1, 3");
+    assert_eq!(
+        entry(&entries, "a").kind,
+        EntryKind::Symlink("b".to_string())
+    );
+    assert_eq!(entry(&entries, "my notes.txt").kind, EntryKind::File);
+}
+
+#[test]
+fn join_path_stops_at_the_root() {
+    assert_eq!(join_path("/app", "src"), "/app/src");
+    assert_eq!(join_path("/app/src", ".."), "/app");
+    assert_eq!(join_path("/app", "../../../etc"), "/etc");
+    assert_eq!(join_path("/", ".."), "/");
+    assert_eq!(join_path("/app", "/var/./log/"), "/var/log");
+}
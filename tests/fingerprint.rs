@@ -0,0 +1,68 @@
+use std::collections::HashMap;
+
+use bollard::secret::{ContainerSummary, Network};
+use dockerrs::fingerprint::{networks_fingerprint, snapshot_fingerprint};
+use dockerrs::poller::ContainerSnapshot;
+
+// Maps are filled in the given order, like each listing deserializing into new maps
+fn labels(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+fn listing(ids: &[&str], status: &str, logs: &str) -> ContainerSnapshot {
+    ids.iter()
+        .map(|id| {
+            let summary = ContainerSummary {
+                id: Some(id.to_string()),
+                names: Some(vec![format!("/{}", id)]),
+                status: Some(status.to_string()),
+                labels: Some(labels(&[("a", "1"), ("b", "2"), ("c", "3")])),
+                ..Default::default()
+            };
+            (id.to_string(), (summary, logs.to_string()))
+        })
+        .collect()
+}
+
+#[test]
+fn identical_listings_have_the_same_fingerprint() {
+    let first = listing(&["abc", "def", "ghi"], "Up 2 seconds", "ready\n");
+    let mut second = listing(&["ghi", "def", "abc"], "Up 2 seconds", "ready\n");
+    second.get_mut("abc").unwrap().0.labels = Some(labels(&[("c", "3"), ("a", "1"), ("b", "2")]));
+    assert_eq!(snapshot_fingerprint(&first), snapshot_fingerprint(&second));
+}
+
+#[test]
+fn status_logs_and_containers_change_the_fingerprint() {
+    let first = snapshot_fingerprint(&listing(&["abc", "def"], "Up 2 seconds", "ready\n"));
+    for changed in [
+        listing(&["abc", "def"], "Up 4 seconds", "ready\n"),
+        listing(&["abc", "def"], "Up 2 seconds", "ready\nserving\n"),
+        listing(&["abc"], "Up 2 seconds", "ready\n"),
+        listing(&["abc", "def", "ghi"], "Up 2 seconds", "ready\n"),
+    ] {
+        assert_ne!(first, snapshot_fingerprint(&changed));
+    }
+    let mut relabeled = listing(&["abc", "def"], "Up 2 seconds", "ready\n");
+    relabeled.get_mut("def").unwrap().0.labels = Some(labels(&[("a", "1")]));
+    assert_ne!(first, snapshot_fingerprint(&relabeled));
+}
+
+#[test]
+fn networks_are_compared_regardless_of_map_order() {
+    let network = |pairs: &[(&str, &str)], driver: &str| Network {
+        id: Some("n1".to_string()),
+        name: Some("backend".to_string()),
+        driver: Some(driver.to_string()),
+        labels: Some(labels(pairs)),
+        ..Default::default()
+    };
+    let first = [network(&[("x", "1"), ("y", "2"), ("z", "3")], "bridge")];
+    let second = [network(&[("z", "3"), ("y", "2"), ("x", "1")], "bridge")];
+    assert_eq!(networks_fingerprint(&first), networks_fingerprint(&second));
+    let changed = [network(&[("x", "1"), ("y", "2"), ("z", "3")], "overlay")];
+    assert_ne!(networks_fingerprint(&first), networks_fingerprint(&changed));
+}
@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+
+use bollard::secret::{
+    ContainerSummary, ContainerSummaryNetworkSettings, EndpointSettings, Network, Port,
+    PortTypeEnum,
+};
+use dockerrs::graph::{escape_id, network_topology_dot, network_topology_svg};
+
+fn network(name: &str, driver: &str) -> Network {
+    Network {
+        name: Some(name.to_string()),
+        driver: Some(driver.to_string()),
+        ..Default::default()
+    }
+}
+
+fn container(id: &str, name: &str, project: Option<&str>, networks: &[&str]) -> ContainerSummary {
+    let labels = project.map(|project| {
+        HashMap::from([(
+            "com.docker.compose.project".to_string(),
+            project.to_string(),
+        )])
+    });
+    let networks = networks
+        .iter()
+        .map(|network| (network.to_string(), EndpointSettings::default()))
+        .collect();
+    ContainerSummary {
+        id: Some(id.to_string()),
+        names: Some(vec![format!("/{name}")]),
+        labels,
+        network_settings: Some(ContainerSummaryNetworkSettings {
+            networks: Some(networks),
+        }),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn identifiers_are_quoted_and_escaped() {
+    assert_eq!(escape_id("web"), "\"web\"");
+    assert_eq!(escape_id("say \"hi\""), "\"say \\\"hi\\\"\"");
+    assert_eq!(escape_id("C:\\data"), "\"C:\\\\data\"");
+    assert_eq!(escape_id("two\nlines"), "\"two\\nlines\"");
+    assert_eq!(escape_id("graph {"), "\"graph {\"");
+}
+
+#[test]
+fn compose_projects_become_clusters() {
+    let networks = [
+        network("shop_default", "bridge"),
+        network("bridge", "bridge"),
+    ];
+    let containers = [
+        container("1", "shop-web-1", Some("shop"), &["shop_default"]),
+        container("2", "shop-db-1", Some("shop"), &["shop_default"]),
+        container("3", "loose", None, &["bridge"]),
+    ];
+    let dot = network_topology_dot(&networks, &containers);
+
+    assert!(dot.starts_with("graph docker {\n"));
+    assert!(dot.ends_with("}\n"));
+    assert!(dot
+        .contains("\"network:shop_default\" [label=\"shop_default\\n(bridge)\", shape=ellipse];"));
+    assert!(dot.contains(concat!(
+        "    subgraph \"cluster_shop\" {\n",
+        "        label=\"shop\";\n",
+        "        \"container:1\" [label=\"shop-web-1\"];\n",
+        "        \"container:2\" [label=\"shop-db-1\"];\n",
+        "    }\n"
+    )));
+    // Containers outside a project are not in any cluster
+    assert!(dot.contains("\n    \"container:3\" [label=\"loose\"];\n"));
+    assert_eq!(dot.matches("subgraph").count(), 1);
+    assert!(dot.contains("    \"container:1\" -- \"network:shop_default\";\n"));
+    assert!(dot.contains("    \"container:3\" -- \"network:bridge\";\n"));
+}
+
+#[test]
+fn published_ports_are_annotated() {
+    let mut web = container("1", "web", None, &["bridge"]);
+    web.ports = Some(vec![
+        Port {
+            private_port: 80,
+            public_port: Some(8080),
+            typ: Some(PortTypeEnum::TCP),
+            ..Default::default()
+        },
+        Port {
+            private_port: 53,
+            public_port: Some(5353),
+            typ: Some(PortTypeEnum::UDP),
+            ..Default::default()
+        },
+        // Exposed but not published
+        Port {
+            private_port: 9000,
+            typ: Some(PortTypeEnum::TCP),
+            ..Default::default()
+        },
+    ]);
+    let dot = network_topology_dot(&[network("bridge", "bridge")], &[web]);
+    assert!(dot.contains("\"container:1\" [label=\"web\\n8080→80/tcp, 5353→53/udp\"];"));
+}
+
+#[test]
+fn the_builtin_layout_draws_every_node_and_edge() {
+    let networks = [
+        network("shop_default", "bridge"),
+        network("<empty>", "bridge"),
+    ];
+    let containers = [
+        container("1", "shop-web-1", Some("shop"), &["shop_default"]),
+        container("2", "a&b", None, &["shop_default"]),
+    ];
+    let svg = network_topology_svg(&networks, &containers);
+
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+    assert!(svg.ends_with("</svg>\n"));
+    assert_eq!(svg.matches("<ellipse").count(), 2);
+    assert_eq!(svg.matches("<line").count(), 2);
+    // Two container boxes and one cluster
+    assert_eq!(svg.matches("<rect").count(), 3);
+    assert!(svg.contains(">shop</text>"));
+    assert!(svg.contains(">a&amp;b</text>"));
+    assert!(svg.contains(">&lt;empty&gt; (bridge)</text>"));
+}
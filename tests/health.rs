@@ -0,0 +1,21 @@
+use dockerrs::health::Health;
+
+#[test]
+fn health_is_read_from_the_end_of_the_status() {
+    assert_eq!(
+        Health::from_status("Up 3 minutes (healthy)"),
+        Some(Health::Healthy)
+    );
+    assert_eq!(
+        Health::from_status("Up 10 seconds (unhealthy)"),
+        Some(Health::Unhealthy)
+    );
+    assert_eq!(
+        Health::from_status("Up 2 seconds (health: starting)"),
+        Some(Health::Starting)
+    );
+    // Containers without a HEALTHCHECK have none
+    assert_eq!(Health::from_status("Up 3 minutes"), None);
+    assert_eq!(Health::from_status("Exited (0) 2 hours ago"), None);
+    assert_eq!(Health::from_status(""), None);
+}
@@ -0,0 +1,101 @@
+use std::sync::atomic::AtomicBool;
+
+use dockerrs::image_analysis::{build_report, read_layers, LayerFiles};
+
+fn append(builder: &mut tar::Builder<Vec<u8>>, path: &str, data: &[u8]) {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder.append_data(&mut header, path, data).unwrap();
+}
+
+fn layer_tar(files: &[(&str, usize)]) -> Vec<u8> {
+    let mut builder = tar::Builder::new(Vec::new());
+    for (path, size) in files {
+        append(&mut builder, path, &vec![0; *size]);
+    }
+    builder.into_inner().unwrap()
+}
+
+fn layers() -> Vec<Vec<u8>> {
+    vec![
+        layer_tar(&[("app/big", 100), ("app/old", 50), ("etc/conf", 10)]),
+        layer_tar(&[("app/big", 100), ("app/.wh.old", 0)]),
+        layer_tar(&[("etc/.wh..wh..opq", 0), ("etc/new", 5)]),
+    ]
+}
+
+// Like `docker save` on Docker 25 and later, with the config as a blob that is no tarball
+fn oci_save(layers: &[Vec<u8>]) -> Vec<u8> {
+    let mut builder = tar::Builder::new(Vec::new());
+    let config = br#"{"architecture":"amd64","os":"linux","rootfs":{"type":"layers"}}"#;
+    append(&mut builder, "blobs/sha256/c0ffee", config);
+    let mut paths = Vec::new();
+    for (index, layer) in layers.iter().enumerate() {
+        let path = format!("blobs/sha256/{:064}", index);
+        append(&mut builder, &path, layer);
+        paths.push(path);
+    }
+    let manifest = serde_json::json!([{
+        "Config": "blobs/sha256/c0ffee",
+        "RepoTags": ["demo:latest"],
+        "Layers": paths,
+    }]);
+    append(
+        &mut builder,
+        "manifest.json",
+        manifest.to_string().as_bytes(),
+    );
+    builder.into_inner().unwrap()
+}
+
+#[test]
+fn replaced_deleted_and_hidden_files_are_wasted() {
+    let cancelled = AtomicBool::new(false);
+    let layers = read_layers(oci_save(&layers()).as_slice(), &cancelled, |_| {}).unwrap();
+    assert_eq!(layers.len(), 3);
+    assert_eq!(layers[1].id, "000000000000");
+
+    let report = build_report("demo".to_string(), &layers);
+    let wasted: Vec<u64> = report.layers.iter().map(|layer| layer.wasted).collect();
+    assert_eq!(wasted, [160, 0, 0]);
+    assert_eq!(report.total_size(), 265);
+    assert!((report.efficiency() - 105.0 / 265.0).abs() < 1e-9);
+
+    let biggest = &report.layers[0].biggest_files;
+    assert_eq!(biggest[0].path, "app/big");
+    assert!(biggest.iter().all(|file| file.wasted));
+    assert!(!report.layers[2].biggest_files[0].wasted);
+}
+
+#[test]
+fn legacy_layout_is_read_in_manifest_order() {
+    let mut builder = tar::Builder::new(Vec::new());
+    let layers = layers();
+    // Written in a different order than the manifest lists them
+    append(&mut builder, "bbb/layer.tar", &layers[1]);
+    append(&mut builder, "aaa/layer.tar", &layers[0]);
+    let manifest = r#"[{"Layers":["aaa/layer.tar","bbb/layer.tar"]}]"#;
+    append(&mut builder, "manifest.json", manifest.as_bytes());
+    let tarball = builder.into_inner().unwrap();
+
+    let cancelled = AtomicBool::new(false);
+    let layers = read_layers(tarball.as_slice(), &cancelled, |_| {}).unwrap();
+    let ids: Vec<&str> = layers.iter().map(|layer| layer.id.as_str()).collect();
+    assert_eq!(ids, ["aaa", "bbb"]);
+    assert_eq!(layers[0].files.len(), 3);
+}
+
+#[test]
+fn cancelled_analysis_stops() {
+    let cancelled = AtomicBool::new(true);
+    let result = read_layers(oci_save(&layers()).as_slice(), &cancelled, |_| {});
+    assert_eq!(result.unwrap_err(), "Analysis cancelled");
+}
+
+#[test]
+fn empty_image_is_fully_efficient() {
+    let report = build_report("scratch".to_string(), &[LayerFiles::default()]);
+    assert_eq!(report.efficiency(), 1.0);
+}
@@ -0,0 +1,103 @@
+use std::path::{Path, PathBuf};
+
+use dockerrs::image_build::{tar_context, DockerIgnore};
+use tokio::sync::mpsc;
+
+fn ignored(ignore: &DockerIgnore, path: &str) -> bool {
+    ignore.is_ignored(Path::new(path))
+}
+
+#[test]
+fn the_last_matching_pattern_wins() {
+    let ignore = DockerIgnore::parse("*.md\n!README.md\nREADME.md\n!CHANGELOG.md").unwrap();
+    assert!(ignored(&ignore, "NOTES.md"));
+    assert!(ignored(&ignore, "README.md"));
+    assert!(!ignored(&ignore, "CHANGELOG.md"));
+    assert!(!ignored(&ignore, "main.rs"));
+}
+
+#[test]
+fn negated_patterns_re_include_below_an_ignored_directory() {
+    let ignore = DockerIgnore::parse("# build output\n\ntarget\n!target/keep.txt\n").unwrap();
+    assert!(ignored(&ignore, "target"));
+    assert!(ignored(&ignore, "target/debug/app"));
+    assert!(!ignored(&ignore, "target/keep.txt"));
+}
+
+#[test]
+fn an_ignored_directory_excludes_its_children() {
+    let ignore = DockerIgnore::parse("node_modules/\nsrc/generated").unwrap();
+    assert!(ignored(&ignore, "node_modules/left-pad/index.js"));
+    assert!(ignored(&ignore, "src/generated/schema.rs"));
+    assert!(!ignored(&ignore, "src/main.rs"));
+}
+
+#[test]
+fn leading_slashes_and_dots_are_trimmed() {
+    let ignore = DockerIgnore::parse("/secrets\n./.env\n!/secrets/README").unwrap();
+    assert!(ignored(&ignore, "secrets/key.pem"));
+    assert!(ignored(&ignore, ".env"));
+    assert!(!ignored(&ignore, "secrets/README"));
+}
+
+#[test]
+fn wildcards_do_not_cross_directories() {
+    let ignore = DockerIgnore::parse("*.log\n**/*.tmp").unwrap();
+    assert!(ignored(&ignore, "debug.log"));
+    assert!(!ignored(&ignore, "logs/debug.log"));
+    assert!(ignored(&ignore, "cache/a/b.tmp"));
+}
+
+fn build_context(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "dockerrs-build-context-{}-{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("logs")).unwrap();
+    dir
+}
+
+fn entries(tarball: &[u8]) -> Vec<String> {
+    let mut archive = tar::Archive::new(tarball);
+    archive
+        .entries()
+        .unwrap()
+        .map(|entry| entry.unwrap().path().unwrap().display().to_string())
+        .collect()
+}
+
+#[test]
+fn files_above_the_size_limit_are_left_out_with_a_warning() {
+    let context = build_context("size");
+    std::fs::write(context.join("Dockerfile"), "FROM scratch\n").unwrap();
+    std::fs::write(context.join("big.bin"), vec![0u8; 2048]).unwrap();
+    std::fs::write(context.join("small.txt"), "small").unwrap();
+    std::fs::write(context.join("app.log"), "ignored").unwrap();
+    std::fs::write(context.join("logs/app.log"), "kept").unwrap();
+    std::fs::write(context.join(".dockerignore"), "*.log\n").unwrap();
+
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    let (tarball, dockerfile) =
+        tar_context(&context, &context.join("Dockerfile"), 1024, &sender).unwrap();
+    assert_eq!(dockerfile, "Dockerfile");
+    assert_eq!(
+        entries(&tarball),
+        [
+            ".dockerignore",
+            "Dockerfile",
+            "logs",
+            "logs/app.log",
+            "small.txt"
+        ]
+    );
+    assert_eq!(
+        receiver.try_recv().unwrap(),
+        "Warning: leaving big.bin out of the build context, 2048 bytes is above the 1024 byte \
+         limit"
+    );
+    assert!(receiver.try_recv().is_err());
+
+    std::fs::remove_dir_all(&context).unwrap();
+}
@@ -0,0 +1,101 @@
+use std::collections::BTreeSet;
+use std::time::{Duration, Instant};
+
+use bollard::secret::ContainerSummary;
+use dockerrs::image_updates::{
+    images_to_check, is_outdated, outdated_label, ImageUpdates, UpdateStatus, CACHE_TTL,
+};
+use dockerrs::poller::ContainerSnapshot;
+
+fn containers(list: &[(&str, &str, &str)]) -> ContainerSnapshot {
+    list.iter()
+        .map(|(id, image, state)| {
+            let summary = ContainerSummary {
+                id: Some(id.to_string()),
+                image: Some(image.to_string()),
+                state: Some(state.to_string()),
+                ..Default::default()
+            };
+            (id.to_string(), (summary, String::new()))
+        })
+        .collect()
+}
+
+#[test]
+fn only_tags_of_running_containers_are_checked_once() {
+    let snapshot = containers(&[
+        ("1", "nginx:1.25", "running"),
+        ("2", "nginx:1.25", "running"),
+        ("3", "redis:7", "exited"),
+        ("4", "postgres@sha256:abc", "running"),
+        ("5", "sha256:0123", "running"),
+        ("6", "ghcr.io/me/app:main", "running"),
+    ]);
+    assert_eq!(
+        images_to_check(&snapshot),
+        BTreeSet::from(["ghcr.io/me/app:main".to_string(), "nginx:1.25".to_string()])
+    );
+}
+
+#[test]
+fn an_image_is_outdated_when_no_pulled_digest_matches() {
+    let digests = vec![
+        "nginx@sha256:aaa".to_string(),
+        "mirror.local/nginx@sha256:bbb".to_string(),
+    ];
+    assert!(!is_outdated(&digests, "sha256:aaa"));
+    assert!(!is_outdated(&digests, "sha256:bbb"));
+    assert!(is_outdated(&digests, "sha256:ccc"));
+}
+
+#[test]
+fn results_are_cached_for_an_hour() {
+    let now = Instant::now();
+    let mut updates = ImageUpdates::default();
+    updates
+        .results
+        .insert("nginx:1.25".to_string(), (now, UpdateStatus::UpToDate));
+    let images = BTreeSet::from(["nginx:1.25".to_string(), "redis:7".to_string()]);
+    assert_eq!(updates.stale(images.clone(), now), ["redis:7"]);
+    assert_eq!(
+        updates.stale(images.clone(), now + CACHE_TTL - Duration::from_secs(1)),
+        ["redis:7"]
+    );
+    assert_eq!(
+        updates.stale(images, now + CACHE_TTL),
+        ["nginx:1.25", "redis:7"]
+    );
+}
+
+#[test]
+fn status_bar_counts_outdated_containers_and_lists_skipped_images() {
+    let now = Instant::now();
+    let mut updates = ImageUpdates::default();
+    for (image, status) in [
+        ("nginx:1.25", UpdateStatus::Outdated),
+        ("redis:7", UpdateStatus::UpToDate),
+        (
+            "registry.local/app:1",
+            UpdateStatus::Skipped("Registry not reachable".to_string()),
+        ),
+    ] {
+        updates.results.insert(image.to_string(), (now, status));
+    }
+    let snapshot = containers(&[
+        ("1", "nginx:1.25", "running"),
+        ("2", "nginx:1.25", "running"),
+        ("3", "nginx:1.25", "exited"),
+        ("4", "redis:7", "running"),
+        ("5", "registry.local/app:1", "running"),
+    ]);
+    assert_eq!(updates.outdated_containers(&snapshot), 2);
+    assert_eq!(
+        outdated_label(2),
+        "2 containers running outdated images".to_string()
+    );
+    assert_eq!(outdated_label(1), "1 container running an outdated image");
+    assert_eq!(
+        updates.skipped(),
+        ["registry.local/app:1: Registry not reachable"]
+    );
+}
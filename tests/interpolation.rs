@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+
+use dockerrs::interpolation::{interpolate, interpolate_line, missing, parse_env_file, Piece};
+
+fn lookup(variables: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+    let variables: HashMap<String, String> = variables
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+    move |name| variables.get(name).cloned()
+}
+
+// The line with unresolved references kept as written
+fn render(line: &str, variables: &[(&str, &str)]) -> String {
+    interpolate_line(line, &lookup(variables))
+        .into_iter()
+        .map(|piece| match piece {
+            Piece::Text(text) => text,
+            Piece::Unresolved { original, .. } => original,
+        })
+        .collect()
+}
+
+fn missing_names(line: &str, variables: &[(&str, &str)]) -> Vec<String> {
+    let lines = vec![interpolate_line(line, &lookup(variables))];
+    missing(&lines)
+        .into_iter()
+        .map(|missing| missing.name.clone())
+        .collect()
+}
+
+#[test]
+fn plain_and_braced_variables_are_substituted() {
+    let variables = [("TAG", "1.2"), ("PORT", "8080")];
+    assert_eq!(render("image: app:$TAG", &variables), "image: app:1.2");
+    assert_eq!(render("image: app:${TAG}", &variables), "image: app:1.2");
+    assert_eq!(
+        render("- \"${PORT}:80\" # $PORT", &variables),
+        "- \"8080:80\" # 8080"
+    );
+    assert_eq!(render("$TAG_SUFFIX", &variables), "$TAG_SUFFIX");
+    assert_eq!(missing_names("$TAG_SUFFIX", &variables), ["TAG_SUFFIX"]);
+}
+
+#[test]
+fn defaults_apply_to_unset_and_with_a_colon_to_empty_variables() {
+    let variables = [("EMPTY", ""), ("SET", "value")];
+    assert_eq!(render("${UNSET:-fallback}", &variables), "fallback");
+    assert_eq!(render("${UNSET-fallback}", &variables), "fallback");
+    assert_eq!(render("${EMPTY:-fallback}", &variables), "fallback");
+    assert_eq!(render("${EMPTY-fallback}", &variables), "");
+    assert_eq!(render("${SET:-fallback}", &variables), "value");
+    assert_eq!(render("${UNSET:-}", &variables), "");
+    assert!(missing_names("${UNSET:-}", &variables).is_empty());
+}
+
+#[test]
+fn defaults_can_reference_other_variables() {
+    let variables = [("HOST", "db")];
+    assert_eq!(
+        render("${URL:-postgres://${HOST}:5432}", &variables),
+        "postgres://db:5432"
+    );
+    assert_eq!(render("${A:-${B:-${HOST}}}", &variables), "db");
+    assert_eq!(
+        render("${URL:-${MISSING}}", &variables),
+        "${URL:-${MISSING}}"
+    );
+    assert_eq!(missing_names("${URL:-${MISSING}}", &variables), ["MISSING"]);
+}
+
+#[test]
+fn required_variables_report_their_message() {
+    let variables = [("EMPTY", "")];
+    let line = interpolate_line("key: ${API_KEY:?set API_KEY in .env}", &lookup(&variables));
+    assert_eq!(line[0], Piece::Text("key: ".to_string()));
+    let Piece::Unresolved {
+        original,
+        missing: reasons,
+    } = &line[1]
+    else {
+        panic!("expected an unresolved reference");
+    };
+    assert_eq!(original, "${API_KEY:?set API_KEY in .env}");
+    assert_eq!(reasons[0].name, "API_KEY");
+    assert_eq!(reasons[0].describe(), "set API_KEY in .env");
+
+    assert_eq!(missing_names("${EMPTY:?}", &variables), ["EMPTY"]);
+    assert!(missing_names("${EMPTY?}", &variables).is_empty());
+    let lines = vec![interpolate_line("${UNSET?}", &lookup(&variables))];
+    assert_eq!(missing(&lines)[0].describe(), "UNSET is required");
+}
+
+#[test]
+fn alternatives_apply_to_set_variables() {
+    let variables = [("DEBUG", "1"), ("EMPTY", "")];
+    assert_eq!(render("${DEBUG:+--verbose}", &variables), "--verbose");
+    assert_eq!(render("${UNSET:+--verbose}", &variables), "");
+    assert_eq!(render("${EMPTY:+--verbose}", &variables), "");
+    assert_eq!(render("${EMPTY+--verbose}", &variables), "--verbose");
+}
+
+#[test]
+fn dollars_that_start_no_variable_stay() {
+    assert_eq!(
+        render("price: $$5 and $1 and a $", &[]),
+        "price: $5 and $1 and a $"
+    );
+    assert!(missing_names("price: $$5 and $1", &[]).is_empty());
+    assert_eq!(render("$$HOME", &[("HOME", "/root")]), "$HOME");
+}
+
+#[test]
+fn invalid_references_are_unresolved() {
+    for line in ["${", "${A", "${}", "${1A}", "${A:}", "${A*b}"] {
+        let lines = vec![interpolate_line(line, &lookup(&[("A", "x")]))];
+        let reasons = missing(&lines);
+        assert_eq!(reasons.len(), 1, "{}", line);
+        assert!(reasons[0].error.is_some(), "{}", line);
+    }
+}
+
+#[test]
+fn every_line_is_interpolated_on_its_own() {
+    let text = "services:\n  web:\n    image: ${IMAGE}\n    user: ${USER:?}\n";
+    let lines = interpolate(text, &lookup(&[("IMAGE", "nginx")]));
+    assert_eq!(lines.len(), 4);
+    assert_eq!(lines[2], vec![Piece::Text("    image: nginx".to_string())]);
+    assert_eq!(missing(&lines).len(), 1);
+}
+
+#[test]
+fn env_files_allow_exports_quotes_and_comments() {
+    let variables = parse_env_file(
+        "# settings\nTAG=1.2\nexport HOST = db \nNAME=\"my app\"\nQUOTE='a # b'\n\
+         PORT=8080 # the port\nnot a variable\n=empty key\nEMPTY=\n",
+    );
+    assert_eq!(variables["TAG"], "1.2");
+    assert_eq!(variables["HOST"], "db");
+    assert_eq!(variables["NAME"], "my app");
+    assert_eq!(variables["QUOTE"], "a # b");
+    assert_eq!(variables["PORT"], "8080");
+    assert_eq!(variables["EMPTY"], "");
+    assert_eq!(variables.len(), 6);
+}
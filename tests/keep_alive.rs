@@ -0,0 +1,65 @@
+use std::time::{Duration, Instant};
+
+use dockerrs::keep_alive::{KeepAlive, KeepAliveEvent, RESTART_DELAYS};
+use dockerrs::poller::ContainerSnapshot;
+
+mod common;
+
+use common::{container, snapshot};
+
+// The watched container, api, in the given state
+fn api(state: &str, status: &str) -> ContainerSnapshot {
+    snapshot([container("1").name("api").state(state).status(status)])
+}
+
+fn restart(attempt: usize) -> KeepAliveEvent {
+    KeepAliveEvent::Restart {
+        id: "1".to_string(),
+        name: "api".to_string(),
+        attempt,
+    }
+}
+
+#[test]
+fn crashed_containers_restart_with_backoff_and_give_up() {
+    let mut keep_alive = KeepAlive::new(["api".to_string()]);
+    let crashed = api("exited", "Exited (1) 1 second ago");
+    let mut now = Instant::now();
+    for (attempt, delay) in RESTART_DELAYS.iter().enumerate() {
+        assert!(keep_alive.observe(&crashed, now).is_empty());
+        assert!(keep_alive
+            .due(now + *delay - Duration::from_millis(1))
+            .is_empty());
+        now += *delay;
+        assert_eq!(keep_alive.due(now), [restart(attempt + 1)]);
+    }
+    assert_eq!(
+        keep_alive.observe(&crashed, now),
+        [KeepAliveEvent::GaveUp {
+            name: "api".to_string()
+        }]
+    );
+    assert!(keep_alive.observe(&crashed, now).is_empty());
+    assert!(!keep_alive.is_waiting());
+}
+
+#[test]
+fn clean_exits_and_manual_stops_are_left_alone() {
+    let mut keep_alive = KeepAlive::new(["api".to_string()]);
+    let now = Instant::now();
+    keep_alive.observe(&api("exited", "Exited (0) 1 second ago"), now);
+    assert!(!keep_alive.is_waiting());
+
+    keep_alive.suspend("api");
+    keep_alive.observe(&api("exited", "Exited (137) 1 second ago"), now);
+    assert!(!keep_alive.is_waiting());
+
+    // Starting it by hand resumes the watcher
+    keep_alive.observe(&api("running", "Up 1 second"), now);
+    keep_alive.observe(&api("exited", "Exited (1) 1 second ago"), now);
+    assert!(keep_alive.is_waiting());
+
+    assert!(!keep_alive.toggle("api"));
+    keep_alive.observe(&api("exited", "Exited (1) 1 second ago"), now);
+    assert!(!keep_alive.is_waiting());
+}
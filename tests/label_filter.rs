@@ -0,0 +1,58 @@
+use std::collections::HashMap;
+
+use dockerrs::label_filter::{LabelCondition, LabelFilter};
+
+fn labels(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+#[test]
+fn expressions_parse_into_conditions() {
+    let filter = LabelFilter::parse(" env = staging, tier!=db ,traefik.enable").unwrap();
+    assert_eq!(
+        filter.conditions,
+        [
+            LabelCondition::Equals("env".to_string(), "staging".to_string()),
+            LabelCondition::NotEquals("tier".to_string(), "db".to_string()),
+            LabelCondition::Exists("traefik.enable".to_string()),
+        ]
+    );
+    assert_eq!(filter.to_string(), "env=staging,tier!=db,traefik.enable");
+    assert!(LabelFilter::parse("  ").unwrap().is_empty());
+
+    // Negations are not sent to the daemon, it cannot check them
+    assert_eq!(
+        filter.daemon_filters(),
+        HashMap::from([(
+            "label".to_string(),
+            vec!["env=staging".to_string(), "traefik.enable".to_string()]
+        )])
+    );
+    assert!(LabelFilter::default().daemon_filters().is_empty());
+}
+
+#[test]
+fn invalid_expressions_are_rejected() {
+    for expression in ["env=staging,", "=staging", "!=db", "my label", "a!b"] {
+        assert!(
+            LabelFilter::parse(expression).is_err(),
+            "{:?} parsed",
+            expression
+        );
+    }
+}
+
+#[test]
+fn every_condition_must_hold() {
+    let filter = LabelFilter::parse("env=staging,tier!=db,traefik.enable").unwrap();
+    let app = labels(&[("env", "staging"), ("tier", "app"), ("traefik.enable", "")]);
+    assert!(filter.matches(Some(&app)));
+    let db = labels(&[("env", "staging"), ("tier", "db"), ("traefik.enable", "")]);
+    assert!(!filter.matches(Some(&db)));
+    assert!(!filter.matches(None));
+    // A missing label is not equal to anything
+    assert!(LabelFilter::parse("tier!=db").unwrap().matches(None));
+}
@@ -0,0 +1,127 @@
+use std::collections::BTreeSet;
+
+use dockerrs::config::Config;
+use dockerrs::layout::{
+    alerts_label, undo_hint, visible_columns, LayoutMode, RowColumn, ALL_COLUMNS, COLUMN_SPACING,
+    DEFAULT_COLUMN_PRIORITY, LOGS_BUTTON_WIDTH, NAME_WIDTH, NARROW_WIDTH,
+};
+
+fn width_for(columns: &[RowColumn]) -> f32 {
+    NAME_WIDTH
+        + LOGS_BUTTON_WIDTH
+        + columns
+            .iter()
+            .map(|column| column.width() + COLUMN_SPACING)
+            .sum::<f32>()
+}
+
+fn all_shown() -> BTreeSet<RowColumn> {
+    ALL_COLUMNS.into_iter().collect()
+}
+
+#[test]
+fn layout_turns_narrow_below_the_threshold() {
+    assert_eq!(
+        LayoutMode::for_width(NARROW_WIDTH - 1.0),
+        LayoutMode::Narrow
+    );
+    assert_eq!(LayoutMode::for_width(NARROW_WIDTH), LayoutMode::Wide);
+    assert_eq!(LayoutMode::for_width(1920.0), LayoutMode::Wide);
+}
+
+#[test]
+fn ports_and_id_go_first_when_the_row_gets_narrow() {
+    let shown = Config::default().shown_columns();
+    let all = width_for(&[
+        RowColumn::Health,
+        RowColumn::Service,
+        RowColumn::Image,
+        RowColumn::Ports,
+        RowColumn::Id,
+    ]);
+    assert_eq!(
+        visible_columns(all, &DEFAULT_COLUMN_PRIORITY, &shown),
+        vec![
+            RowColumn::Service,
+            RowColumn::Health,
+            RowColumn::Image,
+            RowColumn::Ports,
+            RowColumn::Id
+        ]
+    );
+    assert_eq!(
+        visible_columns(all - 1.0, &DEFAULT_COLUMN_PRIORITY, &shown),
+        vec![
+            RowColumn::Service,
+            RowColumn::Health,
+            RowColumn::Image,
+            RowColumn::Ports
+        ]
+    );
+    let without_ports = width_for(&[RowColumn::Health, RowColumn::Service, RowColumn::Image]);
+    assert_eq!(
+        visible_columns(without_ports, &DEFAULT_COLUMN_PRIORITY, &shown),
+        vec![RowColumn::Service, RowColumn::Health, RowColumn::Image]
+    );
+    assert!(visible_columns(NAME_WIDTH, &DEFAULT_COLUMN_PRIORITY, &shown).is_empty());
+}
+
+#[test]
+fn a_narrower_column_fills_the_room_left_by_a_wider_one() {
+    // Room for the health and the ID, but not the ports in between
+    let width = width_for(&[RowColumn::Health, RowColumn::Id]);
+    assert_eq!(
+        visible_columns(
+            width,
+            &[RowColumn::Health, RowColumn::Ports, RowColumn::Id],
+            &all_shown()
+        ),
+        vec![RowColumn::Health, RowColumn::Id]
+    );
+}
+
+#[test]
+fn column_priority_comes_from_the_config() {
+    assert_eq!(Config::default().column_priority(), DEFAULT_COLUMN_PRIORITY);
+    let config: Config = serde_yaml::from_str("column_priority: [id, health]\n").unwrap();
+    assert_eq!(
+        config.column_priority()[..3],
+        [RowColumn::Id, RowColumn::Health, RowColumn::Service]
+    );
+    assert_eq!(config.column_priority().len(), ALL_COLUMNS.len());
+    let width = width_for(&[RowColumn::Id]);
+    assert_eq!(
+        visible_columns(width, &config.column_priority(), &all_shown()),
+        vec![RowColumn::Id]
+    );
+}
+
+#[test]
+fn created_is_hidden_until_chosen() {
+    let mut config = Config::default();
+    assert!(!config.shown_columns().contains(&RowColumn::Created));
+    config.toggle_column(RowColumn::Created);
+    config.toggle_column(RowColumn::Ports);
+    let shown = config.shown_columns();
+    assert!(shown.contains(&RowColumn::Created));
+    assert!(!shown.contains(&RowColumn::Ports));
+
+    let loaded: Config = serde_yaml::from_str(&serde_yaml::to_string(&config).unwrap()).unwrap();
+    assert_eq!(loaded.shown_columns(), shown);
+    let width = width_for(&ALL_COLUMNS);
+    assert!(!visible_columns(width, &loaded.column_priority(), &shown).contains(&RowColumn::Ports));
+}
+
+#[test]
+fn status_bar_hints_are_compact_when_narrow() {
+    assert_eq!(
+        undo_hint("Remove web", 4, LayoutMode::Wide),
+        "Remove web in 4s (Ctrl+Z to undo)"
+    );
+    assert_eq!(
+        undo_hint("Remove web", 4, LayoutMode::Narrow),
+        "Remove web 4s"
+    );
+    assert_eq!(alerts_label(2, LayoutMode::Wide), "Alerts (2)");
+    assert_eq!(alerts_label(2, LayoutMode::Narrow), "⚠ 2");
+}
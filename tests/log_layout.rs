@@ -0,0 +1,133 @@
+use dockerrs::log_layout::{
+    format_prefix, h_offset_label, prefix_color, prefix_width, scroll_offset, wrap_message,
+    LogScroll, H_SCROLL_STEP, PREFIX_SEPARATOR,
+};
+use dockerrs::theme::{Theme, ThemeName};
+use eframe::egui::Vec2;
+
+const ROW: f32 = 14.0;
+const CHAR: f32 = 7.0;
+
+fn scroll(scroll: LogScroll, offset: Vec2, max: Vec2) -> Vec2 {
+    scroll_offset(scroll, offset, Vec2::new(600.0, 140.0), max, ROW, CHAR)
+}
+
+#[test]
+fn page_down_advances_by_the_visible_height_less_a_row() {
+    let max = Vec2::new(0.0, 1000.0);
+    assert_eq!(scroll(LogScroll::PageDown, Vec2::ZERO, max).y, 126.0);
+    assert_eq!(
+        scroll(LogScroll::PageDown, Vec2::new(0.0, 126.0), max).y,
+        252.0
+    );
+    assert_eq!(
+        scroll(LogScroll::PageUp, Vec2::new(0.0, 252.0), max).y,
+        126.0
+    );
+}
+
+// Wrapped logs are laid out taller than their line count, and the offset is in those rows
+#[test]
+fn paging_wrapped_logs_reaches_every_row() {
+    // 20 lines of which 5 wrap onto 3 rows, 30 rows in all
+    let content_rows = 15 + 5 * 3;
+    let max = Vec2::new(0.0, content_rows as f32 * ROW - 140.0);
+    let mut offset = Vec2::ZERO;
+    let mut pages = 0;
+    while offset.y < max.y {
+        offset = scroll(LogScroll::PageDown, offset, max);
+        pages += 1;
+    }
+    assert_eq!(offset.y, max.y);
+    // Nine rows a page over the twenty rows below the first screen
+    assert_eq!(pages, 3);
+}
+
+#[test]
+fn scrolling_stops_at_the_edges() {
+    let max = Vec2::new(300.0, 50.0);
+    assert_eq!(scroll(LogScroll::PageUp, Vec2::new(0.0, 10.0), max).y, 0.0);
+    assert_eq!(
+        scroll(LogScroll::PageDown, Vec2::new(0.0, 10.0), max).y,
+        50.0
+    );
+    assert_eq!(scroll(LogScroll::Left, Vec2::new(20.0, 0.0), max).x, 0.0);
+    assert_eq!(
+        scroll(LogScroll::Right, Vec2::new(280.0, 0.0), max).x,
+        300.0
+    );
+    // Content smaller than the viewport does not scroll at all
+    assert_eq!(
+        scroll(LogScroll::PageDown, Vec2::ZERO, Vec2::new(-10.0, -10.0)),
+        Vec2::ZERO
+    );
+}
+
+#[test]
+fn left_and_right_move_by_a_few_characters() {
+    let max = Vec2::new(1000.0, 0.0);
+    let right = scroll(LogScroll::Right, Vec2::ZERO, max);
+    assert_eq!(right.x, H_SCROLL_STEP as f32 * CHAR);
+    assert_eq!(
+        h_offset_label(right.x, CHAR),
+        Some(format!("→ {} chars", H_SCROLL_STEP))
+    );
+    assert_eq!(scroll(LogScroll::Left, right, max).x, 0.0);
+    assert_eq!(h_offset_label(0.0, CHAR), None);
+    // Wrapped logs have no room to the right
+    assert_eq!(scroll(LogScroll::Right, Vec2::ZERO, Vec2::ZERO).x, 0.0);
+}
+
+#[test]
+fn prefixes_are_padded_to_the_longest_name_up_to_the_max() {
+    let width = prefix_width(["web", "database"], 24);
+    assert_eq!(width, 8);
+    assert_eq!(format_prefix("web", width), "web     ");
+    assert_eq!(format_prefix("database", width), "database");
+    assert_eq!(prefix_width(Vec::<&str>::new(), 24), 0);
+
+    let width = prefix_width(["web", "a-very-long-service-name"], 10);
+    assert_eq!(width, 10);
+    assert_eq!(
+        format_prefix("a-very-long-service-name", width),
+        "a-very-lo…"
+    );
+    assert_eq!(
+        format_prefix("a-very-long-service-name", width)
+            .chars()
+            .count(),
+        10
+    );
+    assert_eq!(format_prefix("web", 0), "");
+}
+
+#[test]
+fn each_name_keeps_its_color() {
+    let theme = Theme::new(ThemeName::Default);
+    assert_eq!(prefix_color("web", &theme), prefix_color("web", &theme));
+    assert!(theme.prefixes.contains(&prefix_color("web", &theme)));
+    let names = ["web", "db", "cache", "worker", "proxy", "queue"];
+    let colors: Vec<_> = names
+        .iter()
+        .map(|name| prefix_color(name, &theme))
+        .collect();
+    assert!(colors.iter().any(|color| *color != colors[0]));
+}
+
+#[test]
+fn continuation_rows_are_indented_under_the_message() {
+    let indent = 4 + PREFIX_SEPARATOR.chars().count();
+    let rows = wrap_message("abcdefghij", 4, indent + 4);
+    assert_eq!(
+        rows,
+        [
+            "abcd".to_string(),
+            format!("{}efgh", " ".repeat(indent)),
+            format!("{}ij", " ".repeat(indent)),
+        ]
+    );
+    assert_eq!(wrap_message("short", 4, 80), ["short"]);
+    assert_eq!(wrap_message("", 4, 80), [""]);
+    // A window narrower than the prefix still shows a character per row
+    assert_eq!(wrap_message("ab", 4, 2).len(), 2);
+}
@@ -0,0 +1,60 @@
+use dockerrs::log_level::{classify, classify_lines, LevelFilter, LogLevel};
+
+#[test]
+fn upper_case_levels_and_logfmt_are_detected() {
+    assert_eq!(
+        classify("2024-05-01T12:00:00Z ERROR db: connection refused"),
+        Some(LogLevel::Error)
+    );
+    assert_eq!(classify("[WARN] disk almost full"), Some(LogLevel::Warn));
+    assert_eq!(classify("INFO: listening on :8080"), Some(LogLevel::Info));
+    assert_eq!(
+        classify("time=12:00 level=debug msg=\"cache miss\""),
+        Some(LogLevel::Debug)
+    );
+}
+
+#[test]
+fn lower_case_words_in_messages_are_ignored() {
+    assert_eq!(classify("retrying after error in upstream"), None);
+    assert_eq!(classify(""), None);
+}
+
+#[test]
+fn json_levels_are_read_from_common_keys() {
+    assert_eq!(
+        classify(r#"{"ts":1,"level":"warn","msg":"slow query"}"#),
+        Some(LogLevel::Warn)
+    );
+    assert_eq!(
+        classify(r#"{"severity": "ERROR", "message": "boom"}"#),
+        Some(LogLevel::Error)
+    );
+    assert_eq!(classify(r#"{"msg":"ERROR in the message only"}"#), None);
+}
+
+#[test]
+fn indented_lines_inherit_the_level_before_them() {
+    let lines = [
+        "ERROR request failed",
+        "    at handler (app.js:10)",
+        "next request",
+    ];
+    assert_eq!(
+        classify_lines(lines),
+        [Some(LogLevel::Error), Some(LogLevel::Error), None]
+    );
+}
+
+#[test]
+fn filters_cycle_from_all_to_errors_only() {
+    let filter = LevelFilter::default();
+    assert!(filter.allows(None));
+    let filter = filter.next();
+    assert!(filter.allows(Some(LogLevel::Error)));
+    assert!(!filter.allows(Some(LogLevel::Info)));
+    assert!(!filter.allows(None));
+    let filter = filter.next();
+    assert!(!filter.allows(Some(LogLevel::Warn)));
+    assert_eq!(filter.next(), LevelFilter::All);
+}
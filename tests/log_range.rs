@@ -0,0 +1,44 @@
+use dockerrs::log_range::{parse_time, LogRange};
+
+const NOW: i64 = 1_714_564_800; // 2024-05-01 12:00:00 UTC
+
+#[test]
+fn times_are_relative_to_now_or_absolute() {
+    assert_eq!(parse_time("now", NOW), Ok(NOW));
+    assert_eq!(parse_time("90s", NOW), Ok(NOW - 90));
+    assert_eq!(parse_time(" 15m ", NOW), Ok(NOW - 900));
+    assert_eq!(parse_time("2h", NOW), Ok(NOW - 7200));
+    assert_eq!(parse_time("1d", NOW), Ok(NOW - 86400));
+    assert_eq!(parse_time("2024-05-01T11:30:00Z", NOW), Ok(NOW - 1800));
+    assert_eq!(parse_time("2024-05-01T13:30:00+02:00", NOW), Ok(NOW - 1800));
+    for invalid in [
+        "",
+        "15",
+        "m",
+        "-5m",
+        "15w",
+        "yesterday",
+        "2024-13-01T00:00:00Z",
+    ] {
+        assert!(parse_time(invalid, NOW).is_err(), "{:?}", invalid);
+    }
+}
+
+#[test]
+fn ranges_need_a_start_before_their_end() {
+    assert_eq!(
+        LogRange::parse("2h", "", NOW),
+        Ok(LogRange {
+            since: NOW - 7200,
+            until: None,
+            label: "since 2h until now".to_string(),
+        })
+    );
+    assert_eq!(
+        LogRange::parse("2h", "1h", NOW).map(|range| range.until),
+        Ok(Some(NOW - 3600))
+    );
+    assert!(LogRange::parse("1h", "2h", NOW).is_err());
+    assert!(LogRange::parse("", "1h", NOW).is_err());
+    assert!(LogRange::parse("2h", "soon", NOW).is_err());
+}
@@ -0,0 +1,52 @@
+use dockerrs::log_selection::{line_count, selected_text, LogSelection};
+
+#[test]
+fn selection_covers_anchor_to_cursor_either_way() {
+    let mut selection = LogSelection::new("web".to_string(), 5);
+    assert_eq!(selection.range(), 5..=5);
+    selection.cursor = 2;
+    assert_eq!(selection.range(), 2..=5);
+    assert!(selection.contains(2) && selection.contains(5));
+    assert!(!selection.contains(6));
+}
+
+#[test]
+fn step_skips_lines_hidden_by_filters() {
+    let shown = [1, 4, 7];
+    let mut selection = LogSelection::new("web".to_string(), 4);
+    selection.step(&shown, true);
+    assert_eq!(selection.cursor, 7);
+    // Past the last shown line it stays put
+    selection.step(&shown, true);
+    assert_eq!(selection.cursor, 7);
+    selection.step(&shown, false);
+    selection.step(&shown, false);
+    assert_eq!(selection.cursor, 1);
+    assert_eq!(selection.anchor, 4);
+}
+
+#[test]
+fn dropped_lines_shrink_the_selection() {
+    let mut selection = LogSelection::new("web".to_string(), 10);
+    selection.cursor = 14;
+    // Streamed lines that do not reach the selection leave it alone
+    let kept = selection.clone().clamp(3).unwrap();
+    assert_eq!(kept, selection);
+    let clamped = selection.clone().clamp(12).unwrap();
+    assert_eq!(clamped.range(), 12..=14);
+    assert!(selection.clamp(15).is_none());
+}
+
+#[test]
+fn selected_text_joins_the_selected_lines() {
+    let lines = [(3, "a"), (4, "b"), (6, "c"), (7, "d")];
+    let mut selection = LogSelection::new("web".to_string(), 4);
+    selection.cursor = 6;
+    assert_eq!(selected_text(lines, &selection), ("b\nc".to_string(), 2));
+}
+
+#[test]
+fn line_count_is_singular_for_one() {
+    assert_eq!(line_count(1), "1 line");
+    assert_eq!(line_count(3), "3 lines");
+}
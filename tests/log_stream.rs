@@ -0,0 +1,97 @@
+use std::time::Instant;
+
+use dockerrs::log_stream::{adjust_scroll, split_timestamp, LogBuffer, LogCursor};
+
+#[test]
+fn buffer_keeps_the_newest_lines_up_to_its_cap() {
+    let mut buffer = LogBuffer::new(3);
+    for line in 0..5 {
+        buffer.push(line);
+    }
+    assert_eq!(buffer.iter().copied().collect::<Vec<_>>(), vec![2, 3, 4]);
+    assert_eq!(buffer.dropped(), 2);
+    assert_eq!(buffer.range(1..3).copied().collect::<Vec<_>>(), vec![3, 4]);
+}
+
+#[test]
+fn appending_past_the_cap_stays_linear() {
+    let time = |count: usize| {
+        let mut buffer = LogBuffer::new(100_000);
+        let start = Instant::now();
+        for line in 0..count {
+            buffer.push(line);
+        }
+        (start.elapsed(), buffer)
+    };
+    let (small, _) = time(100_000);
+    let (large, buffer) = time(1_000_000);
+    assert_eq!(buffer.len(), 100_000);
+    assert_eq!(buffer.dropped(), 900_000);
+    assert_eq!(buffer.iter().next(), Some(&900_000));
+    // Ten times the lines should take about ten times as long, not a hundred
+    assert!(
+        large < small * 40 + std::time::Duration::from_millis(200),
+        "1M pushes took {:?}, 100k took {:?}",
+        large,
+        small
+    );
+}
+
+#[test]
+fn scroll_moves_up_by_the_dropped_rows() {
+    assert_eq!(adjust_scroll(500.0, 10, 20.0), 300.0);
+    assert_eq!(adjust_scroll(100.0, 10, 20.0), 0.0);
+    assert_eq!(adjust_scroll(100.0, 0, 20.0), 100.0);
+}
+
+#[test]
+fn timestamps_are_split_off_the_line() {
+    assert_eq!(
+        split_timestamp("2024-05-01T12:00:00.5Z GET /health 200"),
+        (Some((1714564800, 500_000_000)), "GET /health 200")
+    );
+    assert_eq!(
+        split_timestamp("2024-05-01T12:00:00.000000001Z"),
+        (Some((1714564800, 1)), "")
+    );
+    assert_eq!(
+        split_timestamp("log stream ended: gone"),
+        (None, "log stream ended: gone")
+    );
+}
+
+#[test]
+fn resuming_skips_the_lines_seen_before() {
+    let mut cursor = LogCursor::default();
+    assert_eq!(cursor.resume(), None);
+    assert!(cursor.accept((100, 1)));
+    assert!(cursor.accept((100, 2)));
+    assert!(cursor.accept((101, 5)));
+
+    // The daemon sends the whole second again
+    assert_eq!(cursor.resume(), Some(101));
+    assert!(!cursor.accept((100, 2)));
+    assert!(!cursor.accept((101, 5)));
+    assert!(cursor.accept((101, 6)));
+    assert!(cursor.accept((102, 0)));
+}
+
+#[test]
+fn lines_sharing_a_timestamp_are_counted() {
+    let mut cursor = LogCursor::default();
+    assert!(cursor.accept((100, 7)));
+    assert!(cursor.accept((100, 7)));
+
+    // Both come again, followed by a third written in the same instant
+    cursor.resume();
+    assert!(!cursor.accept((100, 7)));
+    assert!(!cursor.accept((100, 7)));
+    assert!(cursor.accept((100, 7)));
+
+    // Resuming again skips all three
+    cursor.resume();
+    for _ in 0..3 {
+        assert!(!cursor.accept((100, 7)));
+    }
+    assert!(cursor.accept((100, 7)));
+}
@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+
+use bollard::secret::{
+    ContainerSummary, ContainerSummaryNetworkSettings, EndpointSettings, Ipam, IpamConfig, Network,
+    NetworkContainer,
+};
+use dockerrs::networks::{
+    connected_containers, driver_options, ipam_rows, IpamRow, NetworkEndpoint,
+};
+use dockerrs::poller::ContainerSnapshot;
+
+fn attached(id: &str, name: &str, network: &str, ip: &str) -> (String, (ContainerSummary, String)) {
+    let endpoint = EndpointSettings {
+        network_id: Some(format!("{}-id", network)),
+        ip_address: Some(ip.to_string()),
+        ip_prefix_len: Some(16),
+        mac_address: Some("02:42:ac:12:00:02".to_string()),
+        ..Default::default()
+    };
+    let summary = ContainerSummary {
+        id: Some(id.to_string()),
+        names: Some(vec![format!("/{}", name)]),
+        network_settings: Some(ContainerSummaryNetworkSettings {
+            networks: Some(HashMap::from([(network.to_string(), endpoint)])),
+        }),
+        ..Default::default()
+    };
+    (id.to_string(), (summary, String::new()))
+}
+
+fn network(name: &str) -> Network {
+    Network {
+        id: Some(format!("{}-id", name)),
+        name: Some(name.to_string()),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn containers_come_from_the_snapshot_with_their_addresses() {
+    let snapshot: ContainerSnapshot = [
+        attached("b", "web", "app", "172.18.0.3"),
+        attached("a", "api", "app", "172.18.0.2"),
+        attached("c", "db", "other", "172.19.0.2"),
+    ]
+    .into_iter()
+    .collect();
+    let connected = connected_containers(&network("app"), &snapshot);
+    assert_eq!(
+        connected,
+        vec![
+            NetworkEndpoint {
+                name: "api".to_string(),
+                container_id: "a".to_string(),
+                ipv4: "172.18.0.2/16".to_string(),
+                ipv6: String::new(),
+                mac: "02:42:ac:12:00:02".to_string(),
+            },
+            NetworkEndpoint {
+                name: "web".to_string(),
+                container_id: "b".to_string(),
+                ipv4: "172.18.0.3/16".to_string(),
+                ipv6: String::new(),
+                mac: "02:42:ac:12:00:02".to_string(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn inspected_containers_are_used_when_present() {
+    let mut inspected = network("app");
+    inspected.containers = Some(HashMap::from([(
+        "a".to_string(),
+        NetworkContainer {
+            name: Some("api".to_string()),
+            ipv4_address: Some("172.18.0.2/16".to_string()),
+            ipv6_address: Some("fd00::2/64".to_string()),
+            mac_address: Some("02:42:ac:12:00:02".to_string()),
+            ..Default::default()
+        },
+    )]));
+    let connected = connected_containers(&inspected, &ContainerSnapshot::new());
+    assert_eq!(connected.len(), 1);
+    assert_eq!(connected[0].ipv6, "fd00::2/64");
+}
+
+#[test]
+fn ipam_and_driver_options() {
+    let mut bridge = network("app");
+    bridge.ipam = Some(Ipam {
+        config: Some(vec![
+            IpamConfig {
+                subnet: Some("172.18.0.0/16".to_string()),
+                gateway: Some("172.18.0.1".to_string()),
+                ..Default::default()
+            },
+            IpamConfig {
+                subnet: Some("fd00::/64".to_string()),
+                ip_range: Some("fd00::/80".to_string()),
+                ..Default::default()
+            },
+        ]),
+        ..Default::default()
+    });
+    bridge.options = Some(HashMap::from([
+        (
+            "com.docker.network.bridge.name".to_string(),
+            "br-app".to_string(),
+        ),
+        (
+            "com.docker.network.driver.mtu".to_string(),
+            "1500".to_string(),
+        ),
+    ]));
+    assert_eq!(
+        ipam_rows(&bridge),
+        vec![
+            IpamRow {
+                subnet: "172.18.0.0/16".to_string(),
+                gateway: "172.18.0.1".to_string(),
+                ip_range: String::new(),
+            },
+            IpamRow {
+                subnet: "fd00::/64".to_string(),
+                gateway: String::new(),
+                ip_range: "fd00::/80".to_string(),
+            },
+        ]
+    );
+    assert_eq!(
+        driver_options(&bridge),
+        vec![
+            "com.docker.network.bridge.name=br-app",
+            "com.docker.network.driver.mtu=1500"
+        ]
+    );
+    assert!(ipam_rows(&network("host")).is_empty());
+    assert!(driver_options(&network("host")).is_empty());
+}
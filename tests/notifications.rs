@@ -0,0 +1,70 @@
+use std::time::{Duration, Instant};
+
+use dockerrs::notifications::{Notifications, HISTORY_LEN, MAX_SHOWN, NOTIFICATION_DURATION};
+
+fn labels(notifications: &Notifications) -> Vec<String> {
+    notifications
+        .shown
+        .iter()
+        .map(|notification| notification.label())
+        .collect()
+}
+
+#[test]
+fn repeated_messages_are_counted_and_old_ones_taken_down() {
+    let now = Instant::now();
+    let mut notifications = Notifications::default();
+    for index in 0..MAX_SHOWN + 2 {
+        notifications.push(Err(format!("Failed {}", index)), None, now);
+    }
+    assert_eq!(notifications.shown.len(), MAX_SHOWN);
+    assert_eq!(notifications.shown[0].message, "Failed 2");
+
+    notifications.push(Err("Failed 2".to_string()), None, now);
+    assert_eq!(notifications.shown.len(), MAX_SHOWN);
+    assert_eq!(labels(&notifications).last().unwrap(), "Failed 2 (×2)");
+    // A success with the same text is another notification
+    notifications.push(Ok("Failed 2".to_string()), None, now);
+    assert_eq!(labels(&notifications).last().unwrap(), "Failed 2");
+}
+
+#[test]
+fn notifications_expire_but_stay_in_the_history() {
+    let now = Instant::now();
+    let mut notifications = Notifications::default();
+    notifications.push(Ok("Removed 3 containers".to_string()), None, now);
+    let later = now + Duration::from_secs(4);
+    let container = Some("abc".to_string());
+    notifications.push(
+        Err("Failed to stop abc".to_string()),
+        container.clone(),
+        later,
+    );
+
+    assert_eq!(
+        notifications.expire(later),
+        Some(NOTIFICATION_DURATION - Duration::from_secs(4))
+    );
+    assert_eq!(
+        notifications.expire(now + NOTIFICATION_DURATION),
+        Some(Duration::from_secs(4))
+    );
+    assert_eq!(labels(&notifications), ["Failed to stop abc"]);
+    assert_eq!(notifications.shown[0].container, container);
+    notifications.dismiss(0);
+    assert_eq!(notifications.expire(later), None);
+    assert_eq!(notifications.history.len(), 2);
+}
+
+#[test]
+fn the_history_keeps_the_last_fifty() {
+    let now = Instant::now();
+    let mut notifications = Notifications::default();
+    for index in 0..HISTORY_LEN + 10 {
+        notifications.push(Ok(format!("Done {}", index)), None, now);
+    }
+    notifications.push(Ok(format!("Done {}", HISTORY_LEN + 9)), None, now);
+    assert_eq!(notifications.history.len(), HISTORY_LEN);
+    assert_eq!(notifications.history[0].message, "Done 10");
+    assert_eq!(notifications.history.back().unwrap().count, 2);
+}
@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use bollard::secret::{ContainerSummary, Port, PortTypeEnum};
+use dockerrs::compose_file::{ComposeFile, HostPort};
+use dockerrs::poller::ContainerSnapshot;
+use dockerrs::port_conflicts::{find_conflicts, requested_ports, Holder, PendingUp};
+use dockerrs::utils::{COMPOSE_PROJECT_LABEL, COMPOSE_WORKING_DIR_LABEL};
+
+const COMPOSE: &str = r#"
+services:
+  web:
+    ports: ["8080:80", "127.0.0.1:5432:5432"]
+  dns:
+    ports: ["5353:53/udp"]
+"#;
+
+fn container(
+    id: &str,
+    state: &str,
+    ports: &[(Option<&str>, u16, PortTypeEnum)],
+    working_dir: Option<&str>,
+) -> (String, (ContainerSummary, String)) {
+    let summary = ContainerSummary {
+        id: Some(id.to_string()),
+        names: Some(vec![format!("/{}", id)]),
+        state: Some(state.to_string()),
+        ports: Some(
+            ports
+                .iter()
+                .map(|(ip, public, typ)| Port {
+                    ip: ip.map(String::from),
+                    private_port: 1,
+                    public_port: Some(*public),
+                    typ: Some(*typ),
+                })
+                .collect(),
+        ),
+        labels: working_dir.map(|dir| {
+            HashMap::from([
+                (COMPOSE_PROJECT_LABEL.to_string(), "app".to_string()),
+                (COMPOSE_WORKING_DIR_LABEL.to_string(), dir.to_string()),
+            ])
+        }),
+        ..Default::default()
+    };
+    (id.to_string(), (summary, String::new()))
+}
+
+fn conflicts(containers: ContainerSnapshot, services: &[String]) -> Vec<String> {
+    let compose = ComposeFile::parse(COMPOSE).unwrap();
+    find_conflicts(
+        &requested_ports(&compose, services),
+        &containers,
+        Path::new("/srv/app"),
+        |_| false,
+    )
+    .iter()
+    .map(|conflict| conflict.describe())
+    .collect()
+}
+
+#[test]
+fn running_containers_on_the_same_port_conflict() {
+    let containers = ContainerSnapshot::from([
+        container(
+            "nginx",
+            "running",
+            &[(Some("0.0.0.0"), 8080, PortTypeEnum::TCP)],
+            None,
+        ),
+        // Bound on another address
+        container(
+            "db",
+            "running",
+            &[(Some("10.0.0.2"), 5432, PortTypeEnum::TCP)],
+            None,
+        ),
+        // TCP does not take the UDP port
+        container("dns", "running", &[(None, 5353, PortTypeEnum::TCP)], None),
+        container("old", "exited", &[(None, 8080, PortTypeEnum::TCP)], None),
+    ]);
+    assert_eq!(
+        conflicts(containers, &[]),
+        vec!["web: 8080/tcp is used by container /nginx"]
+    );
+}
+
+#[test]
+fn the_projects_own_containers_do_not_conflict() {
+    let containers = ContainerSnapshot::from([container(
+        "app-web-1",
+        "running",
+        &[(Some("0.0.0.0"), 8080, PortTypeEnum::TCP)],
+        Some("/srv/app"),
+    )]);
+    assert!(conflicts(containers, &[]).is_empty());
+}
+
+#[test]
+fn only_the_requested_services_are_checked() {
+    let containers = ContainerSnapshot::from([container(
+        "resolver",
+        "running",
+        &[(Some("127.0.0.1"), 5353, PortTypeEnum::UDP)],
+        None,
+    )]);
+    assert!(conflicts(containers.clone(), &["web".to_string()]).is_empty());
+    assert_eq!(
+        conflicts(containers, &["dns".to_string()]),
+        vec!["dns: 5353/udp is used by container /resolver"]
+    );
+}
+
+#[test]
+fn host_listeners_are_probed_for_ports_no_container_holds() {
+    let compose = ComposeFile::parse(COMPOSE).unwrap();
+    let containers = ContainerSnapshot::from([container(
+        "nginx",
+        "running",
+        &[(None, 8080, PortTypeEnum::TCP)],
+        None,
+    )]);
+    let found = find_conflicts(
+        &requested_ports(&compose, &[]),
+        &containers,
+        Path::new("/srv/app"),
+        |_: &HostPort| true,
+    );
+    let holders: Vec<(&str, u16, &Holder)> = found
+        .iter()
+        .map(|conflict| {
+            (
+                conflict.service.as_str(),
+                conflict.port.port,
+                &conflict.holder,
+            )
+        })
+        .collect();
+    assert_eq!(
+        holders,
+        vec![
+            ("dns", 5353, &Holder::Host),
+            (
+                "web",
+                8080,
+                &Holder::Container {
+                    id: "nginx".to_string(),
+                    name: "/nginx".to_string()
+                }
+            ),
+            ("web", 5432, &Holder::Host),
+        ]
+    );
+
+    let pending = PendingUp {
+        path: PathBuf::from("/srv/app/compose.yaml"),
+        services: Vec::new(),
+        conflicts: found,
+    };
+    assert_eq!(
+        pending.containers(),
+        vec![("nginx".to_string(), "/nginx".to_string())]
+    );
+}
@@ -0,0 +1,34 @@
+use dockerrs::read_only::{self, READ_ONLY_MESSAGE};
+use std::path::Path;
+
+use dockerrs::utils::{remove_image, run_docker_compose, ComposeCommand};
+use tokio::sync::mpsc;
+
+// One test, the flag is global to the process
+#[tokio::test]
+async fn changes_are_refused_before_reaching_the_daemon() {
+    assert!(read_only::check().is_ok());
+    read_only::set(true);
+    assert_eq!(read_only::check(), Err(READ_ONLY_MESSAGE.to_string()));
+    assert_eq!(
+        remove_image("alpine:latest".to_string()).await,
+        Err(READ_ONLY_MESSAGE.to_string())
+    );
+    let (output, _) = mpsc::unbounded_channel();
+    let services = ["web".to_string()];
+    assert_eq!(
+        run_docker_compose(
+            Path::new("."),
+            None,
+            &[],
+            ComposeCommand::Up,
+            &services,
+            output
+        )
+        .await,
+        Err(READ_ONLY_MESSAGE.to_string())
+    );
+    assert!(!ComposeCommand::Logs.is_mutating());
+    read_only::set(false);
+    assert!(!read_only::is_enabled());
+}
@@ -0,0 +1,64 @@
+use dockerrs::registry::{registry_of, split_tag, stored_credentials};
+
+#[test]
+fn docker_hub_hosts_have_no_registry_of_their_own() {
+    assert_eq!(registry_of("nginx"), None);
+    assert_eq!(registry_of("me/app:1.0"), None);
+    assert_eq!(registry_of("docker.io/me/app"), None);
+    assert_eq!(registry_of("index.docker.io/library/nginx"), None);
+    assert_eq!(registry_of("ghcr.io/me/app"), Some("ghcr.io"));
+    assert_eq!(registry_of("localhost:5000/app"), Some("localhost:5000"));
+    assert_eq!(registry_of("localhost/app"), Some("localhost"));
+}
+
+#[test]
+fn tags_and_digests_are_split_off() {
+    assert_eq!(split_tag("nginx"), ("nginx", "latest"));
+    assert_eq!(split_tag("nginx:1.25"), ("nginx", "1.25"));
+    assert_eq!(
+        split_tag("localhost:5000/app"),
+        ("localhost:5000/app", "latest")
+    );
+    assert_eq!(
+        split_tag("localhost:5000/app:dev"),
+        ("localhost:5000/app", "dev")
+    );
+    assert_eq!(split_tag("nginx:1.25@sha256:0123abcd"), ("nginx", "1.25"));
+    assert_eq!(
+        split_tag("nginx@sha256:0123abcd"),
+        ("nginx", "sha256:0123abcd")
+    );
+    assert_eq!(
+        split_tag("localhost:5000/app@sha256:0123abcd"),
+        ("localhost:5000/app", "sha256:0123abcd")
+    );
+}
+
+// The only test in this file that touches DOCKER_CONFIG
+#[tokio::test]
+async fn docker_io_images_use_the_docker_hub_login() {
+    let dir = std::env::temp_dir().join(format!("dockerrs-registry-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    // "me:secret"
+    std::fs::write(
+        dir.join("config.json"),
+        r#"{"auths": {"https://index.docker.io/v1/": {"auth": "bWU6c2VjcmV0"}}}"#,
+    )
+    .unwrap();
+    std::env::set_var("DOCKER_CONFIG", &dir);
+
+    let credentials = stored_credentials(registry_of("docker.io/me/app"))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(credentials.username.as_deref(), Some("me"));
+    assert_eq!(credentials.password.as_deref(), Some("secret"));
+    assert_eq!(
+        credentials.serveraddress.as_deref(),
+        Some("https://index.docker.io/v1/")
+    );
+    assert_eq!(stored_credentials(Some("ghcr.io")).await.unwrap(), None);
+
+    std::env::remove_var("DOCKER_CONFIG");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
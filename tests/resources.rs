@@ -0,0 +1,106 @@
+use bollard::secret::RestartPolicyNameEnum;
+use dockerrs::resources::{
+    describe_restart_policy, memory_swap, parse_memory, restart_policy, restart_policy_change,
+    ResourceLimits,
+};
+use dockerrs::utils::{parse_size_in, SizeUnits};
+
+#[test]
+fn retry_count_only_goes_with_on_failure() {
+    let policy = restart_policy("on-failure", 3).unwrap();
+    assert_eq!(policy.name, Some(RestartPolicyNameEnum::ON_FAILURE));
+    assert_eq!(policy.maximum_retry_count, Some(3));
+    assert_eq!(
+        restart_policy("unless-stopped", 0).unwrap().name,
+        Some(RestartPolicyNameEnum::UNLESS_STOPPED)
+    );
+    assert!(restart_policy("always", 2).is_err());
+    assert!(restart_policy("on-failure", -1).is_err());
+    assert!(restart_policy("sometimes", 0).is_err());
+}
+
+#[test]
+fn restart_policies_are_described_with_their_retries() {
+    assert_eq!(describe_restart_policy("", 0), "no");
+    assert_eq!(describe_restart_policy("always", 0), "always");
+    assert_eq!(describe_restart_policy("on-failure", 0), "on-failure");
+    assert_eq!(
+        describe_restart_policy("on-failure", 1),
+        "on-failure (max 1 retry)"
+    );
+    assert_eq!(
+        describe_restart_policy("on-failure", 5),
+        "on-failure (max 5 retries)"
+    );
+}
+
+#[test]
+fn memory_limits_count_in_1024s_like_the_docker_cli() {
+    let mib = 1024 * 1024;
+    assert_eq!(parse_memory("512m"), Ok(512 * mib));
+    assert_eq!(parse_memory("512M"), Ok(512 * mib));
+    assert_eq!(parse_memory("512mb"), Ok(512 * mib));
+    assert_eq!(parse_memory("1.5g"), Ok(1536 * mib));
+    assert_eq!(parse_memory("8388608"), Ok(8 * mib));
+    assert_eq!(parse_memory("0"), Ok(0));
+    for input in ["512m", "1.5g", "8192k", "2GiB"] {
+        assert_eq!(
+            parse_memory(input),
+            Ok(parse_size_in(input, SizeUnits::Binary).unwrap() as i64),
+            "{}",
+            input
+        );
+    }
+    assert!(parse_memory("5m").is_err());
+    assert!(parse_memory("-1").is_err());
+    assert!(parse_memory("lots").is_err());
+    assert!(parse_memory("99999999999t").is_err());
+}
+
+#[test]
+fn swap_on_top_of_memory_survives_a_new_memory_limit() {
+    let mib = 1024 * 1024;
+    let limits = |memory: i64, memory_swap: i64| ResourceLimits {
+        memory: memory * mib,
+        memory_swap: if memory_swap > 0 {
+            memory_swap * mib
+        } else {
+            memory_swap
+        },
+        ..Default::default()
+    };
+    // The daemon's default of twice the memory keeps that much swap
+    assert_eq!(
+        memory_swap(Some(1024 * mib), &limits(512, 1024)),
+        Some(1536 * mib)
+    );
+    // Swap disabled by setting it to the memory stays disabled
+    assert_eq!(
+        memory_swap(Some(256 * mib), &limits(512, 512)),
+        Some(256 * mib)
+    );
+    // No swap limit before, none after
+    assert_eq!(memory_swap(Some(256 * mib), &limits(512, -1)), Some(-1));
+    assert_eq!(memory_swap(Some(256 * mib), &limits(0, 0)), Some(-1));
+    // Lifting the memory limit lifts the swap limit with it
+    assert_eq!(memory_swap(Some(0), &limits(512, 1024)), Some(-1));
+    // Swap is left alone when the memory limit is
+    assert_eq!(memory_swap(None, &limits(512, 1024)), None);
+}
+
+#[test]
+fn unchanged_restart_policy_is_not_resent() {
+    let current = ResourceLimits {
+        restart_policy: "on-failure".to_string(),
+        restart_max_retries: 5,
+        ..Default::default()
+    };
+    // Resending on-failure would drop the retry count of 5
+    assert_eq!(restart_policy_change("on-failure", &current), Ok(None));
+    assert_eq!(restart_policy_change("", &current), Ok(None));
+
+    let changed = restart_policy_change("always", &current).unwrap().unwrap();
+    assert_eq!(changed.name, Some(RestartPolicyNameEnum::ALWAYS));
+    assert_eq!(changed.maximum_retry_count, Some(0));
+    assert!(restart_policy_change("sometimes", &current).is_err());
+}
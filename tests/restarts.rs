@@ -0,0 +1,80 @@
+use dockerrs::restarts::{RestartInfo, RestartTracker};
+
+mod common;
+
+use common::{container, snapshot};
+
+fn restarted(count: i64) -> Result<RestartInfo, String> {
+    Ok(RestartInfo {
+        restart_count: count,
+        oom_killed: false,
+    })
+}
+
+#[test]
+fn containers_are_inspected_again_only_when_their_status_changes() {
+    let mut tracker = RestartTracker::default();
+    let first = snapshot([
+        container("a").status("Up 3 seconds"),
+        container("b").status("Up 2 hours"),
+    ]);
+    assert_eq!(tracker.due(&first), ["a", "b"]);
+    assert!(tracker.is_pending());
+    assert!(tracker.due(&first).is_empty());
+
+    assert!(tracker.record("a".to_string(), restarted(1)));
+    assert!(tracker.record("b".to_string(), restarted(0)));
+    assert!(!tracker.is_pending());
+
+    // Restarted again, so it looks freshly started
+    let second = snapshot([
+        container("a").status("Restarting (1) 1 second ago"),
+        container("b").status("Up 2 hours"),
+    ]);
+    assert_eq!(tracker.due(&second), ["a"]);
+    assert!(tracker.record("a".to_string(), restarted(2)));
+    assert_eq!(tracker.get("a").restart_count, 2);
+    assert_eq!(tracker.get("a").badge().as_deref(), Some("↻ 2"));
+    assert_eq!(tracker.get("b").badge(), None);
+
+    // Same answer twice is no change
+    let third = snapshot([
+        container("a").status("Up 1 second"),
+        container("b").status("Up 2 hours"),
+    ]);
+    assert_eq!(tracker.due(&third), ["a"]);
+    assert!(!tracker.record("a".to_string(), restarted(2)));
+}
+
+#[test]
+fn gone_containers_are_forgotten_and_failed_inspects_dropped() {
+    let mut tracker = RestartTracker::default();
+    tracker.due(&snapshot([container("a").status("Up 1 minute")]));
+    tracker.due(&snapshot([]));
+    assert!(!tracker.record("a".to_string(), restarted(3)));
+    assert_eq!(tracker.get("a"), RestartInfo::default());
+    assert!(!tracker.is_pending());
+
+    // It comes back and is inspected again
+    assert_eq!(
+        tracker.due(&snapshot([container("a").status("Up 1 minute")])),
+        ["a"]
+    );
+    assert!(!tracker.record("a".to_string(), Err("No such container".to_string())));
+}
+
+#[test]
+fn sorting_puts_the_most_restarted_first_and_keeps_ties_in_order() {
+    let mut tracker = RestartTracker::default();
+    tracker.due(&snapshot([
+        container("a").status("Up"),
+        container("b").status("Up"),
+        container("c").status("Up"),
+    ]));
+    tracker.record("b".to_string(), restarted(12));
+    tracker.record("c".to_string(), restarted(0));
+    let mut ids = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+    tracker.sort(&mut ids);
+    assert_eq!(ids, ["b", "a", "c"]);
+    assert!(tracker.get("b").is_looping());
+}
@@ -0,0 +1,216 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use dockerrs::scan::{
+    compose_file_set, compose_sort_key, is_compose_file, is_compose_override, is_dockerfile, Scan,
+    ScanSettings, DEFAULT_EXCLUDES,
+};
+use tokio::sync::mpsc;
+
+// A fresh directory per test, so tests running in parallel do not see each other's files
+fn fixture(name: &str, files: &[&str]) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("dockerrs-scan-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    for file in files {
+        let path = root.join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, "services: {}\n").unwrap();
+    }
+    root.canonicalize().unwrap()
+}
+
+// Every path the scan finds, sorted
+async fn scan_paths(root: &Path, matches: fn(&str) -> bool) -> Vec<PathBuf> {
+    let settings = ScanSettings {
+        roots: vec![root.to_owned()],
+        excludes: DEFAULT_EXCLUDES
+            .iter()
+            .map(|name| name.to_string())
+            .collect(),
+        max_depth: None,
+    };
+    let (errors, _) = mpsc::unbounded_channel();
+    let mut scan = Scan::start(settings, matches, errors);
+    let mut found = Vec::new();
+    while let Some(path) = scan.receiver.recv().await {
+        found.push(path);
+    }
+    found.sort();
+    found
+}
+
+// The paths relative to `root`
+async fn scan(root: &Path, matches: fn(&str) -> bool) -> Vec<String> {
+    scan_paths(root, matches)
+        .await
+        .iter()
+        .map(|path| {
+            let relative = path.strip_prefix(root).unwrap();
+            relative.to_string_lossy().replace('\\', "/")
+        })
+        .collect()
+}
+
+#[test]
+fn every_compose_file_name_is_recognized() {
+    for name in [
+        "compose.yaml",
+        "compose.yml",
+        "docker-compose.yaml",
+        "docker-compose.yml",
+        "docker_compose.yaml",
+        "Docker-Compose.YML",
+        "compose.override.yaml",
+        "docker-compose.override.yml",
+    ] {
+        assert!(is_compose_file(name), "{}", name);
+    }
+    for name in [
+        "compose.json",
+        "compose",
+        "my-compose.yaml",
+        "docker-compose.prod.yml",
+        "values.yaml",
+    ] {
+        assert!(!is_compose_file(name), "{}", name);
+    }
+    assert!(is_compose_override(Path::new("app/compose.override.yml")));
+    assert!(!is_compose_override(Path::new("app/compose.yml")));
+}
+
+#[test]
+fn overrides_sort_below_their_base_file() {
+    let mut paths = vec![
+        PathBuf::from("b/compose.yaml"),
+        PathBuf::from("a/docker-compose.override.yml"),
+        PathBuf::from("a/compose.yaml"),
+        PathBuf::from("a/docker-compose.yml"),
+        PathBuf::from("a/compose.override.yaml"),
+    ];
+    paths.sort_by_key(|path| compose_sort_key(path));
+    assert_eq!(
+        paths,
+        [
+            PathBuf::from("a/compose.yaml"),
+            PathBuf::from("a/compose.override.yaml"),
+            PathBuf::from("a/docker-compose.yml"),
+            PathBuf::from("a/docker-compose.override.yml"),
+            PathBuf::from("b/compose.yaml"),
+        ]
+    );
+
+    let base = PathBuf::from("a/compose.yaml");
+    let set = [base.clone(), PathBuf::from("a/compose.override.yaml")];
+    assert_eq!(compose_file_set(&paths[1], &paths), set);
+    assert_eq!(compose_file_set(&base, &paths), set);
+    assert_eq!(
+        compose_file_set(Path::new("c/compose.yaml"), &paths),
+        [PathBuf::from("c/compose.yaml")]
+    );
+}
+
+#[tokio::test]
+async fn scans_find_compose_files_and_skip_excluded_directories() {
+    let root = fixture(
+        "compose",
+        &[
+            "compose.yaml",
+            "api/docker-compose.yml",
+            "api/docker-compose.override.yml",
+            "web/Compose.YML",
+            "web/notes.yaml",
+            "node_modules/pkg/compose.yaml",
+            ".git/compose.yaml",
+            "target/compose.yaml",
+            "vendor/lib/docker-compose.yaml",
+            "deep/er/vendored/compose.yml",
+        ],
+    );
+    assert_eq!(
+        scan(&root, is_compose_file).await,
+        [
+            "api/docker-compose.override.yml",
+            "api/docker-compose.yml",
+            "compose.yaml",
+            "deep/er/vendored/compose.yml",
+            "web/Compose.YML",
+        ]
+    );
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn dockerfile_variants_are_recognized() {
+    for name in [
+        "Dockerfile",
+        "dockerfile",
+        "Dockerfile.dev",
+        "Dockerfile.prod",
+        "Dockerfile-prod",
+        "api.dockerfile",
+        "API.Dockerfile",
+    ] {
+        assert!(is_dockerfile(name), "{}", name);
+    }
+    for name in [
+        "dockerfile-notes.md",
+        "Dockerfile.dev.md",
+        "Dockerfile.",
+        "Dockerfiles",
+        "my-dockerfile",
+        ".dockerignore",
+    ] {
+        assert!(!is_dockerfile(name), "{}", name);
+    }
+}
+
+#[tokio::test]
+async fn scans_find_every_dockerfile_variant() {
+    let root = fixture(
+        "dockerfiles",
+        &[
+            "Dockerfile",
+            "Dockerfile.dev",
+            "foo.dockerfile",
+            "dockerfile-notes.md",
+            "README.md",
+        ],
+    );
+    assert_eq!(
+        scan(&root, is_dockerfile).await,
+        ["Dockerfile", "Dockerfile.dev", "foo.dockerfile"]
+    );
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn linked_files_are_followed() {
+    use std::os::unix::fs::symlink;
+
+    let shared = fixture("link-targets", &["compose.yaml", "Dockerfile.base"]);
+    let root = fixture("links", &["api/Dockerfile"]);
+    symlink(shared.join("compose.yaml"), root.join("compose.yaml")).unwrap();
+    symlink(shared.join("Dockerfile.base"), root.join("Dockerfile")).unwrap();
+    // Linked twice, found once
+    symlink(
+        shared.join("Dockerfile.base"),
+        root.join("api/Dockerfile.base"),
+    )
+    .unwrap();
+    // A dangling link is not a file
+    symlink(root.join("gone.yaml"), root.join("compose.override.yaml")).unwrap();
+
+    // Found files are sent as their target
+    assert_eq!(scan(&shared, is_compose_file).await, ["compose.yaml"]);
+    let found = scan_paths(&root, is_compose_file).await;
+    assert_eq!(found, [shared.join("compose.yaml")]);
+    let found = scan_paths(&root, is_dockerfile).await;
+    assert_eq!(
+        found,
+        [shared.join("Dockerfile.base"), root.join("api/Dockerfile")]
+    );
+
+    fs::remove_dir_all(root).unwrap();
+    fs::remove_dir_all(shared).unwrap();
+}
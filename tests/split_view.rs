@@ -0,0 +1,67 @@
+use std::time::{Duration, Instant};
+
+use dockerrs::split_view::{Pane, SplitView, SWITCH_DELAY};
+
+#[test]
+fn first_selection_is_streamed_right_away() {
+    let mut split = SplitView::default();
+    let now = Instant::now();
+    let web = "web".to_string();
+    assert_eq!(split.streamed(Some(&web), now), Some(&web));
+    assert!(!split.is_switching());
+}
+
+#[test]
+fn another_selection_waits_until_it_settles() {
+    let mut split = SplitView::default();
+    let start = Instant::now();
+    let (web, api, db) = ("web".to_string(), "api".to_string(), "db".to_string());
+    split.streamed(Some(&web), start);
+
+    // Moving through the list keeps the old stream
+    assert_eq!(split.streamed(Some(&api), start), Some(&web));
+    assert!(split.is_switching());
+    let later = start + Duration::from_millis(200);
+    assert_eq!(split.streamed(Some(&db), later), Some(&web));
+    assert_eq!(
+        split.streamed(Some(&db), later + SWITCH_DELAY - Duration::from_millis(1)),
+        Some(&web)
+    );
+    assert_eq!(split.streamed(Some(&db), later + SWITCH_DELAY), Some(&db));
+    assert!(!split.is_switching());
+}
+
+#[test]
+fn going_back_cancels_the_switch() {
+    let mut split = SplitView::default();
+    let now = Instant::now();
+    let (web, api) = ("web".to_string(), "api".to_string());
+    split.streamed(Some(&web), now);
+    split.streamed(Some(&api), now);
+    assert_eq!(split.streamed(Some(&web), now), Some(&web));
+    assert!(!split.is_switching());
+}
+
+#[test]
+fn no_selection_streams_nothing() {
+    let mut split = SplitView::default();
+    let now = Instant::now();
+    let web = "web".to_string();
+    split.streamed(Some(&web), now);
+    assert_eq!(split.streamed(None, now), None);
+    // Selecting again afterwards is the first selection again
+    let api = "api".to_string();
+    assert_eq!(split.streamed(Some(&api), now), Some(&api));
+}
+
+#[test]
+fn toggling_starts_over_on_the_list() {
+    let mut split = SplitView::default();
+    split.toggle();
+    assert!(split.enabled);
+    split.focus = split.focus.other();
+    assert_eq!(split.focus, Pane::Logs);
+    split.toggle();
+    assert!(!split.enabled);
+    assert_eq!(split.focus, Pane::List);
+}
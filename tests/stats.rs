@@ -0,0 +1,75 @@
+use dockerrs::stats::{cpu_percent, memory_usage, summary, StatsHistory, StatsSample};
+
+fn stats(total: u64, system: u64, memory: serde_json::Value) -> bollard::container::Stats {
+    let cpu = |total: u64, system: u64| {
+        serde_json::json!({
+            "cpu_usage": {
+                "total_usage": total,
+                "usage_in_usermode": 0,
+                "usage_in_kernelmode": 0
+            },
+            "system_cpu_usage": system,
+            "online_cpus": 4,
+            "throttling_data": {"periods": 0, "throttled_periods": 0, "throttled_time": 0}
+        })
+    };
+    serde_json::from_value(serde_json::json!({
+        "read": "2024-05-01T12:00:01Z",
+        "preread": "2024-05-01T12:00:00Z",
+        "num_procs": 0,
+        "pids_stats": {},
+        "memory_stats": memory,
+        "blkio_stats": {},
+        "cpu_stats": cpu(total, system),
+        "precpu_stats": cpu(1_000, 100_000),
+        "storage_stats": {}
+    }))
+    .unwrap()
+}
+
+#[test]
+fn cpu_is_a_share_of_all_cores_like_docker_stats() {
+    // A quarter of the system time on four cores is one busy core
+    let stats = stats(26_000, 200_000, serde_json::json!({}));
+    assert_eq!(cpu_percent(&stats), Some(100.0));
+}
+
+#[test]
+fn the_first_sample_has_no_cpu() {
+    let stats = stats(1_000, 100_000, serde_json::json!({}));
+    assert_eq!(cpu_percent(&stats), None);
+}
+
+#[test]
+fn memory_leaves_out_the_inactive_page_cache() {
+    let v1 = serde_json::json!({"usage": 1000, "stats": {
+        "cache": 0, "dirty": 0, "mapped_file": 0, "total_inactive_file": 300, "pgpgout": 0,
+        "rss": 0, "total_mapped_file": 0, "writeback": 0, "unevictable": 0, "pgpgin": 0,
+        "total_unevictable": 0, "pgmajfault": 0, "total_rss": 0, "total_rss_huge": 0,
+        "total_writeback": 0, "total_inactive_anon": 0, "rss_huge": 0,
+        "hierarchical_memory_limit": 0, "total_pgfault": 0, "total_active_file": 0,
+        "active_anon": 0, "total_active_anon": 0, "total_pgpgout": 0, "total_cache": 0,
+        "inactive_anon": 0, "active_file": 0, "pgfault": 0, "inactive_file": 0,
+        "total_pgmajfault": 0, "total_pgpgin": 0, "total_dirty": 0
+    }});
+    assert_eq!(memory_usage(&stats(0, 0, v1)), Some(700));
+    assert_eq!(
+        memory_usage(&stats(0, 0, serde_json::json!({"usage": 1000}))),
+        Some(1000)
+    );
+    assert_eq!(memory_usage(&stats(0, 0, serde_json::json!({}))), None);
+}
+
+#[test]
+fn history_keeps_the_newest_samples() {
+    let mut history = StatsHistory::new(3);
+    for memory in 1..=5 {
+        history.push(StatsSample {
+            cpu_percent: memory as f64 * 10.0,
+            memory,
+        });
+    }
+    assert_eq!(history.memory(), [3.0, 4.0, 5.0]);
+    assert_eq!(summary(&history.cpu()), Some((50.0, 30.0, 50.0)));
+    assert_eq!(summary(&[]), None);
+}
@@ -0,0 +1,55 @@
+use bollard::secret::{SystemInfo, SystemInfoCgroupDriverEnum, SystemInfoCgroupVersionEnum};
+use bollard::system::Version;
+use dockerrs::system_info::summarize;
+
+fn value<'a>(rows: &'a [(&'static str, String)], label: &str) -> &'a str {
+    &rows.iter().find(|(name, _)| *name == label).unwrap().1
+}
+
+#[test]
+fn summary_shows_versions_counts_and_warnings() {
+    let info = SystemInfo {
+        containers: Some(10),
+        containers_running: Some(7),
+        containers_paused: Some(1),
+        containers_stopped: Some(2),
+        images: Some(42),
+        driver: Some("overlay2".to_string()),
+        cgroup_version: Some(SystemInfoCgroupVersionEnum::_2),
+        cgroup_driver: Some(SystemInfoCgroupDriverEnum::SYSTEMD),
+        warnings: Some(vec!["WARNING: No swap limit support".to_string()]),
+        ..Default::default()
+    };
+    let version = Version {
+        version: Some("26.1.0".to_string()),
+        api_version: Some("1.45".to_string()),
+        os: Some("linux".to_string()),
+        arch: Some("amd64".to_string()),
+        ..Default::default()
+    };
+    let summary = summarize(&info, &version);
+    assert_eq!(value(&summary.rows, "Daemon version"), "26.1.0");
+    assert_eq!(value(&summary.rows, "API version"), "1.45");
+    assert_eq!(value(&summary.rows, "OS/arch"), "linux/amd64");
+    assert_eq!(
+        value(&summary.rows, "Containers"),
+        "10 (7 running, 1 paused, 2 stopped)"
+    );
+    assert_eq!(value(&summary.rows, "Images"), "42");
+    assert_eq!(value(&summary.rows, "Storage driver"), "overlay2");
+    assert_eq!(value(&summary.rows, "Cgroup"), "v2 (systemd)");
+    assert_eq!(summary.warnings, vec!["WARNING: No swap limit support"]);
+}
+
+#[test]
+fn missing_fields_show_as_unknown() {
+    let info = SystemInfo {
+        os_type: Some("linux".to_string()),
+        ..Default::default()
+    };
+    let summary = summarize(&info, &Version::default());
+    assert_eq!(value(&summary.rows, "Daemon version"), "unknown");
+    assert_eq!(value(&summary.rows, "OS/arch"), "linux/unknown");
+    assert_eq!(value(&summary.rows, "Cgroup"), "unknown");
+    assert!(summary.warnings.is_empty());
+}
@@ -0,0 +1,46 @@
+use std::path::PathBuf;
+
+use bollard::errors::Error;
+use dockerrs::tarballs::write_stream;
+use futures_util::stream;
+use tokio::sync::mpsc;
+
+fn export_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "dockerrs-tarball-{}-{}.tar",
+        name,
+        std::process::id()
+    ))
+}
+
+#[tokio::test]
+async fn complete_streams_are_written_out() {
+    let path = export_path("complete");
+    let chunks: Vec<Result<Vec<u8>, Error>> = vec![Ok(b"first ".to_vec()), Ok(b"second".to_vec())];
+    let (progress, _) = mpsc::unbounded_channel();
+    let written = write_stream(stream::iter(chunks), &path, "web", &progress)
+        .await
+        .unwrap();
+    assert_eq!(written, 12);
+    assert_eq!(std::fs::read(&path).unwrap(), b"first second");
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn a_stream_failing_midway_leaves_no_partial_file() {
+    let path = export_path("partial");
+    let chunks: Vec<Result<Vec<u8>, Error>> = vec![
+        Ok(vec![0; 4096]),
+        Err(Error::IOError {
+            err: std::io::Error::other("connection reset"),
+        }),
+        Ok(vec![0; 4096]),
+    ];
+    let (progress, _) = mpsc::unbounded_channel();
+    let error = write_stream(stream::iter(chunks), &path, "web", &progress)
+        .await
+        .unwrap_err();
+    assert!(error.starts_with("Failed to export web"), "{}", error);
+    assert!(error.contains("connection reset"), "{}", error);
+    assert!(!path.exists());
+}
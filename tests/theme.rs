@@ -0,0 +1,73 @@
+use std::path::Path;
+
+use dockerrs::config::Config;
+use dockerrs::theme::{select, Theme, ThemeName};
+
+#[test]
+fn no_color_only_changes_the_default() {
+    assert_eq!(select(None, None, false), ThemeName::Default);
+    assert_eq!(select(None, None, true), ThemeName::Monochrome);
+    assert_eq!(
+        select(None, Some(ThemeName::HighContrast), true),
+        ThemeName::HighContrast
+    );
+    assert_eq!(
+        select(Some(ThemeName::Default), Some(ThemeName::Monochrome), true),
+        ThemeName::Default
+    );
+}
+
+#[test]
+fn theme_is_read_from_the_config() {
+    let config: Config = serde_yaml::from_str("theme: high-contrast\n").unwrap();
+    assert_eq!(config.theme, Some(ThemeName::HighContrast));
+    assert_eq!(
+        Theme::new(ThemeName::HighContrast).name,
+        ThemeName::HighContrast
+    );
+}
+
+#[test]
+fn monochrome_has_no_hue() {
+    let theme = Theme::new(ThemeName::Monochrome);
+    let mut colors = vec![
+        theme.error,
+        theme.warning,
+        theme.success,
+        theme.muted,
+        theme.cpu,
+        theme.memory,
+        theme.keyword,
+        theme.variable,
+    ];
+    colors.extend(theme.prefixes);
+    for color in colors {
+        assert!(
+            color.r() == color.g() && color.g() == color.b(),
+            "{:?}",
+            color
+        );
+    }
+}
+
+// Views take their colors from the theme, so switching it reaches everything
+#[test]
+fn colors_are_only_defined_in_the_theme() {
+    let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+    for entry in std::fs::read_dir(&src).unwrap() {
+        let path = entry.unwrap().path();
+        if path.file_name().unwrap() == "theme.rs" {
+            continue;
+        }
+        let content = std::fs::read_to_string(&path).unwrap();
+        for (index, line) in content.lines().enumerate() {
+            assert!(
+                !line.contains("Color32::"),
+                "{}:{} uses a color outside the theme: {}",
+                path.display(),
+                index + 1,
+                line.trim()
+            );
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use dockerrs::time_format::{
+    format_age, format_duration, format_timestamp, parse_timestamp, parse_timestamp_nanos, uptime,
+};
+
+#[test]
+fn ages_use_the_largest_unit() {
+    assert_eq!(format_age(1_000, 1_000), "0 seconds ago");
+    assert_eq!(format_age(1_000, 1_060), "1 minute ago");
+    assert_eq!(format_age(0, 3 * 86400 + 5), "3 days ago");
+}
+
+#[test]
+fn future_times_are_clamped_to_zero() {
+    assert_eq!(format_age(2_000, 1_000), "0 seconds ago");
+    assert_eq!(format_duration(-30), "0s");
+    assert_eq!(
+        uptime("2024-05-01T12:00:10Z", 1714564800).as_deref(),
+        Some("0s")
+    );
+}
+
+#[test]
+fn durations_show_the_two_largest_units() {
+    assert_eq!(format_duration(0), "0s");
+    assert_eq!(format_duration(45), "45s");
+    assert_eq!(format_duration(5 * 60 + 12), "5m 12s");
+    assert_eq!(format_duration(3 * 86400 + 4 * 3600 + 59), "3d 4h");
+    assert_eq!(format_duration(2 * 3600 + 30), "2h");
+}
+
+#[test]
+fn timestamps_are_formatted_in_utc() {
+    assert_eq!(format_timestamp(0), "1970-01-01 00:00:00 UTC");
+    assert_eq!(format_timestamp(1714564800), "2024-05-01 12:00:00 UTC");
+    assert_eq!(format_timestamp(951782400), "2000-02-29 00:00:00 UTC");
+}
+
+#[test]
+fn inspect_timestamps_are_parsed() {
+    assert_eq!(
+        parse_timestamp("2024-05-01T12:00:00.123456789Z"),
+        Some(1714564800)
+    );
+    assert_eq!(
+        parse_timestamp("2024-05-01T14:00:00+02:00"),
+        Some(1714564800)
+    );
+    assert_eq!(parse_timestamp("not a time"), None);
+}
+
+#[test]
+fn containers_that_never_started_have_no_uptime() {
+    assert_eq!(uptime("0001-01-01T00:00:00Z", 1714564800), None);
+    assert_eq!(
+        uptime("2024-05-01T12:00:00Z", 1714564800 + 90).as_deref(),
+        Some("1m 30s")
+    );
+}
+
+#[test]
+fn fractions_are_kept_as_nanoseconds() {
+    assert_eq!(
+        parse_timestamp_nanos("2024-05-01T12:00:00.123456789Z"),
+        Some((1714564800, 123_456_789))
+    );
+    // Trailing zeros are left out by the daemon
+    assert_eq!(
+        parse_timestamp_nanos("2024-05-01T12:00:00.12Z"),
+        Some((1714564800, 120_000_000))
+    );
+    assert_eq!(
+        parse_timestamp_nanos("2024-05-01T12:00:00Z"),
+        Some((1714564800, 0))
+    );
+    assert_eq!(parse_timestamp_nanos("GET /health"), None);
+}
@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use dockerrs::stats::{StatsHistory, StatsSample};
+use dockerrs::top::{leaders, sort_by_usage, Resort, Resource, LEADERS, RESORT_INTERVAL};
+use dockerrs::utils::ContainerSort;
+
+fn history(usage: &[(&str, f64, u64)]) -> HashMap<String, StatsHistory> {
+    usage
+        .iter()
+        .map(|(id, cpu_percent, memory)| {
+            let mut history = StatsHistory::new(10);
+            history.push(StatsSample {
+                cpu_percent: *cpu_percent,
+                memory: *memory,
+            });
+            (id.to_string(), history)
+        })
+        .collect()
+}
+
+fn ids(ids: &[&str]) -> Vec<String> {
+    ids.iter().map(|id| id.to_string()).collect()
+}
+
+#[test]
+fn heaviest_users_come_first_and_unsampled_containers_last() {
+    let history = history(&[("a", 5.0, 300), ("b", 80.0, 100), ("c", 5.0, 200)]);
+    let mut order = ids(&["new", "a", "b", "c"]);
+    sort_by_usage(&mut order, &history, Resource::Cpu);
+    assert_eq!(order, ids(&["b", "a", "c", "new"]));
+    sort_by_usage(&mut order, &history, Resource::Memory);
+    assert_eq!(order, ids(&["a", "c", "b", "new"]));
+}
+
+#[test]
+fn leaders_are_the_top_few_with_samples() {
+    let history = history(&[
+        ("a", 1.0, 0),
+        ("b", 2.0, 0),
+        ("c", 3.0, 0),
+        ("d", 4.0, 0),
+        ("e", 5.0, 0),
+        ("f", 6.0, 0),
+    ]);
+    let all = ids(&["a", "b", "c", "d", "e", "f", "unsampled"]);
+    let top: Vec<String> = leaders(&all, &history, Resource::Cpu)
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect();
+    assert_eq!(top.len(), LEADERS);
+    assert_eq!(top, ids(&["f", "e", "d", "c", "b"]));
+    assert!(leaders(&ids(&["unsampled"]), &history, Resource::Memory).is_empty());
+}
+
+#[test]
+fn resorting_happens_at_most_once_per_interval() {
+    let mut resort = Resort::default();
+    let start = Instant::now();
+    assert!(resort.due(start));
+    assert!(!resort.due(start + Duration::from_millis(500)));
+    assert!(resort.due(start + RESORT_INTERVAL));
+    assert!(!resort.due(start + RESORT_INTERVAL + Duration::from_millis(999)));
+}
+
+#[test]
+fn the_sort_cycles_through_usage() {
+    assert_eq!(ContainerSort::Restarts.next(), ContainerSort::Cpu);
+    assert_eq!(ContainerSort::Cpu.next(), ContainerSort::Memory);
+    assert_eq!(ContainerSort::Memory.next(), ContainerSort::Name);
+    assert_eq!(ContainerSort::Cpu.resource(), Some(Resource::Cpu));
+    assert_eq!(ContainerSort::Name.resource(), None);
+}
@@ -0,0 +1,37 @@
+use dockerrs::ui_state::{restore_scroll, ScrollRestore, UiStateCache};
+
+#[test]
+fn the_least_recently_used_container_is_evicted() {
+    let mut cache = UiStateCache::new(2);
+    cache.get_mut("a").log_scroll = 10.0;
+    cache.get_mut("b").log_scroll = 20.0;
+    // Selecting a again makes b the least recently used
+    assert_eq!(cache.get_mut("a").log_scroll, 10.0);
+    cache.get_mut("c").log_scroll = 30.0;
+
+    assert_eq!(cache.get_mut("a").log_scroll, 10.0);
+    assert_eq!(cache.get_mut("c").log_scroll, 30.0);
+    // b comes back as a fresh entry, which in turn evicts a
+    assert_eq!(cache.get_mut("b").log_scroll, 0.0);
+    assert_eq!(cache.get_mut("c").log_scroll, 30.0);
+    assert_eq!(cache.get_mut("a").log_scroll, 0.0);
+}
+
+#[test]
+fn a_capacity_of_zero_still_keeps_the_current_container() {
+    let mut cache = UiStateCache::new(0);
+    cache.get_mut("a").search = "error".to_string();
+    assert_eq!(cache.get_mut("a").search, "error");
+    assert_eq!(cache.get_mut("b").search, "");
+    assert_eq!(cache.get_mut("a").search, "");
+}
+
+#[test]
+fn scroll_offsets_past_the_end_are_clamped() {
+    assert_eq!(restore_scroll(120.0, 500.0), ScrollRestore::Restore(120.0));
+    assert_eq!(restore_scroll(500.0, 500.0), ScrollRestore::Restore(500.0));
+    assert_eq!(restore_scroll(0.0, 0.0), ScrollRestore::Restore(0.0));
+    assert_eq!(restore_scroll(800.0, 500.0), ScrollRestore::Clamp(500.0));
+    // Fewer lines than fit the view leave nothing to scroll
+    assert_eq!(restore_scroll(80.0, -20.0), ScrollRestore::Clamp(0.0));
+}
@@ -0,0 +1,87 @@
+use bollard::secret::{ContainerSummary, ImageSummary};
+use dockerrs::poller::ContainerSnapshot;
+use dockerrs::unused_images::{is_removable, summarize_removals, unused_tags, Removal};
+
+const DAY: i64 = 24 * 60 * 60;
+
+fn image(id: &str, tags: &[&str], created: i64) -> ImageSummary {
+    ImageSummary {
+        id: id.to_string(),
+        repo_tags: tags.iter().map(|tag| tag.to_string()).collect(),
+        created,
+        ..Default::default()
+    }
+}
+
+fn using(image_ids: &[&str]) -> ContainerSnapshot {
+    image_ids
+        .iter()
+        .enumerate()
+        .map(|(index, image_id)| {
+            let summary = ContainerSummary {
+                id: Some(index.to_string()),
+                image_id: Some(image_id.to_string()),
+                ..Default::default()
+            };
+            (index.to_string(), (summary, String::new()))
+        })
+        .collect()
+}
+
+#[test]
+fn unused_tags_group_by_repository_newest_first() {
+    let images = vec![
+        image("sha256:1", &["app:1.0"], 10 * DAY),
+        image("sha256:2", &["app:1.1"], 20 * DAY),
+        image("sha256:3", &["app:1.2"], 30 * DAY),
+        image("sha256:4", &["localhost:5000/db:15", "db:15"], 5 * DAY),
+        image("sha256:5", &["<none>:<none>"], DAY),
+    ];
+    let groups = unused_tags(&images, &using(&["sha256:3"]));
+    let tags = |repository: &str| -> Vec<&str> {
+        groups[repository]
+            .iter()
+            .map(|tag| tag.tag.as_str())
+            .collect()
+    };
+    assert_eq!(
+        groups.keys().collect::<Vec<_>>(),
+        ["app", "db", "localhost:5000/db"]
+    );
+    assert_eq!(tags("app"), ["app:1.1", "app:1.0"]);
+    assert_eq!(tags("localhost:5000/db"), ["localhost:5000/db:15"]);
+}
+
+#[test]
+fn tags_younger_than_the_kept_days_stay() {
+    let images = vec![image("sha256:1", &["app:1.0"], 0)];
+    let tag = &unused_tags(&images, &using(&[]))["app"][0];
+    assert!(!is_removable(tag, 7, 6 * DAY));
+    assert!(is_removable(tag, 7, 7 * DAY));
+    assert!(is_removable(tag, 0, 0));
+}
+
+#[test]
+fn removals_with_children_are_listed_and_other_failures_fail_the_batch() {
+    let outcomes = vec![
+        ("app:1.0".to_string(), Removal::Removed),
+        ("base:1".to_string(), Removal::HasChildren),
+        ("app:1.1".to_string(), Removal::Removed),
+    ];
+    assert_eq!(
+        summarize_removals(&outcomes),
+        Ok("Removed 2 unused tags, kept 1 with dependent child images (base:1)".to_string())
+    );
+
+    let outcomes = vec![
+        ("app:1.0".to_string(), Removal::Removed),
+        (
+            "app:0.9".to_string(),
+            Removal::Failed("timed out".to_string()),
+        ),
+    ];
+    assert_eq!(
+        summarize_removals(&outcomes),
+        Err("Removed 1 unused tags, 1 failed: app:0.9: timed out".to_string())
+    );
+}
@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+
+use bollard::secret::{ContainerSummary, MountPoint, MountPointTypeEnum};
+use dockerrs::poller::ContainerSnapshot;
+use dockerrs::volumes::{mounting_containers, parse_du, VolumeMount};
+
+fn container(name: &str, mounts: Vec<MountPoint>) -> (String, (ContainerSummary, String)) {
+    let summary = ContainerSummary {
+        id: Some(name.to_string()),
+        names: Some(vec![format!("/{}", name)]),
+        mounts: Some(mounts),
+        ..Default::default()
+    };
+    (name.to_string(), (summary, String::new()))
+}
+
+fn volume(name: &str, destination: &str, rw: bool) -> MountPoint {
+    MountPoint {
+        typ: Some(MountPointTypeEnum::VOLUME),
+        name: Some(name.to_string()),
+        destination: Some(destination.to_string()),
+        rw: Some(rw),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn containers_mounting_a_volume_are_found_with_their_mode() {
+    let bind = MountPoint {
+        typ: Some(MountPointTypeEnum::BIND),
+        name: Some("data".to_string()),
+        ..Default::default()
+    };
+    let containers: ContainerSnapshot = HashMap::from([
+        container("web", vec![volume("data", "/srv", false)]),
+        container(
+            "db",
+            vec![volume("data", "/var/lib/db", true), bind.clone()],
+        ),
+        container("cache", vec![volume("other", "/data", true), bind]),
+    ]);
+    assert_eq!(
+        mounting_containers("data", &containers),
+        [
+            VolumeMount {
+                container: "/db".to_string(),
+                destination: "/var/lib/db".to_string(),
+                read_write: true,
+            },
+            VolumeMount {
+                container: "/web".to_string(),
+                destination: "/srv".to_string(),
+                read_write: false,
+            },
+        ]
+    );
+    assert!(mounting_containers("missing", &containers).is_empty());
+}
+
+#[test]
+fn du_totals_are_read_in_bytes() {
+    assert_eq!(parse_du("2048\t/volume\n"), Some(2 * 1024 * 1024));
+    assert_eq!(parse_du(""), None);
+}